@@ -95,3 +95,89 @@ pub(crate) fn normalize_str(s: &str) -> Cow<str> {
         percent_decoded
     }
 }
+
+/// Score how well `pattern` fuzzy-matches `candidate`, higher is better.
+///
+/// The characters of the pattern must appear in the candidate in order
+/// (case-insensitively), otherwise `None` is returned.
+/// Matches at the start of the candidate, consecutive matches and matches
+/// at word boundaries (after `_` or at a camelCase hump) receive bonuses.
+#[must_use]
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    const MATCH: i64 = 1;
+    const PREFIX_BONUS: i64 = 8;
+    const CONSECUTIVE_BONUS: i64 = 5;
+    const BOUNDARY_BONUS: i64 = 6;
+    const GAP_PENALTY: i64 = 1;
+
+    let mut pattern_chars = pattern.chars().flat_map(char::to_lowercase).peekable();
+
+    let mut score = 0;
+    let mut prev_char: Option<char> = None;
+    let mut prev_matched = false;
+    let mut gap = 0;
+
+    for (idx, c) in candidate.chars().enumerate() {
+        let Some(&p) = pattern_chars.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(p.to_lowercase()) {
+            pattern_chars.next();
+            score += MATCH;
+
+            if idx == 0 {
+                score += PREFIX_BONUS;
+            } else if prev_matched {
+                score += CONSECUTIVE_BONUS;
+            } else {
+                score -= gap * GAP_PENALTY;
+            }
+
+            let is_boundary = match prev_char {
+                Some(prev) => prev == '_' || (prev.is_lowercase() && c.is_uppercase()),
+                None => false,
+            };
+
+            if is_boundary {
+                score += BOUNDARY_BONUS;
+            }
+
+            prev_matched = true;
+            gap = 0;
+        } else {
+            prev_matched = false;
+            gap += 1;
+        }
+
+        prev_char = Some(c);
+    }
+
+    if pattern_chars.peek().is_some() {
+        return None;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+#[test]
+fn test_fuzzy_score() {
+    assert_eq!(fuzzy_score("", "anything"), Some(0));
+    assert_eq!(fuzzy_score("prln", "print"), None);
+    assert_eq!(fuzzy_score("xyz", "println"), None);
+
+    let println = fuzzy_score("prln", "println").unwrap();
+    let parse_line = fuzzy_score("prln", "parse_line").unwrap();
+    let spr_len = fuzzy_score("prln", "supreme_length").unwrap();
+    assert!(println > parse_line, "{println} <= {parse_line}");
+    assert!(parse_line > spr_len, "{parse_line} <= {spr_len}");
+
+    // Prefix matches are ranked above matches in the middle.
+    assert!(fuzzy_score("len", "length").unwrap() > fuzzy_score("len", "strlen").unwrap());
+
+    // Case-insensitive and boundary-aware.
+    assert!(fuzzy_score("gV", "getValue").is_some());
+    assert!(fuzzy_score("gv", "getValue").unwrap() > fuzzy_score("gv", "gravy").unwrap());
+    assert!(fuzzy_score("gv", "get_value").unwrap() > fuzzy_score("gv", "gravy").unwrap());
+}
@@ -3,7 +3,7 @@ use std::io::{self, Write};
 use rhai_rowan::{
     ast::{
         AstNode, ExportTarget, Expr, ExprBinary, ExprBlock, ExprConst, ExprContinue, ExprIf,
-        ExprLet, LitStrTemplateSegment,
+        ExprLet, LitStrTemplateSegment, Pat,
     },
     syntax::SyntaxKind::{self, *},
     T,
@@ -414,6 +414,9 @@ impl<S: Write> Formatter<S> {
         if let Some(base) = expr.expr() {
             self.fmt_expr(base)?;
         }
+        if expr.op_not_token().is_some() {
+            self.word("!")?;
+        }
         self.word("(")?;
         self.cbox(1);
         self.zerobreak();
@@ -645,6 +648,9 @@ impl<S: Write> Formatter<S> {
                 if let Some(base) = expr.expr() {
                     self.fmt_access_chain(base, false)?;
                 }
+                if expr.op_not_token().is_some() {
+                    self.word("!")?;
+                }
                 self.word("(")?;
                 self.cbox(1);
                 self.zerobreak();
@@ -835,6 +841,8 @@ impl<S: Write> Formatter<S> {
             if let Some(ident) = expr.ident_token() {
                 self.word(ident.static_text())?;
                 self.comments_after_child(&syntax, IDENT)?;
+            } else if let Some(pat) = expr.pat() {
+                self.fmt_pat(&pat)?;
             }
             self.end();
             if let Some(rhs) = expr.expr() {
@@ -852,6 +860,8 @@ impl<S: Write> Formatter<S> {
             if let Some(ident) = expr.ident_token() {
                 self.word(ident.static_text())?;
                 self.comments_after_child(&syntax, IDENT)?;
+            } else if let Some(pat) = expr.pat() {
+                self.fmt_pat(&pat)?;
             }
             self.end();
             if let Some(rhs) = expr.expr() {
@@ -862,6 +872,48 @@ impl<S: Write> Formatter<S> {
         Ok(())
     }
 
+    /// Destructuring patterns are always formatted on a single line.
+    pub(crate) fn fmt_pat(&mut self, pat: &Pat) -> Result<(), io::Error> {
+        let (open, close) = match pat {
+            Pat::Ident(_) => ("", ""),
+            Pat::Tuple(_) => ("(", ")"),
+            Pat::Array(_) => ("[", "]"),
+            Pat::Object(_) => ("#{", "}"),
+        };
+
+        self.word(open)?;
+
+        if let Pat::Object(obj) = pat {
+            for (i, field) in obj.fields().enumerate() {
+                if i != 0 {
+                    self.word(", ")?;
+                }
+
+                let (Some(property), Some(binding)) = (field.property(), field.binding()) else {
+                    continue;
+                };
+
+                self.word(property.static_text())?;
+
+                if binding != property {
+                    self.word(": ")?;
+                    self.word(binding.static_text())?;
+                }
+            }
+        } else {
+            for (i, ident) in pat.idents().enumerate() {
+                if i != 0 {
+                    self.word(", ")?;
+                }
+                self.word(ident.static_text())?;
+            }
+        }
+
+        self.word(close)?;
+
+        Ok(())
+    }
+
     pub(crate) fn fmt_expr_if(&mut self, expr: ExprIf, no_cbox: bool) -> Result<(), io::Error> {
         self.word("if ")?;
         if !no_cbox {
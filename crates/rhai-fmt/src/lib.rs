@@ -29,7 +29,9 @@ mod ring;
 mod source;
 mod util;
 
+pub mod on_type;
 pub mod options;
+pub mod range;
 
 pub use options::Options;
 use rhai_rowan::{parser::Parser, syntax::SyntaxElement};
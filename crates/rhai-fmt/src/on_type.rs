@@ -0,0 +1,166 @@
+//! Formatting while typing.
+//!
+//! Only the indentation of the line of the typed character is changed,
+//! nested lines are indented by [`Options::indent_string`] relative to
+//! the line of the opening delimiter just like the formatter does.
+
+use rhai_rowan::{
+    syntax::{SyntaxKind::*, SyntaxNode, SyntaxToken},
+    TextRange, TextSize,
+};
+
+use crate::Options;
+
+/// Characters that trigger formatting while typing.
+pub const TRIGGER_CHARACTERS: &[char] = &['}', ';'];
+
+/// Reindent the line of the character `ch` that was typed
+/// right before `offset`.
+///
+/// A `}` is aligned with the line of its opening brace, a `;` reindents
+/// the line of the statement it ends if the statement starts on it.
+///
+/// Returns the range of the indentation to replace and the new indentation,
+/// or `None` if the indentation is already correct, or if the surrounding
+/// syntax is not valid enough to tell.
+#[must_use]
+pub fn format_on_type(
+    root: &SyntaxNode,
+    offset: TextSize,
+    ch: char,
+    options: &Options,
+) -> Option<(TextRange, String)> {
+    let trigger_kind = match ch {
+        '}' => PUNCT_BRACE_END,
+        ';' => PUNCT_SEMI,
+        _ => return None,
+    };
+
+    let typed_start = offset.checked_sub(TextSize::of(ch))?;
+    let token = root
+        .token_at_offset(typed_start)
+        .find(|token| token.text_range().start() == typed_start)?;
+
+    if token.kind() != trigger_kind || token.parent_ancestors().any(|node| node.kind() == ERROR) {
+        return None;
+    }
+
+    let text = root.text().to_string();
+    let line = line_range(&text, typed_start);
+
+    let indent = match trigger_kind {
+        PUNCT_BRACE_END => {
+            // The brace must be the first thing on its line.
+            if !text[TextRange::new(line.start(), typed_start)]
+                .trim()
+                .is_empty()
+            {
+                return None;
+            }
+
+            let block = token.parent()?;
+
+            if has_errors(&block) {
+                return None;
+            }
+
+            let opening = block.children_with_tokens().find_map(|element| {
+                element
+                    .into_token()
+                    .filter(|token| matches!(token.kind(), PUNCT_BRACE_START | PUNCT_MAP_START))
+            })?;
+
+            line_indent(&text, opening.text_range().start()).to_string()
+        }
+        _ => {
+            // Misplaced semicolons end up in other nodes.
+            let stmt = token.parent().filter(|node| node.kind() == STMT)?;
+
+            if has_errors(&stmt) || line_range(&text, first_token_start(&stmt)?) != line {
+                return None;
+            }
+
+            match enclosing_opening(&token, line.start()) {
+                Some(opening) => {
+                    let mut indent = line_indent(&text, opening.text_range().start()).to_string();
+                    indent += &options.indent_string;
+                    indent
+                }
+                None => String::new(),
+            }
+        }
+    };
+
+    let current = line_indent(&text, line.start());
+    let current_range = TextRange::at(line.start(), TextSize::of(current));
+
+    if current == indent {
+        None
+    } else {
+        Some((current_range, indent))
+    }
+}
+
+pub(crate) fn has_errors(node: &SyntaxNode) -> bool {
+    node.descendants().any(|node| node.kind() == ERROR)
+}
+
+/// The start of the first token of the node that is not whitespace or a comment.
+fn first_token_start(node: &SyntaxNode) -> Option<TextSize> {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| {
+            !matches!(
+                token.kind(),
+                WHITESPACE | COMMENT_LINE | COMMENT_LINE_DOC | COMMENT_BLOCK | COMMENT_BLOCK_DOC
+            )
+        })
+        .map(|token| token.text_range().start())
+}
+
+/// The innermost opening delimiter before `line_start`
+/// that is not closed before it.
+fn enclosing_opening(token: &SyntaxToken, line_start: TextSize) -> Option<SyntaxToken> {
+    token.parent_ancestors().find_map(|node| {
+        let mut opening = None;
+
+        for token in node.children_with_tokens().filter_map(|e| e.into_token()) {
+            if token.text_range().start() >= line_start {
+                break;
+            }
+
+            match token.kind() {
+                PUNCT_BRACE_START
+                | PUNCT_MAP_START
+                | PUNCT_BRACKET_START
+                | PUNCT_NULL_BRACKET_START
+                | PUNCT_PAREN_START => opening = Some(token),
+                PUNCT_BRACE_END | PUNCT_BRACKET_END | PUNCT_PAREN_END => opening = None,
+                _ => {}
+            }
+        }
+
+        opening
+    })
+}
+
+/// The range of the line that contains the offset, without the line break.
+pub(crate) fn line_range(text: &str, offset: TextSize) -> TextRange {
+    let offset = usize::from(offset);
+    let start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    let end = text[offset..]
+        .find('\n')
+        .map_or(text.len(), |idx| offset + idx);
+
+    TextRange::new(text_size(start), text_size(end))
+}
+
+/// The leading whitespace of the line that contains the offset.
+pub(crate) fn line_indent(text: &str, offset: TextSize) -> &str {
+    let line = &text[line_range(text, offset)];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+fn text_size(offset: usize) -> TextSize {
+    TextSize::from(offset as u32)
+}
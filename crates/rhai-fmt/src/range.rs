@@ -0,0 +1,147 @@
+//! Formatting a selection of statements.
+//!
+//! The statements are formatted on their own and indented to the
+//! block they are in, the rest of the document is left as it is.
+
+use rhai_rowan::{
+    parser::Parser,
+    syntax::{SyntaxKind::*, SyntaxNode},
+    TextRange, TextSize,
+};
+
+use crate::{
+    format_syntax,
+    on_type::{has_errors, line_indent, line_range},
+    Options,
+};
+
+/// Format the statements that overlap the range, statements that are
+/// only partially selected are formatted as a whole.
+///
+/// All statements are from the same block, if the range spans multiple
+/// blocks the statements of the innermost block that contains the whole
+/// range are formatted.
+///
+/// Returns the range to replace and the formatted statements, or `None`
+/// if there are no statements in the range or they have syntax errors.
+#[must_use]
+pub fn format_range(
+    root: &SyntaxNode,
+    range: TextRange,
+    options: &Options,
+) -> Option<(TextRange, String)> {
+    let text = root.text().to_string();
+
+    let covering = match root.covering_element(range) {
+        rowan::NodeOrToken::Node(node) => node,
+        rowan::NodeOrToken::Token(token) => token.parent()?,
+    };
+
+    let container = covering.ancestors().find_map(|node| {
+        if node.kind() == STMT {
+            node.parent()
+        } else if matches!(node.kind(), RHAI | EXPR_BLOCK) {
+            Some(node)
+        } else {
+            None
+        }
+    })?;
+
+    let statements = container
+        .children()
+        .filter(|node| node.kind() == STMT)
+        .filter_map(|stmt| trimmed_range(&stmt))
+        .filter(|stmt_range| {
+            stmt_range
+                .intersect(range)
+                .is_some_and(|overlap| !overlap.is_empty() || range.is_empty())
+        })
+        .collect::<Vec<_>>();
+
+    let selected = statements.first()?.cover(*statements.last()?);
+
+    if has_errors(&container) {
+        return None;
+    }
+
+    let indent = match container.kind() {
+        EXPR_BLOCK => {
+            let opening = container
+                .children_with_tokens()
+                .find(|element| element.kind() == PUNCT_BRACE_START)?;
+
+            let mut indent = line_indent(&text, opening.text_range().start()).to_string();
+            indent += &options.indent_string;
+            indent
+        }
+        _ => String::new(),
+    };
+
+    let parse = Parser::new(&text[selected]).parse_script();
+
+    if !parse.errors.is_empty() {
+        return None;
+    }
+
+    let formatted = format_syntax(
+        parse.clone_syntax(),
+        Options {
+            max_width: options
+                .max_width
+                .saturating_sub(indent.chars().count() as u64),
+            ..options.clone()
+        },
+    );
+    let formatted_root = Parser::new(&formatted).parse_script().into_syntax();
+
+    let mut new_text = String::new();
+    let mut line_start = 0;
+
+    for line in formatted.trim_end().split_inclusive('\n') {
+        // The lines of multi-line strings must not change.
+        let offset = TextSize::from(line_start as u32);
+        let in_string = formatted_root
+            .token_at_offset(offset)
+            .right_biased()
+            .is_some_and(|token| token.kind() == LIT_STR && token.text_range().start() < offset);
+
+        if !in_string && !line.trim().is_empty() {
+            new_text += &indent;
+        }
+
+        new_text += line;
+        line_start += line.len();
+    }
+
+    // Replace the indentation of the first line unless the
+    // first statement shares the line with other code.
+    let first_line = line_range(&text, selected.start());
+    let replaced = if text[TextRange::new(first_line.start(), selected.start())]
+        .trim()
+        .is_empty()
+    {
+        TextRange::new(first_line.start(), selected.end())
+    } else {
+        new_text = new_text.trim_start().to_string();
+        selected
+    };
+
+    if text[replaced] == new_text {
+        None
+    } else {
+        Some((replaced, new_text))
+    }
+}
+
+/// The range of the node without leading and trailing whitespace.
+fn trimmed_range(node: &SyntaxNode) -> Option<TextRange> {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(rowan::NodeOrToken::into_token)
+        .filter(|token| token.kind() != WHITESPACE);
+
+    let first = tokens.next()?.text_range();
+    let last = tokens.last().map_or(first, |token| token.text_range());
+
+    Some(first.cover(last))
+}
@@ -33,6 +33,7 @@ use test_case::test_case;
 #[test_case("string_escape", include_str!("../../../testdata/valid/string_escape.rhai"))]
 #[test_case("template", include_str!("../../../testdata/valid/template.rhai"))]
 #[test_case("unary_ops", include_str!("../../../testdata/valid/unary_ops.rhai"))]
+#[test_case("call_scope", include_str!("../../../testdata/valid/call_scope.rhai"))]
 fn format(name: &str, src: &str) {
     let formatted = rhai_fmt::format_source(src, Default::default());
     insta::with_settings!(
@@ -0,0 +1,78 @@
+use rhai_fmt::{on_type::format_on_type, Options};
+use rhai_rowan::{parser::Parser, TextSize};
+
+/// Format as if `ch` was just typed at the `$` marker,
+/// returns `None` if there were no edits.
+fn on_type(src: &str, ch: char) -> Option<String> {
+    let offset = src.find('$').unwrap();
+    let src = src.replacen('$', &ch.to_string(), 1);
+
+    let root = Parser::new(&src).parse_script().into_syntax();
+    let offset = TextSize::from((offset + ch.len_utf8()) as u32);
+
+    let (range, indent) = format_on_type(&root, offset, ch, &Options::default())?;
+
+    let mut formatted = src;
+    formatted.replace_range(std::ops::Range::<usize>::from(range), &indent);
+    Some(formatted)
+}
+
+#[test]
+fn test_closing_brace() {
+    let src = "fn foo() {\n  if true {\n    let a = 1;\n      $\n}\n";
+    assert_eq!(
+        on_type(src, '}').unwrap(),
+        "fn foo() {\n  if true {\n    let a = 1;\n  }\n}\n"
+    );
+
+    let src = "let a = #{\n  b: 1\n    $;\n";
+    assert_eq!(on_type(src, '}').unwrap(), "let a = #{\n  b: 1\n};\n");
+}
+
+#[test]
+fn test_closing_brace_in_half_typed_block() {
+    let src = "fn foo() {\n  if true {\n    let a = 1;\n      $\n";
+    assert_eq!(
+        on_type(src, '}').unwrap(),
+        "fn foo() {\n  if true {\n    let a = 1;\n  }\n"
+    );
+}
+
+#[test]
+fn test_closing_brace_after_code() {
+    let src = "fn foo() {\n  let a = 1; $\n";
+    assert_eq!(on_type(src, '}'), None);
+}
+
+#[test]
+fn test_semicolon() {
+    let src = "fn foo() {\n  if true {\n        let a = 1$\n  }\n}\n";
+    assert_eq!(
+        on_type(src, ';').unwrap(),
+        "fn foo() {\n  if true {\n    let a = 1;\n  }\n}\n"
+    );
+
+    let src = "   let a = 1$\n";
+    assert_eq!(on_type(src, ';').unwrap(), "let a = 1;\n");
+}
+
+#[test]
+fn test_semicolon_in_half_typed_block() {
+    let src = "fn foo() {\n  if true {\nlet a = 1$\n";
+    assert_eq!(
+        on_type(src, ';').unwrap(),
+        "fn foo() {\n  if true {\n    let a = 1;\n"
+    );
+}
+
+#[test]
+fn test_invalid_syntax() {
+    let src = "fn foo() {\n  if true {\n      let a = $\n  }\n}\n";
+    assert_eq!(on_type(src, ';'), None);
+}
+
+#[test]
+fn test_multi_line_statement() {
+    let src = "let a = foo(\n    1,\n        2)$\n";
+    assert_eq!(on_type(src, ';'), None);
+}
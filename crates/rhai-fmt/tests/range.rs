@@ -0,0 +1,84 @@
+use rhai_fmt::{range::format_range, Options};
+use rhai_rowan::{parser::Parser, TextRange, TextSize};
+
+/// Format the statements between the two `$` markers,
+/// returns `None` if there were no edits.
+fn range(src: &str) -> Option<String> {
+    let start = src.find('$').unwrap();
+    let end = src.rfind('$').unwrap() - 1;
+    let src = src.replace('$', "");
+
+    let root = Parser::new(&src).parse_script().into_syntax();
+    let range = TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32));
+
+    let (range, new_text) = format_range(&root, range, &Options::default())?;
+
+    let mut formatted = src;
+    formatted.replace_range(std::ops::Range::<usize>::from(range), &new_text);
+    Some(formatted)
+}
+
+#[test]
+fn test_format_fn_body() {
+    let src = r#"
+let   a=1;
+
+fn foo(x) {
+      $let y =   x+1;
+  print(y)$
+}
+
+let   b=2;
+"#;
+
+    assert_eq!(
+        range(src).unwrap(),
+        r#"
+let   a=1;
+
+fn foo(x) {
+  let y = x + 1;
+  print(y)
+}
+
+let   b=2;
+"#
+    );
+}
+
+#[test]
+fn test_partial_statements_are_expanded() {
+    let src = r#"
+    if true {
+        let a   =  [1,2,
+    $3];    let b=a[0];
+        let c  =  $b;
+    }
+"#;
+
+    assert_eq!(
+        range(src).unwrap(),
+        r#"
+    if true {
+      let a = [1, 2, 3];
+      let b = a[0];
+      let c = b;
+    }
+"#
+    );
+}
+
+#[test]
+fn test_top_level_statements() {
+    let src = "let   a=1;\n$let b  = 2;$\nlet   c=3;\n";
+    assert_eq!(range(src).unwrap(), "let   a=1;\nlet b = 2;\nlet   c=3;\n");
+
+    let src = "$let a = 1;$\n";
+    assert_eq!(range(src), None);
+}
+
+#[test]
+fn test_syntax_errors() {
+    let src = "fn foo() {\n  $let a = ;$\n}\n";
+    assert_eq!(range(src), None);
+}
@@ -0,0 +1,169 @@
+//! Binary caching of the HIR state.
+//!
+//! The cache contains every module, scope, symbol, source and type
+//! along with resolved references and inferred types,
+//! so a HIR loaded from the cache can be used without adding and
+//! resolving the sources again.
+//!
+//! Each cache is keyed by the content hashes of the sources it was built from
+//! and the settings that affect resolution and inference: the features,
+//! flow typing, numeric, implicit global, entry module, public module and
+//! union size settings, a cache is ignored if any of them changed.
+//!
+//! Definitions from the external resolver are stored like any other source,
+//! along with the functions the resolver did not know.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use serde::{Deserialize, Serialize};
+use slotmap::SlotMap;
+use url::Url;
+
+use crate::{
+    eval::NumericConfig,
+    hir::BuiltinTypes,
+    module::ModuleData,
+    scope::ScopeData,
+    source::{Source, SourceData},
+    symbol::SymbolData,
+    ty::{Type, TypeData, TypeKey},
+    Hir, Module, Scope, Symbol,
+};
+
+/// Caches with a different format version are ignored.
+const CACHE_VERSION: u32 = 20;
+
+#[derive(Serialize, Deserialize)]
+struct HirCache<'h> {
+    version: u32,
+    source_hashes: BTreeMap<Url, u64>,
+    features: Option<BTreeSet<String>>,
+    flow_typing: bool,
+    numeric: NumericConfig,
+    implicit_globals: bool,
+    entry_module: Option<Url>,
+    public_modules: Vec<Url>,
+    max_union_size: usize,
+    static_module: Module,
+    virtual_source: Source,
+    modules: Cow<'h, SlotMap<Module, ModuleData>>,
+    scopes: Cow<'h, SlotMap<Scope, ScopeData>>,
+    symbols: Cow<'h, SlotMap<Symbol, SymbolData>>,
+    sources: Cow<'h, SlotMap<Source, SourceData>>,
+    types: Cow<'h, SlotMap<Type, TypeData>>,
+    interned_types: Vec<((Option<Source>, TypeKey), Type)>,
+    builtin_types: BuiltinTypes,
+    missing_external_fns: Vec<(String, usize)>,
+}
+
+impl Hir {
+    /// Serialize the HIR into a compact binary format.
+    ///
+    /// The `source_hashes` must contain the [`content_hash`] of each source
+    /// in the HIR, they are used to determine whether the cache is stale
+    /// when it is loaded with [`Hir::load_cache`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the serialization fails.
+    pub fn save_cache(
+        &self,
+        source_hashes: impl IntoIterator<Item = (Url, u64)>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let cache = HirCache {
+            version: CACHE_VERSION,
+            source_hashes: source_hashes.into_iter().collect(),
+            features: self.features.clone(),
+            flow_typing: self.flow_typing,
+            numeric: self.numeric,
+            implicit_globals: self.implicit_globals,
+            entry_module: self.entry_module.clone(),
+            public_modules: self.public_modules.clone(),
+            max_union_size: self.max_union_size,
+            static_module: self.static_module,
+            virtual_source: self.virtual_source,
+            modules: Cow::Borrowed(&self.modules),
+            scopes: Cow::Borrowed(&self.scopes),
+            symbols: Cow::Borrowed(&self.symbols),
+            sources: Cow::Borrowed(&self.sources),
+            types: Cow::Borrowed(&self.types),
+            interned_types: self
+                .interned_types
+                .iter()
+                .map(|(key, ty)| (key.clone(), *ty))
+                .collect(),
+            builtin_types: self.builtin_types,
+            missing_external_fns: self.missing_external_fns.iter().cloned().collect(),
+        };
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&cache, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Replace the contents of the HIR with a cache created by [`Hir::save_cache`].
+    ///
+    /// The cache is only loaded if it was created from sources
+    /// with exactly the given content hashes and with the same
+    /// features, flow typing, numeric, implicit global, entry module,
+    /// public module and maximum union size settings as the HIR,
+    /// otherwise the HIR is left untouched and `false` is returned.
+    ///
+    /// The module and external resolvers of the HIR are not part
+    /// of the cache and are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache is malformed.
+    pub fn load_cache(
+        &mut self,
+        bytes: &[u8],
+        source_hashes: impl IntoIterator<Item = (Url, u64)>,
+    ) -> anyhow::Result<bool> {
+        let cache: HirCache = ciborium::de::from_reader(bytes)?;
+
+        if cache.version != CACHE_VERSION
+            || cache.source_hashes != source_hashes.into_iter().collect::<BTreeMap<_, _>>()
+            || cache.features != self.features
+            || cache.flow_typing != self.flow_typing
+            || cache.numeric != self.numeric
+            || cache.implicit_globals != self.implicit_globals
+            || cache.entry_module != self.entry_module
+            || cache.public_modules != self.public_modules
+            || cache.max_union_size != self.max_union_size
+        {
+            return Ok(false);
+        }
+
+        self.static_module = cache.static_module;
+        self.virtual_source = cache.virtual_source;
+        self.modules = cache.modules.into_owned();
+        self.scopes = cache.scopes.into_owned();
+        self.symbols = cache.symbols.into_owned();
+        self.sources = cache.sources.into_owned();
+        self.types = cache.types.into_owned();
+        self.interned_types = cache.interned_types.into_iter().collect();
+        self.builtin_types = cache.builtin_types;
+        self.missing_external_fns = cache.missing_external_fns.into_iter().collect();
+
+        Ok(true)
+    }
+}
+
+/// A stable hash of the source text to be used with [`Hir::save_cache`].
+///
+/// Unlike the standard library hashers, the result does not change
+/// between runs or compiler versions.
+#[must_use]
+pub fn content_hash(text: &str) -> u64 {
+    // 64-bit FNV-1a
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    text.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
@@ -0,0 +1,173 @@
+//! Comment directives that mark statements active or inactive
+//! depending on the configured features, see [`Hir::set_features`].
+//!
+//! ```rhai
+//! // #[cfg(debug)]
+//! fn log(message) { print(message); }
+//!
+//! // #[cfg(not(debug))]
+//! fn log(message) {}
+//! ```
+//!
+//! A directive is a line comment right before the statement
+//! or its documentation, if there are multiple directives,
+//! all of them must hold. Comments that are not valid directives
+//! are ignored.
+//!
+//! The grammar of the predicates:
+//!
+//! ```text
+//! directive  := "//" "#[cfg(" predicate ")]"
+//! predicate  := feature
+//!             | "not(" predicate ")"
+//!             | "all(" predicates ")"
+//!             | "any(" predicates ")"
+//! predicates := (predicate ("," predicate)* ","?)?
+//! feature    := (letter | digit | "_" | "-")+
+//! ```
+//!
+//! Inactive statements are still parsed and formatted,
+//! but they are not part of the HIR.
+//!
+//! [`Hir::set_features`]: crate::Hir::set_features
+
+use std::collections::BTreeSet;
+
+use rhai_rowan::syntax::{SyntaxKind, SyntaxNode};
+
+/// A condition on the active features.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgPredicate {
+    Feature(String),
+    Not(Box<CfgPredicate>),
+    All(Vec<CfgPredicate>),
+    Any(Vec<CfgPredicate>),
+}
+
+impl CfgPredicate {
+    /// Parse the predicate of a directive comment such as `// #[cfg(feature)]`.
+    ///
+    /// Returns `None` if the comment is not a valid directive.
+    #[must_use]
+    pub fn from_comment(comment: &str) -> Option<Self> {
+        let directive = comment.strip_prefix("//")?.trim();
+
+        if directive.starts_with('/') || directive.starts_with('!') {
+            return None;
+        }
+
+        let mut input = directive
+            .strip_prefix("#[")?
+            .strip_suffix(']')?
+            .trim()
+            .strip_prefix("cfg")?
+            .trim_start()
+            .strip_prefix('(')?
+            .strip_suffix(')')?;
+
+        let predicate = Self::parse(&mut input)?;
+
+        input.trim().is_empty().then_some(predicate)
+    }
+
+    /// Whether the predicate holds for the given features.
+    #[must_use]
+    pub fn is_active(&self, features: &BTreeSet<String>) -> bool {
+        match self {
+            CfgPredicate::Feature(feature) => features.contains(feature),
+            CfgPredicate::Not(predicate) => !predicate.is_active(features),
+            CfgPredicate::All(predicates) => predicates.iter().all(|p| p.is_active(features)),
+            CfgPredicate::Any(predicates) => predicates.iter().any(|p| p.is_active(features)),
+        }
+    }
+
+    fn parse(input: &mut &str) -> Option<Self> {
+        *input = input.trim_start();
+
+        let len = input
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(input.len());
+
+        if len == 0 {
+            return None;
+        }
+
+        let (name, rest) = input.split_at(len);
+        *input = rest.trim_start();
+
+        let Some(rest) = input.strip_prefix('(') else {
+            return Some(CfgPredicate::Feature(name.to_string()));
+        };
+        *input = rest;
+
+        let mut predicates = Vec::new();
+
+        loop {
+            *input = input.trim_start();
+
+            if let Some(rest) = input.strip_prefix(')') {
+                *input = rest;
+                break;
+            }
+
+            predicates.push(Self::parse(input)?);
+
+            *input = input.trim_start();
+
+            if let Some(rest) = input.strip_prefix(',') {
+                *input = rest;
+            } else if !input.starts_with(')') {
+                return None;
+            }
+        }
+
+        match name {
+            "not" if predicates.len() == 1 => Some(CfgPredicate::Not(Box::new(predicates.pop()?))),
+            "all" => Some(CfgPredicate::All(predicates)),
+            "any" => Some(CfgPredicate::Any(predicates)),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the directives before the statement allow it
+/// with the given features.
+///
+/// Only comments on their own lines directly above the statement
+/// are directives of it, comments after the previous statement
+/// on the same line or before an empty line are not.
+pub(crate) fn is_statement_active(stmt: &SyntaxNode, features: &BTreeSet<String>) -> bool {
+    let mut token = stmt.first_token().and_then(|t| t.prev_token());
+
+    // Documentation is part of the statement,
+    // so only comments and whitespace can be before it.
+    while let Some(t) = token {
+        match t.kind() {
+            SyntaxKind::WHITESPACE => {
+                if t.text().matches('\n').count() > 1 {
+                    break;
+                }
+            }
+            SyntaxKind::COMMENT_LINE => {
+                let own_line = t.prev_token().is_none_or(|prev| {
+                    prev.kind() == SyntaxKind::WHITESPACE && prev.text().contains('\n')
+                });
+
+                if !own_line {
+                    break;
+                }
+
+                if let Some(predicate) = CfgPredicate::from_comment(t.text()) {
+                    if !predicate.is_active(features) {
+                        return false;
+                    }
+                }
+            }
+            _ => break,
+        }
+
+        token = t.prev_token();
+    }
+
+    true
+}
@@ -0,0 +1,47 @@
+//! Diagnostics of a source that are ready to be reported,
+//! see [`Hir::diagnostics_for`].
+//!
+//! [`Hir::diagnostics_for`]: crate::Hir::diagnostics_for
+
+use rhai_rowan::TextRange;
+use url::Url;
+
+use crate::lint::{DiagnosticConfig, Lint, LintLevel};
+
+/// A syntax error or an error of a lint at a range of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub range: TextRange,
+    /// The configured level of the lint, never [`LintLevel::Off`].
+    pub level: LintLevel,
+    /// The lint of the diagnostic, `None` for syntax errors.
+    pub lint: Option<Lint>,
+    pub message: String,
+    /// Related ranges in the same source with their messages.
+    pub related: Vec<(TextRange, String)>,
+    pub tags: Vec<DiagnosticTag>,
+}
+
+/// Additional information about the diagnosed code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticTag {
+    /// The code is unused or unreachable.
+    Unnecessary,
+    /// The code uses something deprecated.
+    Deprecated,
+}
+
+/// Diagnostics of the bodies of the top-level functions of a source
+/// from a previous run, see [`Hir::incremental_diagnostics_for`].
+///
+/// [`Hir::incremental_diagnostics_for`]: crate::Hir::incremental_diagnostics_for
+#[derive(Debug, Default, Clone)]
+pub struct DiagnosticsCache {
+    pub(crate) url: Option<Url>,
+    pub(crate) config: Option<DiagnosticConfig>,
+    /// The signatures of the top-level symbols of the module
+    /// that the bodies of the functions can depend on.
+    pub(crate) environment: Vec<String>,
+    /// The diagnostics of the function bodies by the ranges of the functions.
+    pub(crate) functions: Vec<(TextRange, Vec<Diagnostic>)>,
+}
@@ -1,4 +1,9 @@
-use crate::Symbol;
+use crate::{
+    eval::{InvalidLiteral, Value},
+    lint::Lint,
+    Scope, Symbol,
+};
+use rhai_rowan::TextRange;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Error)]
@@ -29,8 +34,163 @@ pub enum ErrorKind {
         reference_symbol: Symbol,
         similar_name: Option<String>,
     },
-    #[error("unresolved import")]
-    UnresolvedImport { import: Symbol },
+    #[error("reference used before its declaration")]
+    UseBeforeDefinition {
+        reference_symbol: Symbol,
+        declaration: Symbol,
+    },
+    #[error("variable is used in its own initializer")]
+    SelfReferentialDeclaration {
+        reference_symbol: Symbol,
+        declaration: Symbol,
+    },
+    #[error("mutating a captured variable inside a closure does not affect the original variable")]
+    ClosureCaptureMutation {
+        reference_symbol: Symbol,
+        declaration: Symbol,
+        closure: Symbol,
+    },
+    #[error(
+        "function is deprecated{}",
+        if reason.is_empty() {
+            String::new()
+        } else {
+            format!(": {reason}")
+        }
+    )]
+    DeprecatedFunction {
+        reference_symbol: Symbol,
+        function: Symbol,
+        reason: String,
+    },
+    #[error("function is private")]
+    PrivateFunction {
+        reference_symbol: Symbol,
+        function: Symbol,
+    },
+    #[error(
+        "switch is not exhaustive, missing {}",
+        missing.iter().map(|value| format!("`{value}`")).collect::<Vec<_>>().join(", ")
+    )]
+    NonExhaustiveSwitch { switch: Symbol, missing: Vec<Value> },
+    #[error("module `{path}` does not exist")]
+    UnresolvedImport { import: Symbol, path: String },
     #[error("nested functions are not allowed")]
     NestedFunction { function: Symbol },
+    #[error("import inside a loop is evaluated on every iteration")]
+    ImportInLoop { import: Symbol, loop_symbol: Symbol },
+    #[error("not all code paths return a value")]
+    MissingReturn {
+        function: Symbol,
+        return_symbol: Symbol,
+    },
+    #[error("`return` without a value in a function that also returns values")]
+    InconsistentReturn {
+        function: Symbol,
+        /// A `return` with a value.
+        value_return: Symbol,
+        /// A `return` without a value.
+        bare_return: Symbol,
+    },
+    #[error("function is never used")]
+    UnusedFunction { function: Symbol },
+    #[error("variable is never used")]
+    UnusedVariable { declaration: Symbol },
+    #[error("export is not used by other modules")]
+    UnusedExport { symbol: Symbol },
+    #[error("duplicate object field `{name}`, only the last value is kept")]
+    DuplicateObjectField {
+        object: Symbol,
+        name: String,
+        /// The property of the duplicate field.
+        duplicate_range: TextRange,
+        /// The property of the first field with the same name.
+        existing_range: TextRange,
+    },
+    #[error("condition is always `{value}`")]
+    ConstantCondition {
+        /// The `if` or `while` symbol.
+        symbol: Symbol,
+        condition: Symbol,
+        value: bool,
+    },
+    #[error("{reason}")]
+    InvalidLiteral {
+        literal: Symbol,
+        reason: InvalidLiteral,
+    },
+    #[error("`{op}` is not defined for `{lhs_ty}` and `{rhs_ty}`")]
+    IncompatibleOperands {
+        binary: Symbol,
+        op: String,
+        lhs_ty: String,
+        rhs_ty: String,
+    },
+    #[error("no field `{name}` on type `{ty}`")]
+    UnknownField {
+        /// The field of the field access.
+        field: Symbol,
+        name: String,
+        /// The type of the accessed object.
+        ty: String,
+    },
+    #[error("empty {kind}")]
+    EmptyBlock {
+        /// The function, `if` or loop symbol.
+        symbol: Symbol,
+        scope: Scope,
+        kind: EmptyBlockKind,
+    },
+    #[error("`{name}` shadows a built-in function")]
+    ShadowedBuiltin {
+        declaration: Symbol,
+        /// The function of the prelude with the same name.
+        builtin: Symbol,
+        name: String,
+    },
+}
+
+impl ErrorKind {
+    /// The lint the error belongs to.
+    #[must_use]
+    pub fn lint(&self) -> Lint {
+        match self {
+            ErrorKind::DuplicateFnParameter { .. } => Lint::DuplicateFnParameter,
+            ErrorKind::UnresolvedReference { .. } => Lint::UnresolvedReference,
+            ErrorKind::UseBeforeDefinition { .. } => Lint::UseBeforeDefinition,
+            ErrorKind::SelfReferentialDeclaration { .. } => Lint::SelfReferentialDeclaration,
+            ErrorKind::ClosureCaptureMutation { .. } => Lint::ClosureCaptureMutation,
+            ErrorKind::DeprecatedFunction { .. } => Lint::DeprecatedFunction,
+            ErrorKind::PrivateFunction { .. } => Lint::PrivateFunction,
+            ErrorKind::NonExhaustiveSwitch { .. } => Lint::NonExhaustiveSwitch,
+            ErrorKind::UnresolvedImport { .. } => Lint::UnresolvedImport,
+            ErrorKind::NestedFunction { .. } => Lint::NestedFunction,
+            ErrorKind::ImportInLoop { .. } => Lint::ImportInLoop,
+            ErrorKind::MissingReturn { .. } => Lint::MissingReturn,
+            ErrorKind::InconsistentReturn { .. } => Lint::InconsistentReturn,
+            ErrorKind::UnusedFunction { .. } => Lint::UnusedFunction,
+            ErrorKind::UnusedVariable { .. } => Lint::UnusedVariable,
+            ErrorKind::UnusedExport { .. } => Lint::UnusedExport,
+            ErrorKind::DuplicateObjectField { .. } => Lint::DuplicateObjectField,
+            ErrorKind::ConstantCondition { .. } => Lint::ConstantCondition,
+            ErrorKind::InvalidLiteral { .. } => Lint::InvalidLiteral,
+            ErrorKind::ShadowedBuiltin { .. } => Lint::ShadowedBuiltin,
+            ErrorKind::IncompatibleOperands { .. } => Lint::IncompatibleOperands,
+            ErrorKind::UnknownField { .. } => Lint::UnknownField,
+            ErrorKind::EmptyBlock { .. } => Lint::EmptyBlock,
+        }
+    }
+}
+
+/// The kinds of blocks reported by [`ErrorKind::EmptyBlock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum EmptyBlockKind {
+    #[error("function body")]
+    Fn,
+    #[error("`if` branch")]
+    If,
+    #[error("`else` branch")]
+    Else,
+    #[error("loop body")]
+    Loop,
 }
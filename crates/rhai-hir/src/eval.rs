@@ -1,4 +1,7 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Value {
     Int(i64),
     Float(f64),
@@ -8,6 +11,60 @@ pub enum Value {
     Unknown,
 }
 
+/// The reason why the value of a literal cannot be decoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+pub enum InvalidLiteral {
+    #[error("integer literal is out of range")]
+    IntegerOverflow,
+    #[error("invalid float literal")]
+    InvalidFloat,
+    #[error("float literal is out of range")]
+    FloatOverflow,
+    #[error("floating-point numbers are not supported")]
+    FloatNotSupported,
+    #[error("malformed escape sequence `{0}`")]
+    MalformedEscapeSequence(String),
+    #[error("character literal must contain exactly one character")]
+    InvalidChar,
+}
+
+/// The numeric types of the Rhai build the scripts run with,
+/// see [`Hir::set_numeric_config`].
+///
+/// [`Hir::set_numeric_config`]: crate::Hir::set_numeric_config
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NumericConfig {
+    /// 32-bit integers (the `only_i32` feature).
+    pub only_i32: bool,
+    /// 32-bit floats (the `f32_float` feature).
+    pub f32_float: bool,
+    /// No floating-point numbers at all (the `no_float` feature).
+    pub no_float: bool,
+}
+
+impl NumericConfig {
+    /// The name of the integer type, as returned by `type_of`.
+    #[must_use]
+    pub fn int_type_name(&self) -> &'static str {
+        if self.only_i32 {
+            "i32"
+        } else {
+            "i64"
+        }
+    }
+
+    /// The name of the float type, as returned by `type_of`.
+    #[must_use]
+    pub fn float_type_name(&self) -> &'static str {
+        if self.f32_float {
+            "f32"
+        } else {
+            "f64"
+        }
+    }
+}
+
 impl core::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
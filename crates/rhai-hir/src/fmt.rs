@@ -110,7 +110,8 @@ use slotmap::{Key, KeyData};
 use crate::{
     scope::ScopeParent,
     source::Source,
-    symbol::{BinaryOpKind, ReferenceTarget, SymbolKind, VirtualSymbol},
+    symbol::{BinaryOpKind, ReferenceTarget, SymbolData, SymbolKind, VirtualSymbol},
+    ty::Type,
     Hir, Module, Scope, Symbol,
 };
 use std::fmt::{self, Write};
@@ -859,3 +860,300 @@ impl fmt::Display for KeyDataFmt {
         Ok(())
     }
 }
+
+impl Hir {
+    /// Render the scope and symbol hierarchy of a module as an indented tree.
+    ///
+    /// Unlike the [`HirFmt`] output, the tree does not contain slot indexes
+    /// and symbols are ordered by their position in the source,
+    /// so the output is deterministic and can be used in snapshot tests.
+    ///
+    /// Each symbol is printed with its kind, name (if any) and inferred type,
+    /// references are followed by their resolved target or `<unresolved>`.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn debug_tree(&self, module: Module) -> String {
+        let mut s = String::new();
+        let tree = TreeFmt {
+            hir: self,
+            indent_level: 0,
+        };
+        // Writing to a string cannot fail.
+        tree.fmt_module(&mut s, module).unwrap();
+        s
+    }
+}
+
+#[derive(Clone, Copy)]
+struct TreeFmt<'h> {
+    hir: &'h Hir,
+    indent_level: usize,
+}
+
+impl TreeFmt<'_> {
+    fn incr_indent(self) -> Self {
+        Self {
+            indent_level: self.indent_level + 1,
+            ..self
+        }
+    }
+
+    fn write_indent(&self, writer: &mut impl Write) -> fmt::Result {
+        for _ in 0..self.indent_level {
+            writer.write_str("  ")?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_module(&self, f: &mut impl Write, module: Module) -> fmt::Result {
+        match self.hir.modules.get(module) {
+            Some(m) => {
+                windentln!(self, f, "module {}", m.kind)?;
+                self.incr_indent().fmt_scope(f, "scope", m.scope)
+            }
+            None => windentln!(self, f, "!MISSING MODULE"),
+        }
+    }
+
+    fn fmt_scope(&self, f: &mut impl Write, label: &str, scope: Scope) -> fmt::Result {
+        let Some(data) = self.hir.scopes.get(scope) else {
+            return windentln!(self, f, "{label} !MISSING SCOPE");
+        };
+
+        if data.is_empty() {
+            return windentln!(self, f, "{label} {{}}");
+        }
+
+        windentln!(self, f, "{label}")?;
+
+        // Hoisted symbols are stored in a hash set,
+        // we sort them by their position for stable output.
+        let mut hoisted = data.hoisted_symbols.iter().copied().collect::<Vec<_>>();
+        hoisted.sort_by_key(|&symbol| {
+            self.hir
+                .symbols
+                .get(symbol)
+                .and_then(SymbolData::text_range)
+                .map(|r| (r.start(), r.end()))
+        });
+
+        let indented = self.incr_indent();
+        for symbol in hoisted.into_iter().chain(data.symbols.iter().copied()) {
+            indented.fmt_symbol(f, symbol)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_child(&self, f: &mut impl Write, label: &str, symbol: Option<Symbol>) -> fmt::Result {
+        match symbol {
+            Some(symbol) => {
+                windentln!(self, f, "{label}:")?;
+                self.incr_indent().fmt_symbol(f, symbol)
+            }
+            None => windentln!(self, f, "{label}: MISSING"),
+        }
+    }
+
+    fn fmt_ty(&self, f: &mut impl Write, ty: Type) -> fmt::Result {
+        if self.hir.types.contains_key(ty) {
+            write!(f, ": {}", ty.fmt(self.hir))
+        } else {
+            write!(f, ": !MISSING TYPE")
+        }
+    }
+
+    fn fmt_target(&self, f: &mut impl Write, target: Option<ReferenceTarget>) -> fmt::Result {
+        match target {
+            Some(ReferenceTarget::Symbol(target)) => match self.hir.symbols.get(target) {
+                Some(data) => {
+                    write!(f, " => {}", <&str>::from(&data.kind))?;
+
+                    if let Some(name) = data.name(self.hir) {
+                        write!(f, " {name}")?;
+                    }
+
+                    if let Some(range) = data.selection_or_text_range() {
+                        write!(f, " {range:?}")?;
+                    }
+
+                    Ok(())
+                }
+                None => write!(f, " => !MISSING SYMBOL"),
+            },
+            Some(ReferenceTarget::Module(m)) => match self.hir.modules.get(m) {
+                Some(m) => write!(f, " => module {}", m.kind),
+                None => write!(f, " => !MISSING MODULE"),
+            },
+            None => write!(f, " => <unresolved>"),
+        }
+    }
+
+    fn fmt_symbol(&self, f: &mut impl Write, symbol: Symbol) -> fmt::Result {
+        let Some(data) = self.hir.symbols.get(symbol) else {
+            return windentln!(self, f, "!MISSING SYMBOL");
+        };
+
+        windent!(
+            self,
+            f,
+            "{export}{kind}",
+            export = if data.export { "export " } else { "" },
+            kind = <&str>::from(&data.kind)
+        )?;
+
+        match &data.kind {
+            SymbolKind::Fn(fn_data) => write!(f, " {}", fn_data.name)?,
+            SymbolKind::Op(op) => write!(f, " {}", op.name)?,
+            SymbolKind::Decl(decl) => write!(
+                f,
+                " {kind}{name}",
+                kind = if decl.is_param {
+                    "param "
+                } else if decl.is_const {
+                    "const "
+                } else {
+                    ""
+                },
+                name = decl.name
+            )?,
+            SymbolKind::Ref(r) => write!(f, " {}", r.name)?,
+            SymbolKind::Lit(lit) => write!(f, " {}", lit.value)?,
+            SymbolKind::Unary(op) => write!(f, " {}", op.lookup_text)?,
+            SymbolKind::Binary(op) => write!(f, " {}", op.lookup_text)?,
+            SymbolKind::Virtual(VirtualSymbol::Module(m)) => write!(f, " {}", m.name)?,
+            SymbolKind::Virtual(VirtualSymbol::Alias(a)) => write!(f, " {}", a.name)?,
+            _ => {}
+        }
+
+        if let Some(range) = data.text_range() {
+            write!(f, " {range:?}")?;
+        }
+
+        self.fmt_ty(f, data.ty)?;
+
+        match &data.kind {
+            SymbolKind::Ref(r) => self.fmt_target(f, r.target)?,
+            SymbolKind::Decl(d) if d.target.is_some() => self.fmt_target(f, d.target)?,
+            SymbolKind::Import(import) => match import.target {
+                Some(target) => self.fmt_target(f, Some(ReferenceTarget::Module(target)))?,
+                None => self.fmt_target(f, None)?,
+            },
+            SymbolKind::Virtual(VirtualSymbol::Proxy(p)) => {
+                self.fmt_target(f, Some(ReferenceTarget::Symbol(p.target)))?;
+            }
+            SymbolKind::Virtual(VirtualSymbol::Module(m)) => {
+                self.fmt_target(f, Some(ReferenceTarget::Module(m.module)))?;
+            }
+            SymbolKind::Virtual(VirtualSymbol::Alias(a)) => {
+                self.fmt_target(f, Some(ReferenceTarget::Symbol(a.target)))?;
+            }
+            _ => {}
+        }
+
+        writeln!(f)?;
+
+        let indented = self.incr_indent();
+
+        match &data.kind {
+            SymbolKind::Block(block) => indented.fmt_scope(f, "scope", block.scope)?,
+            SymbolKind::Fn(fn_data) => indented.fmt_scope(f, "scope", fn_data.scope)?,
+            SymbolKind::Decl(decl) => {
+                if let Some(value_scope) = decl.value_scope {
+                    indented.fmt_scope(f, "value", value_scope)?;
+                }
+            }
+            SymbolKind::Path(path) => {
+                for &segment in &path.segments {
+                    indented.fmt_symbol(f, segment)?;
+                }
+            }
+            SymbolKind::Lit(lit) => {
+                for &scope in &lit.interpolated_scopes {
+                    indented.fmt_scope(f, "template", scope)?;
+                }
+            }
+            SymbolKind::Unary(op) => indented.fmt_child(f, "rhs", op.rhs)?,
+            SymbolKind::Binary(op) => {
+                indented.fmt_child(f, "lhs", op.lhs)?;
+                indented.fmt_child(f, "rhs", op.rhs)?;
+            }
+            SymbolKind::Array(arr) => {
+                for &value in &arr.values {
+                    indented.fmt_symbol(f, value)?;
+                }
+            }
+            SymbolKind::Index(idx) => {
+                indented.fmt_child(f, "base", idx.base)?;
+                indented.fmt_child(f, "index", idx.index)?;
+            }
+            SymbolKind::Object(obj) => {
+                for (key, field) in &obj.fields {
+                    indented.fmt_child(f, key, field.value)?;
+                }
+            }
+            SymbolKind::Call(call) => {
+                indented.fmt_child(f, "lhs", call.lhs)?;
+                for &arg in &call.arguments {
+                    indented.fmt_child(f, "arg", Some(arg))?;
+                }
+            }
+            SymbolKind::Closure(closure) => indented.fmt_scope(f, "scope", closure.scope)?,
+            SymbolKind::If(if_sym) => {
+                for (condition, branch) in &if_sym.branches {
+                    if let Some(condition) = condition {
+                        indented.fmt_child(f, "if", Some(*condition))?;
+                    }
+                    indented.fmt_scope(f, "then", *branch)?;
+                }
+            }
+            SymbolKind::Loop(l) => indented.fmt_scope(f, "scope", l.scope)?,
+            SymbolKind::For(fr) => indented.fmt_scope(f, "scope", fr.scope)?,
+            SymbolKind::While(whl) => {
+                indented.fmt_child(f, "condition", whl.condition)?;
+                indented.fmt_scope(f, "scope", whl.scope)?;
+            }
+            SymbolKind::Break(br) => {
+                if br.expr.is_some() {
+                    indented.fmt_child(f, "value", br.expr)?;
+                }
+            }
+            SymbolKind::Return(ret) => {
+                if ret.expr.is_some() {
+                    indented.fmt_child(f, "value", ret.expr)?;
+                }
+            }
+            SymbolKind::Switch(switch) => {
+                indented.fmt_child(f, "target", switch.target)?;
+                for arm in &switch.arms {
+                    indented.fmt_child(f, "pattern", arm.pat_expr)?;
+                    if arm.condition_expr.is_some() {
+                        indented.fmt_child(f, "if", arm.condition_expr)?;
+                    }
+                    indented.fmt_child(f, "then", arm.value_expr)?;
+                }
+            }
+            SymbolKind::Export(exp) => indented.fmt_child(f, "target", exp.target)?,
+            SymbolKind::Try(t) => {
+                indented.fmt_scope(f, "try", t.try_scope)?;
+                indented.fmt_scope(f, "catch", t.catch_scope)?;
+            }
+            SymbolKind::Throw(t) => indented.fmt_child(f, "value", t.expr)?,
+            SymbolKind::Import(imp) => {
+                indented.fmt_child(f, "path", imp.expr)?;
+                if imp.alias.is_some() {
+                    indented.fmt_child(f, "alias", imp.alias)?;
+                }
+            }
+            SymbolKind::Op(_)
+            | SymbolKind::Ref(_)
+            | SymbolKind::Continue(_)
+            | SymbolKind::Discard(_)
+            | SymbolKind::TypeDecl(_)
+            | SymbolKind::Virtual(_) => {}
+        }
+
+        Ok(())
+    }
+}
@@ -1,22 +1,25 @@
 mod add;
+mod diagnostics;
 mod errors;
 mod query;
 mod remove;
 mod resolve;
 
 use core::ops;
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use crate::{
-    module::{ModuleData, ModuleResolver, DefaultModuleResolver},
+    eval::NumericConfig,
+    module::{DefaultModuleResolver, ExternalResolver, ModuleData, ModuleResolver, PRELUDE_URL},
     scope::ScopeData,
     source::{Source, SourceData},
     symbol::*,
-    ty::{Type, TypeData},
-    Module, Scope,
+    ty::{Type, TypeData, TypeKey},
+    HashMap, HashSet, Module, Scope,
 };
 
 use rhai_rowan::syntax::SyntaxNode;
+use serde::{Deserialize, Serialize};
 use slotmap::{Key, SlotMap};
 use url::Url;
 
@@ -29,10 +32,42 @@ pub struct Hir {
     pub(crate) symbols: SlotMap<Symbol, SymbolData>,
     pub(crate) sources: SlotMap<Source, SourceData>,
     pub(crate) types: SlotMap<Type, TypeData>,
+    /// Inferred types by their sources and structures,
+    /// see [`Hir::intern_type`].
+    pub(crate) interned_types: HashMap<(Option<Source>, TypeKey), Type>,
     pub(crate) builtin_types: BuiltinTypes,
-    pub(crate) module_resolver: Arc<dyn ModuleResolver>
+    pub(crate) module_resolver: Arc<dyn ModuleResolver>,
+    pub(crate) external_resolver: Option<Arc<dyn ExternalResolver>>,
+    /// The functions that the external resolver does not know.
+    pub(crate) missing_external_fns: HashSet<(String, usize)>,
+    pub(crate) max_union_size: usize,
+    pub(crate) features: Option<BTreeSet<String>>,
+    pub(crate) flow_typing: bool,
+    pub(crate) numeric: NumericConfig,
+    pub(crate) implicit_globals: bool,
+    pub(crate) entry_module: Option<Url>,
+    pub(crate) public_modules: Vec<Url>,
+    /// The number of inferred symbol types, see [`Hir::type_inferences`].
+    #[cfg(feature = "testing")]
+    pub(crate) type_inferences: usize,
+    /// The receivers of the functions and closures that bind `this`,
+    /// only kept while the types are resolved.
+    pub(crate) binder_receivers: HashMap<Symbol, Vec<Symbol>>,
 }
 
+/// The number of items in a HIR, see [`Hir::stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HirStats {
+    pub sources: usize,
+    pub modules: usize,
+    pub scopes: usize,
+    pub symbols: usize,
+    pub types: usize,
+}
+
+/// The default maximum number of members of an inferred union type.
+pub const DEFAULT_MAX_UNION_SIZE: usize = 16;
+
 impl Default for Hir {
     fn default() -> Self {
         let mut this = Self {
@@ -43,8 +78,21 @@ impl Default for Hir {
             symbols: Default::default(),
             sources: Default::default(),
             types: Default::default(),
+            interned_types: Default::default(),
             builtin_types: BuiltinTypes::uninit(),
-            module_resolver: Arc::new(DefaultModuleResolver)
+            module_resolver: Arc::new(DefaultModuleResolver),
+            external_resolver: None,
+            missing_external_fns: HashSet::default(),
+            max_union_size: DEFAULT_MAX_UNION_SIZE,
+            features: None,
+            flow_typing: false,
+            numeric: NumericConfig::default(),
+            implicit_globals: false,
+            entry_module: None,
+            public_modules: Vec::new(),
+            #[cfg(feature = "testing")]
+            type_inferences: 0,
+            binder_receivers: HashMap::default(),
         };
         this.prepare();
         this
@@ -62,18 +110,188 @@ impl Hir {
     pub fn set_import_resolver(&mut self, resolver: impl ModuleResolver + 'static) {
         self.module_resolver = Arc::new(resolver);
     }
+
+    /// Set the resolver of the functions that the host registers with
+    /// its Rhai engine, calls of functions that are not declared otherwise
+    /// are looked up with it before they are reported as unresolved.
+    ///
+    /// The resolver is used when references are resolved afterwards,
+    /// definitions from a previous resolver are kept.
+    pub fn set_external_resolver(&mut self, resolver: impl ExternalResolver + 'static) {
+        self.external_resolver = Some(Arc::new(resolver));
+        self.missing_external_fns.clear();
+    }
+
+    /// Set the maximum number of members of inferred union types.
+    ///
+    /// Larger unions are widened to a single type that covers all members,
+    /// this keeps the inferred types of deeply branching code small.
+    ///
+    /// The default is [`DEFAULT_MAX_UNION_SIZE`], the new limit
+    /// only applies to types inferred afterwards.
+    pub fn set_max_union_size(&mut self, size: usize) {
+        self.max_union_size = size;
+    }
+
+    /// Set the active features for `// #[cfg(...)]` directives,
+    /// statements whose directives do not hold are left out of the HIR.
+    ///
+    /// With `None` (the default) directives are ignored
+    /// and every statement is included.
+    ///
+    /// The features only apply to sources added afterwards,
+    /// see [`crate::cfg`] for the syntax of the directives.
+    pub fn set_features(&mut self, features: Option<BTreeSet<String>>) {
+        self.features = features;
+    }
+
+    /// The active features, see [`Hir::set_features`].
+    #[must_use]
+    pub fn features(&self) -> Option<&BTreeSet<String>> {
+        self.features.as_ref()
+    }
+
+    /// Enable or disable flow typing (disabled by default).
+    ///
+    /// With flow typing references to a variable have the type of
+    /// the last value assigned to it before the reference instead of
+    /// the type of the declaration, e.g. `x` is a string after
+    /// `let x = 1; x = "hello";`. If there are assignments in branches,
+    /// the type is the union of the types the variable can have.
+    ///
+    /// The setting only applies to types inferred afterwards.
+    pub fn set_flow_typing(&mut self, enabled: bool) {
+        self.flow_typing = enabled;
+    }
+
+    /// Whether flow typing is enabled, see [`Hir::set_flow_typing`].
+    #[must_use]
+    pub fn flow_typing(&self) -> bool {
+        self.flow_typing
+    }
+
+    /// Set the numeric types of the Rhai build (64-bit integers
+    /// and floats by default).
+    ///
+    /// Integer and float literals outside of the range of the configured
+    /// types are reported as invalid, and so are all float literals with
+    /// `no_float`. The types are also used for the results of `type_of`
+    /// and when formatting types.
+    ///
+    /// The setting only applies to sources added afterwards.
+    pub fn set_numeric_config(&mut self, config: NumericConfig) {
+        self.numeric = config;
+    }
+
+    /// The numeric types, see [`Hir::set_numeric_config`].
+    #[must_use]
+    pub fn numeric_config(&self) -> NumericConfig {
+        self.numeric
+    }
+
+    /// Use 32-bit integers like Rhai with the `only_i32` feature
+    /// (disabled by default), see [`Hir::set_numeric_config`].
+    pub fn set_only_i32(&mut self, enabled: bool) {
+        self.numeric.only_i32 = enabled;
+    }
+
+    /// Whether integers are 32-bit, see [`Hir::set_only_i32`].
+    #[must_use]
+    pub fn only_i32(&self) -> bool {
+        self.numeric.only_i32
+    }
+
+    /// Declare variables that are assigned to without being declared
+    /// at the script level (disabled by default), like Rhai engines
+    /// that create such variables instead of failing.
+    ///
+    /// The first assignment declares the variable for the rest of the script,
+    /// without the setting these are unresolved references.
+    ///
+    /// The setting only applies to references resolved afterwards.
+    pub fn set_implicit_globals(&mut self, enabled: bool) {
+        self.implicit_globals = enabled;
+    }
+
+    /// Whether assignments declare variables, see [`Hir::set_implicit_globals`].
+    #[must_use]
+    pub fn implicit_globals(&self) -> bool {
+        self.implicit_globals
+    }
+
+    /// Treat the module with the given URL and the modules it imports
+    /// (directly or through other imports) as a single program.
+    ///
+    /// References in these modules that are not visible in their
+    /// own module resolve to symbols exported by the other modules
+    /// of the program, so they can be used without paths.
+    /// This is not how Rhai resolves references, so it is disabled
+    /// by default, see [`Hir::program_symbols`].
+    ///
+    /// The setting only applies to references resolved afterwards.
+    pub fn set_entry_module(&mut self, url: Option<Url>) {
+        self.entry_module = url;
+    }
+
+    /// The entry module of the program, see [`Hir::set_entry_module`].
+    #[must_use]
+    pub fn entry_module(&self) -> Option<&Url> {
+        self.entry_module.as_ref()
+    }
+
+    /// Set the modules with exports that are used outside of the
+    /// workspace, e.g. the entry points of a library.
+    ///
+    /// The exports of these modules and of the entry module
+    /// are never reported as unused, see [`Hir::unused_exports`].
+    pub fn set_public_modules(&mut self, urls: Vec<Url>) {
+        self.public_modules = urls;
+    }
+
+    /// The public modules, see [`Hir::set_public_modules`].
+    #[must_use]
+    pub fn public_modules(&self) -> &[Url] {
+        &self.public_modules
+    }
 }
 
 impl Hir {
+    /// Remove all sources along with their modules, scopes,
+    /// symbols and types, the prelude is kept if it was added.
+    ///
+    /// Keys from before clearing are not reused,
+    /// so they do not point to any of the new items.
+    #[allow(clippy::missing_panics_doc)]
     pub fn clear(&mut self) {
+        let has_prelude = self.source_by_url(&PRELUDE_URL.parse().unwrap()).is_some();
+
         self.symbols.clear();
         self.scopes.clear();
         self.modules.clear();
         self.sources.clear();
         self.types.clear();
+        self.interned_types.clear();
         self.builtin_types = BuiltinTypes::uninit();
         self.static_module = Module::null();
+        self.virtual_source = Source::null();
         self.prepare();
+
+        if has_prelude {
+            self.add_prelude();
+        }
+    }
+
+    /// The number of items in the HIR, mostly useful
+    /// for debugging memory usage.
+    #[must_use]
+    pub fn stats(&self) -> HirStats {
+        HirStats {
+            sources: self.sources.len(),
+            modules: self.modules.len(),
+            scopes: self.scopes.len(),
+            symbols: self.symbols.len(),
+            types: self.types.len(),
+        }
     }
 
     #[must_use]
@@ -98,6 +316,11 @@ impl Hir {
         self.scopes.iter()
     }
 
+    #[inline]
+    pub fn types(&self) -> impl Iterator<Item = (Type, &TypeData)> {
+        self.types.iter()
+    }
+
     #[must_use]
     #[inline]
     pub const fn static_module(&self) -> Module {
@@ -226,12 +449,16 @@ impl ops::Index<Type> for Hir {
 /// but always exist in the HIR and cannot be removed.
 ///
 /// This struct keeps track of their keys.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BuiltinTypes {
     pub module: Type,
     pub int: Type,
     pub float: Type,
     pub bool: Type,
+    /// The type of `true`.
+    pub bool_true: Type,
+    /// The type of `false`.
+    pub bool_false: Type,
     pub char: Type,
     pub string: Type,
     pub timestamp: Type,
@@ -247,6 +474,8 @@ impl BuiltinTypes {
             int: Default::default(),
             float: Default::default(),
             bool: Default::default(),
+            bool_true: Default::default(),
+            bool_false: Default::default(),
             char: Default::default(),
             string: Default::default(),
             timestamp: Default::default(),
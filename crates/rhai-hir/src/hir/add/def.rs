@@ -243,7 +243,9 @@ impl Hir {
                     }
                 }
 
-                let ret_ty = expr.ret_ty().map_or(self.builtin_types.unknown, |t| {
+                // Functions without a return type return nothing,
+                // `-> ?` is an unknown return type.
+                let ret_ty = expr.ret_ty().map_or(self.builtin_types.void, |t| {
                     self.add_type(source, None, &t)
                 });
 
@@ -261,6 +263,7 @@ impl Hir {
                             .ident_token()
                             .map(|s| s.text().to_string())
                             .unwrap_or_default(),
+                        tags: DocTags::parse(&docs),
                         docs,
                         scope: fn_scope,
                         getter: expr.has_kw_get(),
@@ -427,7 +430,7 @@ impl Hir {
                         ty: self.builtin_types.unknown,
                     });
 
-                    scope.add_symbol(self, symbol, false);
+                    scope.add_symbol(self, symbol, true);
                 }
             }
         }
@@ -459,7 +462,8 @@ impl Hir {
                     Some(t) => match t.kind() {
                         SyntaxKind::LIT_INT => self.builtin_types.int,
                         SyntaxKind::LIT_FLOAT => self.builtin_types.float,
-                        SyntaxKind::LIT_BOOL => self.builtin_types.bool,
+                        SyntaxKind::LIT_BOOL if t.text() == "true" => self.builtin_types.bool_true,
+                        SyntaxKind::LIT_BOOL => self.builtin_types.bool_false,
                         SyntaxKind::LIT_STR => self.builtin_types.string,
                         SyntaxKind::LIT_CHAR => self.builtin_types.char,
                         _ => self.builtin_types.unknown,
@@ -479,7 +483,9 @@ impl Hir {
                     .fields()
                     .map(|field| {
                         let name = if let Some(lit) = field.name_lit() {
-                            value_of_lit(lit).to_string()
+                            value_of_lit(lit, self.numeric, false)
+                                .unwrap_or_default()
+                                .to_string()
                         } else if let Some(ident) = field.name_ident() {
                             ident.text().to_string()
                         } else {
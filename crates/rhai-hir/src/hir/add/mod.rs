@@ -1,15 +1,16 @@
 use super::*;
 use crate::{
-    eval::Value,
-    module::{ModuleKind, STATIC_URL_SCHEME},
+    eval::{InvalidLiteral, NumericConfig, Value},
+    module::{ModuleKind, PRELUDE, PRELUDE_URL, STATIC_URL_SCHEME},
     scope::ScopeParent,
     source::SourceKind,
     TypeKind,
 };
 use rhai_rowan::{
-    ast::{AstNode, Lit, Rhai, RhaiDef},
+    ast::{AstNode, Lit, LitStrTemplateSegment, Rhai, RhaiDef},
+    parser::Parser,
     syntax::SyntaxKind,
-    util::unescape,
+    util::{unescape, EscapeError},
     TextRange, TextSize,
 };
 
@@ -42,6 +43,19 @@ impl Hir {
             self.add_def(source, &def);
         }
     }
+
+    /// Add the definitions of Rhai's built-in functions to the static module,
+    /// so that references to them resolve like references to any other function.
+    ///
+    /// The prelude is a regular definition source (see [`PRELUDE`]),
+    /// any number of additional static definitions can be added alongside it.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn add_prelude(&mut self) {
+        self.add_source(
+            &PRELUDE_URL.parse().unwrap(),
+            &Parser::new(PRELUDE).parse_def().into_syntax(),
+        );
+    }
 }
 
 impl Hir {
@@ -93,6 +107,14 @@ impl Hir {
                 kind: TypeKind::Bool,
                 ..TypeData::default()
             }),
+            bool_true: self.types.insert(TypeData {
+                kind: TypeKind::LiteralBool(true),
+                ..TypeData::default()
+            }),
+            bool_false: self.types.insert(TypeData {
+                kind: TypeKind::LiteralBool(false),
+                ..TypeData::default()
+            }),
             char: self.types.insert(TypeData {
                 kind: TypeKind::Char,
                 ..TypeData::default()
@@ -275,64 +297,140 @@ impl AddContext {
     }
 }
 
+/// Decode the value of the literal, numbers are limited
+/// to the ranges of the configured numeric types.
+///
+/// Whether the literal is `negated` by a unary minus matters
+/// for the range of integers, see [`int_of_lit`].
+///
+/// The value of a template literal with interpolations
+/// is not known, an empty string is used instead.
 #[allow(clippy::needless_pass_by_value)]
-fn value_of_lit(lit: Lit) -> Value {
+fn value_of_lit(lit: Lit, numeric: NumericConfig, negated: bool) -> Result<Value, InvalidLiteral> {
     if let Some(lit) = lit.lit_token() {
         match lit.kind() {
-            SyntaxKind::LIT_INT => lit
-                .text()
-                .parse::<i64>()
-                .map(Value::Int)
-                .unwrap_or(Value::Unknown),
-            SyntaxKind::LIT_FLOAT => lit
-                .text()
-                .parse::<f64>()
-                .map(Value::Float)
-                .unwrap_or(Value::Unknown),
-            SyntaxKind::LIT_BOOL => lit
+            SyntaxKind::LIT_INT => {
+                int_of_lit(lit.text(), numeric.only_i32, negated).map(Value::Int)
+            }
+            SyntaxKind::LIT_FLOAT => float_of_lit(lit.text(), numeric).map(Value::Float),
+            SyntaxKind::LIT_BOOL => Ok(lit
                 .text()
                 .parse::<bool>()
-                .map(Value::Bool)
-                .unwrap_or(Value::Unknown),
+                .map_or(Value::Unknown, Value::Bool)),
             SyntaxKind::LIT_STR => {
-                let mut text = lit.text();
+                let text = lit.text();
 
                 if text.starts_with('"') {
-                    text = text
-                        .strip_prefix('"')
-                        .unwrap_or(text)
-                        .strip_suffix('"')
-                        .unwrap_or(text);
-
-                    Value::String(unescape(text, '"').0)
+                    unescaped(strip_quotes(text, '"'), '"').map(Value::String)
                 } else {
-                    text = text
-                        .strip_prefix('`')
-                        .unwrap_or(text)
-                        .strip_suffix('`')
-                        .unwrap_or(text);
-                    Value::String(unescape(text, '`').0)
+                    unescaped(strip_quotes(text, '`'), '`').map(Value::String)
                 }
             }
             SyntaxKind::LIT_CHAR => {
-                let mut text = lit.text();
-                text = text
-                    .strip_prefix('\'')
-                    .unwrap_or(text)
-                    .strip_suffix('\'')
-                    .unwrap_or(text);
-
-                Value::Char(
-                    // FIXME: this allocates a string.
-                    unescape(text, '\'').0.chars().next().unwrap_or('💩'),
-                )
+                let text = unescaped(strip_quotes(lit.text(), '\''), '\'')?;
+                let mut chars = text.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Value::Char(c)),
+                    _ => Err(InvalidLiteral::InvalidChar),
+                }
+            }
+            _ => Ok(Value::Unknown),
+        }
+    } else if let Some(template) = lit.lit_str_template() {
+        // The content of template literals is only known
+        // if there are no code interpolations.
+        let mut text = String::new();
+
+        for segment in template.segments() {
+            match segment {
+                LitStrTemplateSegment::LitStr(lit_str) => text += lit_str.text(),
+                LitStrTemplateSegment::Interpolation(_) => return Ok(Value::String(String::new())),
+            }
+        }
+
+        unescaped(strip_quotes(&text, '`'), '`').map(Value::String)
+    } else {
+        Ok(Value::String(String::new()))
+    }
+}
+
+/// Integers with a radix are parsed as unsigned integers,
+/// so that e.g. `0xFFFFFFFFFFFFFFFF` is `-1` like in Rhai.
+///
+/// Negated decimal integers are checked along with the sign,
+/// so that e.g. `-9223372036854775808` is valid. The sign belongs
+/// to the unary operator, so the value is still positive, except for
+/// the minimum itself which has no positive counterpart.
+#[allow(clippy::cast_possible_wrap)]
+fn int_of_lit(text: &str, only_i32: bool, negated: bool) -> Result<i64, InvalidLiteral> {
+    let text = text.replace('_', "");
+
+    let radix = match text.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => {
+            let signed = if negated { format!("-{text}") } else { text };
+
+            let value = if only_i32 {
+                signed.parse::<i32>().map(i64::from)
+            } else {
+                signed.parse::<i64>()
             }
-            _ => Value::Unknown,
+            .map_err(|_| InvalidLiteral::IntegerOverflow)?;
+
+            return Ok(if negated {
+                value.checked_neg().unwrap_or(value)
+            } else {
+                value
+            });
         }
+    };
+
+    if only_i32 {
+        u32::from_str_radix(&text[2..], radix).map(|v| i64::from(v as i32))
     } else {
-        // It's a string template literal
-        // FIXME: we know its content if it has
-        // no code interpolations.
-        Value::String(String::new())
+        u64::from_str_radix(&text[2..], radix).map(|v| v as i64)
+    }
+    .map_err(|_| InvalidLiteral::IntegerOverflow)
+}
+
+/// Floats are parsed with the configured precision,
+/// but stored as `f64` regardless.
+fn float_of_lit(text: &str, numeric: NumericConfig) -> Result<f64, InvalidLiteral> {
+    if numeric.no_float {
+        return Err(InvalidLiteral::FloatNotSupported);
+    }
+
+    let text = text.replace('_', "");
+
+    let value = if numeric.f32_float {
+        text.parse::<f32>().map(f64::from)
+    } else {
+        text.parse::<f64>()
+    }
+    .map_err(|_| InvalidLiteral::InvalidFloat)?;
+
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(InvalidLiteral::FloatOverflow)
+    }
+}
+
+fn strip_quotes(text: &str, quote: char) -> &str {
+    let text = text.strip_prefix(quote).unwrap_or(text);
+    text.strip_suffix(quote).unwrap_or(text)
+}
+
+fn unescaped(text: &str, termination_char: char) -> Result<String, InvalidLiteral> {
+    let (value, errors) = unescape(text, termination_char);
+
+    match errors.into_iter().next() {
+        Some(EscapeError::MalformedEscapeSequence(sequence, _)) => {
+            Err(InvalidLiteral::MalformedEscapeSequence(sequence))
+        }
+        None => Ok(value),
     }
 }
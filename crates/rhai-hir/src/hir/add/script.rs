@@ -1,6 +1,6 @@
-use crate::{eval::Value, source::SourceInfo};
+use crate::{cfg, source::SourceInfo, IndexMap};
 use rhai_rowan::{
-    ast::{ExportTarget, Expr, Item, Rhai, Stmt},
+    ast::{ExportTarget, Expr, ExprUnary, Item, Pat, Rhai, Stmt},
     parser::Parser,
     syntax::{SyntaxKind, SyntaxToken},
     TextSize,
@@ -35,9 +35,16 @@ impl Hir {
         can_export: bool,
         statements: impl Iterator<Item = Stmt>,
     ) {
+        let mut tail = None;
+
         for statement in statements {
-            self.add_statement(source, scope, can_export, statement);
+            let has_semicolon = statement.has_semicolon();
+            tail = self
+                .add_statement(source, scope, can_export, statement)
+                .filter(|_| !has_semicolon);
         }
+
+        self.scope_mut(scope).tail = tail;
     }
 
     #[tracing::instrument(skip(self))]
@@ -48,6 +55,12 @@ impl Hir {
         can_export: bool,
         stmt: Stmt,
     ) -> Option<Symbol> {
+        if let Some(features) = &self.features {
+            if !cfg::is_statement_active(&stmt.syntax(), features) {
+                return None;
+            }
+        }
+
         stmt.item().and_then(|item| {
             item.expr()
                 .and_then(|expr| self.add_expression(source, scope, can_export, expr))
@@ -122,6 +135,71 @@ impl Hir {
             symbol
         }
 
+        /// `let` with a destructuring pattern
+        fn add_destructuring_decls(
+            source: Source,
+            hir: &mut Hir,
+            scope: Scope,
+            value: Option<Expr>,
+            pat: &Pat,
+            syntax: &SyntaxNode,
+            export: bool,
+            unknown_type: Type,
+        ) -> Option<Symbol> {
+            let bindings: Vec<_> = match pat {
+                Pat::Object(obj) => obj
+                    .fields()
+                    .filter_map(|field| {
+                        Some((
+                            field.binding()?,
+                            Destructure::Field(field.property()?.text().to_string()),
+                        ))
+                    })
+                    .collect(),
+                _ => pat
+                    .idents()
+                    .enumerate()
+                    .map(|(idx, ident)| (ident, Destructure::Index(idx)))
+                    .collect(),
+            };
+
+            // The value is only added once and owned by the first declaration,
+            // the rest of the declarations refer to the same value.
+            let mut value = value;
+            let mut first: Option<Symbol> = None;
+
+            for (ident, destructure) in bindings {
+                let symbol = add_decl(
+                    source,
+                    hir,
+                    scope,
+                    value.take(),
+                    Some(ident),
+                    syntax,
+                    false,
+                    export,
+                    unknown_type,
+                );
+
+                let first_value = first.and_then(|first| hir[first].kind.as_decl()?.value);
+
+                if let Some(decl) = hir.symbol_mut(symbol).kind.as_decl_mut() {
+                    decl.is_pat = true;
+                    decl.destructure = Some(destructure);
+
+                    if first_value.is_some() {
+                        decl.value = first_value;
+                    }
+                }
+
+                if first.is_none() {
+                    first = Some(symbol);
+                }
+            }
+
+            first
+        }
+
         match expr {
             Expr::Ident(expr) => {
                 let symbol = self.add_symbol(SymbolData {
@@ -201,6 +279,29 @@ impl Hir {
                 Some(sym)
             }
             Expr::Lit(expr) => {
+                let negated = expr
+                    .syntax()
+                    .parent()
+                    .and_then(|expr| expr.parent())
+                    .and_then(ExprUnary::cast)
+                    .and_then(|unary| unary.op_token())
+                    .is_some_and(|op| op.kind() == SyntaxKind::OP_SUB);
+
+                let lit = match expr
+                    .lit()
+                    .map(|lit| value_of_lit(lit, self.numeric, negated))
+                {
+                    Some(Ok(value)) => LitSymbol {
+                        value,
+                        ..LitSymbol::default()
+                    },
+                    Some(Err(invalid)) => LitSymbol {
+                        invalid: Some(invalid),
+                        ..LitSymbol::default()
+                    },
+                    None => LitSymbol::default(),
+                };
+
                 let symbol = self.add_symbol(SymbolData {
                     export: false,
                     parent_scope: Scope::default(),
@@ -209,10 +310,7 @@ impl Hir {
                         text_range: expr.syntax().text_range().into(),
                         selection_text_range: None,
                     },
-                    kind: SymbolKind::Lit(LitSymbol {
-                        value: expr.lit().map_or(Value::Unknown, value_of_lit),
-                        interpolated_scopes: Vec::default(),
-                    }),
+                    kind: SymbolKind::Lit(lit),
                     ty: self.builtin_types.unknown,
                 });
 
@@ -249,18 +347,30 @@ impl Hir {
                 Some(symbol)
             }
             // `let` and `const` values have a separate scope created for them
-            Expr::Let(expr) => add_decl(
-                source,
-                self,
-                scope,
-                expr.expr(),
-                expr.ident_token(),
-                &expr.syntax(),
-                false,
-                can_export,
-                self.builtin_types.unknown,
-            )
-            .into(),
+            Expr::Let(expr) => match expr.pat() {
+                Some(pat) => add_destructuring_decls(
+                    source,
+                    self,
+                    scope,
+                    expr.expr(),
+                    &pat,
+                    &expr.syntax(),
+                    can_export,
+                    self.builtin_types.unknown,
+                ),
+                None => add_decl(
+                    source,
+                    self,
+                    scope,
+                    expr.expr(),
+                    expr.ident_token(),
+                    &expr.syntax(),
+                    false,
+                    can_export,
+                    self.builtin_types.unknown,
+                )
+                .into(),
+            },
             Expr::Const(expr) => add_decl(
                 source,
                 self,
@@ -320,7 +430,9 @@ impl Hir {
                             .map(|t| t.text().trim().to_string())
                             .unwrap_or_default(),
                         op: expr.op_token().map(|t| t.kind()),
+                        op_range: expr.op_token().map(|t| t.text_range()),
                         rhs,
+                        overload: None,
                     }),
                     ty: self.builtin_types.unknown,
                 });
@@ -357,7 +469,10 @@ impl Hir {
                     }
                 });
 
-                if let Some(BinaryOpKind::Regular(SyntaxKind::PUNCT_DOT)) = op {
+                if let Some(BinaryOpKind::Regular(
+                    SyntaxKind::PUNCT_DOT | SyntaxKind::OP_NULL_ACCESS,
+                )) = op
+                {
                     if let Some(rhs) = rhs {
                         if let Some(ref_rhs) = self.symbol_mut(rhs).kind.as_reference_mut() {
                             ref_rhs.field_access = true;
@@ -381,7 +496,9 @@ impl Hir {
                             .unwrap_or_default(),
                         lhs,
                         op,
+                        op_range: expr.op_token().map(|t| t.text_range()),
                         rhs,
+                        overload: None,
                     }),
                     ty: self.builtin_types.unknown,
                 });
@@ -441,6 +558,35 @@ impl Hir {
                 Some(symbol)
             }
             Expr::Object(expr) => {
+                let mut fields: IndexMap<String, ObjectField> = IndexMap::default();
+                let mut overwritten_fields = Vec::new();
+
+                for field in expr.fields() {
+                    let (Some(name), Some(expr)) = (field.property(), field.expr()) else {
+                        continue;
+                    };
+
+                    let field = ObjectField {
+                        property_name: name.text().to_string(),
+                        property_syntax: SourceInfo {
+                            source: Some(source),
+                            text_range: name.text_range().into(),
+                            selection_text_range: None,
+                        },
+                        field_syntax: SourceInfo {
+                            source: Some(source),
+                            text_range: field.syntax().text_range().into(),
+                            selection_text_range: None,
+                        },
+                        value: self.add_expression(source, scope, false, expr),
+                    };
+
+                    // The last field wins at runtime.
+                    if let Some(overwritten) = fields.insert(name.text().to_string(), field) {
+                        overwritten_fields.push(overwritten);
+                    }
+                }
+
                 let symbol_data = SymbolData {
                     export: false,
                     parent_scope: Scope::default(),
@@ -450,29 +596,8 @@ impl Hir {
                         selection_text_range: None,
                     },
                     kind: SymbolKind::Object(ObjectSymbol {
-                        fields: expr
-                            .fields()
-                            .filter_map(|field| match (field.property(), field.expr()) {
-                                (Some(name), Some(expr)) => Some((
-                                    name.text().to_string(),
-                                    ObjectField {
-                                        property_name: name.text().to_string(),
-                                        property_syntax: SourceInfo {
-                                            source: Some(source),
-                                            text_range: name.text_range().into(),
-                                            selection_text_range: None,
-                                        },
-                                        field_syntax: SourceInfo {
-                                            source: Some(source),
-                                            text_range: field.syntax().text_range().into(),
-                                            selection_text_range: None,
-                                        },
-                                        value: self.add_expression(source, scope, false, expr),
-                                    },
-                                )),
-                                _ => None,
-                            })
-                            .collect(),
+                        fields,
+                        overwritten_fields,
                     }),
                     ty: self.builtin_types.unknown,
                 };
@@ -561,6 +686,7 @@ impl Hir {
                     kind: SymbolKind::Closure(ClosureSymbol {
                         scope: closure_scope,
                         expr: closure_expr_symbol,
+                        captures: Vec::new(),
                     }),
                     ty: self.builtin_types.unknown,
                 });
@@ -683,6 +809,8 @@ impl Hir {
                     ..ScopeData::default()
                 });
 
+                let mut bindings = Vec::new();
+
                 if let Some(pat) = expr.pat() {
                     for ident in pat.idents() {
                         let ident_symbol = self.add_symbol(SymbolData {
@@ -701,7 +829,8 @@ impl Hir {
                             })),
                             ty: self.builtin_types.unknown,
                         });
-                        scope.add_symbol(self, ident_symbol, false);
+                        for_scope.add_symbol(self, ident_symbol, false);
+                        bindings.push(ident_symbol);
                     }
                 }
 
@@ -721,6 +850,7 @@ impl Hir {
                         cursor: expr
                             .iterable()
                             .and_then(|expr| self.add_expression(source, scope, false, expr)),
+                        bindings,
                         scope: for_scope,
                     }),
                     ty: self.builtin_types.unknown,
@@ -817,7 +947,7 @@ impl Hir {
                         arm_list
                             .arms()
                             .map(|arm| {
-                                let condition = None;
+                                let mut condition = None;
                                 let mut left = None;
                                 let mut right = None;
 
@@ -840,7 +970,7 @@ impl Hir {
                                 }
 
                                 if let Some(expr) = arm.condition().and_then(|c| c.expr()) {
-                                    left = self.add_expression(source, scope, false, expr);
+                                    condition = self.add_expression(source, scope, false, expr);
                                 }
 
                                 if let Some(expr) = arm.pattern_expr() {
@@ -969,8 +1099,10 @@ impl Hir {
                             .ident_token()
                             .map(|s| s.text().to_string())
                             .unwrap_or_default(),
+                        tags: DocTags::parse(&docs),
                         docs,
                         scope: fn_scope,
+                        private: expr.kw_private_token().is_some(),
                         ..FnSymbol::default()
                     }),
                     ty: self.builtin_types.unknown,
@@ -1154,7 +1286,7 @@ impl Hir {
                             ty: self.builtin_types.unknown,
                         });
 
-                        scope.add_symbol(self, symbol, false);
+                        catch_scope.add_symbol(self, symbol, false);
                     }
                 }
 
@@ -0,0 +1,532 @@
+use std::cmp::Reverse;
+
+use rhai_rowan::{
+    parser::Parse,
+    util::{self, CommentKind, DelimiterError, Delimiters},
+    TextRange, TextSize,
+};
+use url::Url;
+
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticTag, DiagnosticsCache},
+    error::{EmptyBlockKind, Error, ErrorKind},
+    lint::{DiagnosticConfig, Lint, LintLevel},
+    source::Source,
+    symbol::SymbolKind,
+    HashSet, Hir, Module, Symbol,
+};
+
+impl Hir {
+    /// All diagnostics of the source with the given URL and its parse result,
+    /// ordered by their ranges, then by their levels (the most severe first)
+    /// and lints.
+    ///
+    /// The lints are only checked if there are no syntax errors,
+    /// as most of their results would be caused by the syntax errors.
+    #[must_use]
+    pub fn diagnostics_for(
+        &self,
+        url: &Url,
+        parse: &Parse,
+        config: &DiagnosticConfig,
+    ) -> Vec<Diagnostic> {
+        self.incremental_diagnostics_for(url, parse, config, &mut DiagnosticsCache::default(), None)
+    }
+
+    /// Same as [`Hir::diagnostics_for`], but the diagnostics of the bodies
+    /// of top-level functions are reused from the cache of the previous run
+    /// if the functions are not affected by the edit.
+    ///
+    /// The edit is the range of the previous text that was replaced and
+    /// the length of the new text. Without an edit, or if the signatures of
+    /// the top-level symbols of the module have changed, all diagnostics
+    /// are computed again. Changes of other modules are not tracked,
+    /// so there should be no edit after those.
+    ///
+    /// Lints of the functions themselves, e.g. whether they are used,
+    /// are always checked as they depend on the rest of the module.
+    #[must_use]
+    pub fn incremental_diagnostics_for(
+        &self,
+        url: &Url,
+        parse: &Parse,
+        config: &DiagnosticConfig,
+        cache: &mut DiagnosticsCache,
+        edit: Option<(TextRange, TextSize)>,
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics = parse
+            .reported_errors()
+            .into_iter()
+            .map(|error| Diagnostic {
+                range: error.range,
+                level: LintLevel::Error,
+                lint: None,
+                message: error.kind.to_string(),
+                related: Vec::new(),
+                tags: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        let previous = std::mem::take(cache);
+
+        if diagnostics.is_empty() {
+            if let Some(source) = self.source_by_url(url) {
+                diagnostics = self.lint_diagnostics(source, parse, config, &previous, cache, edit);
+                cache.url = Some(url.clone());
+                cache.config = Some(config.clone());
+            }
+        }
+
+        let todo_level = config.level(Lint::TodoComment);
+
+        if todo_level != LintLevel::Off {
+            diagnostics.extend(todo_comments(parse, todo_level));
+        }
+
+        let delimiter_level = config.level(Lint::UnmatchedDelimiter);
+
+        if delimiter_level != LintLevel::Off {
+            diagnostics.extend(unmatched_delimiters(parse, delimiter_level));
+        }
+
+        diagnostics.sort_by(|a, b| {
+            (a.range.start(), a.range.end(), Reverse(a.level), a.lint)
+                .cmp(&(b.range.start(), b.range.end(), Reverse(b.level), b.lint))
+                .then_with(|| a.message.cmp(&b.message))
+        });
+        diagnostics.dedup();
+
+        diagnostics
+    }
+
+    /// The diagnostics of the errors of the source, the diagnostics of
+    /// function bodies are taken from `previous` if possible and are
+    /// stored in `cache`.
+    fn lint_diagnostics(
+        &self,
+        source: Source,
+        parse: &Parse,
+        config: &DiagnosticConfig,
+        previous: &DiagnosticsCache,
+        cache: &mut DiagnosticsCache,
+        edit: Option<(TextRange, TextSize)>,
+    ) -> Vec<Diagnostic> {
+        // Blocks with comments are empty intentionally.
+        let comments = util::comments(&parse.clone_syntax())
+            .map(|comment| comment.range())
+            .collect::<Vec<_>>();
+
+        let module = self.module_by_source(source);
+
+        cache.environment = module
+            .map(|module| self.module_environment(module))
+            .unwrap_or_default();
+
+        let is_reusable = previous.url.as_ref() == self.sources.get(source).map(|s| &s.url)
+            && previous.config.as_ref() == Some(config)
+            && previous.environment == cache.environment;
+
+        let edit = edit.filter(|_| is_reusable);
+
+        let functions = module
+            .into_iter()
+            .flat_map(|module| self.scope_symbols(self[module].scope))
+            .filter_map(|symbol| {
+                let data = &self[symbol];
+                let f = data.kind.as_fn()?;
+                data.source
+                    .is(source)
+                    .then_some((data.text_range()?, f.scope))
+            })
+            .collect::<Vec<_>>();
+
+        let mut diagnostics = Vec::new();
+        let mut body_symbols = HashSet::default();
+
+        for (range, scope) in functions {
+            let body = self.descendant_symbols(scope).collect::<Vec<_>>();
+            body_symbols.extend(body.iter().copied());
+
+            let cached = edit.and_then(|(edited, new_len)| {
+                previous
+                    .functions
+                    .iter()
+                    .find_map(|(previous_range, diagnostics)| {
+                        (shifted_range(*previous_range, edited, new_len)? == range).then(|| {
+                            diagnostics
+                                .iter()
+                                .cloned()
+                                .map(|diagnostic| shifted_diagnostic(diagnostic, edited, new_len))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+            });
+
+            let body_diagnostics = cached.unwrap_or_else(|| {
+                self.errors_diagnostics(self.errors_for_symbols(body), &comments, config)
+            });
+
+            diagnostics.extend(body_diagnostics.iter().cloned());
+            cache.functions.push((range, body_diagnostics));
+        }
+
+        let rest = self
+            .symbols()
+            .filter(|(symbol, data)| data.source.is(source) && !body_symbols.contains(symbol))
+            .map(|(symbol, _)| symbol);
+
+        diagnostics.extend(self.errors_diagnostics(
+            self.errors_for_symbols(rest),
+            &comments,
+            config,
+        ));
+
+        diagnostics
+    }
+
+    fn errors_diagnostics(
+        &self,
+        errors: Vec<Error>,
+        comments: &[TextRange],
+        config: &DiagnosticConfig,
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (error, level) in config.apply(errors) {
+            if let ErrorKind::EmptyBlock { scope, .. } = error.kind {
+                let range = self[scope].source.text_range.unwrap_or_default();
+
+                if comments
+                    .iter()
+                    .any(|comment| range.contains_range(*comment))
+                {
+                    continue;
+                }
+            }
+
+            diagnostics.extend(self.error_diagnostic(&error, level, config));
+        }
+
+        diagnostics
+    }
+
+    /// The signatures of the top-level symbols of the module,
+    /// the bodies of the functions only depend on these.
+    fn module_environment(&self, module: Module) -> Vec<String> {
+        let mut environment = self
+            .scope_symbols(self[module].scope)
+            .filter_map(|symbol| {
+                let data = &self[symbol];
+                let ty = self.type_of(symbol).fmt(self).to_string();
+
+                match &data.kind {
+                    SymbolKind::Fn(f) => Some(format!(
+                        "fn {} {ty} export={} private={} deprecated={:?}",
+                        f.name, data.export, f.private, f.tags.deprecated
+                    )),
+                    SymbolKind::Decl(decl) => Some(format!(
+                        "decl {} {ty} export={} const={}",
+                        decl.name, data.export, decl.is_const
+                    )),
+                    SymbolKind::Import(import) => Some(format!(
+                        "import {:?} {:?}",
+                        import.import_path(self),
+                        import.alias.and_then(|alias| self[alias].name(self)),
+                    )),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // The symbols are not ordered by their positions.
+        environment.sort();
+        environment
+    }
+
+    fn error_diagnostic(
+        &self,
+        error: &Error,
+        level: LintLevel,
+        config: &DiagnosticConfig,
+    ) -> Option<Diagnostic> {
+        let selection = |symbol: Symbol| self[symbol].selection_or_text_range().unwrap_or_default();
+
+        let (range, related, tags) = match &error.kind {
+            ErrorKind::DuplicateFnParameter {
+                duplicate_symbol,
+                existing_symbol,
+            } => (
+                selection(*duplicate_symbol),
+                vec![(selection(*existing_symbol), "parameter with the same name")],
+                Vec::new(),
+            ),
+            ErrorKind::UnresolvedReference {
+                reference_symbol, ..
+            }
+            | ErrorKind::PrivateFunction {
+                reference_symbol, ..
+            } => (selection(*reference_symbol), Vec::new(), Vec::new()),
+            ErrorKind::UseBeforeDefinition {
+                reference_symbol,
+                declaration,
+            }
+            | ErrorKind::SelfReferentialDeclaration {
+                reference_symbol,
+                declaration,
+            } => (
+                selection(*reference_symbol),
+                vec![(selection(*declaration), "declared here")],
+                Vec::new(),
+            ),
+            ErrorKind::NestedFunction { function: symbol }
+            | ErrorKind::UnknownField { field: symbol, .. }
+            | ErrorKind::UnusedExport { symbol } => (selection(*symbol), Vec::new(), Vec::new()),
+            ErrorKind::UnresolvedImport { import, .. } => {
+                let path = self[*import]
+                    .kind
+                    .as_import()
+                    .and_then(|import| import.expr);
+
+                (
+                    path.map_or_else(|| selection(*import), selection),
+                    Vec::new(),
+                    Vec::new(),
+                )
+            }
+            ErrorKind::ClosureCaptureMutation {
+                reference_symbol,
+                declaration,
+                ..
+            } => (
+                selection(*reference_symbol),
+                vec![(selection(*declaration), "captured variable")],
+                Vec::new(),
+            ),
+            ErrorKind::ImportInLoop {
+                import,
+                loop_symbol,
+            } => (
+                selection(*import),
+                vec![(
+                    self[*loop_symbol].text_range().unwrap_or_default(),
+                    "the enclosing loop",
+                )],
+                Vec::new(),
+            ),
+            ErrorKind::MissingReturn {
+                function,
+                return_symbol,
+            } => (
+                selection(*function),
+                vec![(
+                    self[*return_symbol].text_range().unwrap_or_default(),
+                    "a value is returned here",
+                )],
+                Vec::new(),
+            ),
+            ErrorKind::InconsistentReturn {
+                value_return,
+                bare_return,
+                ..
+            } => (
+                self[*bare_return].text_range().unwrap_or_default(),
+                vec![(
+                    self[*value_return].text_range().unwrap_or_default(),
+                    "a value is returned here",
+                )],
+                Vec::new(),
+            ),
+            ErrorKind::UnusedFunction { function: symbol }
+            | ErrorKind::UnusedVariable {
+                declaration: symbol,
+            } => (
+                selection(*symbol),
+                Vec::new(),
+                vec![DiagnosticTag::Unnecessary],
+            ),
+            ErrorKind::DeprecatedFunction {
+                reference_symbol, ..
+            } => (
+                selection(*reference_symbol),
+                Vec::new(),
+                vec![DiagnosticTag::Deprecated],
+            ),
+            ErrorKind::DuplicateObjectField {
+                duplicate_range,
+                existing_range,
+                ..
+            } => (
+                *duplicate_range,
+                vec![(*existing_range, "first field with the same name")],
+                Vec::new(),
+            ),
+            ErrorKind::NonExhaustiveSwitch { switch, .. } => {
+                // Highlight the target, the whole switch is too noisy.
+                let range = self[*switch]
+                    .kind
+                    .as_switch()
+                    .and_then(|switch| switch.target)
+                    .and_then(|target| self[target].text_range())
+                    .or_else(|| self[*switch].text_range())
+                    .unwrap_or_default();
+
+                (range, Vec::new(), Vec::new())
+            }
+            // The prelude is not a document that related
+            // information could point to.
+            ErrorKind::ShadowedBuiltin { declaration, .. } => {
+                (selection(*declaration), Vec::new(), Vec::new())
+            }
+            ErrorKind::IncompatibleOperands { binary, .. } => (
+                self[*binary].text_range().unwrap_or_default(),
+                Vec::new(),
+                Vec::new(),
+            ),
+            ErrorKind::InvalidLiteral { literal, .. } => (
+                self[*literal].text_range().unwrap_or_default(),
+                Vec::new(),
+                Vec::new(),
+            ),
+            ErrorKind::EmptyBlock {
+                symbol,
+                scope,
+                kind,
+            } => {
+                let range = match kind {
+                    EmptyBlockKind::Else if config.allow_empty_else => return None,
+                    EmptyBlockKind::Loop if config.allow_empty_loop => return None,
+                    // The scope of a function includes its signature.
+                    EmptyBlockKind::Fn => selection(*symbol),
+                    _ => self[*scope].source.text_range.unwrap_or_default(),
+                };
+
+                (range, Vec::new(), Vec::new())
+            }
+            ErrorKind::ConstantCondition {
+                symbol,
+                condition,
+                value,
+            } => {
+                if *value && self[*symbol].kind.is_while() && !config.while_true {
+                    return None;
+                }
+
+                (
+                    self[*condition].text_range().unwrap_or_default(),
+                    Vec::new(),
+                    Vec::new(),
+                )
+            }
+        };
+
+        Some(Diagnostic {
+            range,
+            level,
+            lint: Some(error.kind.lint()),
+            message: error.to_string(),
+            related: related
+                .into_iter()
+                .map(|(range, message)| (range, message.to_string()))
+                .collect(),
+            tags,
+        })
+    }
+}
+
+/// Surface `// TODO` and `// FIXME` comments.
+fn todo_comments(parse: &Parse, level: LintLevel) -> Vec<Diagnostic> {
+    const MARKERS: &[&str] = &["TODO", "FIXME"];
+
+    util::comments(&parse.clone_syntax())
+        .filter(|comment| comment.kind() != CommentKind::Doc)
+        .filter_map(|comment| {
+            let content = comment.content();
+
+            let is_marked = MARKERS.iter().any(|marker| {
+                content.strip_prefix(marker).is_some_and(|rest| {
+                    !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                })
+            });
+
+            is_marked.then(|| Diagnostic {
+                range: comment.range(),
+                level,
+                lint: Some(Lint::TodoComment),
+                message: content.lines().next().unwrap_or(content).trim().into(),
+                related: Vec::new(),
+                tags: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Delimiters without a matching pair, the parser might
+/// recover from these in unexpected ways.
+fn unmatched_delimiters(parse: &Parse, level: LintLevel) -> Vec<Diagnostic> {
+    let syntax = parse.clone_syntax();
+    let text = syntax.text();
+    let delimiter = |range| text.slice(range).to_string();
+
+    Delimiters::new(&syntax)
+        .errors
+        .into_iter()
+        .map(|error| {
+            let (range, message, related) = match error {
+                DelimiterError::Unclosed { open } => (
+                    open,
+                    format!("unclosed delimiter `{}`", delimiter(open)),
+                    Vec::new(),
+                ),
+                DelimiterError::Unopened { close } => (
+                    close,
+                    format!("unexpected closing delimiter `{}`", delimiter(close)),
+                    Vec::new(),
+                ),
+                DelimiterError::Mismatched { open, close } => (
+                    close,
+                    format!("mismatched closing delimiter `{}`", delimiter(close)),
+                    vec![(open, String::from("the innermost open delimiter"))],
+                ),
+            };
+
+            Diagnostic {
+                range,
+                level,
+                lint: Some(Lint::UnmatchedDelimiter),
+                message,
+                related,
+                tags: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// The range after replacing `edited` with `new_len` bytes,
+/// `None` if the range overlaps the edited range.
+fn shifted_range(range: TextRange, edited: TextRange, new_len: TextSize) -> Option<TextRange> {
+    if range.end() <= edited.start() {
+        Some(range)
+    } else if range.start() >= edited.end() {
+        Some(range - edited.len() + new_len)
+    } else {
+        None
+    }
+}
+
+/// The diagnostic after the edit, the diagnostic
+/// must not overlap the edited range.
+fn shifted_diagnostic(
+    mut diagnostic: Diagnostic,
+    edited: TextRange,
+    new_len: TextSize,
+) -> Diagnostic {
+    let shift = |range: TextRange| shifted_range(range, edited, new_len).unwrap_or(range);
+
+    diagnostic.range = shift(diagnostic.range);
+
+    for (range, _) in &mut diagnostic.related {
+        *range = shift(*range);
+    }
+
+    diagnostic
+}
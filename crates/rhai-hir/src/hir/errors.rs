@@ -1,41 +1,137 @@
+use std::cmp::Ordering;
+
 use crate::{
-    error::{Error, ErrorKind},
+    error::{EmptyBlockKind, Error, ErrorKind},
+    eval::Value,
+    module::PRELUDE_URL,
     source::Source,
-    symbol::SymbolKind,
-    HashMap, Hir, Symbol,
+    symbol::{BinaryOpKind, BinarySymbol, SymbolKind},
+    ty::{Type, TypeKind},
+    HashMap, HashSet, Hir, Module, Scope, Symbol,
 };
+use rhai_rowan::syntax::SyntaxKind;
 
 impl Hir {
     #[must_use]
     pub fn errors(&self) -> Vec<Error> {
-        let mut errors = Vec::new();
-
-        for (symbol, _) in self.symbols() {
-            self.collect_errors_from_symbol(symbol, &mut errors);
-        }
-
-        errors
+        self.errors_for_symbols(self.symbols().map(|(symbol, _)| symbol))
     }
 
     #[must_use]
     pub fn errors_for_source(&self, source: Source) -> Vec<Error> {
+        self.errors_for_symbols(
+            self.symbols()
+                .filter(|(_, symbol_data)| symbol_data.source.source == Some(source))
+                .map(|(symbol, _)| symbol),
+        )
+    }
+
+    /// The errors of the given symbols only, errors of other symbols
+    /// are not included even if they are nested in the given symbols.
+    pub(crate) fn errors_for_symbols(
+        &self,
+        symbols: impl IntoIterator<Item = Symbol>,
+    ) -> Vec<Error> {
         let mut errors = Vec::new();
 
-        for (symbol, _) in self
-            .symbols()
-            .filter(|(_, symbol_data)| symbol_data.source.source == Some(source))
-        {
-            self.collect_errors_from_symbol(symbol, &mut errors);
+        // Reachability is computed once for every module
+        // with functions that are checked.
+        let mut live_functions = HashMap::new();
+
+        for symbol in symbols {
+            self.collect_errors_from_symbol(symbol, &mut live_functions, &mut errors);
         }
 
         errors
     }
 
-    fn collect_errors_from_symbol(&self, symbol: Symbol, errors: &mut Vec<Error>) {
+    fn collect_errors_from_symbol(
+        &self,
+        symbol: Symbol,
+        live_functions: &mut HashMap<Module, HashSet<Symbol>>,
+        errors: &mut Vec<Error>,
+    ) {
         if let Some(symbol_data) = self.symbol(symbol) {
+            if let Some(builtin) = self.shadowed_builtin(symbol) {
+                errors.push(Error {
+                    kind: ErrorKind::ShadowedBuiltin {
+                        declaration: symbol,
+                        builtin,
+                        name: self[builtin].name(self).unwrap_or_default().to_string(),
+                    },
+                });
+            }
+
+            if self.is_unused_export(symbol) {
+                errors.push(Error {
+                    kind: ErrorKind::UnusedExport { symbol },
+                });
+            }
+
             match &symbol_data.kind {
-                SymbolKind::Ref(r) => {
-                    if !r.field_access && r.target.is_none() && r.name != "this" {
+                SymbolKind::Ref(r) if !r.field_access && r.name != "this" => {
+                    if let Some(declaration) = self.initialized_declaration(symbol, &r.name) {
+                        let resolved_elsewhere = r
+                            .target
+                            .as_ref()
+                            .and_then(|target| target.as_symbol())
+                            .is_some_and(|&target| target != declaration);
+
+                        if !resolved_elsewhere {
+                            errors.push(Error {
+                                kind: ErrorKind::SelfReferentialDeclaration {
+                                    reference_symbol: symbol,
+                                    declaration,
+                                },
+                            });
+                            return;
+                        }
+                    }
+
+                    if let Some(target) = &r.target {
+                        if let Some(&declaration) = target.as_symbol() {
+                            if let Some(closure) = self.mutated_capture(symbol, declaration) {
+                                errors.push(Error {
+                                    kind: ErrorKind::ClosureCaptureMutation {
+                                        reference_symbol: symbol,
+                                        declaration,
+                                        closure,
+                                    },
+                                });
+                            }
+
+                            if let Some(reason) = self
+                                .symbol(declaration)
+                                .and_then(|data| data.kind.as_fn())
+                                .and_then(|f| f.tags.deprecated.clone())
+                            {
+                                errors.push(Error {
+                                    kind: ErrorKind::DeprecatedFunction {
+                                        reference_symbol: symbol,
+                                        function: declaration,
+                                        reason,
+                                    },
+                                });
+                            }
+                        }
+                        return;
+                    }
+
+                    if let Some(function) = self.private_path_target(symbol) {
+                        errors.push(Error {
+                            kind: ErrorKind::PrivateFunction {
+                                reference_symbol: symbol,
+                                function,
+                            },
+                        });
+                    } else if let Some(declaration) = self.later_declaration(symbol, &r.name) {
+                        errors.push(Error {
+                            kind: ErrorKind::UseBeforeDefinition {
+                                reference_symbol: symbol,
+                                declaration,
+                            },
+                        });
+                    } else {
                         errors.push(Error {
                             kind: ErrorKind::UnresolvedReference {
                                 reference_symbol: symbol,
@@ -49,15 +145,31 @@ impl Hir {
                         return;
                     }
 
-                    let top_level = self
+                    let module = self
                         .modules
                         .iter()
-                        .any(|(_, m)| m.scope == symbol_data.parent_scope);
+                        .find(|(_, m)| m.scope == symbol_data.parent_scope)
+                        .map(|(module, _)| module);
 
-                    if !top_level {
-                        errors.push(Error {
-                            kind: ErrorKind::NestedFunction { function: symbol },
-                        });
+                    match module {
+                        None => {
+                            errors.push(Error {
+                                kind: ErrorKind::NestedFunction { function: symbol },
+                            });
+                        }
+                        Some(module) if !symbol_data.export => {
+                            let is_live = live_functions
+                                .entry(module)
+                                .or_insert_with(|| self.live_functions(module))
+                                .contains(&symbol);
+
+                            if !is_live {
+                                errors.push(Error {
+                                    kind: ErrorKind::UnusedFunction { function: symbol },
+                                });
+                            }
+                        }
+                        Some(_) => {}
                     }
 
                     let mut param_names: HashMap<&str, Symbol> = HashMap::new();
@@ -82,11 +194,184 @@ impl Hir {
                             }
                         }
                     }
+
+                    if let Some(return_symbol) = self.value_return(symbol, f.scope) {
+                        if !self.scope_completes(f.scope, true) {
+                            errors.push(Error {
+                                kind: ErrorKind::MissingReturn {
+                                    function: symbol,
+                                    return_symbol,
+                                },
+                            });
+                        }
+
+                        if let Some(bare_return) = self.bare_return(symbol, f.scope) {
+                            errors.push(Error {
+                                kind: ErrorKind::InconsistentReturn {
+                                    function: symbol,
+                                    value_return: return_symbol,
+                                    bare_return,
+                                },
+                            });
+                        }
+                    }
+
+                    self.collect_empty_block(symbol, f.scope, EmptyBlockKind::Fn, &[], errors);
+                }
+                // Top-level declarations are exported and might be used by other modules.
+                SymbolKind::Decl(decl)
+                    if !decl.is_param
+                        && !decl.is_import
+                        && !symbol_data.export
+                        && !decl.name.starts_with('_')
+                        && decl.references.is_empty() =>
+                {
+                    let is_script = symbol_data
+                        .source
+                        .source
+                        .and_then(|source| self.sources.get(source))
+                        .is_some_and(|source| source.kind.is_script());
+
+                    if is_script {
+                        errors.push(Error {
+                            kind: ErrorKind::UnusedVariable {
+                                declaration: symbol,
+                            },
+                        });
+                    }
+                }
+                SymbolKind::Lit(lit) => {
+                    if let Some(reason) = lit.invalid.clone() {
+                        errors.push(Error {
+                            kind: ErrorKind::InvalidLiteral {
+                                literal: symbol,
+                                reason,
+                            },
+                        });
+                    }
+                }
+                SymbolKind::Binary(binary) => {
+                    if let Some((lhs_ty, rhs_ty)) = self.incompatible_operands(binary) {
+                        errors.push(Error {
+                            kind: ErrorKind::IncompatibleOperands {
+                                binary: symbol,
+                                op: binary.lookup_text.clone(),
+                                lhs_ty: self.non_literal_bool(lhs_ty).fmt(self).to_string(),
+                                rhs_ty: self.non_literal_bool(rhs_ty).fmt(self).to_string(),
+                            },
+                        });
+                    }
+
+                    if let Some((field, ty)) = self.unknown_field(binary) {
+                        errors.push(Error {
+                            kind: ErrorKind::UnknownField {
+                                field,
+                                name: self[field].name(self).unwrap_or_default().to_string(),
+                                ty: ty.fmt(self).to_string(),
+                            },
+                        });
+                    }
+                }
+                SymbolKind::If(if_symbol) => {
+                    for condition in if_symbol.branches.iter().filter_map(|(c, _)| *c) {
+                        self.collect_constant_condition(symbol, condition, errors);
+                    }
+
+                    for (condition, scope) in &if_symbol.branches {
+                        let kind = if condition.is_some() {
+                            EmptyBlockKind::If
+                        } else {
+                            EmptyBlockKind::Else
+                        };
+
+                        self.collect_empty_block(symbol, *scope, kind, &[], errors);
+                    }
+                }
+                SymbolKind::While(while_symbol) => {
+                    if let Some(condition) = while_symbol.condition {
+                        self.collect_constant_condition(symbol, condition, errors);
+                    }
+
+                    self.collect_empty_block(
+                        symbol,
+                        while_symbol.scope,
+                        EmptyBlockKind::Loop,
+                        &[],
+                        errors,
+                    );
+                }
+                SymbolKind::Loop(loop_symbol) => {
+                    self.collect_empty_block(
+                        symbol,
+                        loop_symbol.scope,
+                        EmptyBlockKind::Loop,
+                        &[],
+                        errors,
+                    );
+                }
+                SymbolKind::For(for_symbol) => {
+                    self.collect_empty_block(
+                        symbol,
+                        for_symbol.scope,
+                        EmptyBlockKind::Loop,
+                        &for_symbol.bindings,
+                        errors,
+                    );
+                }
+                SymbolKind::Object(object) if !object.overwritten_fields.is_empty() => {
+                    for (name, field) in &object.fields {
+                        let mut ranges = object
+                            .overwritten_fields
+                            .iter()
+                            .filter(|overwritten| overwritten.property_name == *name)
+                            .chain([field])
+                            .filter_map(|field| field.property_syntax.text_range);
+
+                        let Some(existing_range) = ranges.next() else {
+                            continue;
+                        };
+
+                        for duplicate_range in ranges {
+                            errors.push(Error {
+                                kind: ErrorKind::DuplicateObjectField {
+                                    object: symbol,
+                                    name: name.clone(),
+                                    duplicate_range,
+                                    existing_range,
+                                },
+                            });
+                        }
+                    }
+                }
+                SymbolKind::Switch(_) => {
+                    let missing = self.missing_switch_values(symbol);
+
+                    if !missing.is_empty() {
+                        errors.push(Error {
+                            kind: ErrorKind::NonExhaustiveSwitch {
+                                switch: symbol,
+                                missing,
+                            },
+                        });
+                    }
                 }
                 SymbolKind::Import(import) => {
-                    if import.target.is_none() {
+                    // Paths that are not known statically are not checked.
+                    if let (None, Some(path)) = (import.target, import.import_path(self)) {
                         errors.push(Error {
-                            kind: ErrorKind::UnresolvedImport { import: symbol },
+                            kind: ErrorKind::UnresolvedImport {
+                                import: symbol,
+                                path: path.to_string(),
+                            },
+                        });
+                    }
+
+                    if let Some(loop_symbol) = self.enclosing_loop(symbol) {
+                        errors.push(Error {
+                            kind: ErrorKind::ImportInLoop {
+                                import: symbol,
+                                loop_symbol,
+                            },
                         });
                     }
                 }
@@ -94,4 +379,395 @@ impl Hir {
             }
         }
     }
+
+    /// The function of the prelude with the same name as
+    /// a function or variable declared in a script.
+    fn shadowed_builtin(&self, symbol: Symbol) -> Option<Symbol> {
+        let symbol_data = self.symbol(symbol)?;
+
+        match &symbol_data.kind {
+            SymbolKind::Fn(f) if !f.is_def => {}
+            SymbolKind::Decl(decl) if !decl.is_param && !decl.is_import => {}
+            _ => return None,
+        }
+
+        let is_script = symbol_data
+            .source
+            .source
+            .and_then(|source| self.sources.get(source))
+            .is_some_and(|source| source.kind.is_script());
+
+        if !is_script {
+            return None;
+        }
+
+        let prelude = self.source_by_url(&PRELUDE_URL.parse().ok()?)?;
+        let name = symbol_data.name(self)?;
+
+        self.scope_symbols(self[self.static_module].scope)
+            .find(|&builtin| {
+                self[builtin].source.is(prelude)
+                    && self[builtin].kind.as_fn().is_some_and(|f| f.name == name)
+            })
+    }
+
+    /// The types of the operands of an arithmetic operator or
+    /// a comparison if they are known and the operator is
+    /// neither built-in nor defined for them.
+    fn incompatible_operands(&self, binary: &BinarySymbol) -> Option<(Type, Type)> {
+        let Some(BinaryOpKind::Regular(op)) = binary.op else {
+            return None;
+        };
+
+        if binary.overload.is_some() {
+            return None;
+        }
+
+        let lhs_ty = self[binary.lhs?].ty;
+        let rhs_ty = self[binary.rhs?].ty;
+
+        let lhs = OperandKind::of(&self[lhs_ty].kind)?;
+        let rhs = OperandKind::of(&self[rhs_ty].kind)?;
+
+        let builtin = match op {
+            // Strings can be concatenated with any value.
+            SyntaxKind::OP_ADD => {
+                lhs == OperandKind::Text
+                    || rhs == OperandKind::Text
+                    || (lhs == rhs
+                        && matches!(
+                            lhs,
+                            OperandKind::Number | OperandKind::Array | OperandKind::Object
+                        ))
+                    || (lhs == OperandKind::Timestamp && rhs == OperandKind::Number)
+            }
+            SyntaxKind::OP_SUB => matches!(
+                (lhs, rhs),
+                (
+                    OperandKind::Number | OperandKind::Timestamp,
+                    OperandKind::Number
+                ) | (OperandKind::Timestamp, OperandKind::Timestamp)
+            ),
+            SyntaxKind::OP_MUL | SyntaxKind::OP_DIV | SyntaxKind::OP_MOD | SyntaxKind::OP_POW => {
+                lhs == OperandKind::Number && rhs == OperandKind::Number
+            }
+            SyntaxKind::OP_LT | SyntaxKind::OP_LT_EQ | SyntaxKind::OP_GT | SyntaxKind::OP_GT_EQ => {
+                lhs == rhs
+            }
+            _ => return None,
+        };
+
+        (!builtin).then_some((lhs_ty, rhs_ty))
+    }
+
+    /// The field and the object type of a field access
+    /// on a constant object that does not have the field.
+    ///
+    /// Other objects can gain fields at runtime, so they are
+    /// not checked.
+    fn unknown_field(&self, binary: &BinarySymbol) -> Option<(Symbol, Type)> {
+        if binary.lookup_text != "." {
+            return None;
+        }
+
+        let (lhs, field) = (binary.lhs?, binary.rhs?);
+        let name = self[field].kind.as_reference()?.name.as_str();
+
+        let ty = self[lhs].ty;
+        let object = self[ty.unaliased(self)].kind.as_object()?;
+
+        if object.fields.contains_key(name) || !self.is_const_object(lhs) {
+            return None;
+        }
+
+        Some((field, ty))
+    }
+
+    /// Whether the expression is a constant or
+    /// a chain of field accesses on a constant.
+    fn is_const_object(&self, mut expr: Symbol) -> bool {
+        loop {
+            match &self[expr].kind {
+                SymbolKind::Binary(binary) if binary.lookup_text == "." => {
+                    let Some(lhs) = binary.lhs else {
+                        return false;
+                    };
+                    expr = lhs;
+                }
+                SymbolKind::Ref(r) if !r.field_access => {
+                    return r
+                        .target
+                        .and_then(|target| target.as_symbol().copied())
+                        .and_then(|target| self[target].kind.as_decl())
+                        .is_some_and(|decl| decl.is_const);
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    /// Report the scope if it has no symbols other than the parameters
+    /// of functions and the `bindings` of the block.
+    fn collect_empty_block(
+        &self,
+        symbol: Symbol,
+        scope: Scope,
+        kind: EmptyBlockKind,
+        bindings: &[Symbol],
+        errors: &mut Vec<Error>,
+    ) {
+        let Some(scope_data) = self.scope(scope) else {
+            return;
+        };
+
+        let is_empty = scope_data.hoisted_symbols.is_empty()
+            && scope_data
+                .symbols
+                .iter()
+                .all(|s| self[*s].is_param() || bindings.contains(s));
+
+        if is_empty {
+            errors.push(Error {
+                kind: ErrorKind::EmptyBlock {
+                    symbol,
+                    scope,
+                    kind,
+                },
+            });
+        }
+    }
+
+    fn collect_constant_condition(
+        &self,
+        symbol: Symbol,
+        condition: Symbol,
+        errors: &mut Vec<Error>,
+    ) {
+        let value = match self.const_value(condition) {
+            Value::Bool(value) => Some(value),
+            _ => self[self[condition].ty].kind.as_literal_bool(),
+        };
+
+        if let Some(value) = value {
+            errors.push(Error {
+                kind: ErrorKind::ConstantCondition {
+                    symbol,
+                    condition,
+                    value,
+                },
+            });
+        }
+    }
+
+    /// Find a declaration with the given name that is not visible
+    /// from the reference only because it is declared after it
+    /// in one of the enclosing scopes, the search stops at functions.
+    fn later_declaration(&self, reference: Symbol, name: &str) -> Option<Symbol> {
+        let mut anchor = reference;
+
+        loop {
+            let scope = self.symbol(anchor)?.parent_scope;
+            let scope_data = self.scope(scope)?;
+
+            let declaration = scope_data.symbols.iter().copied().find(|&symbol| {
+                self.symbol_order(symbol, anchor) == Some(Ordering::Greater)
+                    && self[symbol]
+                        .kind
+                        .as_decl()
+                        .is_some_and(|decl| decl.name == name)
+            });
+
+            if declaration.is_some() {
+                return declaration;
+            }
+
+            // Symbols in parent scopes are all visible.
+            anchor = *scope_data.parent?.as_symbol()?;
+
+            // Functions cannot see declarations outside of them.
+            if self.symbol(anchor)?.kind.is_fn() {
+                return None;
+            }
+        }
+    }
+
+    /// Find a declaration with the given name whose initializer
+    /// contains the reference.
+    fn initialized_declaration(&self, reference: Symbol, name: &str) -> Option<Symbol> {
+        self.ancestors(reference).find_map(|(scope, parent)| {
+            let parent = parent?;
+
+            match &self[parent].kind {
+                SymbolKind::Decl(decl) if decl.name == name && decl.value_scope == Some(scope) => {
+                    Some(parent)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Find the closure in which the reference writes to
+    /// a declaration that was captured from outside of the closure.
+    fn mutated_capture(&self, reference: Symbol, declaration: Symbol) -> Option<Symbol> {
+        if !self.symbol(declaration)?.kind.is_decl() || !self.reference_access(reference).is_write()
+        {
+            return None;
+        }
+
+        let closure = self.enclosing_closure(reference)?;
+
+        self.symbol(closure)?
+            .kind
+            .as_closure()?
+            .captures
+            .contains(&declaration)
+            .then_some(closure)
+    }
+
+    /// Find the closest closure that contains the given symbol,
+    /// the search stops at functions.
+    fn enclosing_closure(&self, symbol: Symbol) -> Option<Symbol> {
+        self.ancestors(symbol)
+            .filter_map(|(_, parent)| parent)
+            .find(|&parent| {
+                matches!(
+                    self[parent].kind,
+                    SymbolKind::Closure(_) | SymbolKind::Fn(_)
+                )
+            })
+            .filter(|&parent| self[parent].kind.is_closure())
+    }
+
+    /// Find a `return` with a value that returns from the function.
+    fn value_return(&self, function: Symbol, scope: Scope) -> Option<Symbol> {
+        self.fn_return(function, scope, true)
+    }
+
+    /// Find a `return` without a value that returns from the function.
+    fn bare_return(&self, function: Symbol, scope: Scope) -> Option<Symbol> {
+        self.fn_return(function, scope, false)
+    }
+
+    fn fn_return(&self, function: Symbol, scope: Scope, with_value: bool) -> Option<Symbol> {
+        self.descendant_symbols(scope).find(|&symbol| {
+            self[symbol]
+                .kind
+                .as_return()
+                .is_some_and(|ret| ret.expr.is_some() == with_value)
+                && self.enclosing_fn(symbol) == Some(function)
+                && self.enclosing_closure(symbol).is_none()
+        })
+    }
+
+    /// Whether every path through the scope ends in
+    /// a `return`, a `throw` or a tail expression.
+    ///
+    /// Tail expressions only count if the value of the
+    /// scope is used, e.g. it is not followed by a semicolon.
+    fn scope_completes(&self, scope: Scope, has_value: bool) -> bool {
+        self.scope(scope).is_some_and(|data| {
+            data.symbols.last().is_some_and(|&last| {
+                self.symbol_completes(last, has_value && data.tail == Some(last))
+            })
+        })
+    }
+
+    /// Whether every path through the symbol ends in
+    /// a `return`, a `throw` or a value that is used.
+    fn symbol_completes(&self, symbol: Symbol, has_value: bool) -> bool {
+        match &self[symbol].kind {
+            SymbolKind::If(if_symbol) => {
+                if_symbol
+                    .branches
+                    .iter()
+                    .any(|(condition, _)| condition.is_none())
+                    && if_symbol
+                        .branches
+                        .iter()
+                        .all(|&(_, scope)| self.scope_completes(scope, has_value))
+            }
+            SymbolKind::Switch(switch) => {
+                switch.arms.iter().any(|arm| {
+                    arm.condition_expr.is_none()
+                        && arm.pat_expr.is_some_and(|pat| self[pat].kind.is_discard())
+                }) && switch.arms.iter().all(|arm| {
+                    arm.value_expr
+                        .is_some_and(|value| self.symbol_completes(value, has_value))
+                })
+            }
+            SymbolKind::Block(block) => self.scope_completes(block.scope, has_value),
+            SymbolKind::Try(try_symbol) => {
+                self.scope_completes(try_symbol.try_scope, has_value)
+                    && self.scope_completes(try_symbol.catch_scope, has_value)
+            }
+            // A loop can only be left with `break`.
+            SymbolKind::Loop(loop_symbol) => {
+                !self.descendant_symbols(loop_symbol.scope).any(|inner| {
+                    self[inner].kind.is_break() && self.enclosing_loop(inner) == Some(symbol)
+                })
+            }
+            SymbolKind::Decl(_)
+            | SymbolKind::Fn(_)
+            | SymbolKind::For(_)
+            | SymbolKind::While(_)
+            | SymbolKind::Break(_)
+            | SymbolKind::Continue(_)
+            | SymbolKind::Export(_)
+            | SymbolKind::Import(_)
+            | SymbolKind::Discard(_)
+            | SymbolKind::Virtual(_)
+            | SymbolKind::TypeDecl(_) => false,
+            SymbolKind::Return(_) | SymbolKind::Throw(_) => true,
+            _ => has_value,
+        }
+    }
+
+    /// Find the closest loop that contains the given symbol.
+    ///
+    /// Functions and closures are not executed as part of
+    /// the loop body, so the search stops at them.
+    fn enclosing_loop(&self, symbol: Symbol) -> Option<Symbol> {
+        self.ancestors(symbol)
+            .filter_map(|(_, parent)| parent)
+            .find(|&parent| {
+                matches!(
+                    self[parent].kind,
+                    SymbolKind::Loop(_)
+                        | SymbolKind::For(_)
+                        | SymbolKind::While(_)
+                        | SymbolKind::Fn(_)
+                        | SymbolKind::Closure(_)
+                )
+            })
+            .filter(|&parent| !self[parent].kind.is_fn() && !self[parent].kind.is_closure())
+    }
+}
+
+/// Groups of types that built-in operators treat alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperandKind {
+    Number,
+    Text,
+    Bool,
+    Timestamp,
+    Array,
+    Object,
+    Void,
+}
+
+impl OperandKind {
+    /// `None` for types that are not known well enough,
+    /// e.g. unions or unknown types.
+    fn of(kind: &TypeKind) -> Option<Self> {
+        match kind {
+            TypeKind::Int | TypeKind::Float => Some(Self::Number),
+            TypeKind::Char | TypeKind::String | TypeKind::LiteralString(_) => Some(Self::Text),
+            TypeKind::Bool | TypeKind::LiteralBool(_) => Some(Self::Bool),
+            TypeKind::Timestamp => Some(Self::Timestamp),
+            TypeKind::Array(_) => Some(Self::Array),
+            TypeKind::Object(_) => Some(Self::Object),
+            TypeKind::Void => Some(Self::Void),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,225 @@
+use rhai_rowan::syntax::SyntaxKind;
+
+use crate::scope::ScopeParent;
+
+use super::*;
+
+impl Hir {
+    /// Determine whether the given symbol reads or writes
+    /// the value it refers to.
+    ///
+    /// A symbol is written if it is the left-hand side of an assignment,
+    /// compound assignments (e.g. `x += 1`) are both reads and writes.
+    ///
+    /// The base of an indexing or a field access that is assigned to
+    /// is written as well, e.g. `arr` in `arr[i] = v` or `obj` in `obj.field = v`,
+    /// the index expressions are still reads.
+    ///
+    /// Every other symbol is considered a read.
+    #[must_use]
+    pub fn reference_access(&self, symbol: Symbol) -> ReferenceAccess {
+        let target = self.assignment_target(symbol);
+
+        let Some((_, binary)) = self
+            .enclosing_binary(target)
+            .filter(|(_, binary)| binary.lhs == Some(target))
+        else {
+            return ReferenceAccess::Read;
+        };
+
+        if binary.is_compound_assignment() {
+            ReferenceAccess::ReadWrite
+        } else if binary.is_assignment() {
+            ReferenceAccess::Write
+        } else {
+            ReferenceAccess::Read
+        }
+    }
+
+    /// Whether any reference of the declaration writes its value.
+    ///
+    /// Method calls on the variable or on its fields are counted
+    /// as writes as well, since methods can modify `this`.
+    ///
+    /// Returns `false` for symbols that are not declarations.
+    #[must_use]
+    pub fn is_mutated(&self, declaration: Symbol) -> bool {
+        let (reads, writes) = self.reads_and_writes(declaration);
+
+        !writes.is_empty()
+            || reads
+                .into_iter()
+                .any(|reference| self.is_method_receiver(reference))
+    }
+
+    /// Split the references of the declaration into the ones
+    /// that only read its value and the ones that write it,
+    /// see [`Hir::reference_access`].
+    ///
+    /// Compound assignments are writes, both lists are
+    /// in the order of the references in their sources.
+    #[must_use]
+    pub fn reads_and_writes(&self, declaration: Symbol) -> (Vec<Symbol>, Vec<Symbol>) {
+        let Some(decl) = self
+            .symbols
+            .get(declaration)
+            .and_then(|data| data.kind.as_decl())
+        else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let mut references = decl.references.iter().copied().collect::<Vec<_>>();
+        references.sort_by_key(|&reference| {
+            let data = &self[reference];
+            (data.source.source, data.text_range().map(TextRange::start))
+        });
+
+        references
+            .into_iter()
+            .partition(|&reference| !self.reference_access(reference).is_write())
+    }
+
+    /// The references that write the value of the declaration,
+    /// see [`Hir::reads_and_writes`].
+    #[must_use]
+    pub fn find_all_writes(&self, declaration: Symbol) -> Vec<Symbol> {
+        self.reads_and_writes(declaration).1
+    }
+
+    /// Whether the `let` declaration could be a `const` instead,
+    /// that is its value is a constant expression
+    /// and it is never mutated.
+    ///
+    /// Values that are only known at runtime (e.g. calls)
+    /// are not accepted, even if they would be valid.
+    #[must_use]
+    pub fn can_be_const(&self, declaration: Symbol) -> bool {
+        let Some(decl) = self
+            .symbols
+            .get(declaration)
+            .and_then(|data| data.kind.as_decl())
+        else {
+            return false;
+        };
+
+        if decl.is_const || decl.is_param || decl.is_pat || decl.is_import {
+            return false;
+        }
+
+        decl.value
+            .is_some_and(|value| !self.const_value(value).is_unknown())
+            && !self.is_mutated(declaration)
+    }
+
+    /// Whether a method is called on the symbol or
+    /// on a field or an item of it.
+    fn is_method_receiver(&self, mut symbol: Symbol) -> bool {
+        loop {
+            if let Some(index) = self.enclosing_index(symbol) {
+                symbol = index;
+                continue;
+            }
+
+            match self.enclosing_binary(symbol) {
+                Some((binary_symbol, binary))
+                    if binary.lhs == Some(symbol)
+                        && matches!(
+                            binary.op,
+                            Some(BinaryOpKind::Regular(SyntaxKind::PUNCT_DOT))
+                        ) =>
+                {
+                    // Method calls are parsed as calls of field accesses.
+                    if self.is_callee(binary_symbol) {
+                        return true;
+                    }
+
+                    symbol = binary_symbol;
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    /// The callee of a call is placed in the same scope
+    /// as the call symbol.
+    fn is_callee(&self, symbol: Symbol) -> bool {
+        let Some(parent_scope) = self.symbols.get(symbol).map(|data| data.parent_scope) else {
+            return false;
+        };
+
+        self.scopes.get(parent_scope).is_some_and(|scope| {
+            scope.symbols.iter().any(|&candidate| {
+                self.symbols
+                    .get(candidate)
+                    .and_then(|data| data.kind.as_call())
+                    .is_some_and(|call| call.lhs == Some(symbol))
+            })
+        })
+    }
+
+    /// The outermost indexing or field access that
+    /// the symbol is the base of, or the symbol itself.
+    fn assignment_target(&self, mut symbol: Symbol) -> Symbol {
+        loop {
+            if let Some(index) = self.enclosing_index(symbol) {
+                symbol = index;
+                continue;
+            }
+
+            match self.enclosing_binary(symbol) {
+                Some((binary_symbol, binary))
+                    if binary.lhs == Some(symbol)
+                        && matches!(
+                            binary.op,
+                            Some(BinaryOpKind::Regular(SyntaxKind::PUNCT_DOT))
+                        ) =>
+                {
+                    symbol = binary_symbol;
+                }
+                _ => return symbol,
+            }
+        }
+    }
+
+    /// Whether the symbol itself is assigned a new value with `=`,
+    /// e.g. `x` in `x = 1` but not in `x[0] = 1`, `x.field = 1` or `x += 1`.
+    pub(crate) fn is_assigned(&self, symbol: Symbol) -> bool {
+        self.enclosing_binary(symbol).is_some_and(|(_, binary)| {
+            binary.lhs == Some(symbol) && binary.is_assignment() && !binary.is_compound_assignment()
+        })
+    }
+
+    /// The operands of binary expressions are placed in the scope
+    /// of the binary symbol, so we can find it via the scope parent.
+    fn enclosing_binary(&self, symbol: Symbol) -> Option<(Symbol, &BinarySymbol)> {
+        let parent_scope = self.symbols.get(symbol)?.parent_scope;
+
+        match self.scopes.get(parent_scope)?.parent? {
+            ScopeParent::Symbol(parent) => self
+                .symbols
+                .get(parent)?
+                .kind
+                .as_binary()
+                .map(|binary| (parent, binary)),
+            ScopeParent::Scope(_) => None,
+        }
+    }
+
+    /// The base of an indexing is placed in the same scope
+    /// as the indexing symbol.
+    fn enclosing_index(&self, symbol: Symbol) -> Option<Symbol> {
+        let parent_scope = self.symbols.get(symbol)?.parent_scope;
+
+        self.scopes
+            .get(parent_scope)?
+            .symbols
+            .iter()
+            .copied()
+            .find(|&candidate| {
+                self.symbols
+                    .get(candidate)
+                    .and_then(|data| data.kind.as_index())
+                    .is_some_and(|index| index.base == Some(symbol))
+            })
+    }
+}
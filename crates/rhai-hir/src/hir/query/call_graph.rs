@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    visit::{walk_module, Visitor},
+    Module,
+};
+
+use super::*;
+
+impl Hir {
+    /// The `(caller, callee)` edges of the calls in the module,
+    /// ordered by the callers and then by the callees.
+    ///
+    /// The caller is the closest function or closure around the call,
+    /// `None` for top-level statements. Only calls that resolve to
+    /// a function are included, so method calls and calls of closures
+    /// stored in variables are not part of the graph.
+    ///
+    /// An edge appears once no matter how many times the callee
+    /// is called by the same caller.
+    #[must_use]
+    pub fn call_graph(&self, module: Module) -> Vec<(Option<Symbol>, Symbol)> {
+        let mut edges = Edges(Vec::new());
+        walk_module(&mut edges, self, module);
+
+        let Edges(mut edges) = edges;
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// The locations of the calls in all modules that resolve
+    /// to a function with the given name, grouped by the number
+    /// of arguments of the calls.
+    ///
+    /// The calls are matched by the names of the resolved functions,
+    /// so calls of overloads and of different functions with the same
+    /// name in other modules are all included. Method calls are not
+    /// resolved, so they are never included.
+    #[must_use]
+    pub fn call_sites_by_arity(&self, name: &str) -> BTreeMap<usize, Vec<(Url, TextRange)>> {
+        let mut call_sites: BTreeMap<usize, Vec<(Url, TextRange)>> = BTreeMap::new();
+
+        for (symbol, data) in self.symbols() {
+            let Some(call) = data.kind.as_call() else {
+                continue;
+            };
+
+            let is_match = call
+                .lhs
+                .and_then(|lhs| self.callee(lhs))
+                .is_some_and(|callee| self[callee].name(self) == Some(name));
+
+            if !is_match {
+                continue;
+            }
+
+            if let Some(location) = self.symbol_location(symbol) {
+                call_sites
+                    .entry(call.arguments.len())
+                    .or_default()
+                    .push(location);
+            }
+        }
+
+        for locations in call_sites.values_mut() {
+            locations.sort_unstable_by(|(url_a, range_a), (url_b, range_b)| {
+                (url_a, range_a.start()).cmp(&(url_b, range_b.start()))
+            });
+        }
+
+        call_sites
+    }
+
+    /// The locations of all calls of functions with the given name,
+    /// ordered by the number of arguments, see [`Hir::call_sites_by_arity`].
+    #[must_use]
+    pub fn call_sites(&self, name: &str) -> Vec<(Url, TextRange)> {
+        self.call_sites_by_arity(name)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    /// The function that the called expression resolves to.
+    fn callee(&self, expr: Symbol) -> Option<Symbol> {
+        let reference = match &self[expr].kind {
+            SymbolKind::Path(path) => *path.segments.last()?,
+            _ => expr,
+        };
+
+        let r = self[reference].kind.as_reference()?;
+
+        if r.field_access {
+            return None;
+        }
+
+        let &target = r.target.as_ref()?.as_symbol()?;
+
+        self[target].kind.is_fn().then_some(target)
+    }
+}
+
+/// Collects the `(caller, callee)` edges of calls, see [`Hir::call_graph`].
+struct Edges(Vec<(Option<Symbol>, Symbol)>);
+
+impl Visitor for Edges {
+    fn visit_call(&mut self, hir: &Hir, symbol: Symbol, call: &CallSymbol) {
+        if let Some(callee) = call.lhs.and_then(|lhs| hir.callee(lhs)) {
+            // The closest function or closure is also the one
+            // that binds `this`.
+            self.0.push((hir.this_binder(symbol), callee));
+        }
+    }
+}
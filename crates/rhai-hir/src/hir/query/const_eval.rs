@@ -0,0 +1,125 @@
+use crate::{eval::Value, symbol::BinaryOpKind};
+use rhai_rowan::syntax::SyntaxKind;
+
+use super::*;
+
+impl Hir {
+    /// The value of the expression if it is known without running the script.
+    ///
+    /// Only literals and operators applied to them are folded,
+    /// references are never followed, so `x == x` is [`Value::Unknown`].
+    #[must_use]
+    pub fn const_value(&self, symbol: Symbol) -> Value {
+        let Some(data) = self.symbol(symbol) else {
+            return Value::Unknown;
+        };
+
+        match &data.kind {
+            SymbolKind::Lit(lit) if lit.interpolated_scopes.is_empty() => lit.value.clone(),
+            SymbolKind::Unary(unary) => {
+                let rhs = unary
+                    .rhs
+                    .map_or(Value::Unknown, |rhs| self.const_value(rhs));
+
+                match (unary.op, rhs) {
+                    (Some(SyntaxKind::OP_NOT), Value::Bool(v)) => Value::Bool(!v),
+                    (Some(SyntaxKind::OP_SUB), Value::Int(v)) => match v.checked_neg() {
+                        Some(v) => Value::Int(v),
+                        // The value of the literal in `-9223372036854775808`
+                        // is the minimum itself, see `int_of_lit`.
+                        None if unary.rhs.is_some_and(|rhs| self[rhs].kind.is_lit()) => {
+                            Value::Int(v)
+                        }
+                        None => Value::Unknown,
+                    },
+                    (Some(SyntaxKind::OP_SUB), Value::Float(v)) => Value::Float(-v),
+                    (Some(SyntaxKind::OP_ADD), v @ (Value::Int(_) | Value::Float(_))) => v,
+                    _ => Value::Unknown,
+                }
+            }
+            SymbolKind::Binary(binary) => {
+                let Some(BinaryOpKind::Regular(op)) = binary.op else {
+                    return Value::Unknown;
+                };
+
+                let lhs = binary
+                    .lhs
+                    .map_or(Value::Unknown, |lhs| self.const_value(lhs));
+
+                // The right-hand side does not matter if it is not evaluated.
+                match (op, &lhs) {
+                    (SyntaxKind::OP_BOOL_AND, Value::Bool(false)) => return Value::Bool(false),
+                    (SyntaxKind::OP_BOOL_OR, Value::Bool(true)) => return Value::Bool(true),
+                    _ => {}
+                }
+
+                let rhs = binary
+                    .rhs
+                    .map_or(Value::Unknown, |rhs| self.const_value(rhs));
+
+                fold_binary(op, lhs, rhs)
+            }
+            _ => Value::Unknown,
+        }
+    }
+}
+
+fn fold_binary(op: SyntaxKind, lhs: Value, rhs: Value) -> Value {
+    use SyntaxKind::*;
+
+    match (lhs, rhs) {
+        (Value::Bool(l), Value::Bool(r)) => match op {
+            OP_BOOL_AND | OP_BIT_AND => Value::Bool(l && r),
+            OP_BOOL_OR | OP_BIT_OR => Value::Bool(l || r),
+            OP_BIT_XOR => Value::Bool(l ^ r),
+            OP_EQ => Value::Bool(l == r),
+            OP_NOT_EQ => Value::Bool(l != r),
+            _ => Value::Unknown,
+        },
+        (Value::Int(l), Value::Int(r)) => match op {
+            OP_ADD => l.checked_add(r).map_or(Value::Unknown, Value::Int),
+            OP_SUB => l.checked_sub(r).map_or(Value::Unknown, Value::Int),
+            OP_MUL => l.checked_mul(r).map_or(Value::Unknown, Value::Int),
+            OP_DIV => l.checked_div(r).map_or(Value::Unknown, Value::Int),
+            OP_MOD => l.checked_rem(r).map_or(Value::Unknown, Value::Int),
+            _ => compare(op, &l, &r),
+        },
+        #[allow(clippy::cast_precision_loss)]
+        (Value::Int(l), Value::Float(r)) => fold_float(op, l as f64, r),
+        #[allow(clippy::cast_precision_loss)]
+        (Value::Float(l), Value::Int(r)) => fold_float(op, l, r as f64),
+        (Value::Float(l), Value::Float(r)) => fold_float(op, l, r),
+        (Value::String(l), Value::String(r)) => match op {
+            OP_ADD => Value::String(l + &r),
+            _ => compare(op, &l, &r),
+        },
+        (Value::Char(l), Value::Char(r)) => compare(op, &l, &r),
+        _ => Value::Unknown,
+    }
+}
+
+fn fold_float(op: SyntaxKind, lhs: f64, rhs: f64) -> Value {
+    use SyntaxKind::*;
+
+    match op {
+        OP_ADD => Value::Float(lhs + rhs),
+        OP_SUB => Value::Float(lhs - rhs),
+        OP_MUL => Value::Float(lhs * rhs),
+        OP_DIV => Value::Float(lhs / rhs),
+        _ => compare(op, &lhs, &rhs),
+    }
+}
+
+fn compare<T: PartialOrd>(op: SyntaxKind, lhs: &T, rhs: &T) -> Value {
+    use SyntaxKind::*;
+
+    match op {
+        OP_EQ => Value::Bool(lhs == rhs),
+        OP_NOT_EQ => Value::Bool(lhs != rhs),
+        OP_LT => Value::Bool(lhs < rhs),
+        OP_LT_EQ => Value::Bool(lhs <= rhs),
+        OP_GT => Value::Bool(lhs > rhs),
+        OP_GT_EQ => Value::Bool(lhs >= rhs),
+        _ => Value::Unknown,
+    }
+}
@@ -0,0 +1,127 @@
+use core::fmt::Write;
+
+use crate::{module::ModuleKind, ty::Type, Module, TypeKind};
+
+use super::*;
+
+impl Hir {
+    /// Definitions of the exported functions and constants of the module
+    /// with their inferred types in the format of definition (`.d.rhai`) files.
+    ///
+    /// Documentation is preserved, types that could not be inferred
+    /// or cannot be expressed in definitions (e.g. function types) are `?`.
+    #[must_use]
+    pub fn emit_definitions(&self, module: Module) -> String {
+        let module_data = &self[module];
+
+        let mut out = String::new();
+        write_docs(&mut out, &module_data.docs);
+
+        match &module_data.kind {
+            ModuleKind::Static => out += "module static;\n",
+            ModuleKind::Inline => out += "module;\n",
+            ModuleKind::Url(url) => {
+                writeln!(out, "module \"{}\";", url.as_str().replace('"', "\\\"")).unwrap();
+            }
+        }
+
+        // Not indexing to skip proxies to symbols of other modules.
+        let mut symbols = self
+            .scope_symbols(module_data.scope)
+            .filter_map(|symbol| self.symbol(symbol))
+            .filter(|data| data.export)
+            .collect::<Vec<_>>();
+
+        // Functions are hoisted, keep the order of the source instead.
+        symbols.sort_by_key(|data| data.text_range().map(TextRange::start));
+
+        for data in symbols {
+            match &data.kind {
+                SymbolKind::Fn(f) => {
+                    out += "\n";
+                    write_docs(&mut out, &f.docs);
+
+                    out += "fn ";
+                    if f.getter {
+                        out += "get ";
+                    } else if f.setter {
+                        out += "set ";
+                    }
+                    out += &f.name;
+
+                    let params = self
+                        .scope_symbols(f.scope)
+                        .take_while(|&param| self[param].is_param())
+                        .filter_map(|param| {
+                            Some(format!(
+                                "{}: {}",
+                                self[param].name(self)?,
+                                self.def_type(self.type_of(param))
+                            ))
+                        })
+                        .collect::<Vec<_>>();
+
+                    write!(out, "({})", params.join(", ")).unwrap();
+
+                    let ret = match &self[data.ty].kind {
+                        TypeKind::Fn(func) => func.ret,
+                        _ => self.builtin_types.unknown,
+                    };
+
+                    if !self[ret].kind.is_void() {
+                        write!(out, " -> {}", self.def_type(ret)).unwrap();
+                    }
+
+                    out += ";\n";
+                }
+                SymbolKind::Decl(decl) if decl.is_const && !decl.is_param => {
+                    out += "\n";
+                    write_docs(&mut out, &decl.docs);
+                    writeln!(out, "const {}: {};", decl.name, self.def_type(data.ty)).unwrap();
+                }
+                _ => {}
+            }
+        }
+
+        out
+    }
+
+    /// The type as it would appear in definitions.
+    fn def_type(&self, ty: Type) -> String {
+        if self.is_definable(ty) {
+            ty.fmt(self).to_string()
+        } else {
+            String::from("?")
+        }
+    }
+
+    /// Whether the type can be expressed in definitions.
+    fn is_definable(&self, ty: Type) -> bool {
+        if ty.is_null() {
+            return false;
+        }
+
+        match &self[ty].kind {
+            TypeKind::Array(array) => self.is_definable(array.items),
+            TypeKind::Range(range) => !range.inclusive && self.is_definable(range.items),
+            TypeKind::Object(object) => object
+                .fields
+                .iter()
+                .all(|(name, &ty)| is_valid_ident(name) && self.is_definable(ty)),
+            TypeKind::Union(types) => types.iter().all(|&ty| self.is_definable(ty)),
+            TypeKind::Tuple(types) => types.iter().all(|&ty| self.is_definable(ty)),
+            TypeKind::Fn(_) | TypeKind::Never => false,
+            _ => true,
+        }
+    }
+}
+
+fn write_docs(out: &mut String, docs: &str) {
+    for line in docs.trim_end().lines() {
+        if line.is_empty() {
+            *out += "///\n";
+        } else {
+            writeln!(out, "/// {line}").unwrap();
+        }
+    }
+}
@@ -2,17 +2,31 @@ use rhai_rowan::{parser, util::is_valid_ident, TextRange, TextSize};
 use std::cmp::Ordering;
 
 use super::*;
+use crate::{module::PRELUDE_URL, scope::ScopeParent};
 
+pub mod access;
+pub mod call_graph;
+pub mod const_eval;
+pub mod definitions;
 pub mod modules;
+pub mod reachability;
+pub mod references;
+pub mod rename;
 pub mod scope_iter;
+pub mod switch;
+pub mod symbols_by_kind;
+pub mod this;
 pub mod types;
 
-// Nested ranges only.
+// Nested ranges only, the innermost range is the least,
+// e.g. a segment of a path that starts at the start of the path.
 fn range_scope(r1: TextRange, r2: TextRange) -> Ordering {
-    if r1.start() < r2.start() || r1.end() > r2.end() {
-        Ordering::Greater
-    } else if r1.start() > r2.start() || r2.end() < r2.end() {
+    if r1 == r2 {
+        Ordering::Equal
+    } else if r2.contains_range(r1) {
         Ordering::Less
+    } else if r1.contains_range(r2) {
+        Ordering::Greater
     } else {
         Ordering::Equal
     }
@@ -76,6 +90,141 @@ impl Hir {
             .or_else(|| self.module_by_source(source).map(|m| self[m].scope))
     }
 
+    /// The top-level statements of the source that overlap the range,
+    /// in the order of their positions.
+    ///
+    /// Statements that are only partially covered are included as
+    /// a whole, so the range of a selection can be extended to the
+    /// ranges of the returned statements. An empty range selects
+    /// the statement that contains it.
+    #[must_use]
+    pub fn statements_in_range(&self, source: Source, range: TextRange) -> Vec<Symbol> {
+        let Some(module) = self.module_by_source(source) else {
+            return Vec::new();
+        };
+
+        self.statements_in_scope_range(self[module].scope, source, range)
+    }
+
+    /// Same as [`Hir::statements_in_range`], but the statements are
+    /// the ones of the innermost scope that contains the whole range,
+    /// e.g. the statements of a function body.
+    #[must_use]
+    pub fn inner_statements_in_range(&self, source: Source, range: TextRange) -> Vec<Symbol> {
+        let scope = self
+            .scopes()
+            .filter(|(_, d)| d.source.is(source))
+            // Other expressions, e.g. declarations and binary expressions
+            // have scopes for their operands, but they have no statements.
+            .filter(|(_, d)| match d.parent {
+                Some(ScopeParent::Symbol(parent)) => matches!(
+                    self[parent].kind,
+                    SymbolKind::Block(_)
+                        | SymbolKind::Fn(_)
+                        | SymbolKind::Closure(_)
+                        | SymbolKind::If(_)
+                        | SymbolKind::Loop(_)
+                        | SymbolKind::For(_)
+                        | SymbolKind::While(_)
+                        | SymbolKind::Switch(_)
+                        | SymbolKind::Try(_)
+                ),
+                _ => true,
+            })
+            .filter_map(|(scope, d)| Some((scope, d.source.text_range?)))
+            .filter(|(_, scope_range)| scope_range.contains_range(range))
+            .min_by(|(_, r1), (_, r2)| range_scope(*r1, *r2))
+            .map(|(scope, _)| scope)
+            .or_else(|| self.module_by_source(source).map(|m| self[m].scope));
+
+        match scope {
+            Some(scope) => self.statements_in_scope_range(scope, source, range),
+            None => Vec::new(),
+        }
+    }
+
+    fn statements_in_scope_range(
+        &self,
+        scope: Scope,
+        source: Source,
+        range: TextRange,
+    ) -> Vec<Symbol> {
+        let mut statements = self
+            .scope_symbols(scope)
+            .filter(|&symbol| self[symbol].source.is(source))
+            .filter_map(|symbol| Some((symbol, self[symbol].text_range()?)))
+            .filter(|(_, statement_range)| {
+                statement_range
+                    .intersect(range)
+                    .is_some_and(|overlap| !overlap.is_empty() || range.is_empty())
+            })
+            .collect::<Vec<_>>();
+
+        // Some expressions, e.g. the callees of calls are also
+        // in the scope, only the outermost symbols are statements.
+        let ranges = statements.iter().map(|(_, r)| *r).collect::<Vec<_>>();
+        statements.retain(|(_, statement_range)| {
+            !ranges
+                .iter()
+                .any(|r| r != statement_range && r.contains_range(*statement_range))
+        });
+
+        statements.sort_by_key(|(_, statement_range)| statement_range.start());
+
+        statements.into_iter().map(|(symbol, _)| symbol).collect()
+    }
+
+    /// Compare the order of two symbols in the same scope.
+    ///
+    /// Hoisted symbols are ordered before all other symbols,
+    /// `None` is returned if the symbols are in different scopes
+    /// or if both of them are hoisted.
+    #[must_use]
+    pub fn symbol_order(&self, a: Symbol, b: Symbol) -> Option<Ordering> {
+        let scope = self.symbol(a)?.parent_scope;
+
+        if self.symbol(b)?.parent_scope != scope {
+            return None;
+        }
+
+        let scope_data = self.scope(scope)?;
+
+        match (scope_data.symbol_index(a), scope_data.symbol_index(b)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            (Some(_), None) => Some(Ordering::Greater),
+            (None, Some(_)) => Some(Ordering::Less),
+            (None, None) => None,
+        }
+    }
+
+    /// Find the closest function that contains the given symbol.
+    ///
+    /// Closures are not considered functions and are skipped,
+    /// `None` is returned for symbols that are not inside a function.
+    #[must_use]
+    pub fn enclosing_fn(&self, symbol: Symbol) -> Option<Symbol> {
+        self.ancestors(symbol)
+            .filter_map(|(_, parent)| parent)
+            .find(|&parent| self[parent].kind.is_fn())
+    }
+
+    /// The URL of the source that defines the symbol
+    /// and the range of its name, or the whole symbol if it has no name.
+    ///
+    /// Symbols without a source and symbols of the prelude, which
+    /// is not a document, have no location.
+    #[must_use]
+    pub fn symbol_location(&self, symbol: Symbol) -> Option<(Url, TextRange)> {
+        let symbol_data = self.symbol(symbol)?;
+        let url = &self.sources.get(symbol_data.source.source?)?.url;
+
+        if url.as_str() == PRELUDE_URL {
+            return None;
+        }
+
+        Some((url.clone(), symbol_data.selection_or_text_range()?))
+    }
+
     #[must_use]
     pub fn source_by_url(&self, url: &Url) -> Option<Source> {
         for (src, data) in self.sources.iter() {
@@ -111,6 +260,67 @@ impl Hir {
     pub fn operator_by_name(&self, name: &str) -> Option<&OpSymbol> {
         self.operators().find(|&op| op.name == name)
     }
+
+    /// The unary or binary expression whose operator token
+    /// is at the offset.
+    #[must_use]
+    pub fn operator_at(&self, source: Source, offset: TextSize) -> Option<Symbol> {
+        self.symbols()
+            .filter(|(_, d)| d.source.is(source))
+            .find(|(_, d)| {
+                let op_range = match &d.kind {
+                    SymbolKind::Unary(u) => u.op_range,
+                    SymbolKind::Binary(b) if !b.is_field_access() => b.op_range,
+                    _ => None,
+                };
+
+                op_range.is_some_and(|range| range.contains(offset))
+            })
+            .map(|(symbol, _)| symbol)
+    }
+
+    /// The signature of the operator of a unary or binary expression,
+    /// e.g. `int + float -> float`.
+    ///
+    /// The types are from the chosen operator definition if there is one,
+    /// otherwise they are the inferred types of the operands and the result.
+    #[must_use]
+    pub fn operator_signature(&self, symbol: Symbol) -> Option<String> {
+        let data = self.symbol(symbol)?;
+
+        let (lookup_text, lhs, rhs, overload) = match &data.kind {
+            SymbolKind::Unary(u) => (&u.lookup_text, None, u.rhs, u.overload),
+            SymbolKind::Binary(b) => (&b.lookup_text, Some(b.lhs), b.rhs, b.overload),
+            _ => return None,
+        };
+
+        let operand_ty = |operand: Option<Symbol>| {
+            operand
+                .and_then(|operand| self.symbol(operand))
+                .map_or(self.builtin_types.unknown, |operand| operand.ty)
+        };
+
+        let (lhs_ty, rhs_ty, ret_ty) = match overload
+            .and_then(|op| self.symbol(op))
+            .and_then(|op| op.kind.as_op())
+        {
+            Some(op) => match op.rhs_ty {
+                Some(rhs_ty) => (Some(op.lhs_ty), rhs_ty, op.ret_ty),
+                None => (None, op.lhs_ty, op.ret_ty),
+            },
+            None => (lhs.map(operand_ty), operand_ty(rhs), data.ty),
+        };
+
+        Some(match lhs_ty {
+            Some(lhs_ty) => format!(
+                "{} {lookup_text} {} -> {}",
+                lhs_ty.fmt(self),
+                rhs_ty.fmt(self),
+                ret_ty.fmt(self)
+            ),
+            None => format!("{lookup_text}{} -> {}", rhs_ty.fmt(self), ret_ty.fmt(self)),
+        })
+    }
 }
 
 /// Used for filtering shadowed symbols.
@@ -1,4 +1,5 @@
-use crate::scope::ScopeParent;
+use crate::{scope::ScopeParent, HashSet};
+use rhai_rowan::TextSize;
 
 use super::*;
 
@@ -96,12 +97,238 @@ impl Hir {
             .find(|s| self[*s].name(self) == Some(name))
     }
 
+    /// The modules of the program, the entry module first and then the
+    /// modules it imports directly or indirectly in breadth-first order.
+    ///
+    /// Empty if there is no entry module, see [`Hir::set_entry_module`].
+    #[must_use]
+    pub fn program_modules(&self) -> Vec<Module> {
+        let Some(entry) = self
+            .entry_module
+            .as_ref()
+            .and_then(|url| self.module_by_url(url))
+        else {
+            return Vec::new();
+        };
+
+        let mut modules = vec![entry];
+        let mut seen = HashSet::from_iter([entry]);
+        let mut index = 0;
+
+        while let Some(&module) = modules.get(index) {
+            index += 1;
+
+            for symbol in self.scope_symbols(self[module].scope) {
+                if let Some(&target) = self[symbol]
+                    .kind
+                    .as_import()
+                    .and_then(|import| import.target.as_ref())
+                {
+                    if seen.insert(target) {
+                        modules.push(target);
+                    }
+                }
+            }
+        }
+
+        modules
+    }
+
+    /// The symbols exported by the other modules of the program
+    /// that references in the module can resolve to without paths,
+    /// in the order of [`Hir::program_modules`].
+    ///
+    /// Empty if the module is not part of the program.
+    #[must_use]
+    pub fn program_symbols(&self, module: Module) -> Vec<Symbol> {
+        let modules = self.program_modules();
+
+        if !modules.contains(&module) {
+            return Vec::new();
+        }
+
+        modules
+            .into_iter()
+            .filter(|&m| m != module)
+            .flat_map(|m| self.scope_symbols(self[m].scope))
+            .filter(|&symbol| {
+                self[symbol].export
+                    && match &self[symbol].kind {
+                        SymbolKind::Fn(_) => true,
+                        SymbolKind::Decl(decl) => !decl.is_import,
+                        _ => false,
+                    }
+            })
+            .collect()
+    }
+
+    /// Exported functions and variables that are not
+    /// referenced by any other module.
+    ///
+    /// Only modules that other modules import are checked, the rest are
+    /// usually scripts that are run on their own. The exports of the entry
+    /// module and the public modules are always considered used,
+    /// see [`Hir::set_public_modules`].
+    #[must_use]
+    pub fn unused_exports(&self) -> Vec<Symbol> {
+        self.symbols()
+            .map(|(symbol, _)| symbol)
+            .filter(|&symbol| self.is_unused_export(symbol))
+            .collect()
+    }
+
+    /// Whether the symbol is an unused export, see [`Hir::unused_exports`].
+    #[must_use]
+    pub fn is_unused_export(&self, symbol: Symbol) -> bool {
+        let Some(data) = self.symbol(symbol) else {
+            return false;
+        };
+
+        let is_candidate = data.export
+            && match &data.kind {
+                SymbolKind::Fn(f) => !f.is_def,
+                SymbolKind::Decl(decl) => !decl.is_param && !decl.is_import,
+                _ => false,
+            };
+
+        if !is_candidate {
+            return false;
+        }
+
+        let Some(module) = self.module_by_symbol(symbol) else {
+            return false;
+        };
+
+        let is_public = self[module].url().is_some_and(|url| {
+            self.entry_module.as_ref() == Some(url) || self.public_modules.contains(url)
+        });
+
+        let is_script = data
+            .source
+            .source
+            .and_then(|source| self.sources.get(source))
+            .is_some_and(|source| source.kind.is_script());
+
+        if is_public || !is_script || self[module].scope != data.parent_scope {
+            return false;
+        }
+
+        // Paths resolve to the first overload only,
+        // the overloads of a function are used together.
+        let name = data.name(self);
+        let is_referenced = self
+            .scope_symbols(self[module].scope)
+            .filter(|&other| {
+                other == symbol
+                    || (data.kind.is_fn()
+                        && self[other].kind.is_fn()
+                        && self[other].export
+                        && self[other].name(self) == name)
+            })
+            .flat_map(|other| match &self[other].kind {
+                SymbolKind::Fn(f) => f.references.iter().copied().collect::<Vec<_>>(),
+                SymbolKind::Decl(decl) => decl.references.iter().copied().collect(),
+                _ => Vec::new(),
+            })
+            .any(|reference| self.module_by_symbol(reference) != Some(module));
+
+        if is_referenced {
+            return false;
+        }
+
+        self.symbols().any(|(import, import_data)| {
+            import_data
+                .kind
+                .as_import()
+                .is_some_and(|import| import.target == Some(module))
+                && self.module_by_symbol(import) != Some(module)
+        })
+    }
+
+    /// Symbols of the modules that paths can start with at the offset,
+    /// these are the aliases of visible imports and the virtual modules.
+    pub fn visible_modules_from_offset(
+        &self,
+        source: Source,
+        offset: TextSize,
+    ) -> impl Iterator<Item = Symbol> + '_ {
+        self.visible_symbols_from_offset(source, offset, false)
+            .filter_map(|symbol| match &self[symbol].kind {
+                SymbolKind::Import(import) => import.alias,
+                SymbolKind::Virtual(VirtualSymbol::Module(_)) => Some(symbol),
+                _ => None,
+            })
+    }
+
+    /// The exported symbols of the module that the path with the given
+    /// segments refers to at the offset, e.g. the members of `foo::bar`
+    /// for `["foo", "bar"]`.
+    ///
+    /// The result is empty if any of the segments is not a module.
+    #[must_use]
+    pub fn path_members(
+        &self,
+        source: Source,
+        offset: TextSize,
+        segments: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Vec<Symbol> {
+        let mut symbols = self
+            .visible_modules_from_offset(source, offset)
+            .collect::<Vec<_>>();
+
+        for segment in segments {
+            let module = symbols
+                .iter()
+                .find(|&&symbol| self[symbol].name(self) == Some(segment.as_ref()))
+                .and_then(|&symbol| self.target_module(symbol));
+
+            let Some(module) = module else {
+                return Vec::new();
+            };
+
+            symbols = self
+                .scope_symbols(self[module].scope)
+                .filter(|&symbol| self[symbol].export)
+                .collect();
+        }
+
+        symbols
+    }
+
+    /// The private function of another module that the path segment
+    /// would refer to if the function was not private.
+    #[must_use]
+    pub fn private_path_target(&self, segment: Symbol) -> Option<Symbol> {
+        let path = self.scope(self.symbol(segment)?.parent_scope)?.parent?;
+        let path = self[*path.as_symbol()?].kind.as_path()?;
+
+        let index = path.segments.iter().position(|&s| s == segment)?;
+        let module = self.target_module(path.segments[index.checked_sub(1)?])?;
+        let name = self[segment].name(self)?;
+
+        self.scope_symbols(self[module].scope).find(|&symbol| {
+            self[symbol]
+                .kind
+                .as_fn()
+                .is_some_and(|f| f.private && f.name == name)
+        })
+    }
+
     /// Recursively resolve a module from a reference.
+    ///
+    /// Modules re-export the modules they import, the chain
+    /// of targets is followed until a module is found
+    /// or a symbol is visited twice.
     #[must_use]
     pub fn target_module(&self, reference_symbol: Symbol) -> Option<Module> {
         let mut reference_symbol = Some(reference_symbol);
+        let mut seen = HashSet::default();
 
         while let Some(ref_symbol) = reference_symbol.take() {
+            if !seen.insert(ref_symbol) {
+                return None;
+            }
+
             match self[ref_symbol].target() {
                 Some(target) => match target {
                     ReferenceTarget::Symbol(sym) => {
@@ -0,0 +1,104 @@
+use crate::{
+    visit::{walk_module, Visitor},
+    HashMap, HashSet, Module,
+};
+
+use super::*;
+
+impl Hir {
+    /// Functions of the module that can be reached through calls
+    /// from its top-level statements or from its exported functions.
+    ///
+    /// Method calls are resolved by name only, so every function
+    /// of the module with the name of a called method is considered live.
+    #[must_use]
+    pub fn live_functions(&self, module: Module) -> HashSet<Symbol> {
+        let scope = self[module].scope;
+
+        let functions = self
+            .scope_symbols(scope)
+            .filter(|&symbol| self[symbol].kind.is_fn())
+            .collect::<Vec<_>>();
+
+        let mut visitor = Calls {
+            functions: &functions,
+            calls: HashMap::new(),
+        };
+        walk_module(&mut visitor, self, module);
+
+        let calls = visitor.calls;
+
+        let mut live = HashSet::new();
+        let mut queue = functions
+            .iter()
+            .copied()
+            .filter(|&f| self[f].export)
+            .map(Some)
+            .chain([None])
+            .collect::<Vec<_>>();
+
+        while let Some(caller) = queue.pop() {
+            if let Some(caller) = caller {
+                if !live.insert(caller) {
+                    continue;
+                }
+            }
+
+            if let Some(callees) = calls.get(&caller) {
+                queue.extend(
+                    callees
+                        .iter()
+                        .filter(|callee| !live.contains(*callee))
+                        .copied()
+                        .map(Some),
+                );
+            }
+        }
+
+        live
+    }
+
+    /// Whether the function is reachable from the top-level statements
+    /// or the exported functions of its module.
+    ///
+    /// Symbols that are not functions of a module are always live.
+    #[must_use]
+    pub fn is_live(&self, symbol: Symbol) -> bool {
+        if !self[symbol].kind.is_fn() {
+            return true;
+        }
+
+        match self.module_by_symbol(symbol) {
+            Some(module) if self[module].scope == self[symbol].parent_scope => {
+                self.live_functions(module).contains(&symbol)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Collects the functions of a module that are called by each function.
+struct Calls<'f> {
+    functions: &'f [Symbol],
+    /// Callees by the calling function, `None` for top-level statements.
+    calls: HashMap<Option<Symbol>, Vec<Symbol>>,
+}
+
+impl Visitor for Calls<'_> {
+    fn visit_ref(&mut self, hir: &Hir, symbol: Symbol, r: &ReferenceSymbol) {
+        let callees = self.calls.entry(hir.enclosing_fn(symbol)).or_default();
+
+        if r.field_access {
+            callees.extend(
+                self.functions
+                    .iter()
+                    .copied()
+                    .filter(|&f| hir[f].name(hir) == Some(r.name.as_str())),
+            );
+        } else if let Some(&target) = r.target.as_ref().and_then(|t| t.as_symbol()) {
+            if self.functions.contains(&target) {
+                callees.push(target);
+            }
+        }
+    }
+}
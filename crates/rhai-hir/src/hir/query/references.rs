@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use crate::{symbol::VirtualSymbol, IndexSet};
+
+use super::*;
+
+impl Hir {
+    /// All references to the given function or declaration grouped by
+    /// the URLs of their sources, in the order they appear in the source.
+    ///
+    /// Next to references in its own module, this includes references
+    /// from other modules through paths such as `m::f`, also if the path
+    /// goes through import aliases of intermediate modules.
+    ///
+    /// Cross-module paths are only resolved by [`Hir::resolve_all`].
+    #[must_use]
+    pub fn references_to(&self, symbol: Symbol) -> BTreeMap<Url, Vec<Symbol>> {
+        let mut references: IndexSet<Symbol> = match &self[symbol].kind {
+            SymbolKind::Fn(f) => f.references.iter().copied().collect(),
+            SymbolKind::Decl(decl) => decl.references.iter().copied().collect(),
+            _ => return BTreeMap::new(),
+        };
+
+        for (_, data) in self.symbols() {
+            let Some(&last_segment) = data.kind.as_path().and_then(|p| p.segments.last()) else {
+                continue;
+            };
+
+            if self.reference_target(last_segment) == Some(symbol) {
+                references.insert(last_segment);
+            }
+        }
+
+        let mut grouped: BTreeMap<Url, Vec<Symbol>> = BTreeMap::new();
+
+        for reference in references {
+            let Some(source) = self[reference].source.source else {
+                continue;
+            };
+
+            grouped
+                .entry(self[source].url.clone())
+                .or_default()
+                .push(reference);
+        }
+
+        for references in grouped.values_mut() {
+            references.sort_by_key(|&reference| self[reference].text_range().map(TextRange::start));
+        }
+
+        grouped
+    }
+
+    /// The symbol the reference resolves to, aliases
+    /// and exported identifiers are followed.
+    fn reference_target(&self, reference: Symbol) -> Option<Symbol> {
+        let mut target = *self[reference].kind.as_reference()?.target?.as_symbol()?;
+
+        if let Some(alias) = self[target]
+            .kind
+            .as_virtual()
+            .and_then(VirtualSymbol::as_alias)
+        {
+            target = alias.target;
+        }
+
+        // Identifiers exported with `export x;` are references themselves.
+        if let Some(r) = self[target].kind.as_reference() {
+            target = *r.target?.as_symbol()?;
+        }
+
+        Some(target)
+    }
+}
@@ -0,0 +1,237 @@
+use std::{collections::BTreeMap, ffi::OsStr, path::Path};
+
+use percent_encoding::percent_decode_str;
+
+use crate::symbol::{RenameCollision, VirtualSymbol};
+
+use super::*;
+
+impl Hir {
+    /// Edits that rename the given function or declaration and all of
+    /// its references to `new_name`, grouped by the URLs of their sources.
+    ///
+    /// References from other modules are included, except for the ones
+    /// that refer to the symbol under an alias (`export x as alias;`),
+    /// as those keep working with the alias after the rename.
+    ///
+    /// The edits of each source are sorted and do not overlap.
+    #[must_use]
+    pub fn rename_edits(
+        &self,
+        symbol: Symbol,
+        new_name: &str,
+    ) -> BTreeMap<Url, Vec<(TextRange, String)>> {
+        let Some(name) = self[symbol].name(self) else {
+            return BTreeMap::new();
+        };
+
+        let mut edits: BTreeMap<Url, Vec<(TextRange, String)>> = BTreeMap::new();
+
+        let declaration = self[symbol]
+            .source
+            .source
+            .map(|source| (self[source].url.clone(), vec![symbol]));
+
+        for (url, symbols) in declaration.into_iter().chain(self.references_to(symbol)) {
+            let ranges = symbols
+                .into_iter()
+                .filter(|&s| self[s].name(self) == Some(name))
+                .filter_map(|s| self[s].selection_or_text_range());
+
+            edits
+                .entry(url)
+                .or_default()
+                .extend(ranges.map(|range| (range, new_name.to_string())));
+        }
+
+        for source_edits in edits.values_mut() {
+            source_edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+
+            let mut last_end = None;
+            source_edits.retain(|(range, _)| {
+                if last_end.is_some_and(|end| range.start() < end) {
+                    return false;
+                }
+
+                last_end = Some(range.end());
+                true
+            });
+        }
+
+        edits.retain(|_, source_edits| !source_edits.is_empty());
+
+        edits
+    }
+
+    /// Edits that update the import paths that refer to the file
+    /// at `old_url` after it is moved to `new_url`, grouped by the URLs
+    /// of the importing sources.
+    ///
+    /// Relative paths stay relative to the importing file, absolute
+    /// paths and URLs stay absolute, and the `.rhai` extension
+    /// is only kept if the path had it.
+    #[must_use]
+    pub fn file_rename_edits(
+        &self,
+        old_url: &Url,
+        new_url: &Url,
+    ) -> BTreeMap<Url, Vec<(TextRange, String)>> {
+        let mut edits: BTreeMap<Url, Vec<(TextRange, String)>> = BTreeMap::new();
+
+        for (symbol, data) in self.symbols() {
+            let SymbolKind::Import(import) = &data.kind else {
+                continue;
+            };
+
+            let (Some(path), Some(expr), Some(module)) = (
+                import.import_path(self),
+                import.expr,
+                self.module_by_symbol(symbol),
+            ) else {
+                continue;
+            };
+
+            let imports_old_url = self
+                .module_resolver
+                .resolve_url_from_module(self, module, path)
+                .is_ok_and(|url| url == *old_url);
+
+            if !imports_old_url {
+                continue;
+            }
+
+            let (Some(importer_url), Some(range)) = (
+                data.source.source.map(|source| &self[source].url),
+                self[expr].text_range(),
+            ) else {
+                continue;
+            };
+
+            // An import of the file in itself moves with the file.
+            let base = if importer_url == old_url {
+                new_url
+            } else {
+                importer_url
+            };
+
+            if let Some(new_path) = moved_import_path(path, base, new_url) {
+                edits
+                    .entry(importer_url.clone())
+                    .or_default()
+                    .push((range, format!("\"{}\"", escaped(&new_path))));
+            }
+        }
+
+        for source_edits in edits.values_mut() {
+            source_edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+        }
+
+        edits
+    }
+
+    /// Check whether renaming the given declaration or function
+    /// to `new_name` would change what any reference resolves to.
+    ///
+    /// References to the symbol must not be captured by
+    /// a different declaration with the new name, and existing
+    /// references with the new name must not be captured by the
+    /// renamed symbol.
+    #[must_use]
+    pub fn rename_collisions(&self, symbol: Symbol, new_name: &str) -> Vec<RenameCollision> {
+        let mut collisions = Vec::new();
+
+        let references = match &self[symbol].kind {
+            SymbolKind::Decl(decl) => &decl.references,
+            SymbolKind::Fn(f) => &f.references,
+            _ => return collisions,
+        };
+
+        for &reference in references {
+            match self.resolve_renamed(reference, symbol, new_name) {
+                Some(declaration) if declaration != symbol => {
+                    collisions.push(RenameCollision::Captured {
+                        reference,
+                        declaration,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        for (reference, data) in self.symbols() {
+            let SymbolKind::Ref(r) = &data.kind else {
+                continue;
+            };
+
+            if r.part_of_path
+                || r.field_access
+                || r.name != new_name
+                || references.contains(&reference)
+            {
+                continue;
+            }
+
+            if self.resolve_renamed(reference, symbol, new_name) == Some(symbol) {
+                collisions.push(RenameCollision::Shadowed {
+                    reference,
+                    declaration: r.target.and_then(|t| t.as_symbol().copied()),
+                });
+            }
+        }
+
+        collisions
+    }
+
+    /// Resolve the reference as if it was called `name`
+    /// and the `renamed` symbol was also called `name`.
+    fn resolve_renamed(&self, reference: Symbol, renamed: Symbol, name: &str) -> Option<Symbol> {
+        self.visible_symbols_from_symbol(reference)
+            .find(|&visible| {
+                visible == renamed
+                    || (self[visible].name(self) == Some(name)
+                        && matches!(
+                            &self[visible].kind,
+                            SymbolKind::Fn(_)
+                                | SymbolKind::Decl(_)
+                                | SymbolKind::Virtual(VirtualSymbol::Module(..))
+                        ))
+            })
+    }
+}
+
+/// The path that imports `target` from `base`
+/// in the same style as the original `path`.
+///
+/// Paths other than URLs are written without percent-encoding.
+fn moved_import_path(path: &str, base: &Url, target: &Url) -> Option<String> {
+    let new_path = if Url::parse(path).is_ok() {
+        return Some(target.to_string());
+    } else if path.starts_with('/') {
+        target.path().to_string()
+    } else {
+        let relative = base.make_relative(target)?;
+
+        if path.starts_with("./") && !relative.starts_with('.') {
+            format!("./{relative}")
+        } else {
+            relative
+        }
+    };
+
+    let mut new_path = percent_decode_str(&new_path)
+        .decode_utf8()
+        .ok()?
+        .into_owned();
+
+    if Path::new(path).extension() != Some(OsStr::new("rhai")) {
+        if let Some(stripped) = new_path.strip_suffix(".rhai") {
+            new_path.truncate(stripped.len());
+        }
+    }
+
+    Some(new_path)
+}
+
+fn escaped(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
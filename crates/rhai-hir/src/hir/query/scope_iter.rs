@@ -7,6 +7,12 @@ use std::cmp::Ordering;
 use super::*;
 
 impl Hir {
+    /// Symbols visible from the given symbol, nearest first.
+    ///
+    /// Scopes are visited from the innermost outwards, within a scope
+    /// symbols declared later come first followed by hoisted symbols,
+    /// so the first symbol with a matching name is the one that
+    /// a reference with that name binds to.
     pub fn visible_symbols_from_symbol(&self, symbol: Symbol) -> impl Iterator<Item = Symbol> + '_ {
         VisibleSymbols {
             hir: self,
@@ -31,6 +37,20 @@ impl Hir {
         }
     }
 
+    /// The scopes that enclose the symbol from the innermost outwards,
+    /// each with the symbol it belongs to if it is the scope of
+    /// a symbol (e.g. the body of a function) rather than a nested block.
+    ///
+    /// The last scope is the scope of the static module
+    /// that the scopes of all modules are nested in.
+    #[must_use]
+    pub fn ancestors(&self, symbol: Symbol) -> Ancestors<'_> {
+        Ancestors {
+            hir: self,
+            scope: self.symbol(symbol).map(|data| data.parent_scope),
+        }
+    }
+
     /// Same as [`Self::scope_symbols`], but includes all symbols in the tree.
     pub fn descendant_symbols(&self, scope: Scope) -> impl Iterator<Item = Symbol> + '_ {
         DescendantSymbols {
@@ -173,6 +193,33 @@ impl<'h> Iterator for VisibleSymbols<'h> {
     }
 }
 
+pub struct Ancestors<'h> {
+    hir: &'h Hir,
+    scope: Option<Scope>,
+}
+
+impl Iterator for Ancestors<'_> {
+    type Item = (Scope, Option<Symbol>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let scope = self.scope.take()?;
+
+        let parent_symbol = match self.hir.scope(scope)?.parent {
+            Some(ScopeParent::Scope(parent_scope)) => {
+                self.scope = Some(parent_scope);
+                None
+            }
+            Some(ScopeParent::Symbol(parent_symbol)) => {
+                self.scope = self.hir.symbol(parent_symbol).map(|data| data.parent_scope);
+                Some(parent_symbol)
+            }
+            None => None,
+        };
+
+        Some((scope, parent_symbol))
+    }
+}
+
 pub struct DescendantSymbols<'h> {
     hir: &'h Hir,
     iter_stack: Vec<Box<dyn Iterator<Item = Symbol> + 'h>>,
@@ -226,15 +273,8 @@ fn collect_symbol_scope_iters<'h>(
                 collect_symbol_scope_iters(hir, iters, sym);
             }
         }
-        SymbolKind::Binary(sym) => {
-            if let Some(sym) = sym.lhs {
-                collect_symbol_scope_iters(hir, iters, sym);
-            }
-
-            if let Some(sym) = sym.rhs {
-                collect_symbol_scope_iters(hir, iters, sym);
-            }
-        }
+        // The operands are in the scope of the binary expression.
+        SymbolKind::Binary(sym) => iters.push(Box::new(hir.scope_symbols(sym.scope))),
         SymbolKind::Array(sym) => {
             for &value in &sym.values {
                 collect_symbol_scope_iters(hir, iters, value);
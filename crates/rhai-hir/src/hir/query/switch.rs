@@ -0,0 +1,135 @@
+use crate::{eval::Value, TypeKind};
+use itertools::Itertools;
+
+use super::*;
+
+impl Hir {
+    /// Literal values of the switch target that no arm pattern
+    /// covers yet, in the order they are found.
+    ///
+    /// A `bool` target can be `true` or `false`, a variable can be any
+    /// of the constant values it is declared or assigned with, other
+    /// targets have no known values.
+    #[must_use]
+    pub fn uncovered_switch_values(&self, switch: Symbol) -> Vec<Value> {
+        let Some(switch) = self[switch].kind.as_switch() else {
+            return Vec::new();
+        };
+
+        let Some(target) = switch.target else {
+            return Vec::new();
+        };
+
+        let (values, _) = self.switch_target_values(target);
+        self.uncovered_values(switch, values)
+    }
+
+    /// The values that the switch does not handle if the target
+    /// can only have a known set of values, see [`Hir::uncovered_switch_values`].
+    ///
+    /// A default arm (`_`) handles every value, arms with conditions
+    /// do not handle any.
+    #[must_use]
+    pub fn missing_switch_values(&self, switch: Symbol) -> Vec<Value> {
+        let Some(switch) = self[switch].kind.as_switch() else {
+            return Vec::new();
+        };
+
+        let Some(target) = switch.target else {
+            return Vec::new();
+        };
+
+        if switch.arms.iter().any(|arm| {
+            arm.condition_expr.is_none()
+                && arm.pat_expr.is_some_and(|pat| self[pat].kind.is_discard())
+        }) {
+            return Vec::new();
+        }
+
+        match self.switch_target_values(target) {
+            (values, true) => self.uncovered_values(switch, values),
+            (_, false) => Vec::new(),
+        }
+    }
+
+    fn uncovered_values(&self, switch: &SwitchSymbol, values: Vec<Value>) -> Vec<Value> {
+        let covered = switch
+            .arms
+            .iter()
+            .filter(|arm| arm.condition_expr.is_none())
+            .filter_map(|arm| arm.pat_expr)
+            .map(|pattern| self.const_value(pattern))
+            .filter(|value| !value.is_unknown())
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+
+        values
+            .into_iter()
+            .filter(|value| !value.is_unknown())
+            .unique_by(ToString::to_string)
+            .filter(|value| !covered.contains(&value.to_string()))
+            .collect()
+    }
+
+    /// The known values of the switch target, and whether
+    /// the target cannot have any other value.
+    fn switch_target_values(&self, target: Symbol) -> (Vec<Value>, bool) {
+        match self[self[target].ty].kind {
+            TypeKind::Bool => return (vec![Value::Bool(true), Value::Bool(false)], true),
+            TypeKind::LiteralBool(value) => return (vec![Value::Bool(value)], true),
+            _ => {}
+        }
+
+        let Some(&decl) = self[target]
+            .kind
+            .as_reference()
+            .and_then(|r| r.target.as_ref())
+            .and_then(|target| target.as_symbol())
+        else {
+            return (Vec::new(), false);
+        };
+
+        let Some(decl_symbol) = self[decl].kind.as_decl() else {
+            return (Vec::new(), false);
+        };
+
+        // Compound assignments (e.g. `+=`) can produce any value.
+        let mut closed = decl_symbol.value.is_some();
+        let mut values = decl_symbol
+            .value
+            .map(|value| self.const_value(value))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        for (_, data) in self.symbols() {
+            let Some(binary) = data.kind.as_binary() else {
+                continue;
+            };
+
+            if !binary.is_assignment() {
+                continue;
+            }
+
+            let assigns_decl = binary
+                .lhs
+                .and_then(|lhs| self[lhs].kind.as_reference())
+                .and_then(|r| r.target.as_ref())
+                .is_some_and(|target| target.as_symbol() == Some(&decl));
+
+            if !assigns_decl {
+                continue;
+            }
+
+            match binary.rhs {
+                Some(rhs) if !binary.is_compound_assignment() => {
+                    values.push(self.const_value(rhs));
+                }
+                _ => closed = false,
+            }
+        }
+
+        closed &= values.iter().all(|value| !value.is_unknown());
+
+        (values, closed)
+    }
+}
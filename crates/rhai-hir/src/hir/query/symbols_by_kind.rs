@@ -0,0 +1,63 @@
+use crate::Module;
+
+use super::*;
+
+impl Hir {
+    /// Symbols from the sources of the module whose kind matches
+    /// the predicate along with their ranges, in the order they
+    /// appear in the sources.
+    ///
+    /// Symbols without a range in the source (e.g. virtual symbols) are skipped.
+    pub fn symbols_by_kind(
+        &self,
+        module: Module,
+        predicate: impl Fn(&SymbolKind) -> bool,
+    ) -> Vec<(Symbol, TextRange)> {
+        let mut symbols = self
+            .symbols()
+            .filter(|(_, data)| predicate(&data.kind))
+            .filter(|(_, data)| {
+                data.source
+                    .source
+                    .and_then(|source| self.sources.get(source))
+                    .is_some_and(|source| source.module == module)
+            })
+            .filter_map(|(symbol, data)| Some((symbol, data.text_range()?)))
+            .collect::<Vec<_>>();
+
+        symbols.sort_by_key(|(_, range)| range.start());
+        symbols
+    }
+
+    /// All functions declared in the module.
+    #[must_use]
+    pub fn functions(&self, module: Module) -> Vec<(Symbol, TextRange)> {
+        self.symbols_by_kind(module, SymbolKind::is_fn)
+    }
+
+    /// All declarations in the module, including parameters
+    /// and bindings of patterns.
+    #[must_use]
+    pub fn declarations(&self, module: Module) -> Vec<(Symbol, TextRange)> {
+        self.symbols_by_kind(module, SymbolKind::is_decl)
+    }
+
+    /// All string literals in the module with their decoded values,
+    /// e.g. for extracting messages for translation.
+    ///
+    /// Template strings with interpolations have no value
+    /// that is known in advance and are skipped.
+    #[must_use]
+    pub fn string_literals(&self, module: Module) -> Vec<(Symbol, TextRange, String)> {
+        self.symbols_by_kind(module, |kind| {
+            kind.as_lit()
+                .is_some_and(|lit| lit.value.is_string() && lit.interpolated_scopes.is_empty())
+        })
+        .into_iter()
+        .filter_map(|(symbol, range)| {
+            let value = self[symbol].kind.as_lit()?.value.as_string()?.clone();
+            Some((symbol, range, value))
+        })
+        .collect()
+    }
+}
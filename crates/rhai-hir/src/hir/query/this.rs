@@ -0,0 +1,98 @@
+use super::*;
+
+impl Hir {
+    /// The closest function or closure that contains the symbol,
+    /// this is the one that `this` is bound by.
+    #[must_use]
+    pub fn this_binder(&self, symbol: Symbol) -> Option<Symbol> {
+        self.ancestors(symbol)
+            .filter_map(|(_, parent)| parent)
+            .find(|&parent| self[parent].kind.is_fn() || self[parent].kind.is_closure())
+    }
+
+    /// Expressions whose values are bound to `this` when the given
+    /// function or closure is called as a method, in no particular order.
+    ///
+    /// - For functions these are the receivers of method calls
+    ///   with the name of the function wherever it is visible.
+    /// - For closures stored in object fields this is the object.
+    /// - For closures stored in variables these are the receivers
+    ///   of `receiver.call(closure)` calls.
+    #[must_use]
+    pub fn this_receivers(&self, binder: Symbol) -> Vec<Symbol> {
+        match &self[binder].kind {
+            SymbolKind::Fn(f) => self.method_call_receivers(|hir, call, name, _| {
+                name == f.name && hir.visible_symbols_from_symbol(call).any(|s| s == binder)
+            }),
+            SymbolKind::Closure(_) => {
+                let parent_scope = self[binder].parent_scope;
+
+                let object = self.scope_symbols(parent_scope).find(|&symbol| {
+                    self[symbol].kind.as_object().is_some_and(|object| {
+                        object
+                            .fields
+                            .values()
+                            .any(|field| field.value == Some(binder))
+                    })
+                });
+
+                if let Some(object) = object {
+                    return vec![object];
+                }
+
+                // The value of a declaration is in its own scope.
+                let variable = self
+                    .scope(parent_scope)
+                    .and_then(|scope| scope.parent)
+                    .and_then(|parent| parent.as_symbol().copied())
+                    .filter(|&decl| {
+                        self[decl]
+                            .kind
+                            .as_decl()
+                            .is_some_and(|decl| decl.value == Some(binder))
+                    });
+
+                let Some(variable) = variable else {
+                    return Vec::new();
+                };
+
+                self.method_call_receivers(|hir, _, name, arguments| {
+                    name == "call"
+                        && arguments.first().is_some_and(|&argument| {
+                            hir[argument]
+                                .kind
+                                .as_reference()
+                                .and_then(|r| r.target)
+                                .and_then(|target| target.as_symbol().copied())
+                                == Some(variable)
+                        })
+                })
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Receivers of all method calls (`receiver.name(arguments)`)
+    /// that match the predicate.
+    fn method_call_receivers(
+        &self,
+        predicate: impl Fn(&Hir, Symbol, &str, &[Symbol]) -> bool,
+    ) -> Vec<Symbol> {
+        self.symbols()
+            .filter_map(|(call, data)| {
+                let call_symbol = data.kind.as_call()?;
+                let method = self[call_symbol.lhs?].kind.as_binary()?;
+
+                if !method.is_field_access() {
+                    return None;
+                }
+
+                let name = self[method.rhs?].name(self)?;
+
+                predicate(self, call, name, &call_symbol.arguments)
+                    .then_some(method.lhs)
+                    .flatten()
+            })
+            .collect()
+    }
+}
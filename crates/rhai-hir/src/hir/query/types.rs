@@ -1,4 +1,4 @@
-use crate::{hir::BuiltinTypes, Hir};
+use crate::{hir::BuiltinTypes, ty::Type, Hir, Symbol};
 
 impl Hir {
     #[must_use]
@@ -6,4 +6,29 @@ impl Hir {
     pub const fn builtin_types(&self) -> BuiltinTypes {
         self.builtin_types
     }
+
+    /// The inferred type of the symbol.
+    ///
+    /// Types are inferred once for every symbol by [`Hir::resolve_types`]
+    /// and kept with the symbol until the next resolution, this never
+    /// runs inference again.
+    ///
+    /// References have the type of their targets, symbols whose type
+    /// depends on themselves (e.g. recursive functions) are unknown
+    /// at the point of the recursion.
+    #[must_use]
+    pub fn type_of(&self, symbol: Symbol) -> Type {
+        self[symbol].ty
+    }
+
+    /// The number of times the type of a symbol was inferred
+    /// since the HIR was created.
+    ///
+    /// Every resolution infers the type of each symbol once,
+    /// only available for tests.
+    #[cfg(feature = "testing")]
+    #[must_use]
+    pub fn type_inferences(&self) -> usize {
+        self.type_inferences
+    }
 }
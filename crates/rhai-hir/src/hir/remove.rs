@@ -32,6 +32,9 @@ impl Hir {
             self.remove_type(ty);
         }
 
+        self.interned_types
+            .retain(|(ty_source, _), _| *ty_source != Some(source));
+
         for m in self.modules.values_mut() {
             m.sources.remove(&source);
         }
@@ -110,7 +113,7 @@ impl Hir {
 
     /// Recursively remove all descendant symbols and scopes,
     /// and then remove the symbol itself.
-    fn remove_symbol(&mut self, symbol: Symbol) {
+    pub(crate) fn remove_symbol(&mut self, symbol: Symbol) {
         if let Some(s) = self.symbols.remove(symbol) {
             if self.scopes.contains_key(s.parent_scope) {
                 self.scope_mut(s.parent_scope).symbols.shift_remove(&symbol);
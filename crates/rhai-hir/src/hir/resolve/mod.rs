@@ -1,13 +1,29 @@
 use crate::{
-    symbol::{ReferenceTarget, SymbolKind, VirtualSymbol},
-    Hir, Module, Symbol,
+    module::EXTERNAL_URL_SCHEME,
+    source::SourceInfo,
+    symbol::{DeclSymbol, ReferenceTarget, SymbolData, SymbolKind, VirtualSymbol},
+    HashMap, Hir, Module, Symbol,
 };
 use itertools::Itertools;
+use rhai_rowan::{parser::Parser, TextRange, TextSize};
+use url::Url;
 
 mod types;
 
 impl Hir {
     pub fn clear_references(&mut self) {
+        // Implicit declarations are added during resolution.
+        let implicit_declarations = self
+            .symbols
+            .iter()
+            .filter(|(_, data)| data.kind.as_decl().is_some_and(|decl| decl.is_implicit))
+            .map(|(symbol, _)| symbol)
+            .collect::<Vec<_>>();
+
+        for symbol in implicit_declarations {
+            self.remove_symbol(symbol);
+        }
+
         let ref_symbols = self.symbols.iter_mut();
 
         for (_, sym_data) in ref_symbols {
@@ -18,6 +34,7 @@ impl Hir {
                     d.references.clear();
                 }
                 SymbolKind::Ref(r) => r.target = None,
+                SymbolKind::Closure(c) => c.captures.clear(),
                 _ => {}
             }
         }
@@ -56,49 +73,301 @@ impl Hir {
             })
             .collect();
 
-        for ref_symbol in ref_symbols_to_resolve {
-            let mut visible_symbols = self.visible_symbols_from_symbol(ref_symbol);
+        for &ref_symbol in &ref_symbols_to_resolve {
+            self.resolve_scope_reference(ref_symbol);
+        }
 
-            while let Some(visible_symbol) = visible_symbols.next() {
-                if self[visible_symbol].name(self) != self[ref_symbol].name(self) {
-                    continue;
-                }
+        self.resolve_external_references(&ref_symbols_to_resolve);
 
-                match &self[ref_symbol].kind {
-                    SymbolKind::Ref(_) => {
-                        if matches!(
-                            &self[visible_symbol].kind,
-                            SymbolKind::Fn(_)
-                                | SymbolKind::Decl(_)
-                                | SymbolKind::Virtual(VirtualSymbol::Module(..))
-                        ) {
-                            drop(visible_symbols);
-                            let vis_symbol_data = self.symbol_mut(visible_symbol);
-
-                            match &mut vis_symbol_data.kind {
-                                SymbolKind::Fn(target) => {
-                                    target.references.insert(ref_symbol);
-                                }
-                                SymbolKind::Decl(target) => {
-                                    target.references.insert(ref_symbol);
-                                }
-                                _ => {}
-                            }
+        if self.implicit_globals {
+            self.resolve_implicit_declarations(&ref_symbols_to_resolve);
+        }
 
-                            match &mut self.symbol_mut(ref_symbol).kind {
-                                SymbolKind::Ref(r) => {
-                                    r.target = Some(ReferenceTarget::Symbol(visible_symbol));
-                                }
-                                _ => {}
-                            }
+        if self.entry_module.is_some() {
+            self.resolve_program_references(&ref_symbols_to_resolve);
+        }
 
-                            break;
-                        }
+        self.resolve_closure_captures(&ref_symbols_to_resolve);
+    }
+
+    /// Resolve the reference to the closest visible declaration
+    /// or function with the same name, returns whether it was found.
+    fn resolve_scope_reference(&mut self, ref_symbol: Symbol) -> bool {
+        let name = self[ref_symbol].name(self);
+
+        let Some(target_symbol) = self
+            .visible_symbols_from_symbol(ref_symbol)
+            .find(|&visible| {
+                self[visible].name(self) == name
+                    && matches!(
+                        &self[visible].kind,
+                        SymbolKind::Fn(_)
+                            | SymbolKind::Decl(_)
+                            | SymbolKind::Virtual(VirtualSymbol::Module(..))
+                    )
+            })
+        else {
+            return false;
+        };
+
+        match &mut self.symbol_mut(target_symbol).kind {
+            SymbolKind::Fn(target) => {
+                target.references.insert(ref_symbol);
+            }
+            SymbolKind::Decl(target) => {
+                target.references.insert(ref_symbol);
+            }
+            _ => {}
+        }
+
+        if let Some(r) = self.symbol_mut(ref_symbol).kind.as_reference_mut() {
+            r.target = Some(ReferenceTarget::Symbol(target_symbol));
+        }
+
+        true
+    }
+
+    /// Add the definitions of the functions that the external resolver
+    /// knows and that are called without being declared, then resolve
+    /// the remaining references again, see [`Hir::set_external_resolver`].
+    fn resolve_external_references(&mut self, ref_symbols: &[Symbol]) {
+        let Some(resolver) = self.external_resolver.clone() else {
+            return;
+        };
+
+        let unresolved = ref_symbols
+            .iter()
+            .copied()
+            .filter(|&symbol| self[symbol].target().is_none())
+            .collect::<Vec<_>>();
+
+        let mut fns = resolver.known_fns();
+
+        for &symbol in &unresolved {
+            let Some(call) = self.call_of(symbol) else {
+                continue;
+            };
+
+            let arity = self[call]
+                .kind
+                .as_call()
+                .map_or(0, |call| call.arguments.len());
+
+            if let Some(name) = self[symbol].name(self) {
+                fns.push((name.to_string(), arity));
+            }
+        }
+
+        let mut added = false;
+
+        for (name, arity) in fns {
+            let Ok(url) = format!("{EXTERNAL_URL_SCHEME}:///{name}/{arity}.d.rhai").parse::<Url>()
+            else {
+                continue;
+            };
+
+            if self.source_of(&url).is_some()
+                || self.missing_external_fns.contains(&(name.clone(), arity))
+            {
+                continue;
+            }
+
+            if let Some(definition) = resolver.resolve_fn(&name, arity) {
+                let definition = format!("module static;\n\n{definition}");
+                self.add_source(&url, &Parser::new(&definition).parse_def().into_syntax());
+                added = true;
+            } else {
+                self.missing_external_fns.insert((name, arity));
+            }
+        }
+
+        if added {
+            for symbol in unresolved {
+                self.resolve_scope_reference(symbol);
+            }
+        }
+    }
+
+    /// Declare the variables that are assigned to at the script level
+    /// without being declared, see [`Hir::set_implicit_globals`].
+    ///
+    /// The declarations are placed before the top-level statements
+    /// with the first assignments, then the remaining references
+    /// are resolved again.
+    fn resolve_implicit_declarations(&mut self, ref_symbols: &[Symbol]) {
+        let mut unresolved = ref_symbols
+            .iter()
+            .copied()
+            .filter(|&symbol| self[symbol].target().is_none())
+            .collect::<Vec<_>>();
+
+        unresolved.sort_by_key(|&symbol| {
+            (
+                self[symbol].source.source,
+                self[symbol].text_range().map(TextRange::start),
+            )
+        });
+
+        for &ref_symbol in &unresolved {
+            // Assigning to an index or a field of an undeclared
+            // variable does not declare it.
+            if !self.is_assigned(ref_symbol) || self.resolve_scope_reference(ref_symbol) {
+                continue;
+            }
+
+            if self.add_implicit_declaration(ref_symbol).is_some() {
+                self.resolve_scope_reference(ref_symbol);
+            }
+        }
+
+        for &ref_symbol in &unresolved {
+            if self[ref_symbol].target().is_none() {
+                self.resolve_scope_reference(ref_symbol);
+            }
+        }
+    }
+
+    /// Collect the declarations that the closures capture from
+    /// their surrounding scopes, a reference in a nested closure
+    /// is a capture of every enclosing closure it escapes.
+    fn resolve_closure_captures(&mut self, ref_symbols: &[Symbol]) {
+        let mut captures: Vec<(Symbol, Symbol)> = Vec::new();
+
+        for &ref_symbol in ref_symbols {
+            let Some(declaration) = self[ref_symbol]
+                .target()
+                .and_then(|target| target.as_symbol().copied())
+            else {
+                continue;
+            };
+
+            if !self[declaration].kind.is_decl() {
+                continue;
+            }
+
+            let declaration_owners = self
+                .ancestors(declaration)
+                .filter_map(|(_, owner)| owner)
+                .collect::<Vec<_>>();
+
+            for (_, owner) in self.ancestors(ref_symbol) {
+                let Some(owner) = owner else {
+                    continue;
+                };
+
+                match &self[owner].kind {
+                    SymbolKind::Closure(_) if !declaration_owners.contains(&owner) => {
+                        captures.push((owner, declaration));
                     }
+                    SymbolKind::Closure(_) | SymbolKind::Fn(_) => break,
                     _ => {}
                 }
             }
         }
+
+        for (closure, declaration) in captures {
+            if let SymbolKind::Closure(c) = &mut self.symbol_mut(closure).kind {
+                if !c.captures.contains(&declaration) {
+                    c.captures.push(declaration);
+                }
+            }
+        }
+    }
+
+    /// Add a declaration for the assigned reference right before
+    /// the top-level statement it is in.
+    ///
+    /// Returns `None` if the assignment is in a function or a closure,
+    /// these cannot declare variables of the script.
+    fn add_implicit_declaration(&mut self, ref_symbol: Symbol) -> Option<Symbol> {
+        let module_scope = self[self.module_by_symbol(ref_symbol)?].scope;
+
+        let mut statement = ref_symbol;
+
+        for (scope, parent) in self.ancestors(ref_symbol) {
+            if scope == module_scope {
+                break;
+            }
+
+            if let Some(parent) = parent {
+                if matches!(
+                    self[parent].kind,
+                    SymbolKind::Fn(_) | SymbolKind::Closure(_)
+                ) {
+                    return None;
+                }
+
+                statement = parent;
+            }
+        }
+
+        let index = self[module_scope].symbols.get_index_of(&statement)?;
+        let name = self[ref_symbol].name(self)?.to_string();
+
+        // The range of the reference might include whitespace.
+        let range = TextRange::at(self[ref_symbol].text_range()?.start(), TextSize::of(&name));
+
+        let declaration = self.symbols.insert(SymbolData {
+            export: false,
+            parent_scope: module_scope,
+            source: SourceInfo {
+                source: self[ref_symbol].source.source,
+                text_range: Some(range),
+                selection_text_range: Some(range),
+            },
+            kind: SymbolKind::Decl(Box::new(DeclSymbol {
+                name,
+                is_implicit: true,
+                ..DeclSymbol::default()
+            })),
+            ty: self.builtin_types.unknown,
+        });
+
+        let scope_symbols = &mut self.scope_mut(module_scope).symbols;
+        scope_symbols.insert(declaration);
+        scope_symbols.move_index(scope_symbols.len() - 1, index);
+
+        Some(declaration)
+    }
+
+    /// Resolve the references that are not visible in their modules
+    /// to the symbols of the other modules of the program,
+    /// see [`Hir::set_entry_module`].
+    fn resolve_program_references(&mut self, ref_symbols: &[Symbol]) {
+        let mut program_symbols: HashMap<Module, Vec<Symbol>> = HashMap::default();
+
+        for &ref_symbol in ref_symbols {
+            if self[ref_symbol].target().is_some() {
+                continue;
+            }
+
+            let Some(module) = self.module_by_symbol(ref_symbol) else {
+                continue;
+            };
+
+            let symbols = program_symbols
+                .entry(module)
+                .or_insert_with(|| self.program_symbols(module));
+
+            let name = self[ref_symbol].name(self);
+            let Some(&target_symbol) = symbols.iter().find(|&&s| self[s].name(self) == name) else {
+                continue;
+            };
+
+            match &mut self.symbol_mut(target_symbol).kind {
+                SymbolKind::Fn(target) => {
+                    target.references.insert(ref_symbol);
+                }
+                SymbolKind::Decl(target) => {
+                    target.references.insert(ref_symbol);
+                }
+                _ => {}
+            }
+
+            if let Some(r) = self.symbol_mut(ref_symbol).kind.as_reference_mut() {
+                r.target = Some(ReferenceTarget::Symbol(target_symbol));
+            }
+        }
     }
 
     fn resolve_imports(&mut self) {
@@ -219,6 +488,12 @@ impl Hir {
                                     _ => {}
                                 }
 
+                                if let SymbolKind::Decl(alias) =
+                                    &mut self.symbol_mut(visible_symbol).kind
+                                {
+                                    alias.references.insert(module_reference);
+                                }
+
                                 break;
                             }
                         }
@@ -1,10 +1,13 @@
 use crate::{
     eval::Value,
     hir::BuiltinTypes,
-    symbol::{ReferenceTarget, SymbolKind},
-    ty::{Array, Function, Object, Type, TypeData},
-    HashSet, Hir, IndexMap, IndexSet, Symbol, TypeKind,
+    scope::ScopeParent,
+    source::SourceInfo,
+    symbol::{BinaryOpKind, Destructure, ReferenceTarget, SymbolKind},
+    ty::{Array, Function, Object, Range, Type, TypeData, TypeKey},
+    HashMap, HashSet, Hir, IndexMap, IndexSet, Scope, Symbol, TypeKind,
 };
+use rhai_rowan::syntax::SyntaxKind;
 use slotmap::SlotMap;
 
 impl Hir {
@@ -15,6 +18,8 @@ impl Hir {
         for symbol in symbols {
             self.resolve_type_for_symbol(&mut seen, symbol);
         }
+
+        self.binder_receivers.clear();
     }
 
     pub(crate) fn resolve_type_aliases(&mut self) {
@@ -127,6 +132,11 @@ impl Hir {
         }
         seen.insert(symbol);
 
+        #[cfg(feature = "testing")]
+        {
+            self.type_inferences += 1;
+        }
+
         let sym_data = self.symbols.get_mut(symbol).unwrap();
         let source = sym_data.source;
 
@@ -136,7 +146,8 @@ impl Hir {
                 sym_data.ty = match &lit.value {
                     Value::Int(_) => self.builtin_types.int,
                     Value::Float(_) => self.builtin_types.float,
-                    Value::Bool(_) => self.builtin_types.bool,
+                    Value::Bool(true) => self.builtin_types.bool_true,
+                    Value::Bool(false) => self.builtin_types.bool_false,
                     Value::String(_) => self.builtin_types.string,
                     Value::Char(_) => self.builtin_types.char,
                     Value::Unknown => self.builtin_types.unknown,
@@ -145,24 +156,59 @@ impl Hir {
             SymbolKind::Ref(r) => match r.target {
                 Some(ReferenceTarget::Symbol(target_sym)) => {
                     self.resolve_type_for_symbol(seen, target_sym);
+
+                    let flow_ty = if self.flow_typing && self[target_sym].kind.is_decl() {
+                        self.flow_type(seen, symbol, target_sym)
+                    } else {
+                        None
+                    };
+
                     let target_sym_data = self.symbols.get(target_sym).unwrap();
-                    self.symbols.get_mut(symbol).unwrap().ty = target_sym_data.ty;
+                    self.symbols.get_mut(symbol).unwrap().ty =
+                        flow_ty.unwrap_or(target_sym_data.ty);
                 }
                 Some(ReferenceTarget::Module(_)) => {
                     sym_data.ty = self.builtin_types.module;
                 }
+                None if r.name == "this" && !r.field_access => {
+                    let ty = self.this_type(seen, symbol);
+                    self.symbols.get_mut(symbol).unwrap().ty = ty;
+                }
                 None => sym_data.ty = self.builtin_types.unknown,
             },
             SymbolKind::Decl(decl) => {
+                let is_param = decl.is_param;
+                // Variables can be assigned other values later.
+                let is_literal = decl.is_const || decl.ty_decl.is_some();
+
                 let ty = if let Some(ty) = decl.ty_decl {
                     ty
                 } else if let Some(val) = decl.value {
+                    let destructure = decl.destructure.clone();
                     self.resolve_type_for_symbol(seen, val);
-                    self.symbols.get(val).unwrap().ty
+
+                    match destructure {
+                        Some(destructure) => self.destructured_type(val, &destructure),
+                        None => self.symbols.get(val).unwrap().ty,
+                    }
+                } else if let Some(for_symbol) =
+                    decl.is_pat.then(|| self.for_of_binding(symbol)).flatten()
+                {
+                    // The type of the binding is set by the loop.
+                    self.resolve_type_for_symbol(seen, for_symbol);
+                    return;
+                } else if is_param {
+                    self.param_type(seen, symbol)
                 } else {
                     self.builtin_types.unknown
                 };
 
+                let ty = if is_literal {
+                    ty
+                } else {
+                    self.non_literal_bool(ty)
+                };
+
                 self.symbols.get_mut(symbol).unwrap().ty = ty;
             }
             SymbolKind::Block(block) => {
@@ -190,58 +236,37 @@ impl Hir {
                     self.resolve_type_for_symbol(seen, arm_expr);
                     switch_types.insert(self.symbols.get(arm_expr).unwrap().ty);
                 }
-                self.symbols.get_mut(symbol).unwrap().ty = if switch_types.is_empty() {
-                    self.builtin_types.void
-                } else if switch_types.len() == 1 {
-                    switch_types.pop().unwrap()
-                } else {
-                    self.types.insert(TypeData {
-                        source,
-                        kind: TypeKind::Union(switch_types),
-                        protected: false,
-                    })
-                };
+                self.symbols.get_mut(symbol).unwrap().ty = self.union_of(source, switch_types);
             }
             SymbolKind::If(if_sym) => {
-                let branch_symbols = if_sym
+                let branch_scopes = if_sym
                     .branches
                     .iter()
-                    .map(|(_, scope)| self.scopes.get(*scope).unwrap().symbols.last().copied())
+                    .map(|&(_, scope)| scope)
                     .collect::<Vec<_>>();
 
-                for branch_sym in branch_symbols.iter().filter_map(|&s| s) {
-                    self.resolve_type_for_symbol(seen, branch_sym);
-                }
+                // Without an `else` branch there might be no value.
+                let has_else = if_sym.branches.iter().any(|(c, _)| c.is_none());
 
-                let mut branch_types = branch_symbols
+                let mut branch_types = branch_scopes
                     .into_iter()
-                    .map(|sym| match sym {
-                        Some(last_branch_sym) => self.symbols.get(last_branch_sym).unwrap().ty,
-                        None => self.builtin_types.void,
-                    })
+                    .filter_map(|scope| self.tail_type(seen, scope))
                     .collect::<IndexSet<_>>();
 
+                if !has_else {
+                    branch_types.insert(self.builtin_types.void);
+                }
+
                 self.symbols.get_mut(symbol).unwrap().ty = if branch_types.is_empty() {
-                    self.builtin_types.void
-                } else if branch_types.len() == 1 {
-                    branch_types.pop().unwrap()
+                    self.builtin_types.never
                 } else {
-                    self.types.insert(TypeData {
-                        source,
-                        kind: TypeKind::Union(branch_types),
-                        protected: false,
-                    })
+                    self.union_of(source, branch_types)
                 };
             }
             SymbolKind::Fn(f) => {
                 let scope = f.scope;
                 let is_def = f.is_def;
-
-                let ret_ty = if is_def && f.ret_ty == self.builtin_types.unknown {
-                    self.builtin_types.void
-                } else {
-                    f.ret_ty
-                };
+                let ret_ty = f.ret_ty;
 
                 let params = self
                     .scopes
@@ -279,12 +304,12 @@ impl Hir {
                     .find(|&sym| !self.symbols.get(sym).unwrap().is_param())
                 {
                     self.resolve_type_for_symbol(seen, last_expr);
-                    self.symbols.get(last_expr).unwrap().ty
+                    self.non_literal_bool(self.symbols.get(last_expr).unwrap().ty)
                 } else {
                     self.builtin_types.unknown
                 };
 
-                self.symbols.get_mut(symbol).unwrap().ty = self.types.insert(TypeData {
+                self.symbols.get_mut(symbol).unwrap().ty = self.intern_type(TypeData {
                     source,
                     protected: false,
                     kind: TypeKind::Fn(Function {
@@ -296,6 +321,7 @@ impl Hir {
             }
             SymbolKind::Closure(f) => {
                 let scope = f.scope;
+                let body = f.expr;
 
                 let params = self
                     .scopes
@@ -321,49 +347,73 @@ impl Hir {
                     .map(|(name, sym)| (name, self.symbols.get(sym).unwrap().ty))
                     .collect::<Vec<_>>();
 
-                let ret = if let Some(last_expr) = self
-                    .scopes
-                    .get(scope)
-                    .unwrap()
-                    .symbols
-                    .iter()
-                    .copied()
-                    .find(|&sym| !self.symbols.get(sym).unwrap().is_param())
-                {
+                // The body of the closure is its value, other symbols in
+                // its scope can be nested in the body.
+                let ret = if let Some(last_expr) = body.or_else(|| {
+                    self.scopes
+                        .get(scope)
+                        .unwrap()
+                        .symbols
+                        .iter()
+                        .copied()
+                        .find(|&sym| !self.symbols.get(sym).unwrap().is_param())
+                }) {
                     self.resolve_type_for_symbol(seen, last_expr);
-                    self.symbols.get(last_expr).unwrap().ty
+                    self.non_literal_bool(self.symbols.get(last_expr).unwrap().ty)
                 } else {
                     self.builtin_types.unknown
                 };
 
-                self.symbols.get_mut(symbol).unwrap().ty = self.types.insert(TypeData {
+                self.symbols.get_mut(symbol).unwrap().ty = self.intern_type(TypeData {
                     source,
                     protected: false,
                     kind: TypeKind::Fn(Function {
-                        is_closure: false,
+                        is_closure: true,
                         params,
                         ret,
                     }),
                 });
             }
             SymbolKind::Call(call) => {
+                let arguments = call.arguments.clone();
+
                 if let Some(lhs) = call.lhs {
                     self.resolve_type_for_symbol(seen, lhs);
-                    let ty_data = self.types.get(self.symbols.get(lhs).unwrap().ty).unwrap();
+                    let ty_data = self
+                        .types
+                        .get(self.symbols.get(lhs).unwrap().ty.unaliased(self))
+                        .unwrap();
 
-                    let ty = if let Some(ty_fn) = ty_data.kind.as_fn() {
+                    let mut ty = if let Some(ty_fn) = ty_data.kind.as_fn() {
                         ty_fn.ret
                     } else {
                         self.builtin_types.unknown
                     };
 
+                    if let Some(type_name) = self.type_of_call(seen, lhs, &arguments) {
+                        ty = self.intern_type(TypeData {
+                            source,
+                            kind: TypeKind::LiteralString(type_name.into()),
+                            protected: false,
+                        });
+                    }
+
+                    if self[ty].kind.is_unknown() {
+                        if let Some(result_ty) = self.higher_order_call_type(seen, symbol) {
+                            ty = result_ty;
+                        }
+                    }
+
                     self.symbols.get_mut(symbol).unwrap().ty = ty;
                 }
             }
             SymbolKind::Index(idx) => {
                 if let Some(base) = idx.base {
                     self.resolve_type_for_symbol(seen, base);
-                    let ty_data = self.types.get(self.symbols.get(base).unwrap().ty).unwrap();
+                    let ty_data = self
+                        .types
+                        .get(self.symbols.get(base).unwrap().ty.unaliased(self))
+                        .unwrap();
 
                     let ty = if let Some(arr) = ty_data.kind.as_array() {
                         arr.items
@@ -381,24 +431,15 @@ impl Hir {
                     self.resolve_type_for_symbol(seen, *elem);
                 }
 
-                let mut types = elems
+                // Elements can be assigned other values later.
+                let types = elems
                     .into_iter()
-                    .map(|sym| self.symbols.get(sym).unwrap().ty)
+                    .map(|sym| self.non_literal_bool(self.symbols.get(sym).unwrap().ty))
                     .collect::<IndexSet<_>>();
 
-                let items = if types.is_empty() {
-                    self.builtin_types.void
-                } else if types.len() == 1 {
-                    types.pop().unwrap()
-                } else {
-                    self.types.insert(TypeData {
-                        source,
-                        kind: TypeKind::Union(types),
-                        protected: false,
-                    })
-                };
+                let items = self.union_of(source, self.distinct_types(types));
 
-                let arr_ty = self.types.insert(TypeData {
+                let arr_ty = self.intern_type(TypeData {
                     source,
                     kind: TypeKind::Array(Array { items }),
                     protected: false,
@@ -417,12 +458,18 @@ impl Hir {
                     self.resolve_type_for_symbol(seen, *field);
                 }
 
+                // Fields can be assigned other values later.
                 let fields = fields
                     .into_iter()
-                    .map(|(name, sym)| (name, self.symbols.get(sym).unwrap().ty))
+                    .map(|(name, sym)| {
+                        (
+                            name,
+                            self.non_literal_bool(self.symbols.get(sym).unwrap().ty),
+                        )
+                    })
                     .collect::<IndexMap<_, _>>();
 
-                self.symbols.get_mut(symbol).unwrap().ty = self.types.insert(TypeData {
+                self.symbols.get_mut(symbol).unwrap().ty = self.intern_type(TypeData {
                     source,
                     kind: TypeKind::Object(Object { fields }),
                     protected: false,
@@ -440,15 +487,86 @@ impl Hir {
                 let (lhs, rhs) = (b.lhs, b.rhs);
                 let lookup_text = b.lookup_text.clone();
 
-                let ty = if b.is_field_access() {
-                    lhs.map(|lhs| {
+                let range_inclusive = match &b.op {
+                    Some(BinaryOpKind::Regular(SyntaxKind::OP_RANGE)) => Some(false),
+                    Some(BinaryOpKind::Regular(SyntaxKind::OP_RANGE_INCLUSIVE)) => Some(true),
+                    _ => None,
+                };
+
+                let null_access = matches!(
+                    &b.op,
+                    Some(BinaryOpKind::Regular(SyntaxKind::OP_NULL_ACCESS))
+                );
+                let null_or = matches!(&b.op, Some(BinaryOpKind::Regular(SyntaxKind::OP_NULL_OR)));
+
+                let mut overload = None;
+
+                let ty = if let Some(inclusive) = range_inclusive {
+                    let mut items = self.builtin_types.unknown;
+
+                    for operand in [lhs, rhs].into_iter().flatten() {
+                        self.resolve_type_for_symbol(seen, operand);
+
+                        if !self[self[operand].ty].kind.is_unknown() {
+                            items = self[operand].ty;
+                            break;
+                        }
+                    }
+
+                    Some(self.intern_type(TypeData {
+                        source,
+                        kind: TypeKind::Range(Range { items, inclusive }),
+                        protected: false,
+                    }))
+                } else if b.is_field_access() {
+                    let object_ty = lhs.map(|lhs| {
                         self.resolve_type_for_symbol(seen, lhs);
-                        lhs
-                    })
-                    .and_then(|lhs| self[self[lhs].ty].kind.as_object())
-                    .and_then(|object| Some((object, rhs.and_then(|rhs| self[rhs].name(self))?)))
-                    .and_then(|(object, field_name)| object.fields.get(field_name))
-                    .copied()
+                        self[lhs].ty
+                    });
+
+                    let field_name = rhs
+                        .and_then(|rhs| self[rhs].name(self))
+                        .map(ToString::to_string);
+
+                    let field_ty = object_ty
+                        .zip(field_name)
+                        .and_then(|(ty, field_name)| self.field_type(source, ty, &field_name));
+
+                    // Fields are not resolved as references,
+                    // their types are only known from the object.
+                    if let Some(rhs) = rhs {
+                        if self[rhs].kind.is_reference() {
+                            seen.insert(rhs);
+                            self.symbols.get_mut(rhs).unwrap().ty =
+                                field_ty.unwrap_or(self.builtin_types.unknown);
+                        }
+                    }
+
+                    if null_access {
+                        field_ty.map(|ty| {
+                            let types = IndexSet::from_iter([ty, self.builtin_types.void]);
+                            self.union_of(source, types)
+                        })
+                    } else {
+                        field_ty
+                    }
+                } else if null_or {
+                    match (lhs, rhs) {
+                        (Some(lhs), Some(rhs)) => {
+                            self.resolve_type_for_symbol(seen, lhs);
+                            self.resolve_type_for_symbol(seen, rhs);
+
+                            // The right-hand side is only used if the left-hand side is `()`.
+                            let mut types = self
+                                .non_void_types(self[lhs].ty)
+                                .into_iter()
+                                .collect::<IndexSet<_>>();
+                            types.insert(self[rhs].ty);
+
+                            Some(self.union_of(source, types))
+                        }
+                        _ => None,
+                    }
                 } else {
                     match (lhs, rhs) {
                         (Some(lhs), Some(rhs)) => {
@@ -458,7 +576,7 @@ impl Hir {
                             let lhs_ty = self[lhs].ty;
                             let rhs_ty = self[rhs].ty;
 
-                            // (lhs, rhs, ret)
+                            // (op, lhs, rhs, ret)
                             let mut op_types = self
                                 .symbols
                                 .keys()
@@ -468,7 +586,7 @@ impl Hir {
                                             && op.lhs_ty.is(self, lhs_ty, false)
                                             && op.rhs_ty?.is(self, rhs_ty, false)
                                         {
-                                            Some((op.lhs_ty, op.rhs_ty?, op.ret_ty))
+                                            Some((sym, op.lhs_ty, op.rhs_ty?, op.ret_ty))
                                         } else {
                                             None
                                         }
@@ -480,27 +598,36 @@ impl Hir {
 
                             let exact_types = op_types
                                 .iter()
-                                .find(|(op_lhs, op_rhs, _)| {
+                                .find(|(_, op_lhs, op_rhs, _)| {
                                     op_lhs.is(self, lhs_ty, true) && op_rhs.is(self, rhs_ty, true)
                                 })
                                 .copied();
 
-                            exact_types.or_else(|| op_types.pop()).map(|(.., ty)| ty)
+                            let chosen = exact_types.or_else(|| op_types.pop());
+                            overload = chosen.map(|(op, ..)| op);
+                            chosen.map(|(.., ty)| ty)
                         }
                         _ => None,
                     }
                 };
 
-                if let Some(ty) = ty {
-                    self.symbols.get_mut(symbol).unwrap().ty = ty;
-                } else {
-                    self.symbols.get_mut(symbol).unwrap().ty = self.builtin_types.unknown;
+                let sym_data = self.symbols.get_mut(symbol).unwrap();
+                sym_data.ty = ty.unwrap_or(self.builtin_types.unknown);
+
+                if let SymbolKind::Binary(b) = &mut sym_data.kind {
+                    b.overload = overload;
                 }
             }
             SymbolKind::Unary(u) => {
                 let lookup_text = u.lookup_text.clone();
-                if let Some(rhs_ty) = u.rhs.map(|rhs| self[rhs].ty) {
-                    // (lhs/rhs, ret)
+                let (op, rhs) = (u.op, u.rhs);
+
+                if let Some(rhs) = rhs {
+                    self.resolve_type_for_symbol(seen, rhs);
+                }
+
+                if let Some(rhs_ty) = rhs.map(|rhs| self[rhs].ty) {
+                    // (op, lhs/rhs, ret)
                     let mut op_types = self
                         .symbols
                         .keys()
@@ -510,7 +637,7 @@ impl Hir {
                                     && op.rhs_ty.is_none()
                                     && op.lhs_ty.is(self, rhs_ty, false)
                                 {
-                                    Some((op.lhs_ty, op.ret_ty))
+                                    Some((sym, op.lhs_ty, op.ret_ty))
                                 } else {
                                     None
                                 }
@@ -522,16 +649,18 @@ impl Hir {
 
                     let exact_types = op_types
                         .iter()
-                        .find(|(op_lhs, _)| op_lhs.is(self, rhs_ty, true))
+                        .find(|(_, op_lhs, _)| op_lhs.is(self, rhs_ty, true))
                         .copied();
 
-                    let ty = exact_types
-                        .map(|(_, ret)| ret)
-                        .or_else(|| op_types.pop().map(|(_, ret)| ret))
-                        .or(Some(rhs_ty))
-                        .unwrap_or(self.builtin_types.unknown);
+                    let chosen = exact_types.or_else(|| op_types.pop());
+                    let builtin_ty = self.builtin_unary_type(op, rhs_ty);
 
-                    self.symbols.get_mut(symbol).unwrap().ty = ty;
+                    let sym_data = self.symbols.get_mut(symbol).unwrap();
+                    sym_data.ty = chosen.map_or(builtin_ty, |(.., ret)| ret);
+
+                    if let SymbolKind::Unary(u) = &mut sym_data.kind {
+                        u.overload = chosen.map(|(op, ..)| op);
+                    }
                 }
             }
             SymbolKind::Throw(_)
@@ -545,17 +674,871 @@ impl Hir {
             | SymbolKind::TypeDecl(_) => {
                 sym_data.ty = self.builtin_types.never;
             }
+            SymbolKind::For(f) => {
+                let cursor = f.cursor;
+                let bindings = f.bindings.clone();
+                sym_data.ty = self.builtin_types.void;
+
+                let item_ty = match cursor {
+                    Some(cursor) => {
+                        self.resolve_type_for_symbol(seen, cursor);
+                        self.iterated_type(self[cursor].ty)
+                    }
+                    None => self.builtin_types.unknown,
+                };
+
+                if let Some(&item) = bindings.first() {
+                    self.symbols.get_mut(item).unwrap().ty = item_ty;
+                }
+
+                if let Some(&counter) = bindings.get(1) {
+                    self.symbols.get_mut(counter).unwrap().ty = self.builtin_types.int;
+                }
+            }
             SymbolKind::Import(_)
             | SymbolKind::Export(_)
-            | SymbolKind::For(_)
             | SymbolKind::Loop(_)
             | SymbolKind::While(_) => {
                 sym_data.ty = self.builtin_types.void;
             }
         }
     }
+
+    /// The result of a unary operator without a definition:
+    /// `!` is always boolean and `-` or `+` keep numeric types.
+    fn builtin_unary_type(&self, op: Option<SyntaxKind>, rhs_ty: Type) -> Type {
+        let rhs_kind = &self[rhs_ty].kind;
+
+        match op {
+            Some(SyntaxKind::OP_NOT) => match rhs_kind.as_literal_bool() {
+                Some(true) => self.builtin_types.bool_false,
+                Some(false) => self.builtin_types.bool_true,
+                None => self.builtin_types.bool,
+            },
+            Some(SyntaxKind::OP_SUB | SyntaxKind::OP_ADD)
+                if rhs_kind.is_int() || rhs_kind.is_float() =>
+            {
+                rhs_ty
+            }
+            _ => self.builtin_types.unknown,
+        }
+    }
+
+    /// Insert the type, or reuse an existing type of the same
+    /// source with the same structure.
+    ///
+    /// Types are inferred from the types they contain, which are
+    /// interned before them, so contained types are compared by
+    /// their keys and cyclic types cannot occur.
+    pub(crate) fn intern_type(&mut self, data: TypeData) -> Type {
+        let Some(key) = TypeKey::of(&data.kind) else {
+            return self.types.insert(data);
+        };
+
+        let key = (data.source.source, key);
+
+        if let Some(&ty) = self.interned_types.get(&key) {
+            if self.types.contains_key(ty) {
+                return ty;
+            }
+        }
+
+        let ty = self.types.insert(data);
+        self.interned_types.insert(key, ty);
+        ty
+    }
+
+    /// The type of the field of an object type.
+    ///
+    /// For unions the field types of the object members are combined,
+    /// if the field might be missing because some members are not objects
+    /// or do not have the field, the result includes `()` as well.
+    /// Members that are `()` are ignored, as accessing their fields fails.
+    fn field_type(&mut self, source: SourceInfo, ty: Type, field_name: &str) -> Option<Type> {
+        let members = match &self[ty.unaliased(self)].kind {
+            TypeKind::Object(object) => return object.fields.get(field_name).copied(),
+            TypeKind::Union(members) => members.clone(),
+            _ => return None,
+        };
+
+        let mut types = IndexSet::default();
+        let mut is_certain = true;
+
+        for member in members {
+            match &self[member.unaliased(self)].kind {
+                TypeKind::Object(object) => match object.fields.get(field_name) {
+                    Some(&field_ty) => {
+                        types.insert(field_ty);
+                    }
+                    None => is_certain = false,
+                },
+                TypeKind::Void => {}
+                _ => is_certain = false,
+            }
+        }
+
+        if types.is_empty() {
+            return None;
+        }
+
+        if !is_certain {
+            types.insert(self.builtin_types.void);
+        }
+
+        Some(self.union_of(source, types))
+    }
+
+    /// The type, or the members of a union type
+    /// without `()`.
+    fn non_void_types(&self, ty: Type) -> Vec<Type> {
+        let members = match &self[ty].kind {
+            TypeKind::Union(members) => members.iter().copied().collect(),
+            _ => vec![ty],
+        };
+
+        members
+            .into_iter()
+            .filter(|&member| !self[member].kind.is_void())
+            .collect()
+    }
+
+    /// The type of the value of the scope, which is the value of its last
+    /// statement, `None` if the scope never finishes with a value.
+    fn tail_type(&mut self, seen: &mut HashSet<Symbol>, scope: Scope) -> Option<Type> {
+        let Some(tail) = self.scopes.get(scope).unwrap().symbols.last().copied() else {
+            return Some(self.builtin_types.void);
+        };
+
+        match &self.symbols.get(tail).unwrap().kind {
+            SymbolKind::Return(_)
+            | SymbolKind::Throw(_)
+            | SymbolKind::Break(_)
+            | SymbolKind::Continue(_) => None,
+            SymbolKind::Decl(_)
+            | SymbolKind::Fn(_)
+            | SymbolKind::Import(_)
+            | SymbolKind::Export(_)
+            | SymbolKind::For(_)
+            | SymbolKind::While(_) => Some(self.builtin_types.void),
+            _ => {
+                self.resolve_type_for_symbol(seen, tail);
+                Some(self.symbols.get(tail).unwrap().ty)
+            }
+        }
+    }
+
+    /// A union of the given types.
+    ///
+    /// Unions with more members than the configured maximum
+    /// are widened to a single type instead, see [`Hir::set_max_union_size`].
+    fn union_of(&mut self, source: SourceInfo, types: IndexSet<Type>) -> Type {
+        let mut types = self.canonical_bool_types(types);
+
+        if types.is_empty() {
+            self.builtin_types.void
+        } else if types.len() == 1 {
+            types.pop().unwrap()
+        } else if types.len() > self.max_union_size {
+            self.widened_type(source, &types)
+        } else {
+            self.intern_type(TypeData {
+                source,
+                kind: TypeKind::Union(types),
+                protected: false,
+            })
+        }
+    }
+
+    /// The types with `bool` instead of the literal boolean types
+    /// if the types include both `true` and `false`, or `bool`.
+    fn canonical_bool_types(&self, types: IndexSet<Type>) -> IndexSet<Type> {
+        let has = |b: bool| {
+            types
+                .iter()
+                .any(|&ty| self[ty].kind.as_literal_bool() == Some(b))
+        };
+
+        let has_bool = types.iter().any(|&ty| self[ty].kind.is_bool());
+
+        if !(has_bool || has(true) && has(false)) {
+            return types;
+        }
+
+        types
+            .into_iter()
+            .map(|ty| {
+                if self[ty].kind.is_boolean() {
+                    self.builtin_types.bool
+                } else {
+                    ty
+                }
+            })
+            .collect()
+    }
+
+    /// The type, or `bool` instead of a literal boolean type.
+    pub(crate) fn non_literal_bool(&self, ty: Type) -> Type {
+        if self[ty].kind.as_literal_bool().is_some() {
+            self.builtin_types.bool
+        } else {
+            ty
+        }
+    }
+
+    /// The types without the ones that are the same as a previous one,
+    /// e.g. the types of elements that are closures with the same signature.
+    ///
+    /// Boolean literals are kept apart from `bool`, they are
+    /// combined by [`Hir::union_of`] instead.
+    fn distinct_types(&self, types: IndexSet<Type>) -> IndexSet<Type> {
+        let mut distinct = IndexSet::default();
+
+        for ty in types {
+            if !distinct.iter().any(|&other: &Type| {
+                ty.is(self, other, true)
+                    && other.is(self, ty, true)
+                    && self[ty].kind.as_literal_bool() == self[other].kind.as_literal_bool()
+            }) {
+                distinct.insert(ty);
+            }
+        }
+
+        distinct
+    }
+
+    /// A type that covers all the given types.
+    ///
+    /// Types of the same kind are widened to that kind, mixed
+    /// numbers to `float`, and everything else to an unknown type.
+    fn widened_type(&mut self, source: SourceInfo, types: &IndexSet<Type>) -> Type {
+        let first = types[0];
+
+        let same_kind = types.iter().all(|&ty| {
+            core::mem::discriminant(&self[ty].kind) == core::mem::discriminant(&self[first].kind)
+        });
+
+        if !same_kind {
+            return if types
+                .iter()
+                .all(|&ty| matches!(self[ty].kind, TypeKind::Int | TypeKind::Float))
+            {
+                self.builtin_types.float
+            } else {
+                self.builtin_types.unknown
+            };
+        }
+
+        match &self[first].kind {
+            TypeKind::LiteralString(_) => self.builtin_types.string,
+            TypeKind::LiteralBool(_) => self.builtin_types.bool,
+            TypeKind::Module
+            | TypeKind::Int
+            | TypeKind::Float
+            | TypeKind::Bool
+            | TypeKind::Char
+            | TypeKind::String
+            | TypeKind::Timestamp
+            | TypeKind::Void
+            | TypeKind::Never => first,
+            TypeKind::Array(_) => self.intern_type(TypeData {
+                source,
+                kind: TypeKind::Array(Array {
+                    items: self.builtin_types.unknown,
+                }),
+                protected: false,
+            }),
+            _ => self.builtin_types.unknown,
+        }
+    }
+
+    /// The type of `this` at the given reference, the types of
+    /// all receivers of the function or closure that binds it.
+    fn this_type(&mut self, seen: &mut HashSet<Symbol>, reference: Symbol) -> Type {
+        let Some(binder) = self.this_binder(reference) else {
+            return self.builtin_types.unknown;
+        };
+
+        // Every method call is searched, so the receivers are only
+        // collected once for all references in the same function.
+        let receivers = match self.binder_receivers.get(&binder) {
+            Some(receivers) => receivers.clone(),
+            None => {
+                let receivers = self.this_receivers(binder);
+                self.binder_receivers.insert(binder, receivers.clone());
+                receivers
+            }
+        };
+
+        for &receiver in &receivers {
+            self.resolve_type_for_symbol(seen, receiver);
+        }
+
+        let types = receivers
+            .into_iter()
+            .map(|receiver| self[receiver].ty)
+            .filter(|&ty| !self[ty].kind.is_unknown())
+            .collect::<IndexSet<_>>();
+
+        if types.is_empty() {
+            return self.builtin_types.unknown;
+        }
+
+        let source = self[reference].source;
+        self.union_of(source, self.distinct_types(types))
+    }
+
+    /// The type of a parameter of a script function based on
+    /// the arguments of the calls of the function, the union of
+    /// the argument types if they differ.
+    ///
+    /// Calls with a different number of arguments call other overloads,
+    /// arguments whose types depend on the parameter itself
+    /// (e.g. in recursive calls) are unknown and ignored.
+    ///
+    /// Parameters of closures are only known for closures
+    /// passed to built-in higher-order methods.
+    fn param_type(&mut self, seen: &mut HashSet<Symbol>, param: Symbol) -> Type {
+        let scope = self[param].parent_scope;
+
+        let Some(function) = self[scope]
+            .parent
+            .and_then(|parent| parent.as_symbol().copied())
+        else {
+            return self.builtin_types.unknown;
+        };
+
+        if self[function].kind.is_closure() {
+            return self.closure_param_type(seen, function, param);
+        }
+
+        let Some(f) = self[function].kind.as_fn().filter(|f| !f.is_def) else {
+            return self.builtin_types.unknown;
+        };
+
+        let params = self
+            .scope_symbols(scope)
+            .take_while(|&sym| self[sym].is_param())
+            .collect::<Vec<_>>();
+
+        let Some(index) = params.iter().position(|&p| p == param) else {
+            return self.builtin_types.unknown;
+        };
+
+        let mut references = f.references.iter().copied().collect::<Vec<_>>();
+        references.sort_unstable();
+
+        let arguments = references
+            .into_iter()
+            .filter_map(|reference| self.call_of(reference))
+            .filter_map(|call| {
+                let arguments = &self[call].kind.as_call()?.arguments;
+                (arguments.len() == params.len()).then(|| arguments[index])
+            })
+            .collect::<Vec<_>>();
+
+        for &argument in &arguments {
+            self.resolve_type_for_symbol(seen, argument);
+        }
+
+        let types = arguments
+            .into_iter()
+            .map(|argument| self[argument].ty)
+            .filter(|&ty| !self[ty].kind.is_unknown())
+            .collect::<IndexSet<_>>();
+
+        if types.is_empty() {
+            return self.builtin_types.unknown;
+        }
+
+        let source = self[param].source;
+        self.union_of(source, self.distinct_types(types))
+    }
+
+    /// The type of the parameter of a closure that is passed to a
+    /// built-in higher-order method, e.g. `x` in `[1, 2].map(|x| x + 1)`
+    /// is an element of the array, see [`HIGHER_ORDER_FNS`].
+    fn closure_param_type(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        closure: Symbol,
+        param: Symbol,
+    ) -> Type {
+        let call = self
+            .scope_symbols(self[closure].parent_scope)
+            .find(|&symbol| {
+                self[symbol]
+                    .kind
+                    .as_call()
+                    .is_some_and(|call| call.arguments.first() == Some(&closure))
+            });
+
+        let Some((call, (receiver, element_param, result))) =
+            call.and_then(|call| Some((call, self.higher_order_call(call)?)))
+        else {
+            return self.builtin_types.unknown;
+        };
+
+        let index = self
+            .scope_symbols(self[param].parent_scope)
+            .take_while(|&sym| self[sym].is_param())
+            .position(|p| p == param);
+
+        if matches!(result, HigherOrderResult::Accumulator) && index == Some(0) {
+            return self
+                .initial_value_type(seen, call)
+                .unwrap_or(self.builtin_types.unknown);
+        }
+
+        if index != Some(element_param) {
+            return self.builtin_types.unknown;
+        }
+
+        self.resolve_type_for_symbol(seen, receiver);
+
+        match &self[self[receiver].ty.unaliased(self)].kind {
+            TypeKind::Array(arr) => arr.items,
+            _ => self.builtin_types.unknown,
+        }
+    }
+
+    /// The result type of a call of a built-in higher-order method,
+    /// `None` if the method is not one of [`HIGHER_ORDER_FNS`]
+    /// or the receiver is not an array.
+    fn higher_order_call_type(&mut self, seen: &mut HashSet<Symbol>, call: Symbol) -> Option<Type> {
+        let (receiver, _, result) = self.higher_order_call(call)?;
+
+        self.resolve_type_for_symbol(seen, receiver);
+
+        let receiver_ty = self[receiver].ty;
+        let items = self[receiver_ty.unaliased(self)].kind.as_array()?.items;
+
+        let closure_ret = match self[call].kind.as_call()?.arguments.first().copied() {
+            Some(closure) => {
+                self.resolve_type_for_symbol(seen, closure);
+                self[self[closure].ty.unaliased(self)]
+                    .kind
+                    .as_fn()
+                    .map_or(self.builtin_types.unknown, |f| f.ret)
+            }
+            None => self.builtin_types.unknown,
+        };
+
+        let source = self[call].source;
+
+        let ty = match result {
+            HigherOrderResult::MappedArray => self.intern_type(TypeData {
+                source,
+                kind: TypeKind::Array(Array { items: closure_ret }),
+                protected: false,
+            }),
+            HigherOrderResult::Receiver => receiver_ty,
+            HigherOrderResult::ClosureReturn => closure_ret,
+            HigherOrderResult::Accumulator => {
+                self.initial_value_type(seen, call).unwrap_or(closure_ret)
+            }
+            HigherOrderResult::Element => {
+                let types = IndexSet::from_iter([items, self.builtin_types.void]);
+                self.union_of(source, types)
+            }
+            HigherOrderResult::Bool => self.builtin_types.bool,
+        };
+
+        Some(ty)
+    }
+
+    /// The type of the initial value that is passed
+    /// after the closure to a method like `reduce`.
+    fn initial_value_type(&mut self, seen: &mut HashSet<Symbol>, call: Symbol) -> Option<Type> {
+        let initial = self[call].kind.as_call()?.arguments.get(1).copied()?;
+        self.resolve_type_for_symbol(seen, initial);
+        Some(self[initial].ty)
+    }
+
+    /// The receiver of a call of a built-in higher-order method, the
+    /// parameter of the closure that is an element of the receiver,
+    /// and how the result type is inferred.
+    fn higher_order_call(&self, call: Symbol) -> Option<(Symbol, usize, HigherOrderResult)> {
+        let binary = self[self[call].kind.as_call()?.lhs?].kind.as_binary()?;
+
+        if !binary.is_field_access() {
+            return None;
+        }
+
+        let name = self[binary.rhs?].name(self)?;
+
+        HIGHER_ORDER_FNS
+            .iter()
+            .find(|(method, ..)| *method == name)
+            .and_then(|&(_, element_param, result)| Some((binary.lhs?, element_param, result)))
+    }
+
+    /// The call that calls the expression, which is either
+    /// the reference itself or a path that ends with it.
+    pub(crate) fn call_of(&self, reference: Symbol) -> Option<Symbol> {
+        let scope = self[reference].parent_scope;
+
+        let callee = match self[scope].parent {
+            Some(ScopeParent::Symbol(path)) if self[path].kind.is_path() => path,
+            _ => reference,
+        };
+
+        self.scope_symbols(self[callee].parent_scope)
+            .find(|&symbol| {
+                self[symbol]
+                    .kind
+                    .as_call()
+                    .is_some_and(|call| call.lhs == Some(callee))
+            })
+    }
+
+    /// The type of the declaration at the reference based on the
+    /// assignments before it, see [`Hir::set_flow_typing`].
+    ///
+    /// Returns `None` if the assignments cannot be followed back
+    /// to the declaration (e.g. the reference is in a function or closure
+    /// that does not contain the declaration).
+    fn flow_type(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        reference: Symbol,
+        decl: Symbol,
+    ) -> Option<Type> {
+        let mut types = IndexSet::default();
+        let mut anchor = reference;
+
+        loop {
+            let scope = self[anchor].parent_scope;
+            self.scopes.get(scope)?;
+
+            if self.assigned_types(seen, decl, scope, Some(anchor), &mut types) {
+                break;
+            }
+
+            let parent = *self.scopes.get(scope)?.parent?.as_symbol()?;
+
+            match &self[parent].kind {
+                SymbolKind::Fn(_) | SymbolKind::Closure(_) => return None,
+                // Assignments later in the body run before
+                // the reference in the next iteration.
+                SymbolKind::Loop(_) | SymbolKind::While(_) | SymbolKind::For(_) => {
+                    let body = self.descendant_symbols(scope).collect::<Vec<_>>();
+                    for symbol in body {
+                        if let Some(ty) = self.assignment_type(seen, decl, symbol) {
+                            types.insert(ty);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            anchor = parent;
+        }
+
+        let source = self[reference].source;
+        Some(self.union_of(source, self.distinct_types(types)))
+    }
+
+    /// Collect the types the declaration can have after the symbols
+    /// of a scope that come before the given symbol, or after all
+    /// symbols of the scope.
+    ///
+    /// Returns whether a value is assigned to the declaration
+    /// in every case, so earlier symbols do not matter.
+    fn assigned_types(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        decl: Symbol,
+        scope: Scope,
+        before: Option<Symbol>,
+        types: &mut IndexSet<Type>,
+    ) -> bool {
+        let symbols = self[scope]
+            .symbols
+            .iter()
+            .copied()
+            .take_while(|&symbol| Some(symbol) != before)
+            .collect::<Vec<_>>();
+
+        // The values of switch arms are in the same scope as the (hoisted)
+        // switch, but only one of them runs, so the arms are handled
+        // together once the last one is found.
+        let arm_switches = self[scope]
+            .hoisted_symbols
+            .iter()
+            .filter_map(|&symbol| Some((symbol, self[symbol].kind.as_switch()?)))
+            .flat_map(|(symbol, switch)| {
+                switch
+                    .arms
+                    .iter()
+                    .filter_map(move |arm| Some((arm.value_expr?, symbol)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // The other arms of a switch do not run before an arm.
+        let mut handled_switches = before
+            .and_then(|before| arm_switches.get(&before).copied())
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        for symbol in symbols.into_iter().rev() {
+            if let Some(&switch) = arm_switches.get(&symbol) {
+                if handled_switches.insert(switch)
+                    && self.switch_assigned_types(seen, decl, switch, types)
+                {
+                    return true;
+                }
+
+                continue;
+            }
+
+            if symbol == decl {
+                // The value of a variable is its initial value at first.
+                let initial = self[decl]
+                    .kind
+                    .as_decl()
+                    .filter(|decl| decl.destructure.is_none() && decl.ty_decl.is_none())
+                    .and_then(|decl| decl.value)
+                    .map_or(self[decl].ty, |value| self[value].ty);
+                types.insert(initial);
+                return true;
+            }
+
+            if let Some(ty) = self.assignment_type(seen, decl, symbol) {
+                types.insert(ty);
+                return true;
+            }
+
+            // Whether the scopes always run, and the scopes.
+            let (exhaustive, scopes) = match &self[symbol].kind {
+                SymbolKind::Block(block) => (true, vec![block.scope]),
+                SymbolKind::If(if_symbol) => (
+                    if_symbol
+                        .branches
+                        .last()
+                        .is_some_and(|(condition, _)| condition.is_none()),
+                    if_symbol.branches.iter().map(|&(_, scope)| scope).collect(),
+                ),
+                SymbolKind::Loop(loop_symbol) => (false, vec![loop_symbol.scope]),
+                SymbolKind::While(while_symbol) => (false, vec![while_symbol.scope]),
+                SymbolKind::For(for_symbol) => (false, vec![for_symbol.scope]),
+                // The `try` block might not finish, the `catch`
+                // block runs instead in that case.
+                SymbolKind::Try(try_symbol) => {
+                    (true, vec![try_symbol.try_scope, try_symbol.catch_scope])
+                }
+                _ => continue,
+            };
+
+            let mut assigned = exhaustive;
+
+            for scope in scopes {
+                assigned &= self.assigned_types(seen, decl, scope, None, types);
+            }
+
+            if assigned {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Collect the types the declaration can have after a switch,
+    /// see [`Hir::assigned_types`].
+    fn switch_assigned_types(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        decl: Symbol,
+        switch: Symbol,
+        types: &mut IndexSet<Type>,
+    ) -> bool {
+        let Some(switch) = self[switch].kind.as_switch() else {
+            return false;
+        };
+
+        // Without a default arm none of the arms might run.
+        let exhaustive = switch.arms.iter().any(|arm| {
+            arm.condition_expr.is_none()
+                && arm.pat_expr.is_some_and(|pat| self[pat].kind.is_discard())
+        });
+        let values = switch
+            .arms
+            .iter()
+            .filter_map(|arm| arm.value_expr)
+            .collect::<Vec<_>>();
+
+        let mut assigned = exhaustive;
+
+        for value in values {
+            if let Some(ty) = self.assignment_type(seen, decl, value) {
+                types.insert(ty);
+            } else if let SymbolKind::Block(block) = &self[value].kind {
+                let scope = block.scope;
+                assigned &= self.assigned_types(seen, decl, scope, None, types);
+            } else {
+                assigned = false;
+            }
+        }
+
+        assigned
+    }
+
+    /// The type of the assigned value if the symbol is
+    /// an assignment (`=`) to the declaration.
+    fn assignment_type(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        decl: Symbol,
+        symbol: Symbol,
+    ) -> Option<Type> {
+        let binary = self[symbol].kind.as_binary()?;
+
+        if !binary.is_assignment() || binary.is_compound_assignment() {
+            return None;
+        }
+
+        let target = self[binary.lhs?].kind.as_reference()?.target.as_ref()?;
+
+        if target.as_symbol() != Some(&decl) {
+            return None;
+        }
+
+        let rhs = binary.rhs?;
+        self.resolve_type_for_symbol(seen, rhs);
+        Some(self[rhs].ty)
+    }
+
+    /// Find the `for` loop that declares the given pattern binding.
+    fn for_of_binding(&self, binding: Symbol) -> Option<Symbol> {
+        let for_symbol = *self
+            .scopes
+            .get(self.symbols.get(binding)?.parent_scope)?
+            .parent?
+            .as_symbol()?;
+
+        self[for_symbol]
+            .kind
+            .as_for()
+            .is_some_and(|f| f.bindings.contains(&binding))
+            .then_some(for_symbol)
+    }
+
+    /// The type of the part of a value that is bound
+    /// by a destructuring declaration.
+    ///
+    /// Array literals are destructured by their items
+    /// so that each position can have a different type.
+    fn destructured_type(&self, value: Symbol, destructure: &Destructure) -> Type {
+        if let (SymbolKind::Array(arr), Destructure::Index(idx)) = (&self[value].kind, destructure)
+        {
+            if let Some(&item) = arr.values.get(*idx) {
+                return self[item].ty;
+            }
+        }
+
+        match (&self[self[value].ty.unaliased(self)].kind, destructure) {
+            (TypeKind::Array(arr), Destructure::Index(_)) => arr.items,
+            (TypeKind::Tuple(items), Destructure::Index(idx)) => items
+                .get(*idx)
+                .copied()
+                .unwrap_or(self.builtin_types.unknown),
+            (TypeKind::Object(obj), Destructure::Field(name)) => obj
+                .fields
+                .get(name)
+                .copied()
+                .unwrap_or(self.builtin_types.unknown),
+            _ => self.builtin_types.unknown,
+        }
+    }
+
+    /// The type of the items produced by iterating over
+    /// a value of the given type.
+    fn iterated_type(&self, ty: Type) -> Type {
+        match &self[ty.unaliased(self)].kind {
+            TypeKind::Range(range) => range.items,
+            TypeKind::Array(arr) => arr.items,
+            TypeKind::String | TypeKind::LiteralString(_) => self.builtin_types.char,
+            _ => self.builtin_types.unknown,
+        }
+    }
+
+    /// The type name returned by a call of the builtin `type_of`
+    /// function with the given arguments, if the type of the argument is known.
+    fn type_of_call(
+        &mut self,
+        seen: &mut HashSet<Symbol>,
+        callee: Symbol,
+        arguments: &[Symbol],
+    ) -> Option<&'static str> {
+        let reference = self[callee].kind.as_reference()?;
+
+        if reference.name != "type_of" || arguments.len() != 1 {
+            return None;
+        }
+
+        // Script functions can shadow the builtin function.
+        if let Some(ReferenceTarget::Symbol(target)) = reference.target {
+            if self[target].kind.as_fn().is_some_and(|f| !f.is_def) {
+                return None;
+            }
+        }
+
+        let argument = arguments[0];
+        self.resolve_type_for_symbol(seen, argument);
+
+        let type_name = match &self[self[argument].ty.unaliased(self)].kind {
+            TypeKind::Int => self.numeric.int_type_name(),
+            TypeKind::Float => self.numeric.float_type_name(),
+            TypeKind::Bool | TypeKind::LiteralBool(_) => "bool",
+            TypeKind::Char => "char",
+            TypeKind::String | TypeKind::LiteralString(_) => "string",
+            TypeKind::Timestamp => "timestamp",
+            TypeKind::Array(_) => "array",
+            TypeKind::Object(_) => "map",
+            TypeKind::Range(range) if range.inclusive => "range=",
+            TypeKind::Range(_) => "range",
+            TypeKind::Fn(_) => "Fn",
+            TypeKind::Void => "()",
+            _ => return None,
+        };
+
+        Some(type_name)
+    }
 }
 
+/// How the result type of a built-in higher-order method follows from
+/// the receiver and the return type of the closure, see [`HIGHER_ORDER_FNS`].
+#[derive(Debug, Clone, Copy)]
+enum HigherOrderResult {
+    /// An array of the return type of the closure.
+    MappedArray,
+    /// The type of the receiver.
+    Receiver,
+    /// The return type of the closure.
+    ClosureReturn,
+    /// The type of the initial value of the accumulator,
+    /// or the return type of the closure without one.
+    Accumulator,
+    /// An element of the receiver, or `()` if there is none.
+    Element,
+    Bool,
+}
+
+/// Built-in array methods that take a closure, with the parameter of
+/// the closure that is an element of the array and the result type.
+const HIGHER_ORDER_FNS: &[(&str, usize, HigherOrderResult)] = &[
+    ("map", 0, HigherOrderResult::MappedArray),
+    ("filter", 0, HigherOrderResult::Receiver),
+    ("retain", 0, HigherOrderResult::Receiver),
+    ("drain", 0, HigherOrderResult::Receiver),
+    ("reduce", 1, HigherOrderResult::Accumulator),
+    ("reduce_rev", 1, HigherOrderResult::Accumulator),
+    ("find", 0, HigherOrderResult::Element),
+    ("find_map", 0, HigherOrderResult::ClosureReturn),
+    ("some", 0, HigherOrderResult::Bool),
+    ("all", 0, HigherOrderResult::Bool),
+];
+
 fn resolve_and_replace(
     types: &mut SlotMap<Type, TypeData>,
     builtin_types: BuiltinTypes,
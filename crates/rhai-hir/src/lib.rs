@@ -11,14 +11,21 @@
     clippy::too_many_arguments
 )]
 
+pub mod cache;
+pub mod cfg;
+pub mod diagnostic;
 pub mod error;
 pub mod eval;
 pub mod hir;
+pub mod lint;
 pub mod module;
 pub mod scope;
 pub mod source;
 pub mod symbol;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod ty;
+pub mod visit;
 pub(crate) mod util;
 pub mod fmt;
 
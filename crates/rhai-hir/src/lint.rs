@@ -0,0 +1,187 @@
+//! Configurable levels of the errors reported by the HIR.
+//!
+//! Every [`ErrorKind`] belongs to a [`Lint`] with a stable
+//! identifier, [`DiagnosticConfig`] overrides the default
+//! level of each lint or turns it off.
+//!
+//! [`ErrorKind`]: crate::error::ErrorKind
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// How errors of a lint are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintLevel {
+    Off,
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+/// Stable identifiers of the kinds of errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Lint {
+    DuplicateFnParameter,
+    UnresolvedReference,
+    UseBeforeDefinition,
+    SelfReferentialDeclaration,
+    ClosureCaptureMutation,
+    DeprecatedFunction,
+    PrivateFunction,
+    NonExhaustiveSwitch,
+    UnresolvedImport,
+    NestedFunction,
+    ImportInLoop,
+    MissingReturn,
+    /// Functions with `return` statements both with and without values.
+    InconsistentReturn,
+    UnusedFunction,
+    UnusedVariable,
+    /// Exports of imported modules that no other module uses.
+    UnusedExport,
+    DuplicateObjectField,
+    ConstantCondition,
+    /// `// TODO` and `// FIXME` comments.
+    TodoComment,
+    /// Delimiters without a matching pair.
+    UnmatchedDelimiter,
+    /// Literals with values that cannot be decoded.
+    InvalidLiteral,
+    /// Declarations with the names of functions of the prelude.
+    ShadowedBuiltin,
+    /// Arithmetic and comparisons between types that do not support them.
+    IncompatibleOperands,
+    /// Fields that constant objects do not have.
+    UnknownField,
+    /// Branches, loops and functions without statements.
+    EmptyBlock,
+}
+
+impl Lint {
+    /// The level of the lint if it is not configured.
+    #[must_use]
+    pub fn default_level(self) -> LintLevel {
+        match self {
+            Lint::DuplicateFnParameter
+            | Lint::UnresolvedReference
+            | Lint::UseBeforeDefinition
+            | Lint::SelfReferentialDeclaration
+            | Lint::PrivateFunction
+            | Lint::UnresolvedImport
+            | Lint::NestedFunction
+            | Lint::UnmatchedDelimiter
+            | Lint::InvalidLiteral => LintLevel::Error,
+            Lint::DeprecatedFunction
+            | Lint::NonExhaustiveSwitch
+            | Lint::ImportInLoop
+            | Lint::MissingReturn
+            | Lint::UnusedFunction
+            | Lint::DuplicateObjectField
+            | Lint::ConstantCondition
+            | Lint::ShadowedBuiltin
+            | Lint::UnknownField
+            | Lint::EmptyBlock => LintLevel::Warning,
+            Lint::ClosureCaptureMutation | Lint::TodoComment => LintLevel::Info,
+            Lint::UnusedVariable
+            | Lint::UnusedExport
+            | Lint::IncompatibleOperands
+            | Lint::InconsistentReturn => LintLevel::Hint,
+        }
+    }
+}
+
+/// The level of each lint, lints that are not set
+/// keep their default levels.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiagnosticConfig {
+    pub duplicate_fn_parameter: Option<LintLevel>,
+    pub unresolved_reference: Option<LintLevel>,
+    pub use_before_definition: Option<LintLevel>,
+    pub self_referential_declaration: Option<LintLevel>,
+    pub closure_capture_mutation: Option<LintLevel>,
+    pub deprecated_function: Option<LintLevel>,
+    pub private_function: Option<LintLevel>,
+    pub non_exhaustive_switch: Option<LintLevel>,
+    pub unresolved_import: Option<LintLevel>,
+    pub nested_function: Option<LintLevel>,
+    pub import_in_loop: Option<LintLevel>,
+    pub missing_return: Option<LintLevel>,
+    pub inconsistent_return: Option<LintLevel>,
+    pub unused_function: Option<LintLevel>,
+    pub unused_variable: Option<LintLevel>,
+    pub unused_export: Option<LintLevel>,
+    pub duplicate_object_field: Option<LintLevel>,
+    pub constant_condition: Option<LintLevel>,
+    pub todo_comment: Option<LintLevel>,
+    pub unmatched_delimiter: Option<LintLevel>,
+    pub invalid_literal: Option<LintLevel>,
+    pub shadowed_builtin: Option<LintLevel>,
+    pub incompatible_operands: Option<LintLevel>,
+    pub unknown_field: Option<LintLevel>,
+    pub empty_block: Option<LintLevel>,
+    /// Report `while true` loops as constant conditions,
+    /// these are usually intentional.
+    #[serde(skip)]
+    pub while_true: bool,
+    /// Do not report empty `else` branches.
+    #[serde(skip)]
+    pub allow_empty_else: bool,
+    /// Do not report empty loop bodies, e.g. of loops
+    /// that wait for their conditions.
+    #[serde(skip)]
+    pub allow_empty_loop: bool,
+}
+
+impl DiagnosticConfig {
+    /// The configured level of the lint.
+    #[must_use]
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        let level = match lint {
+            Lint::DuplicateFnParameter => self.duplicate_fn_parameter,
+            Lint::UnresolvedReference => self.unresolved_reference,
+            Lint::UseBeforeDefinition => self.use_before_definition,
+            Lint::SelfReferentialDeclaration => self.self_referential_declaration,
+            Lint::ClosureCaptureMutation => self.closure_capture_mutation,
+            Lint::DeprecatedFunction => self.deprecated_function,
+            Lint::PrivateFunction => self.private_function,
+            Lint::NonExhaustiveSwitch => self.non_exhaustive_switch,
+            Lint::UnresolvedImport => self.unresolved_import,
+            Lint::NestedFunction => self.nested_function,
+            Lint::ImportInLoop => self.import_in_loop,
+            Lint::MissingReturn => self.missing_return,
+            Lint::InconsistentReturn => self.inconsistent_return,
+            Lint::UnusedFunction => self.unused_function,
+            Lint::UnusedVariable => self.unused_variable,
+            Lint::UnusedExport => self.unused_export,
+            Lint::DuplicateObjectField => self.duplicate_object_field,
+            Lint::ConstantCondition => self.constant_condition,
+            Lint::TodoComment => self.todo_comment,
+            Lint::UnmatchedDelimiter => self.unmatched_delimiter,
+            Lint::InvalidLiteral => self.invalid_literal,
+            Lint::ShadowedBuiltin => self.shadowed_builtin,
+            Lint::IncompatibleOperands => self.incompatible_operands,
+            Lint::UnknownField => self.unknown_field,
+            Lint::EmptyBlock => self.empty_block,
+        };
+
+        level.unwrap_or_else(|| lint.default_level())
+    }
+
+    /// Pair the errors with their configured levels,
+    /// errors of lints that are turned off are left out.
+    pub fn apply(&self, errors: impl IntoIterator<Item = Error>) -> Vec<(Error, LintLevel)> {
+        errors
+            .into_iter()
+            .map(|error| {
+                let level = self.level(error.kind.lint());
+                (error, level)
+            })
+            .filter(|(_, level)| *level != LintLevel::Off)
+            .collect()
+    }
+}
@@ -1,11 +1,12 @@
 use std::{ffi::OsStr, path::Path};
 
 use crate::{source::Source, Hir, IndexSet, Scope};
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 slotmap::new_key_type! { pub struct Module; }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub enum ModuleKind {
     /// The static module is the root of every Rhai script,
     /// items and modules defined in the scope of the static module
@@ -31,7 +32,7 @@ impl core::fmt::Display for ModuleKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleData {
     pub scope: Scope,
     pub kind: ModuleKind,
@@ -54,6 +55,46 @@ impl ModuleData {
 
 pub const STATIC_URL_SCHEME: &str = "rhai-static";
 
+/// The URL of the source added by [`Hir::add_prelude`].
+pub const PRELUDE_URL: &str = "rhai-prelude:///prelude.d.rhai";
+
+/// Definitions of the built-in functions available in every script.
+pub const PRELUDE: &str = include_str!("prelude.d.rhai");
+
+/// The URL scheme of the definitions of functions
+/// that are provided by an [`ExternalResolver`].
+pub const EXTERNAL_URL_SCHEME: &str = "rhai-external";
+
+/// Used to look up functions that the host registers with its Rhai
+/// engine at runtime, see [`Hir::set_external_resolver`].
+///
+/// The definitions of the functions are added to the static module
+/// like the ones of the prelude, so references to them resolve and
+/// they are offered in completions.
+pub trait ExternalResolver: Send + Sync {
+    /// The definition of the global function with the given name that
+    /// accepts the given number of arguments, if there is one.
+    ///
+    /// The definition is in the syntax of definition files
+    /// and can have documentation, e.g.:
+    ///
+    /// ```rhai
+    /// /// Add two numbers.
+    /// fn add(a: int, b: int) -> int;
+    /// ```
+    ///
+    /// This function is called for calls of functions that
+    /// are not declared otherwise.
+    fn resolve_fn(&self, name: &str, arity: usize) -> Option<String>;
+
+    /// The names and the numbers of arguments of the functions that
+    /// are known in advance, their definitions are looked up even
+    /// if they are not called, e.g. for completions.
+    fn known_fns(&self) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+}
+
 /// Used to resolve module URLs for import statements and definitions.
 pub trait ModuleResolver: Send + Sync {
     /// Construct an URL for a module that should be imported
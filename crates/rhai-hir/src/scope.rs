@@ -1,14 +1,18 @@
 use crate::{source::SourceInfo, HashSet, IndexSet, Symbol};
+use serde::{Deserialize, Serialize};
 
 slotmap::new_key_type! { pub struct Scope; }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct ScopeData {
     pub source: SourceInfo,
     pub parent: Option<ScopeParent>,
     pub symbols: IndexSet<Symbol>,
     pub hoisted_symbols: HashSet<Symbol>,
+    /// The last statement if it is not followed by a semicolon,
+    /// its value is the value of the scope.
+    pub tail: Option<Symbol>,
 }
 
 impl ScopeData {
@@ -30,9 +34,16 @@ impl ScopeData {
     pub fn is_empty(&self) -> bool {
         self.symbol_count() == 0
     }
+
+    /// The insertion index of a non-hoisted symbol in the scope,
+    /// which is also the textual order of the symbols.
+    #[must_use]
+    pub fn symbol_index(&self, symbol: Symbol) -> Option<usize> {
+        self.symbols.get_index_of(&symbol)
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ScopeParent {
     Scope(Scope),
     Symbol(Symbol),
@@ -1,11 +1,17 @@
 use super::module::Module;
-use crate::{eval::Value, source::SourceInfo, ty::Type, HashSet, Hir, IndexMap, Scope};
+use crate::{
+    eval::{InvalidLiteral, Value},
+    source::SourceInfo,
+    ty::Type,
+    HashSet, Hir, IndexMap, Scope,
+};
 use rhai_rowan::{syntax::SyntaxKind, TextRange};
+use serde::{Deserialize, Serialize};
 use strum::IntoStaticStr;
 
 slotmap::new_key_type! { pub struct Symbol; }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct SymbolData {
     pub source: SourceInfo,
@@ -97,7 +103,7 @@ impl SymbolData {
     }
 }
 
-#[derive(Debug, Clone, IntoStaticStr)]
+#[derive(Debug, Clone, IntoStaticStr, Serialize, Deserialize)]
 pub enum SymbolKind {
     Block(BlockSymbol),
     Fn(FnSymbol),
@@ -670,12 +676,13 @@ impl SymbolKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockSymbol {
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FnSymbol {
     pub name: String,
     pub docs: String,
@@ -684,10 +691,56 @@ pub struct FnSymbol {
     pub getter: bool,
     pub setter: bool,
     pub is_def: bool,
+    /// Whether the function is marked `private`,
+    /// private functions cannot be used from other modules.
+    pub private: bool,
     pub ret_ty: Type,
+    pub tags: DocTags,
+}
+
+/// Metadata collected from `@tag` lines in documentation.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DocTags {
+    /// Set by `@deprecated`, optionally followed by a reason
+    /// or a suggested replacement on the same line.
+    pub deprecated: Option<String>,
+}
+
+impl DocTags {
+    /// Collect the tags from documentation text,
+    /// lines in code blocks are ignored.
+    #[must_use]
+    pub fn parse(docs: &str) -> Self {
+        let mut tags = Self::default();
+        let mut in_code_block = false;
+
+        for line in docs.lines().map(str::trim) {
+            if line.starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+
+            if in_code_block {
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix("@deprecated") {
+                if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                    tags.deprecated = Some(rest.trim().to_string());
+                }
+            }
+        }
+
+        tags
+    }
+
+    #[must_use]
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.is_some()
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct OpSymbol {
     pub name: String,
     pub docs: String,
@@ -717,7 +770,7 @@ impl OpSymbol {
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DeclSymbol {
     pub name: String,
     pub docs: String,
@@ -733,9 +786,25 @@ pub struct DeclSymbol {
     /// however in some cases they can delegate the resolution
     /// to a target, e.g. in case of module aliases.
     pub target: Option<ReferenceTarget>,
+    /// The part of the value the declaration is bound to
+    /// in case of destructuring, e.g. `let [a, b] = arr`.
+    pub destructure: Option<Destructure>,
+    /// The variable is not declared, but assigned to at the script level,
+    /// see [`Hir::set_implicit_globals`].
+    ///
+    /// [`Hir::set_implicit_globals`]: crate::Hir::set_implicit_globals
+    pub is_implicit: bool,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Destructure {
+    /// An array or tuple item at the given position.
+    Index(usize),
+    /// An object field with the given name.
+    Field(String),
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ReferenceSymbol {
     pub target: Option<ReferenceTarget>,
     pub part_of_path: bool,
@@ -743,42 +812,68 @@ pub struct ReferenceSymbol {
     pub name: String,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct PathSymbol {
     pub scope: Scope,
     pub segments: Vec<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct LitSymbol {
     pub value: Value,
+    /// The reason why the value is unknown if the literal is invalid.
+    pub invalid: Option<InvalidLiteral>,
     pub interpolated_scopes: Vec<Scope>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnarySymbol {
     pub lookup_text: String,
     pub op: Option<SyntaxKind>,
+    /// The range of the operator token.
+    pub op_range: Option<TextRange>,
     pub rhs: Option<Symbol>,
+    /// The operator definition chosen based on the operand type.
+    pub overload: Option<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinarySymbol {
     pub scope: Scope,
     pub lookup_text: String,
     pub lhs: Option<Symbol>,
     pub op: Option<BinaryOpKind>,
+    /// The range of the operator token.
+    pub op_range: Option<TextRange>,
     pub rhs: Option<Symbol>,
+    /// The operator definition chosen based on the operand types.
+    pub overload: Option<Symbol>,
 }
 
 impl BinarySymbol {
+    /// Whether the operator is a field access (`.`)
+    /// or a safe navigation (`?.`).
     #[must_use]
     pub fn is_field_access(&self) -> bool {
-        self.lookup_text == "."
+        matches!(self.lookup_text.as_str(), "." | "?.")
+    }
+
+    /// Whether the operator is an assignment (`=`) or
+    /// a compound assignment (e.g. `+=`).
+    #[must_use]
+    pub fn is_assignment(&self) -> bool {
+        matches!(&self.op, Some(BinaryOpKind::Regular(op)) if op.is_assign_op())
+    }
+
+    /// Whether the operator is a compound assignment (e.g. `+=`)
+    /// that both reads and writes the left-hand side.
+    #[must_use]
+    pub fn is_compound_assignment(&self) -> bool {
+        matches!(&self.op, Some(BinaryOpKind::Regular(op)) if op.is_compound_assign_op())
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BinaryOpKind {
     Regular(SyntaxKind),
     Custom(CustomBinaryOp),
@@ -820,35 +915,47 @@ impl BinaryOpKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomBinaryOp {
     pub name: String,
     pub range: TextRange,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArraySymbol {
     pub values: Vec<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexSymbol {
     pub base: Option<Symbol>,
     pub index: Option<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+/// A call of any of the forms:
+///
+/// - `f(x)`, the callee is a reference.
+/// - `f!(x)`, a call that shares the scope of the caller,
+///   the callee is a reference.
+/// - `m::f(x)`, the callee is a path.
+/// - `x.f(y)`, a method call, the callee is the `.` binary expression
+///   and the receiver is not one of the arguments.
+/// - `(f)(x)` or `f(x)(y)`, calls of any other expressions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallSymbol {
     pub lhs: Option<Symbol>,
     pub arguments: Vec<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObjectSymbol {
     pub fields: IndexMap<String, ObjectField>,
+    /// Fields that are overwritten by a later field
+    /// with the same name, in the order they appear.
+    pub overwritten_fields: Vec<ObjectField>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObjectField {
     pub property_syntax: SourceInfo,
     pub property_name: String,
@@ -856,72 +963,80 @@ pub struct ObjectField {
     pub value: Option<Symbol>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThrowSymbol {
     pub expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ClosureSymbol {
     pub scope: Scope,
     pub expr: Option<Symbol>,
+    /// Declarations outside of the closure that are referenced
+    /// in its body.
+    ///
+    /// This is set during reference resolution.
+    pub captures: Vec<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct IfSymbol {
     /// Conditions and scopes for each branch.
     pub branches: Vec<(Option<Symbol>, Scope)>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct LoopSymbol {
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ForSymbol {
     pub cursor: Option<Symbol>,
+    /// The declarations in the loop pattern,
+    /// the item and the optional counter.
+    pub bindings: Vec<Symbol>,
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct WhileSymbol {
     pub condition: Option<Symbol>,
     pub scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BreakSymbol {
     pub expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ContinueSymbol {}
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ReturnSymbol {
     pub expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SwitchSymbol {
     pub target: Option<Symbol>,
     pub arms: Vec<SwitchArm>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SwitchArm {
     pub pat_expr: Option<Symbol>,
     pub condition_expr: Option<Symbol>,
     pub value_expr: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ExportSymbol {
     pub target: Option<Symbol>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ImportSymbol {
     pub scope: Scope,
     pub expr: Option<Symbol>,
@@ -937,16 +1052,56 @@ impl ImportSymbol {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TrySymbol {
     pub try_scope: Scope,
     pub catch_scope: Scope,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DiscardSymbol {}
 
-#[derive(Debug, Clone, Copy)]
+/// The way a symbol accesses the value it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceAccess {
+    Read,
+    Write,
+    /// Compound assignments (e.g. `x += 1`) both read and write.
+    ReadWrite,
+}
+
+impl ReferenceAccess {
+    /// Returns `true` if the value is read.
+    #[must_use]
+    pub fn is_read(&self) -> bool {
+        matches!(self, Self::Read | Self::ReadWrite)
+    }
+
+    /// Returns `true` if the value is written.
+    #[must_use]
+    pub fn is_write(&self) -> bool {
+        matches!(self, Self::Write | Self::ReadWrite)
+    }
+}
+
+/// A change in name resolution that renaming a symbol would cause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameCollision {
+    /// A reference to the renamed symbol would resolve
+    /// to the given declaration instead.
+    Captured {
+        reference: Symbol,
+        declaration: Symbol,
+    },
+    /// A reference to the given declaration would resolve
+    /// to the renamed symbol instead.
+    Shadowed {
+        reference: Symbol,
+        declaration: Option<Symbol>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ReferenceTarget {
     Symbol(Symbol),
     Module(Module),
@@ -990,7 +1145,7 @@ impl ReferenceTarget {
 
 /// A symbol that does not and cannot originate
 /// from source code and was injected into the hir.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VirtualSymbol {
     Proxy(VirtualProxySymbol),
     Module(VirtualModuleSymbol),
@@ -1052,14 +1207,14 @@ impl VirtualSymbol {
 }
 
 /// A symbol that points to some other symbols transparently.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct VirtualProxySymbol {
     pub target: Symbol,
 }
 
 /// A symbol that is used to inject modules into scopes
 /// without an import statement.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VirtualModuleSymbol {
     pub name: String,
     pub module: Module,
@@ -1068,13 +1223,13 @@ pub struct VirtualModuleSymbol {
 /// A symbol that points to some other symbol under an alias.
 /// 
 /// Has the same semantics as [`VirtualModuleSymbol`].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VirtualAliasSymbol {
     pub name: String,
     pub target: Symbol,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeDeclSymbol {
     pub docs: String,
     pub ty: Type,
@@ -0,0 +1,28 @@
+//! Helpers for tests that only need a HIR of a single script.
+
+use rhai_rowan::parser::Parser;
+use url::Url;
+
+use crate::{Hir, Module};
+
+/// The URL of the script added by [`build`].
+pub const TEST_URL: &str = "test:///test.rhai";
+
+/// Parse the script, add it to an empty HIR and resolve
+/// all references and types.
+///
+/// # Panics
+///
+/// Panics if the script was not added as a module,
+/// syntax errors are not checked.
+#[must_use]
+pub fn build(src: &str) -> (Hir, Module) {
+    let url: Url = TEST_URL.parse().unwrap();
+
+    let mut hir = Hir::new();
+    hir.add_source(&url, &Parser::new(src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let module = hir.module_by_url(&url).unwrap();
+    (hir, module)
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 use crate::{source::SourceInfo, Hir, IndexMap, IndexSet};
 use core::fmt;
+use serde::{Deserialize, Serialize};
 
 slotmap::new_key_type! { pub struct Type; }
 
@@ -10,10 +11,31 @@ impl Type {
         TypeFormatter { hir, ty: self }
     }
 
+    /// Follow type aliases until the aliased type.
+    #[must_use]
+    pub fn unaliased(self, hir: &Hir) -> Type {
+        let mut ty = self;
+        let mut depth = 0;
+
+        // The depth limit guards against cyclic aliases.
+        while let TypeKind::Alias(_, aliased) = &hir[ty].kind {
+            if depth > 32 {
+                break;
+            }
+
+            ty = *aliased;
+            depth += 1;
+        }
+
+        ty
+    }
+
     /// Type deep equality comparison to other type via the HIR.
     ///
     /// If `exact` is false, types are always equal if at least one of them
     /// are unknown.
+    ///
+    /// Boolean literal types are the same as `bool`, but not as each other.
     #[must_use]
     pub fn is(self, hir: &Hir, other: Type, exact: bool) -> bool {
         if self == other {
@@ -25,9 +47,16 @@ impl Type {
 
         match (&this.kind, &other.kind) {
             (TypeKind::Unknown, _) | (_, TypeKind::Unknown) => !exact,
-            (TypeKind::Unresolved(ty1), TypeKind::Unresolved(ty2)) => ty1 == ty2,
+            (TypeKind::LiteralBool(b1), TypeKind::LiteralBool(b2)) => b1 == b2,
+            (TypeKind::LiteralBool(_), TypeKind::Bool)
+            | (TypeKind::Bool, TypeKind::LiteralBool(_)) => true,
+            (TypeKind::Unresolved(ty1), TypeKind::Unresolved(ty2))
+            | (TypeKind::LiteralString(ty1), TypeKind::LiteralString(ty2)) => ty1 == ty2,
             (TypeKind::Alias(_, ty1), TypeKind::Alias(_, ty2)) => ty1.is(hir, *ty2, true),
             (TypeKind::Array(arr1), TypeKind::Array(arr2)) => arr1.items.is(hir, arr2.items, true),
+            (TypeKind::Range(r1), TypeKind::Range(r2)) => {
+                r1.inclusive == r2.inclusive && r1.items.is(hir, r2.items, true)
+            }
             (TypeKind::Fn(f1), TypeKind::Fn(f2)) => {
                 f1.params.len() == f2.params.len()
                     && f1
@@ -58,7 +87,7 @@ impl Type {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct TypeData {
     pub source: SourceInfo,
@@ -78,11 +107,16 @@ impl core::fmt::Display for TypeFormatter<'_> {
 
         match &data.kind {
             TypeKind::Module => f.write_str("module")?,
+            // The Rhai names are used for the default widths.
+            TypeKind::Int if self.hir.numeric.only_i32 => f.write_str("i32")?,
             TypeKind::Int => f.write_str("int")?,
+            TypeKind::Float if self.hir.numeric.f32_float => f.write_str("f32")?,
             TypeKind::Float => f.write_str("float")?,
             TypeKind::Bool => f.write_str("bool")?,
+            TypeKind::LiteralBool(value) => write!(f, "{value}")?,
             TypeKind::Char => f.write_str("char")?,
             TypeKind::String => f.write_str("String")?,
+            TypeKind::LiteralString(value) => write!(f, "{value:?}")?,
             TypeKind::Timestamp => f.write_str("timestamp")?,
             TypeKind::Tuple(tys) => {
                 f.write_str("(")?;
@@ -103,6 +137,15 @@ impl core::fmt::Display for TypeFormatter<'_> {
                 write!(f, "{}", arr.items.fmt(self.hir))?;
                 f.write_str("]")?;
             }
+            TypeKind::Range(range) => {
+                if range.inclusive {
+                    f.write_str("range=<")?;
+                } else {
+                    f.write_str("range<")?;
+                }
+                write!(f, "{}", range.items.fmt(self.hir))?;
+                f.write_str(">")?;
+            }
             TypeKind::Object(obj) => {
                 f.write_str("#{")?;
 
@@ -165,16 +208,24 @@ impl core::fmt::Display for TypeFormatter<'_> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeKind {
     Module,
     Int,
     Float,
+    /// Either `true` or `false`, unions of both
+    /// literal boolean types are always this type instead.
     Bool,
+    /// The type of the `true` or `false` literal.
+    LiteralBool(bool),
     Char,
     String,
+    /// A string with a value known in advance,
+    /// e.g. the result of `type_of`.
+    LiteralString(String),
     Timestamp,
     Array(Array),
+    Range(Range),
     Object(Object),
     Union(IndexSet<Type>),
     Void,
@@ -221,6 +272,24 @@ impl TypeKind {
         matches!(self, Self::Bool)
     }
 
+    #[must_use]
+    pub fn as_literal_bool(&self) -> Option<bool> {
+        if let Self::LiteralBool(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the type kind is [`Bool`] or [`LiteralBool`].
+    ///
+    /// [`Bool`]: TypeKind::Bool
+    /// [`LiteralBool`]: TypeKind::LiteralBool
+    #[must_use]
+    pub fn is_boolean(&self) -> bool {
+        matches!(self, Self::Bool | Self::LiteralBool(..))
+    }
+
     /// Returns `true` if the type kind is [`Char`].
     ///
     /// [`Char`]: TypeKind::Char
@@ -237,6 +306,23 @@ impl TypeKind {
         matches!(self, Self::String)
     }
 
+    /// Returns `true` if the type kind is [`LiteralString`].
+    ///
+    /// [`LiteralString`]: TypeKind::LiteralString
+    #[must_use]
+    pub fn is_literal_string(&self) -> bool {
+        matches!(self, Self::LiteralString(..))
+    }
+
+    #[must_use]
+    pub fn as_literal_string(&self) -> Option<&str> {
+        if let Self::LiteralString(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the type kind is [`Timestamp`].
     ///
     /// [`Timestamp`]: TypeKind::Timestamp
@@ -262,6 +348,23 @@ impl TypeKind {
         }
     }
 
+    /// Returns `true` if the type kind is [`Range`].
+    ///
+    /// [`Range`]: TypeKind::Range
+    #[must_use]
+    pub fn is_range(&self) -> bool {
+        matches!(self, Self::Range(..))
+    }
+
+    #[must_use]
+    pub fn as_range(&self) -> Option<&Range> {
+        if let Self::Range(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Returns `true` if the type kind is [`Object`].
     ///
     /// [`Object`]: TypeKind::Object
@@ -377,17 +480,71 @@ impl Default for TypeKind {
     }
 }
 
-#[derive(Debug, Clone)]
+/// The structure of a compound type with the keys of the types
+/// it contains, types with equal keys are interchangeable.
+///
+/// Object fields and union members are sorted, so their order
+/// does not matter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum TypeKey {
+    LiteralString(String),
+    Array(Type),
+    Range(Type, bool),
+    Object(Vec<(String, Type)>),
+    Union(Vec<Type>),
+    Fn(bool, Vec<(String, Type)>, Type),
+    Tuple(Vec<Type>),
+}
+
+impl TypeKey {
+    /// `None` for types that are not interned.
+    pub(crate) fn of(kind: &TypeKind) -> Option<Self> {
+        let key = match kind {
+            TypeKind::LiteralString(s) => Self::LiteralString(s.clone()),
+            TypeKind::Array(arr) => Self::Array(arr.items),
+            TypeKind::Range(r) => Self::Range(r.items, r.inclusive),
+            TypeKind::Object(obj) => {
+                let mut fields = obj
+                    .fields
+                    .iter()
+                    .map(|(name, &ty)| (name.clone(), ty))
+                    .collect::<Vec<_>>();
+                fields.sort();
+                Self::Object(fields)
+            }
+            TypeKind::Union(members) => {
+                let mut members = members.iter().copied().collect::<Vec<_>>();
+                members.sort();
+                Self::Union(members)
+            }
+            TypeKind::Fn(f) => Self::Fn(f.is_closure, f.params.clone(), f.ret),
+            TypeKind::Tuple(items) => Self::Tuple(items.clone()),
+            _ => return None,
+        };
+
+        Some(key)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Object {
     pub fields: IndexMap<String, Type>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Array {
     pub items: Type,
 }
 
-#[derive(Debug, Clone)]
+/// A range of values created with `..` or `..=`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Range {
+    pub items: Type,
+    /// Whether the end of the range is included (`..=`).
+    pub inclusive: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Function {
     pub is_closure: bool,
     pub params: Vec<(String, Type)>,
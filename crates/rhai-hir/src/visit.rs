@@ -0,0 +1,168 @@
+//! Traversal of symbols and their children.
+//!
+//! Analyses implement [`Visitor`] and override the methods
+//! of the symbol kinds they are interested in, [`walk_symbol`],
+//! [`walk_scope`] and [`walk_module`] take care of visiting
+//! every child symbol exactly once.
+
+use crate::{symbol::*, Hir, Module, Scope, Symbol};
+
+/// Callbacks for the symbols visited by the walk functions.
+///
+/// All methods do nothing by default, the walk functions
+/// visit the children regardless of what the methods do.
+#[allow(unused_variables)]
+pub trait Visitor {
+    /// Called for every symbol before the method of its kind.
+    fn visit_symbol(&mut self, hir: &Hir, symbol: Symbol) {}
+
+    fn visit_block(&mut self, hir: &Hir, symbol: Symbol, block: &BlockSymbol) {}
+    fn visit_fn(&mut self, hir: &Hir, symbol: Symbol, f: &FnSymbol) {}
+    fn visit_op(&mut self, hir: &Hir, symbol: Symbol, op: &OpSymbol) {}
+    fn visit_decl(&mut self, hir: &Hir, symbol: Symbol, decl: &DeclSymbol) {}
+    fn visit_ref(&mut self, hir: &Hir, symbol: Symbol, reference: &ReferenceSymbol) {}
+    fn visit_path(&mut self, hir: &Hir, symbol: Symbol, path: &PathSymbol) {}
+    fn visit_lit(&mut self, hir: &Hir, symbol: Symbol, lit: &LitSymbol) {}
+    fn visit_unary(&mut self, hir: &Hir, symbol: Symbol, unary: &UnarySymbol) {}
+    fn visit_binary(&mut self, hir: &Hir, symbol: Symbol, binary: &BinarySymbol) {}
+    fn visit_array(&mut self, hir: &Hir, symbol: Symbol, array: &ArraySymbol) {}
+    fn visit_index(&mut self, hir: &Hir, symbol: Symbol, index: &IndexSymbol) {}
+    fn visit_object(&mut self, hir: &Hir, symbol: Symbol, object: &ObjectSymbol) {}
+    fn visit_call(&mut self, hir: &Hir, symbol: Symbol, call: &CallSymbol) {}
+    fn visit_closure(&mut self, hir: &Hir, symbol: Symbol, closure: &ClosureSymbol) {}
+    fn visit_if(&mut self, hir: &Hir, symbol: Symbol, if_symbol: &IfSymbol) {}
+    fn visit_loop(&mut self, hir: &Hir, symbol: Symbol, loop_symbol: &LoopSymbol) {}
+    fn visit_for(&mut self, hir: &Hir, symbol: Symbol, for_symbol: &ForSymbol) {}
+    fn visit_while(&mut self, hir: &Hir, symbol: Symbol, while_symbol: &WhileSymbol) {}
+    fn visit_break(&mut self, hir: &Hir, symbol: Symbol, break_symbol: &BreakSymbol) {}
+    fn visit_continue(&mut self, hir: &Hir, symbol: Symbol, continue_symbol: &ContinueSymbol) {}
+    fn visit_return(&mut self, hir: &Hir, symbol: Symbol, return_symbol: &ReturnSymbol) {}
+    fn visit_switch(&mut self, hir: &Hir, symbol: Symbol, switch: &SwitchSymbol) {}
+    fn visit_export(&mut self, hir: &Hir, symbol: Symbol, export: &ExportSymbol) {}
+    fn visit_try(&mut self, hir: &Hir, symbol: Symbol, try_symbol: &TrySymbol) {}
+    fn visit_throw(&mut self, hir: &Hir, symbol: Symbol, throw: &ThrowSymbol) {}
+    fn visit_import(&mut self, hir: &Hir, symbol: Symbol, import: &ImportSymbol) {}
+    fn visit_discard(&mut self, hir: &Hir, symbol: Symbol, discard: &DiscardSymbol) {}
+    fn visit_virtual(&mut self, hir: &Hir, symbol: Symbol, virtual_symbol: &VirtualSymbol) {}
+    fn visit_type_decl(&mut self, hir: &Hir, symbol: Symbol, type_decl: &TypeDeclSymbol) {}
+}
+
+/// Visit all symbols in the scope of the module.
+///
+/// Symbols of other modules (e.g. imported ones) are not visited.
+pub fn walk_module<V: Visitor + ?Sized>(visitor: &mut V, hir: &Hir, module: Module) {
+    if let Some(module_data) = hir.module(module) {
+        walk_scope(visitor, hir, module_data.scope);
+    }
+}
+
+/// Visit all symbols in the scope and their children.
+pub fn walk_scope<V: Visitor + ?Sized>(visitor: &mut V, hir: &Hir, scope: Scope) {
+    if hir.scope(scope).is_none() {
+        return;
+    }
+
+    for symbol in hir.scope_symbols(scope) {
+        walk_symbol(visitor, hir, symbol);
+    }
+}
+
+/// Visit the symbol and all of its children.
+///
+/// Child expressions (e.g. operands and arguments) are in the same scope
+/// as their parents, so they are visited by [`walk_scope`] right before them
+/// instead of being visited here, only the scopes of the symbol are walked.
+pub fn walk_symbol<V: Visitor + ?Sized>(visitor: &mut V, hir: &Hir, symbol: Symbol) {
+    let Some(data) = hir.symbol(symbol) else {
+        return;
+    };
+
+    visitor.visit_symbol(hir, symbol);
+
+    match &data.kind {
+        SymbolKind::Block(block) => {
+            visitor.visit_block(hir, symbol, block);
+            walk_scope(visitor, hir, block.scope);
+        }
+        SymbolKind::Fn(f) => {
+            visitor.visit_fn(hir, symbol, f);
+            walk_scope(visitor, hir, f.scope);
+        }
+        SymbolKind::Op(op) => visitor.visit_op(hir, symbol, op),
+        SymbolKind::Decl(decl) => {
+            visitor.visit_decl(hir, symbol, decl);
+
+            if let Some(scope) = decl.value_scope {
+                walk_scope(visitor, hir, scope);
+            }
+        }
+        SymbolKind::Ref(reference) => visitor.visit_ref(hir, symbol, reference),
+        SymbolKind::Path(path) => {
+            visitor.visit_path(hir, symbol, path);
+            walk_scope(visitor, hir, path.scope);
+        }
+        SymbolKind::Lit(lit) => {
+            visitor.visit_lit(hir, symbol, lit);
+
+            for &scope in &lit.interpolated_scopes {
+                walk_scope(visitor, hir, scope);
+            }
+        }
+        SymbolKind::Unary(unary) => visitor.visit_unary(hir, symbol, unary),
+        // The operands are in the scope of the binary expression.
+        SymbolKind::Binary(binary) => {
+            visitor.visit_binary(hir, symbol, binary);
+            walk_scope(visitor, hir, binary.scope);
+        }
+        SymbolKind::Array(array) => visitor.visit_array(hir, symbol, array),
+        SymbolKind::Index(index) => visitor.visit_index(hir, symbol, index),
+        SymbolKind::Object(object) => visitor.visit_object(hir, symbol, object),
+        SymbolKind::Call(call) => visitor.visit_call(hir, symbol, call),
+        SymbolKind::Closure(closure) => {
+            visitor.visit_closure(hir, symbol, closure);
+            walk_scope(visitor, hir, closure.scope);
+        }
+        SymbolKind::If(if_symbol) => {
+            visitor.visit_if(hir, symbol, if_symbol);
+
+            for &(_, scope) in &if_symbol.branches {
+                walk_scope(visitor, hir, scope);
+            }
+        }
+        SymbolKind::Loop(loop_symbol) => {
+            visitor.visit_loop(hir, symbol, loop_symbol);
+            walk_scope(visitor, hir, loop_symbol.scope);
+        }
+        // The bindings are in the scope of the loop body.
+        SymbolKind::For(for_symbol) => {
+            visitor.visit_for(hir, symbol, for_symbol);
+            walk_scope(visitor, hir, for_symbol.scope);
+        }
+        SymbolKind::While(while_symbol) => {
+            visitor.visit_while(hir, symbol, while_symbol);
+            walk_scope(visitor, hir, while_symbol.scope);
+        }
+        SymbolKind::Break(break_symbol) => visitor.visit_break(hir, symbol, break_symbol),
+        SymbolKind::Continue(continue_symbol) => {
+            visitor.visit_continue(hir, symbol, continue_symbol);
+        }
+        SymbolKind::Return(return_symbol) => visitor.visit_return(hir, symbol, return_symbol),
+        SymbolKind::Switch(switch) => visitor.visit_switch(hir, symbol, switch),
+        SymbolKind::Export(export) => visitor.visit_export(hir, symbol, export),
+        SymbolKind::Try(try_symbol) => {
+            visitor.visit_try(hir, symbol, try_symbol);
+            walk_scope(visitor, hir, try_symbol.try_scope);
+            walk_scope(visitor, hir, try_symbol.catch_scope);
+        }
+        SymbolKind::Throw(throw) => visitor.visit_throw(hir, symbol, throw),
+        SymbolKind::Import(import) => {
+            visitor.visit_import(hir, symbol, import);
+            walk_scope(visitor, hir, import.scope);
+        }
+        SymbolKind::Discard(discard) => visitor.visit_discard(hir, symbol, discard),
+        // Virtual symbols only point to symbols
+        // that belong somewhere else.
+        SymbolKind::Virtual(virtual_symbol) => visitor.visit_virtual(hir, symbol, virtual_symbol),
+        SymbolKind::TypeDecl(type_decl) => visitor.visit_type_decl(hir, symbol, type_decl),
+    }
+}
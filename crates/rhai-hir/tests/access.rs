@@ -0,0 +1,175 @@
+use rhai_hir::{symbol::ReferenceAccess, testing, Symbol};
+use test_case::test_case;
+
+fn access_of_references(src: &str) -> Vec<ReferenceAccess> {
+    let (hir, _) = testing::build(src);
+
+    let (_, decl) = hir
+        .symbols()
+        .find_map(|(symbol, data)| {
+            data.kind
+                .as_decl()
+                .filter(|d| d.name == "x")
+                .map(|d| (symbol, d))
+        })
+        .unwrap();
+
+    let mut refs = decl.references.iter().copied().collect::<Vec<_>>();
+    refs.sort_by_key(|&r| hir[r].text_range().unwrap().start());
+
+    refs.into_iter().map(|r| hir.reference_access(r)).collect()
+}
+
+#[test_case("+=" ; "add")]
+#[test_case("-=" ; "sub")]
+#[test_case("*=" ; "mul")]
+#[test_case("/=" ; "div")]
+#[test_case("%=" ; "rem")]
+#[test_case("**=" ; "pow")]
+#[test_case("&=" ; "and")]
+#[test_case("|=" ; "or")]
+#[test_case("^=" ; "xor")]
+#[test_case("<<=" ; "shift_left")]
+#[test_case(">>=" ; "shift_right")]
+fn test_compound_assignment(op: &str) {
+    let src = format!("let x = 1; x {op} 2;");
+    assert_eq!(access_of_references(&src), [ReferenceAccess::ReadWrite]);
+}
+
+#[test]
+fn test_assignment() {
+    let src = "let x = 1; x = 2; let y = x + 1; y = x;";
+    assert_eq!(
+        access_of_references(src),
+        [
+            ReferenceAccess::Write,
+            ReferenceAccess::Read,
+            ReferenceAccess::Read
+        ]
+    );
+}
+
+#[test_case("let x = [1]; let i = 0; let v = 2; x[i] = v;" ; "index")]
+#[test_case("let x = #{}; let v = 2; x.field = v;" ; "field")]
+#[test_case("let x = #{}; let v = 2; x.a.b = v;" ; "nested_field")]
+#[test_case("let x = #{}; let i = 0; let v = 2; x.a[i] = v;" ; "field_index")]
+#[test_case("let x = [[1]]; let i = 0; let v = 2; x[i][0] = v;" ; "nested_index")]
+fn test_assignment_target_base(src: &str) {
+    assert_eq!(access_of_references(src), [ReferenceAccess::Write]);
+}
+
+#[test]
+fn test_assignment_target_compound() {
+    let src = "let x = [1]; x[0] += 1; x.len += 1;";
+    assert_eq!(
+        access_of_references(src),
+        [ReferenceAccess::ReadWrite, ReferenceAccess::ReadWrite]
+    );
+}
+
+#[test]
+fn test_assignment_target_inner_reads() {
+    let src = "let a = [1]; let x = 0; a[x] = x; a[0] = x.len; let y = a[x]; let z = x.field;";
+    assert_eq!(access_of_references(src), [ReferenceAccess::Read; 5]);
+}
+
+fn is_mutated(src: &str) -> bool {
+    let (hir, _) = testing::build(src);
+
+    let symbol = hir
+        .symbols()
+        .find_map(|(symbol, data)| {
+            data.kind
+                .as_decl()
+                .filter(|d| d.name == "x")
+                .map(|_| symbol)
+        })
+        .unwrap();
+
+    hir.is_mutated(symbol)
+}
+
+#[test_case("let x = 1; x = 2;" ; "assignment")]
+#[test_case("let x = 1; x += 2;" ; "compound_assignment")]
+#[test_case("let x = #{}; x.field = 2;" ; "field")]
+#[test_case("let x = [1]; x[0] = 2;" ; "index")]
+#[test_case("let x = []; x.push(1);" ; "method_call")]
+#[test_case("let x = #{ a: [] }; x.a.push(1);" ; "field_method_call")]
+#[test_case("let x = 1; let f = || { x = 2; };" ; "closure")]
+fn test_mutated(src: &str) {
+    assert!(is_mutated(src));
+}
+
+#[test_case("let x = 1;" ; "unused")]
+#[test_case("let x = 1; let y = x + 1; print(x);" ; "read")]
+#[test_case("let x = #{ a: 1 }; let y = x.a; let z = x[\"a\"];" ; "field_read")]
+#[test_case("let x = 1; let y = 2; y = x;" ; "assigned_to_other")]
+fn test_not_mutated(src: &str) {
+    assert!(!is_mutated(src));
+}
+
+fn can_be_const(src: &str) -> bool {
+    let (hir, _) = testing::build(src);
+
+    let symbol = hir
+        .symbols()
+        .find_map(|(symbol, data)| {
+            data.kind
+                .as_decl()
+                .filter(|d| d.name == "x")
+                .map(|_| symbol)
+        })
+        .unwrap();
+
+    hir.can_be_const(symbol)
+}
+
+#[test_case("let x = 1; print(x);" ; "literal")]
+#[test_case("let x = \"a\";" ; "string")]
+#[test_case("let x = -(1 + 2) * 3;" ; "arithmetic")]
+#[test_case("let x = !true || false;" ; "logic")]
+fn test_can_be_const(src: &str) {
+    assert!(can_be_const(src));
+}
+
+#[test_case("const x = 1;" ; "already_const")]
+#[test_case("let x;" ; "uninitialized")]
+#[test_case("let x = foo();" ; "call")]
+#[test_case("let y = 1; let x = y;" ; "reference")]
+#[test_case("let x = `a${1}`;" ; "interpolated")]
+#[test_case("let x = 1; x = 2;" ; "assigned")]
+#[test_case("let x = 1; x += 1;" ; "compound_assigned")]
+#[test_case("fn f(x) { x }" ; "param")]
+#[test_case("for x in [1] {}" ; "loop_binding")]
+fn test_cannot_be_const(src: &str) {
+    assert!(!can_be_const(src));
+}
+
+#[test]
+fn test_reads_and_writes() {
+    let src = "let x = [1]; let y = x[0]; x[0] = 2; print(x);";
+    let (hir, _) = testing::build(src);
+
+    let symbol = hir
+        .symbols()
+        .find_map(|(symbol, data)| {
+            data.kind
+                .as_decl()
+                .filter(|d| d.name == "x")
+                .map(|_| symbol)
+        })
+        .unwrap();
+
+    let starts = |symbols: Vec<Symbol>| {
+        symbols
+            .into_iter()
+            .map(|symbol| u32::from(hir[symbol].text_range().unwrap().start()))
+            .collect::<Vec<_>>()
+    };
+
+    let (reads, writes) = hir.reads_and_writes(symbol);
+
+    assert_eq!(starts(reads), [21, 43]);
+    assert_eq!(starts(writes), [27]);
+    assert_eq!(starts(hir.find_all_writes(symbol)), [27]);
+}
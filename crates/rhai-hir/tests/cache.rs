@@ -0,0 +1,217 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use rhai_hir::{cache::content_hash, hir::DEFAULT_MAX_UNION_SIZE, module::ExternalResolver, Hir};
+use rhai_rowan::parser::Parser;
+use url::Url;
+
+fn build(sources: &[(&str, &str)]) -> (Hir, Vec<(Url, u64)>) {
+    let mut hir = Hir::new();
+    let mut hashes = Vec::new();
+
+    for (url, src) in sources {
+        let url: Url = url.parse().unwrap();
+        hir.add_source(&url, &Parser::new(src).parse_script().into_syntax());
+        hashes.push((url, content_hash(src)));
+    }
+
+    hir.resolve_all();
+
+    (hir, hashes)
+}
+
+const ROOT_SRC: &str = r#"
+import "./module.rhai" as m;
+
+fn add(a, b) {
+    a + b
+}
+
+let x = add(1, 2);
+let y = m::value + x;
+let obj = #{ a: x, b: "b" };
+"#;
+
+const MODULE_SRC: &str = r#"
+export const value = 2;
+"#;
+
+#[test]
+fn test_cache_round_trip() {
+    let sources = [
+        ("test:///root.rhai", ROOT_SRC),
+        ("test:///module.rhai", MODULE_SRC),
+    ];
+
+    let (hir, hashes) = build(&sources);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut cached = Hir::new();
+    assert!(cached.load_cache(&bytes, hashes).unwrap());
+
+    for (url, _) in sources {
+        let url = url.parse().unwrap();
+        let module = hir.module_by_url(&url).unwrap();
+        let cached_module = cached.module_by_url(&url).unwrap();
+
+        // Keys survive the round trip.
+        assert_eq!(module, cached_module);
+        assert_eq!(hir.debug_tree(module), cached.debug_tree(cached_module));
+    }
+
+    for (symbol, data) in hir.symbols() {
+        let cached_data = cached.symbol(symbol).unwrap();
+
+        assert_eq!(
+            format!("{:?}", data.target()),
+            format!("{:?}", cached_data.target())
+        );
+        assert_eq!(
+            data.ty.fmt(&hir).to_string(),
+            cached_data.ty.fmt(&cached).to_string()
+        );
+    }
+
+    assert_eq!(hir.errors().len(), cached.errors().len());
+}
+
+#[test]
+fn test_stale_cache() {
+    let (hir, hashes) = build(&[("test:///root.rhai", MODULE_SRC)]);
+    let bytes = hir.save_cache(hashes).unwrap();
+
+    let (mut other, other_hashes) = build(&[("test:///root.rhai", ROOT_SRC)]);
+    let tree = other.debug_tree(
+        other
+            .module_by_url(&"test:///root.rhai".parse().unwrap())
+            .unwrap(),
+    );
+
+    assert!(!other.load_cache(&bytes, other_hashes).unwrap());
+
+    // The HIR is untouched.
+    assert_eq!(
+        tree,
+        other.debug_tree(
+            other
+                .module_by_url(&"test:///root.rhai".parse().unwrap())
+                .unwrap()
+        )
+    );
+}
+
+#[test]
+fn test_cache_with_different_features() {
+    let (hir, hashes) = build(&[("test:///root.rhai", MODULE_SRC)]);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut other = Hir::new();
+    other.set_features(Some(["debug".to_string()].into()));
+    assert!(!other.load_cache(&bytes, hashes.clone()).unwrap());
+
+    other.set_features(None);
+    assert!(other.load_cache(&bytes, hashes).unwrap());
+}
+
+#[test]
+fn test_cache_with_different_flow_typing() {
+    let (hir, hashes) = build(&[("test:///root.rhai", MODULE_SRC)]);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut other = Hir::new();
+    other.set_flow_typing(true);
+    assert!(!other.load_cache(&bytes, hashes.clone()).unwrap());
+
+    other.set_flow_typing(false);
+    assert!(other.load_cache(&bytes, hashes).unwrap());
+}
+
+#[test]
+fn test_cache_with_different_integer_width() {
+    let (hir, hashes) = build(&[("test:///root.rhai", MODULE_SRC)]);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut other = Hir::new();
+    other.set_only_i32(true);
+    assert!(!other.load_cache(&bytes, hashes.clone()).unwrap());
+
+    other.set_only_i32(false);
+    assert!(other.load_cache(&bytes, hashes).unwrap());
+}
+
+#[test]
+fn test_cache_with_different_public_modules() {
+    let (hir, hashes) = build(&[("test:///root.rhai", MODULE_SRC)]);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut other = Hir::new();
+    other.set_public_modules(vec!["test:///root.rhai".parse().unwrap()]);
+    assert!(!other.load_cache(&bytes, hashes.clone()).unwrap());
+
+    other.set_public_modules(Vec::new());
+    assert!(other.load_cache(&bytes, hashes).unwrap());
+}
+
+#[test]
+fn test_cache_with_different_max_union_size() {
+    let (hir, hashes) = build(&[("test:///root.rhai", MODULE_SRC)]);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut other = Hir::new();
+    other.set_max_union_size(2);
+    assert!(!other.load_cache(&bytes, hashes.clone()).unwrap());
+
+    other.set_max_union_size(DEFAULT_MAX_UNION_SIZE);
+    assert!(other.load_cache(&bytes, hashes).unwrap());
+}
+
+#[test]
+fn test_cache_keeps_interned_types() {
+    let (mut hir, hashes) = build(&[("test:///root.rhai", ROOT_SRC)]);
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut cached = Hir::new();
+    assert!(cached.load_cache(&bytes, hashes).unwrap());
+
+    // Inferring the same types again reuses the interned ones.
+    hir.resolve_all();
+    cached.resolve_all();
+    assert_eq!(hir.stats(), cached.stats());
+}
+
+/// Only knows `log` with a single argument.
+struct CountingResolver(Arc<AtomicUsize>);
+
+impl ExternalResolver for CountingResolver {
+    fn resolve_fn(&self, name: &str, arity: usize) -> Option<String> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        (name == "log" && arity == 1).then(|| "fn log(message: String);".into())
+    }
+}
+
+#[test]
+fn test_cache_keeps_external_fns() {
+    let src = r#"log("a"); unknown();"#;
+    let url: Url = "test:///root.rhai".parse().unwrap();
+    let hashes = vec![(url.clone(), content_hash(src))];
+
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let mut hir = Hir::new();
+    hir.set_external_resolver(CountingResolver(lookups.clone()));
+    hir.add_source(&url, &Parser::new(src).parse_script().into_syntax());
+    hir.resolve_all();
+    assert_eq!(lookups.load(Ordering::Relaxed), 2);
+
+    let bytes = hir.save_cache(hashes.clone()).unwrap();
+
+    let mut cached = Hir::new();
+    cached.set_external_resolver(CountingResolver(lookups.clone()));
+    assert!(cached.load_cache(&bytes, hashes).unwrap());
+    cached.resolve_all();
+
+    // Neither the known nor the missing function is looked up again.
+    assert_eq!(lookups.load(Ordering::Relaxed), 2);
+    assert_eq!(hir.errors().len(), cached.errors().len());
+}
@@ -0,0 +1,218 @@
+use rhai_hir::{testing, Hir, Module, Symbol};
+use rhai_rowan::parser::Parser;
+use test_case::test_case;
+
+const SRC: &str = r#"
+fn is_even(n) {
+    if n == 0 { true } else { is_odd(n - 1) }
+}
+
+fn is_odd(n) {
+    if n == 0 { false } else { is_even(n - 1) }
+}
+
+fn main() {
+    let check = |n| is_even(n) && is_even(n + 2);
+    check.call(1);
+    print(is_odd(3));
+}
+
+main();
+"#;
+
+fn parse() -> (Hir, Module) {
+    testing::build(SRC)
+}
+
+fn node_name(hir: &Hir, symbol: Option<Symbol>) -> &str {
+    match symbol {
+        Some(symbol) => match hir[symbol].kind.as_fn() {
+            Some(f) => &f.name,
+            None => {
+                assert!(hir[symbol].kind.is_closure());
+                "<closure>"
+            }
+        },
+        None => "<top-level>",
+    }
+}
+
+fn named_edges(hir: &Hir, edges: &[(Option<Symbol>, Symbol)]) -> Vec<(String, String)> {
+    let mut named = edges
+        .iter()
+        .map(|&(caller, callee)| {
+            (
+                node_name(hir, caller).to_string(),
+                node_name(hir, Some(callee)).to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    named.sort_unstable();
+    named
+}
+
+#[test]
+fn test_call_graph_mutual_recursion() {
+    let (hir, module) = parse();
+    let edges = hir.call_graph(module);
+
+    assert_eq!(
+        named_edges(&hir, &edges),
+        [
+            ("<closure>", "is_even"),
+            ("<top-level>", "main"),
+            ("is_even", "is_odd"),
+            ("is_odd", "is_even"),
+            ("main", "is_odd"),
+        ]
+        .map(|(caller, callee)| (caller.to_string(), callee.to_string()))
+    );
+
+    // The cycle can be found by following the edges.
+    let callees_of = |caller: Symbol| {
+        edges
+            .iter()
+            .filter(move |(c, _)| *c == Some(caller))
+            .map(|&(_, callee)| callee)
+    };
+    let is_even = edges
+        .iter()
+        .find(|(_, callee)| node_name(&hir, Some(*callee)) == "is_even")
+        .unwrap()
+        .1;
+    assert!(callees_of(is_even).any(|callee| callees_of(callee).any(|c| c == is_even)));
+}
+
+#[test]
+fn test_call_graph_is_ordered() {
+    let (hir, module) = parse();
+    let edges = hir.call_graph(module);
+
+    let mut sorted = edges.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    assert_eq!(edges, sorted);
+    assert_eq!(edges, hir.call_graph(module));
+}
+
+#[test]
+fn test_call_sites_across_modules() {
+    let lib_src = r#"
+fn shared(a) { a }
+fn shared(a, b) { a + b }
+
+fn local() {
+    shared(1);
+}
+"#;
+
+    let main_src = r#"
+import "./lib.rhai" as lib;
+
+lib::shared(1);
+lib::shared(1, 2);
+lib::local();
+"#;
+
+    let other_src = r#"
+import "./lib.rhai" as lib;
+
+let f = lib::shared;
+lib::shared(3);
+"#;
+
+    let mut hir = Hir::new();
+
+    for (url, src) in [
+        ("test:///lib.rhai", lib_src),
+        ("test:///main.rhai", main_src),
+        ("test:///other.rhai", other_src),
+    ] {
+        hir.add_source(
+            &url.parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+    }
+
+    hir.resolve_all();
+
+    let call_sites = hir.call_sites_by_arity("shared");
+    let counts = call_sites
+        .iter()
+        .map(|(&arity, locations)| (arity, locations.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [(1, 3), (2, 1)]);
+
+    let urls = hir
+        .call_sites("shared")
+        .into_iter()
+        .map(|(url, _)| url.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        urls,
+        [
+            "test:///lib.rhai",
+            "test:///main.rhai",
+            "test:///other.rhai",
+            "test:///main.rhai"
+        ]
+    );
+
+    assert!(hir.call_sites("missing").is_empty());
+}
+
+#[test_case("f(a, b)" ; "plain")]
+#[test_case("f!(a, b)" ; "shared scope")]
+#[test_case("m::f(a, b)" ; "path")]
+#[test_case("a.f(a, b)" ; "method")]
+#[test_case("(f)(a, b)" ; "parenthesized")]
+fn test_call_arguments(call: &str) {
+    let main_src = format!(
+        r#"
+import "./m.rhai" as m;
+
+fn f(x, y) {{ x + y }}
+
+let a = 1;
+let b = 2;
+
+{call};
+"#
+    );
+
+    let mut hir = Hir::new();
+
+    for (url, src) in [
+        ("test:///main.rhai", main_src.as_str()),
+        ("test:///m.rhai", "fn f(x, y) { x + y }"),
+    ] {
+        hir.add_source(
+            &url.parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+    }
+
+    hir.resolve_all();
+
+    let (symbol, call) = hir
+        .symbols()
+        .find_map(|(symbol, data)| Some((symbol, data.kind.as_call()?)))
+        .unwrap();
+
+    let arguments = call
+        .arguments
+        .iter()
+        .map(|&argument| {
+            let r = hir[argument].kind.as_reference().unwrap();
+            assert!(r.target.is_some(), "unresolved argument `{}`", r.name);
+            r.name.as_str()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(arguments, ["a", "b"]);
+
+    // Method calls are not resolved.
+    let is_method = call.lhs.is_some_and(|lhs| hir[lhs].kind.is_binary());
+    let module = hir.module_by_symbol(symbol).unwrap();
+    assert_eq!(hir.call_graph(module).len(), usize::from(!is_method));
+}
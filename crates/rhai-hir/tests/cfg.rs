@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+
+use rhai_hir::{cfg::CfgPredicate, error::ErrorKind, Hir};
+use rhai_rowan::parser::Parser;
+use test_case::test_case;
+
+const SRC: &str = r#"
+// #[cfg(debug)]
+fn log(message) {
+    message
+}
+
+// #[cfg(not(debug))]
+/// Does nothing.
+fn log(message) {}
+
+{
+    // #[cfg(all(debug, verbose))]
+    let trace = true;
+}
+
+log("hello");
+
+// #[cfg(debug)]
+undefined();
+"#;
+
+fn build(features: Option<&[&str]>) -> Hir {
+    let mut hir = Hir::new();
+    hir.set_features(features.map(|f| f.iter().map(|&f| f.to_string()).collect()));
+    hir.add_source(
+        &"test:///cfg.rhai".parse().unwrap(),
+        &Parser::new(SRC).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+    hir
+}
+
+/// The docs of the functions named `log`.
+fn log_fns(hir: &Hir) -> Vec<&str> {
+    let mut docs = hir
+        .symbols()
+        .filter_map(|(_, data)| data.kind.as_fn())
+        .filter(|f| f.name == "log")
+        .map(|f| f.docs.trim())
+        .collect::<Vec<_>>();
+    docs.sort_unstable();
+    docs
+}
+
+fn has_decl(hir: &Hir, name: &str) -> bool {
+    hir.symbols()
+        .any(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+}
+
+#[test]
+fn test_cfg_toggles_function() {
+    let hir = build(Some(&["debug"]));
+    assert_eq!(log_fns(&hir), [""]);
+    assert!(!has_decl(&hir, "trace"));
+
+    let hir = build(Some(&[]));
+    assert_eq!(log_fns(&hir), ["Does nothing."]);
+
+    let hir = build(Some(&["debug", "verbose"]));
+    assert_eq!(log_fns(&hir), [""]);
+    assert!(has_decl(&hir, "trace"));
+}
+
+#[test]
+fn test_cfg_ignored_without_features() {
+    let hir = build(None);
+    assert_eq!(log_fns(&hir), ["", "Does nothing."]);
+    assert!(has_decl(&hir, "trace"));
+}
+
+#[test_case("let a = 1; // #[cfg(debug)]\nlet trace = true;" ; "trailing comment")]
+#[test_case("// #[cfg(debug)]\n\nlet trace = true;" ; "empty line")]
+fn test_cfg_not_directly_above(src: &str) {
+    let mut hir = Hir::new();
+    hir.set_features(Some(BTreeSet::new()));
+    hir.add_source(
+        &"test:///cfg.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    assert!(has_decl(&hir, "trace"));
+}
+
+fn has_unresolved(hir: &Hir) -> bool {
+    hir.errors()
+        .iter()
+        .any(|e| matches!(e.kind, ErrorKind::UnresolvedReference { .. }))
+}
+
+#[test]
+fn test_cfg_inactive_references() {
+    let hir = build(Some(&[]));
+
+    // The only reference to `message` is in the inactive function.
+    let message = hir
+        .symbols()
+        .filter_map(|(_, data)| data.kind.as_decl())
+        .filter(|d| d.name == "message")
+        .collect::<Vec<_>>();
+    assert_eq!(message.len(), 1);
+    assert!(message[0].references.is_empty());
+
+    assert!(!has_unresolved(&hir));
+    assert!(has_unresolved(&build(Some(&["debug"]))));
+}
+
+#[test_case("// #[cfg(debug)]", &["debug"], true ; "feature")]
+#[test_case("// #[cfg(debug)]", &[], false ; "missing feature")]
+#[test_case("//#[cfg( not( debug ) )]", &[], true ; "not")]
+#[test_case("// #[cfg(all(a, b))]", &["a"], false ; "all")]
+#[test_case("// #[cfg(all())]", &[], true ; "all empty")]
+#[test_case("// #[cfg(any(a, b,))]", &["b"], true ; "any")]
+#[test_case("// #[cfg(any(my-feature, not(all(a, b))))]", &["a", "b"], false ; "nested")]
+fn test_cfg_predicate(comment: &str, features: &[&str], expected: bool) {
+    let features = features
+        .iter()
+        .map(|&f| f.to_string())
+        .collect::<BTreeSet<_>>();
+
+    assert_eq!(
+        CfgPredicate::from_comment(comment)
+            .unwrap()
+            .is_active(&features),
+        expected
+    );
+}
+
+#[test_case("// cfg(debug)" ; "no attribute")]
+#[test_case("/// #[cfg(debug)]" ; "doc comment")]
+#[test_case("// #[cfg(not(a, b))]" ; "not with multiple")]
+#[test_case("// #[cfg(a b)]" ; "missing comma")]
+#[test_case("// #[cfg(other(a))]" ; "unknown operator")]
+#[test_case("// #[cfg()]" ; "empty")]
+fn test_cfg_invalid_directive(comment: &str) {
+    assert_eq!(CfgPredicate::from_comment(comment), None);
+}
@@ -0,0 +1,38 @@
+use rhai_hir::{module::PRELUDE_URL, Hir};
+use rhai_rowan::parser::Parser;
+
+#[test]
+fn test_clear() {
+    let mut hir = Hir::new();
+    hir.add_prelude();
+    hir.resolve_all();
+
+    let baseline = hir.stats();
+
+    let url = "test:///clear.rhai".parse().unwrap();
+    hir.add_source(
+        &url,
+        &Parser::new("let a = #{ b: [1] }; fn f(x) { x + a.b[0] }")
+            .parse_script()
+            .into_syntax(),
+    );
+    hir.resolve_all();
+
+    let source = hir.source_by_url(&url).unwrap();
+    let (symbol, _) = hir
+        .symbols()
+        .find(|(_, data)| data.source.is(source))
+        .unwrap();
+
+    assert!(hir.stats().symbols > baseline.symbols);
+
+    hir.clear();
+    hir.resolve_all();
+
+    assert_eq!(hir.stats(), baseline);
+    assert!(hir.source_by_url(&url).is_none());
+    assert!(hir.source_by_url(&PRELUDE_URL.parse().unwrap()).is_some());
+
+    // Keys from before clearing must not point to new items.
+    assert!(hir.symbol(symbol).is_none());
+}
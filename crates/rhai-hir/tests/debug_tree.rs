@@ -0,0 +1,85 @@
+use rhai_hir::{testing, Hir};
+
+fn build(src: &str) -> (Hir, String) {
+    let (hir, module) = testing::build(src);
+    let tree = hir.debug_tree(module);
+    (hir, tree)
+}
+
+#[test]
+fn test_debug_tree() {
+    let src = r#"
+fn foo(p1, p2) {
+  let a = 2;
+  return a + p2 + missing;
+}
+
+fn bar() {}
+"#;
+
+    let (_, tree) = build(src);
+
+    assert!(
+        tree.starts_with(&format!("module {}\n", testing::TEST_URL)),
+        "{tree}"
+    );
+    assert!(tree.contains("Fn foo"), "{tree}");
+    assert!(tree.contains("Decl param p1"), "{tree}");
+    assert!(tree.contains("Decl a"), "{tree}");
+    assert!(tree.contains("Lit 2"), "{tree}");
+    assert!(tree.contains("Ref a"), "{tree}");
+    assert!(tree.contains("=> Decl a"), "{tree}");
+    assert!(tree.contains("Ref missing"), "{tree}");
+    assert!(tree.contains("=> <unresolved>"), "{tree}");
+
+    // Hoisted functions are ordered by their position.
+    assert!(tree.find("Fn foo").unwrap() < tree.find("Fn bar").unwrap());
+}
+
+#[test]
+fn test_debug_tree_is_deterministic() {
+    let src = r#"
+fn a() {}
+fn b() {}
+fn c() {}
+fn d() {}
+let x = [1, 2, #{ a: a(), b: b() }];
+"#;
+
+    let (_, first) = build(src);
+
+    for _ in 0..10 {
+        let (_, tree) = build(src);
+        assert_eq!(first, tree);
+    }
+}
+
+#[test]
+fn test_debug_tree_snapshot() {
+    let src = r#"
+import "missing" as m;
+
+fn add(a, b) {
+    a + b
+}
+
+const ITEMS = [1, 2, 3];
+
+let total = 0;
+
+for (item, i) in ITEMS {
+    total += add(item, i);
+}
+
+let f = |x| x * total;
+
+if total > 10 {
+    print(f.call(m::value));
+} else {
+    throw "too small";
+}
+"#;
+
+    let (_, tree) = build(src);
+    insta::assert_snapshot!(tree);
+}
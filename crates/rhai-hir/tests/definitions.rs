@@ -112,3 +112,111 @@ fn print();
 
     assert!(hir.errors().is_empty());
 }
+
+#[test]
+fn test_definition_types() {
+    let root_src = r#"
+let a = sin(1.0);
+let p = origin();
+let x = p.x;
+"#;
+
+    let def_src = r#"
+module static;
+
+type Point = #{ x: float, y: float };
+
+fn sin(x: float) -> float;
+
+fn origin() -> Point;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///static.d.rhai".parse().unwrap(),
+        &Parser::new(def_src).parse_def().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty(), "{:#?}", hir.errors());
+
+    let type_of = |name: &str| {
+        let (_, data) = hir
+            .symbols()
+            .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+            .unwrap();
+        data.ty.fmt(&hir).to_string()
+    };
+
+    assert_eq!(type_of("a"), "float");
+    assert_eq!(type_of("p"), "Point");
+    assert_eq!(type_of("x"), "float");
+}
+
+#[test]
+fn test_emit_definitions_round_trip() {
+    let src = r#"
+/// Greets someone.
+///
+/// Returns the greeting.
+fn greet(name) {
+    `hello ${name}`
+}
+
+fn add(a, b) {
+    a + b
+}
+
+private fn hidden() {}
+
+/// The answer.
+const ANSWER = 42;
+
+const ORIGIN = #{ x: 0.0, y: 0.0 };
+
+let not_const = 1;
+"#;
+
+    let url = "test:///lib.rhai".parse().unwrap();
+
+    let mut hir = Hir::new();
+    hir.add_source(&url, &Parser::new(src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let module = hir.module_by_url(&url).unwrap();
+    let emitted = hir.emit_definitions(module);
+
+    assert_eq!(
+        emitted,
+        r#"module "test:///lib.rhai";
+
+/// Greets someone.
+///
+/// Returns the greeting.
+fn greet(name: ?) -> String;
+
+fn add(a: ?, b: ?) -> ?;
+
+/// The answer.
+const ANSWER: int;
+
+const ORIGIN: #{x: float, y: float};
+"#
+    );
+
+    let def = Parser::new(&emitted).parse_def();
+    assert!(def.errors.is_empty(), "{:#?}", def.errors);
+
+    let mut loaded = Hir::new();
+    loaded.add_source(&"test:///lib.d.rhai".parse().unwrap(), &def.into_syntax());
+    loaded.resolve_all();
+
+    let module = loaded.module_by_url(&url).unwrap();
+    assert_eq!(loaded.emit_definitions(module), emitted);
+}
@@ -0,0 +1,257 @@
+use rhai_hir::{
+    diagnostic::{Diagnostic, DiagnosticsCache},
+    lint::{DiagnosticConfig, Lint, LintLevel},
+    Hir,
+};
+use rhai_rowan::{
+    parser::{Parse, Parser},
+    TextRange, TextSize,
+};
+use url::Url;
+
+fn diagnostics(src: &str) -> Vec<(String, LintLevel, Option<Lint>, String)> {
+    let url: Url = "test:///diagnostics.rhai".parse().unwrap();
+    let parse: Parse = Parser::new(src).parse_script();
+
+    let mut hir = Hir::new();
+    hir.add_source(&url, &parse.clone_syntax());
+    hir.resolve_all();
+
+    hir.diagnostics_for(&url, &parse, &DiagnosticConfig::default())
+        .into_iter()
+        .map(|diag| {
+            (
+                src[diag.range].to_string(),
+                diag.level,
+                diag.lint,
+                diag.message,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_multiple_lints() {
+    let src = r#"
+// TODO: remove this
+if true {
+    let unused = #{ a: 1, a: 2 };
+    missing
+}
+"#;
+
+    assert_eq!(
+        diagnostics(src),
+        [
+            (
+                "// TODO: remove this".into(),
+                LintLevel::Info,
+                Some(Lint::TodoComment),
+                "TODO: remove this".into()
+            ),
+            (
+                "true".into(),
+                LintLevel::Warning,
+                Some(Lint::ConstantCondition),
+                "condition is always `true`".into()
+            ),
+            (
+                "unused".into(),
+                LintLevel::Hint,
+                Some(Lint::UnusedVariable),
+                "variable is never used".into()
+            ),
+            (
+                "a".into(),
+                LintLevel::Warning,
+                Some(Lint::DuplicateObjectField),
+                "duplicate object field `a`, only the last value is kept".into()
+            ),
+            (
+                "missing".into(),
+                LintLevel::Error,
+                Some(Lint::UnresolvedReference),
+                "cannot resolve reference".into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_syntax_errors_hide_lints() {
+    let src = r#"
+// FIXME
+let unused = ;
+missing
+"#;
+
+    let diags = diagnostics(src);
+
+    assert_eq!(diags[0].2, Some(Lint::TodoComment));
+    assert!(diags[1..]
+        .iter()
+        .all(|(_, level, lint, _)| *level == LintLevel::Error && lint.is_none()));
+    assert!(diags.len() > 1);
+}
+
+#[test]
+fn test_unmatched_delimiters() {
+    let src = "let a = [1);\n";
+
+    let delimiters = diagnostics(src)
+        .into_iter()
+        .filter(|(_, _, lint, _)| *lint == Some(Lint::UnmatchedDelimiter))
+        .map(|(text, level, _, message)| (text, level, message))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        delimiters,
+        [
+            (
+                "[".into(),
+                LintLevel::Error,
+                "unclosed delimiter `[`".into()
+            ),
+            (
+                ")".into(),
+                LintLevel::Error,
+                "mismatched closing delimiter `)`".into()
+            ),
+        ]
+    );
+}
+
+fn incremental_diagnostics(
+    src: &str,
+    cache: &mut DiagnosticsCache,
+    edit: Option<(TextRange, TextSize)>,
+) -> Vec<Diagnostic> {
+    let url: Url = "test:///diagnostics.rhai".parse().unwrap();
+    let parse: Parse = Parser::new(src).parse_script();
+
+    let mut hir = Hir::new();
+    hir.add_source(&url, &parse.clone_syntax());
+    hir.resolve_all();
+
+    hir.incremental_diagnostics_for(&url, &parse, &DiagnosticConfig::default(), cache, edit)
+}
+
+/// The edit that replaces the first `old` in `src` with `new`.
+fn edit(src: &str, old: &str, new: &str) -> (String, (TextRange, TextSize)) {
+    let start = src.find(old).unwrap();
+    let range = TextRange::at(
+        TextSize::from(start as u32),
+        TextSize::from(old.len() as u32),
+    );
+
+    (
+        src.replacen(old, new, 1),
+        (range, TextSize::from(new.len() as u32)),
+    )
+}
+
+#[test]
+fn test_incremental_diagnostics() {
+    let src = r#"
+fn a() {
+    let x = 1;
+    b()
+}
+
+fn b() {
+    let y = 2;
+    3
+}
+
+a();
+"#;
+
+    let mut cache = DiagnosticsCache::default();
+    let first = incremental_diagnostics(src, &mut cache, None);
+
+    let (new_src, change) = edit(src, "let x = 1;", "let x = 1;\n    let unused = 2;");
+    let second = incremental_diagnostics(&new_src, &mut cache, Some(change));
+
+    let full = incremental_diagnostics(&new_src, &mut DiagnosticsCache::default(), None);
+    assert_eq!(second, full);
+
+    // The diagnostic of the untouched function moved along with it.
+    let y = |diags: &[Diagnostic], src: &str| {
+        diags.iter().filter(|diag| &src[diag.range] == "y").count()
+    };
+    assert_eq!(y(&first, src), 1);
+    assert_eq!(y(&second, &new_src), 1);
+}
+
+#[test]
+fn test_incremental_diagnostics_unused_function() {
+    let src = r#"
+private fn a() {
+    1
+}
+
+private fn b() {
+    2
+}
+
+a();
+b();
+"#;
+
+    let mut cache = DiagnosticsCache::default();
+    let first = incremental_diagnostics(src, &mut cache, None);
+    assert!(first
+        .iter()
+        .all(|diag| diag.lint != Some(Lint::UnusedFunction)));
+
+    // The bodies are reused, but `b` is not called anymore.
+    let (new_src, change) = edit(src, "b();", "");
+    let second = incremental_diagnostics(&new_src, &mut cache, Some(change));
+
+    let unused = second
+        .iter()
+        .filter(|diag| diag.lint == Some(Lint::UnusedFunction))
+        .map(|diag| &new_src[diag.range])
+        .collect::<Vec<_>>();
+    assert_eq!(unused, ["b"]);
+}
+
+#[test]
+fn test_incremental_diagnostics_edited_function() {
+    let src = r#"
+fn a() {
+    let x = 1;
+}
+
+fn b() {
+    let yy = 2;
+}
+
+a();
+b();
+"#;
+
+    let mut cache = DiagnosticsCache::default();
+    incremental_diagnostics(src, &mut cache, None);
+
+    // Only the edit of `a` is reported, renaming the variable of `b`
+    // is not, so its diagnostics are only up to date if recomputed.
+    let (new_src, change) = edit(src, "let x = 1;", "let x = 1;\n    let z = 3;");
+    let new_src = new_src.replacen("let yy", "let _y", 1);
+
+    let unused = |diags: &[Diagnostic]| {
+        diags
+            .iter()
+            .filter(|diag| diag.lint == Some(Lint::UnusedVariable))
+            .map(|diag| new_src[diag.range].to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // `a` is checked again, the diagnostic of `b` is shifted
+    // to the new position of its declaration.
+    let second = incremental_diagnostics(&new_src, &mut cache, Some(change));
+    assert_eq!(unused(&second), ["x", "z", "_y"]);
+
+    let full = incremental_diagnostics(&new_src, &mut DiagnosticsCache::default(), None);
+    assert_eq!(unused(&full), ["x", "z"]);
+}
@@ -0,0 +1,120 @@
+use rhai_hir::{testing, Hir, Symbol};
+
+fn parse(src: &str) -> Hir {
+    testing::build(src).0
+}
+
+fn symbol_named(hir: &Hir, name: &str) -> Symbol {
+    hir.symbols()
+        .find(|(_, data)| {
+            data.kind.as_decl().is_some_and(|d| d.name == name)
+                || data.kind.as_fn().is_some_and(|f| f.name == name)
+        })
+        .map(|(symbol, _)| symbol)
+        .unwrap()
+}
+
+#[test]
+fn test_enclosing_fn_top_level() {
+    let hir = parse("let a = 1; fn foo() {}");
+
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "a")), None);
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "foo")), None);
+}
+
+#[test]
+fn test_enclosing_fn_nested() {
+    let hir = parse(
+        r#"
+fn outer(a) {
+    let b = 1;
+
+    if true {
+        let c = 2;
+    }
+
+    fn inner(d) {
+        let e = 3;
+    }
+}
+"#,
+    );
+
+    let outer = symbol_named(&hir, "outer");
+    let inner = symbol_named(&hir, "inner");
+
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "a")), Some(outer));
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "b")), Some(outer));
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "c")), Some(outer));
+    assert_eq!(hir.enclosing_fn(inner), Some(outer));
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "d")), Some(inner));
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "e")), Some(inner));
+}
+
+#[test]
+fn test_enclosing_fn_closure() {
+    let hir = parse(
+        r#"
+let f = |a| {
+    let b = 1;
+};
+
+fn foo() {
+    let g = |c| c + 1;
+}
+"#,
+    );
+
+    let foo = symbol_named(&hir, "foo");
+
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "a")), None);
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "b")), None);
+    assert_eq!(hir.enclosing_fn(symbol_named(&hir, "c")), Some(foo));
+}
+
+#[test]
+fn test_ancestors() {
+    let (hir, module) = testing::build(
+        r#"
+fn outer() {
+    while true {
+        if true {
+            let a = 1;
+        }
+    }
+}
+"#,
+    );
+
+    let a = symbol_named(&hir, "a");
+    let outer = symbol_named(&hir, "outer");
+
+    let chain = hir
+        .ancestors(a)
+        .map(|(scope, parent)| {
+            let parent = parent.map(|parent| {
+                let kind = &hir[parent].kind;
+
+                if kind.is_if() {
+                    "if"
+                } else if kind.is_while() {
+                    "while"
+                } else if kind.is_fn() {
+                    "fn"
+                } else {
+                    "other"
+                }
+            });
+
+            (scope, parent)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        chain.iter().map(|(_, parent)| *parent).collect::<Vec<_>>(),
+        [Some("if"), Some("while"), Some("fn"), None, None]
+    );
+    assert_eq!(chain[0].0, hir[a].parent_scope);
+    assert_eq!(chain[3].0, hir[module].scope);
+    assert_eq!(hir.ancestors(outer).next(), Some((hir[module].scope, None)));
+}
@@ -0,0 +1,584 @@
+use rhai_hir::{
+    error::ErrorKind,
+    lint::{DiagnosticConfig, LintLevel},
+    Hir,
+};
+use rhai_rowan::parser::Parser;
+
+fn errors_of(src: &str) -> (Hir, Vec<ErrorKind>) {
+    let mut hir = Hir::new();
+    hir.add_source(
+        &"test:///errors.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    // Most snippets only declare variables and empty
+    // functions to trigger other errors.
+    let config = DiagnosticConfig {
+        unused_variable: Some(LintLevel::Off),
+        empty_block: Some(LintLevel::Off),
+        ..DiagnosticConfig::default()
+    };
+
+    let errors = config
+        .apply(hir.errors())
+        .into_iter()
+        .map(|(e, _)| e.kind)
+        .collect();
+    (hir, errors)
+}
+
+#[test]
+fn test_use_before_definition() {
+    let (hir, errors) = errors_of(
+        r#"
+let a = b + 1;
+let b = 2;
+
+{
+    c + 1;
+    let c = 3;
+}
+"#,
+    );
+
+    let names = errors
+        .iter()
+        .map(|e| match e {
+            ErrorKind::UseBeforeDefinition {
+                reference_symbol,
+                declaration,
+            } => {
+                assert_eq!(
+                    hir[*reference_symbol].name(&hir),
+                    hir[*declaration].name(&hir)
+                );
+                hir[*declaration].name(&hir).unwrap().to_string()
+            }
+            _ => panic!("unexpected error {e:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(names.len(), 2, "{errors:#?}");
+    assert!(names.contains(&String::from("b")));
+    assert!(names.contains(&String::from("c")));
+}
+
+#[test]
+fn test_fn_hoisting_is_not_use_before_definition() {
+    let (_, errors) = errors_of(
+        r#"
+let a = foo();
+
+fn foo() {
+    1
+}
+"#,
+    );
+
+    assert!(errors.is_empty(), "{errors:#?}");
+}
+
+#[test]
+fn test_later_declaration_outside_fn_is_unresolved() {
+    let (_, errors) = errors_of(
+        r#"
+fn foo() {
+    a + 1
+}
+
+let a = 1;
+"#,
+    );
+
+    assert!(
+        errors
+            .iter()
+            .all(|e| !matches!(e, ErrorKind::UseBeforeDefinition { .. })),
+        "{errors:#?}"
+    );
+    assert!(
+        errors
+            .iter()
+            .any(|e| matches!(e, ErrorKind::UnresolvedReference { .. })),
+        "{errors:#?}"
+    );
+}
+
+#[test]
+fn test_symbol_order() {
+    let (hir, _) = errors_of("let a = 1; let b = 2;");
+
+    let decl = |name: &str| {
+        hir.symbols()
+            .find(|(_, d)| d.kind.as_decl().is_some_and(|d| d.name == name))
+            .unwrap()
+            .0
+    };
+
+    assert_eq!(
+        hir.symbol_order(decl("a"), decl("b")),
+        Some(std::cmp::Ordering::Less)
+    );
+    assert_eq!(
+        hir.symbol_order(decl("b"), decl("a")),
+        Some(std::cmp::Ordering::Greater)
+    );
+}
+
+#[test]
+fn test_self_referential_declaration() {
+    let (hir, errors) = errors_of(
+        r#"
+let x = x + 1;
+"#,
+    );
+
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+
+    match &errors[0] {
+        ErrorKind::SelfReferentialDeclaration {
+            reference_symbol,
+            declaration,
+        } => {
+            assert_eq!(hir[*reference_symbol].name(&hir), Some("x"));
+            assert!(hir[*declaration].kind.is_decl());
+        }
+        e => panic!("unexpected error {e:?}"),
+    }
+}
+
+#[test]
+fn test_shadowing_is_not_self_referential() {
+    let (_, errors) = errors_of(
+        r#"
+let x = 1;
+
+{
+    let x = x + 1;
+}
+"#,
+    );
+
+    assert!(errors.is_empty(), "{errors:#?}");
+}
+
+#[test]
+fn test_destructured_declarations_resolve() {
+    let (_, errors) = errors_of(
+        r#"
+let [a, b] = [1, 2];
+let #{c, d: e} = #{c: a, d: b};
+a + b + c + e;
+"#,
+    );
+
+    assert!(errors.is_empty(), "{errors:#?}");
+}
+
+#[test]
+fn test_closure_capture_mutation() {
+    let (hir, errors) = errors_of(
+        r#"
+let x = 0;
+let f = || { x = 1; };
+"#,
+    );
+
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+
+    match &errors[0] {
+        ErrorKind::ClosureCaptureMutation {
+            reference_symbol,
+            declaration,
+            closure,
+        } => {
+            assert_eq!(hir[*reference_symbol].name(&hir), Some("x"));
+            assert_eq!(hir[*declaration].name(&hir), Some("x"));
+            assert!(hir[*closure].kind.is_closure());
+        }
+        e => panic!("unexpected error {e:?}"),
+    }
+}
+
+#[test]
+fn test_closure_local_mutation() {
+    let (_, errors) = errors_of(
+        r#"
+let x = 0;
+let f = |a| {
+    let y = x;
+    y = 1;
+    a = 2;
+};
+"#,
+    );
+
+    assert!(errors.is_empty(), "{errors:#?}");
+}
+
+#[test]
+fn test_deprecated_function_call() {
+    let (hir, errors) = errors_of(
+        r#"
+/// Old and slow.
+///
+/// @deprecated use `bar` instead
+fn foo() {}
+
+/// Only the example mentions it.
+///
+/// ```rhai
+/// @deprecated
+/// ```
+fn bar() {}
+
+foo();
+bar();
+"#,
+    );
+
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+
+    match &errors[0] {
+        ErrorKind::DeprecatedFunction {
+            reference_symbol,
+            function,
+            reason,
+        } => {
+            assert_eq!(hir[*reference_symbol].name(&hir), Some("foo"));
+            assert_eq!(hir[*function].name(&hir), Some("foo"));
+            assert_eq!(reason, "use `bar` instead");
+        }
+        e => panic!("unexpected error {e:?}"),
+    }
+}
+
+#[test]
+fn test_safe_navigation_field_is_not_a_reference() {
+    let (_, errors) = errors_of("let a = #{ b: 1 }; let x = a?.b;");
+    assert!(errors.is_empty(), "{errors:#?}");
+}
+
+#[test]
+fn test_missing_return() {
+    let (hir, errors) = errors_of(
+        r#"
+fn missing_else(x) {
+    if x {
+        return 1;
+    }
+}
+
+fn with_else(x) {
+    if x {
+        return 1;
+    } else {
+        2
+    }
+}
+
+fn with_tail(x) {
+    if x {
+        return 1;
+    }
+
+    2
+}
+
+fn with_default(x) {
+    switch x {
+        1 => { return 1; }
+        _ => throw "unexpected"
+    }
+}
+
+fn without_value(x) {
+    if x {
+        return;
+    }
+}
+
+fn in_closure(x) {
+    let f = || { if x { return 1; } };
+    f
+}
+
+fn foo() {}
+
+fn discarded_tail(x) {
+    if x {
+        return 1;
+    }
+
+    foo();
+}
+"#,
+    );
+
+    let missing = errors
+        .iter()
+        .map(|error| match error {
+            ErrorKind::MissingReturn {
+                function,
+                return_symbol,
+            } => {
+                assert!(hir[*return_symbol].kind.is_return());
+                hir[*function].name(&hir).unwrap()
+            }
+            e => panic!("unexpected error {e:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(missing, ["missing_else", "discarded_tail"]);
+}
+
+#[test]
+fn test_inconsistent_return() {
+    let (hir, errors) = errors_of(
+        r#"
+fn mixed(x) {
+    if x {
+        return 1;
+    }
+
+    return;
+}
+
+fn only_values(x) {
+    if x {
+        return 1;
+    }
+
+    return 2;
+}
+
+fn only_bare(x) {
+    if x {
+        return;
+    }
+
+    return;
+}
+
+fn in_closure(x) {
+    let f = || { return; };
+
+    return f;
+}
+
+mixed(true);
+only_values(true);
+only_bare(true);
+in_closure(true);
+"#,
+    );
+
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+
+    match &errors[0] {
+        ErrorKind::InconsistentReturn {
+            function,
+            value_return,
+            bare_return,
+        } => {
+            assert_eq!(hir[*function].name(&hir), Some("mixed"));
+            assert!(hir[*value_return]
+                .kind
+                .as_return()
+                .is_some_and(|ret| ret.expr.is_some()));
+            assert!(hir[*bare_return]
+                .kind
+                .as_return()
+                .is_some_and(|ret| ret.expr.is_none()));
+        }
+        e => panic!("unexpected error {e:?}"),
+    }
+}
+
+#[test]
+fn test_unused_private_function() {
+    let (hir, errors) = errors_of(
+        r#"
+private fn unused_helper() {}
+
+private fn ping() { pong() }
+private fn pong() { ping() }
+
+private fn called_from_export() {}
+private fn called_from_top_level() {}
+private fn method() {}
+
+fn exported() {
+    called_from_export();
+}
+
+called_from_top_level();
+"".method();
+"#,
+    );
+
+    let mut unused = errors
+        .iter()
+        .map(|e| match e {
+            ErrorKind::UnusedFunction { function } => hir[*function].name(&hir).unwrap(),
+            e => panic!("unexpected error {e:?}"),
+        })
+        .collect::<Vec<_>>();
+    unused.sort_unstable();
+
+    assert_eq!(unused, ["ping", "pong", "unused_helper"]);
+}
+
+#[test]
+fn test_constant_condition() {
+    let constant_conditions = |src: &str| {
+        let (hir, errors) = errors_of(src);
+
+        errors
+            .iter()
+            .filter_map(|e| match e {
+                ErrorKind::ConstantCondition {
+                    symbol,
+                    condition,
+                    value,
+                } => {
+                    assert!(hir[*symbol]
+                        .text_range()
+                        .unwrap()
+                        .contains_range(hir[*condition].text_range().unwrap()));
+                    Some(*value)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(constant_conditions("if false {} else {}"), [false]);
+    assert_eq!(constant_conditions("while true {}"), [true]);
+    assert_eq!(constant_conditions("while !(1 < 2) {}"), [false]);
+    assert_eq!(
+        constant_conditions("let x = 1; if x > 0 {} else if 2 == 2.0 || x {}"),
+        [true]
+    );
+    assert_eq!(constant_conditions("let x = 1; if x == x {}"), []);
+    assert_eq!(constant_conditions("let x = true; while x || false {}"), []);
+}
+
+#[test]
+fn test_constant_condition_literal_type() {
+    let mut hir = Hir::new();
+    hir.set_flow_typing(true);
+    hir.add_source(
+        &"test:///errors.rhai".parse().unwrap(),
+        &Parser::new("let flag = true; if flag {}")
+            .parse_script()
+            .into_syntax(),
+    );
+    hir.resolve_all();
+
+    let values = hir
+        .errors()
+        .into_iter()
+        .filter_map(|e| match e.kind {
+            ErrorKind::ConstantCondition { value, .. } => Some(value),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // The value is only known from the type of `flag` at the condition.
+    assert_eq!(values, [true]);
+}
+
+#[test]
+fn test_no_constant_condition_for_mutated_values() {
+    for src in [
+        "let o = #{ a: true }; o.a = false; if o.a {}",
+        "let arr = [true]; arr[0] = false; if arr[0] {}",
+    ] {
+        let mut hir = Hir::new();
+        hir.set_flow_typing(true);
+        hir.add_source(
+            &"test:///errors.rhai".parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+        hir.resolve_all();
+
+        assert!(
+            !hir.errors()
+                .iter()
+                .any(|e| matches!(e.kind, ErrorKind::ConstantCondition { .. })),
+            "{src}"
+        );
+    }
+}
+
+#[test]
+fn test_duplicate_object_field() {
+    let src = "let x = #{ a: 1, b: 2, a: 3, a: 4 };";
+    let (_, errors) = errors_of(src);
+
+    let duplicates = errors
+        .iter()
+        .filter_map(|e| match e {
+            ErrorKind::DuplicateObjectField {
+                name,
+                duplicate_range,
+                existing_range,
+                ..
+            } => Some((
+                name.as_str(),
+                usize::from(duplicate_range.start()),
+                usize::from(existing_range.start()),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let first = src.find("a:").unwrap();
+    let mut later = src.match_indices("a:").skip(1).map(|(i, _)| i);
+    assert_eq!(
+        duplicates,
+        [
+            ("a", later.next().unwrap(), first),
+            ("a", later.next().unwrap(), first),
+        ]
+    );
+
+    assert!(errors_of("let x = #{ a: 1, b: #{ a: 2 } };").1.is_empty());
+}
+
+#[test]
+fn test_non_exhaustive_switch() {
+    let missing_values = |src: &str| {
+        let (_, errors) = errors_of(src);
+
+        errors
+            .iter()
+            .filter_map(|e| match e {
+                ErrorKind::NonExhaustiveSwitch { missing, .. } => {
+                    Some(missing.iter().map(ToString::to_string).collect::<Vec<_>>())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        missing_values("let x = true; switch x { true => 1 }"),
+        [["false"]]
+    );
+    assert_eq!(
+        missing_values("let x = true; switch x { true => 1, false if x => 2 }"),
+        [["false"]]
+    );
+    assert_eq!(
+        missing_values(r#"let x = "a"; x = "b"; switch x { "b" => 1 }"#),
+        [[r#""a""#]]
+    );
+    assert!(missing_values("let x = true; switch x { true => 1, false => 2 }").is_empty());
+    assert!(missing_values("let x = true; switch x { true => 1, _ => 2 }").is_empty());
+    assert!(missing_values("fn f(x) { switch x { 1 => 1 } }").is_empty());
+    assert!(missing_values("let x = 1; x += 1; switch x { 1 => 1 }").is_empty());
+}
@@ -0,0 +1,100 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use rhai_hir::{error::ErrorKind, module::ExternalResolver, Hir};
+use rhai_rowan::parser::Parser;
+
+/// Knows `send` with two arguments and `log` in advance.
+#[derive(Default)]
+struct MockResolver {
+    lookups: Arc<AtomicUsize>,
+}
+
+impl ExternalResolver for MockResolver {
+    fn resolve_fn(&self, name: &str, arity: usize) -> Option<String> {
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+
+        match (name, arity) {
+            ("send", 2) => {
+                Some("/// Send a message.\nfn send(to: String, body: String) -> bool;".into())
+            }
+            ("log", 1) => Some("fn log(message: String);".into()),
+            _ => None,
+        }
+    }
+
+    fn known_fns(&self) -> Vec<(String, usize)> {
+        vec![("log".into(), 1)]
+    }
+}
+
+fn build(src: &str, resolver: MockResolver) -> (Hir, Vec<ErrorKind>) {
+    let mut hir = Hir::new();
+    hir.set_external_resolver(resolver);
+    hir.add_source(
+        &"test:///external.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let errors = hir.errors().into_iter().map(|error| error.kind).collect();
+    (hir, errors)
+}
+
+fn is_unresolved(errors: &[ErrorKind]) -> bool {
+    errors
+        .iter()
+        .any(|error| matches!(error, ErrorKind::UnresolvedReference { .. }))
+}
+
+#[test]
+fn test_external_fn() {
+    let (hir, errors) = build(r#"let sent = send("a", "b");"#, MockResolver::default());
+    assert!(!is_unresolved(&errors), "{errors:#?}");
+
+    let (_, sent) = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|decl| decl.name == "sent"))
+        .unwrap();
+    assert_eq!(sent.ty.fmt(&hir).to_string(), "bool");
+
+    let (_, send) = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "send"))
+        .unwrap();
+    assert_eq!(send.docs(), Some("Send a message."));
+}
+
+#[test]
+fn test_external_fn_arity() {
+    let (_, errors) = build(r#"send("a");"#, MockResolver::default());
+    assert!(is_unresolved(&errors));
+}
+
+#[test]
+fn test_known_external_fns() {
+    let (hir, _) = build("", MockResolver::default());
+
+    assert!(hir
+        .symbols()
+        .any(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "log")));
+}
+
+#[test]
+fn test_missing_external_fns_are_looked_up_once() {
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let (mut hir, errors) = build(
+        "unknown(); unknown();",
+        MockResolver {
+            lookups: lookups.clone(),
+        },
+    );
+    assert!(is_unresolved(&errors));
+
+    hir.resolve_all();
+
+    // `log` and `unknown` with no arguments.
+    assert_eq!(lookups.load(Ordering::Relaxed), 2);
+}
@@ -1,5 +1,11 @@
-use rhai_hir::Hir;
-use rhai_rowan::parser::Parser;
+use rhai_hir::{
+    error::ErrorKind,
+    lint::{DiagnosticConfig, Lint, LintLevel},
+    symbol::ReferenceTarget,
+    Hir,
+};
+use rhai_rowan::{parser::Parser, TextSize};
+use url::Url;
 
 #[test]
 fn test_relative_import() {
@@ -83,3 +89,523 @@ import "./foo.rhai" as foo;
 
     assert_eq!(hir.missing_modules().len(), 1);
 }
+
+#[test]
+fn test_import_in_loop() {
+    let root_src = r#"
+import "./module.rhai" as top;
+
+fn foo() {
+    import "./module.rhai" as in_fn;
+}
+
+let i = 0;
+while i < 10 {
+    if i > 5 {
+        import "./module.rhai" as in_loop;
+    }
+    i += 1;
+}
+"#;
+
+    let module_src = r#"
+export const x = 1;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///module.rhai".parse().unwrap(),
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    // The module is only imported to be checked.
+    let config = DiagnosticConfig {
+        unused_export: Some(LintLevel::Off),
+        ..DiagnosticConfig::default()
+    };
+    let errors = config
+        .apply(hir.errors())
+        .into_iter()
+        .map(|(error, _)| error)
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+
+    match &errors[0].kind {
+        ErrorKind::ImportInLoop {
+            import,
+            loop_symbol,
+        } => {
+            assert_eq!(hir[*import].name(&hir), Some("in_loop"));
+            assert!(hir[*loop_symbol].kind.is_while());
+        }
+        _ => panic!("unexpected error {:?}", errors[0]),
+    }
+}
+
+#[test]
+fn test_private_function_across_modules() {
+    let root_src = r#"
+import "./module.rhai" as m;
+
+m::shown();
+m::hidden();
+"#;
+
+    let module_src = r#"
+private fn hidden() {}
+
+fn shown() {
+    hidden()
+}
+"#;
+
+    let mut hir = Hir::new();
+
+    let root_url = "test:///root.rhai".parse().unwrap();
+    let module_url = "test:///module.rhai".parse().unwrap();
+
+    hir.add_source(
+        &root_url,
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &module_url,
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let module = hir.module_by_url(&module_url).unwrap();
+    assert!(hir.find_in_module(module, "shown").is_some());
+    assert!(hir.find_in_module(module, "hidden").is_none());
+
+    // The call within the module is unaffected.
+    let module_source = hir.source_by_url(&module_url).unwrap();
+    assert!(hir
+        .errors_for_source(module_source)
+        .iter()
+        .all(|error| error.kind.lint() == Lint::EmptyBlock));
+
+    let root_source = hir.source_by_url(&root_url).unwrap();
+    let errors = hir.errors_for_source(root_source);
+    assert_eq!(errors.len(), 1, "{errors:#?}");
+
+    match &errors[0].kind {
+        ErrorKind::PrivateFunction {
+            reference_symbol,
+            function,
+        } => {
+            assert_eq!(hir[*reference_symbol].name(&hir), Some("hidden"));
+            assert!(hir[*function].kind.as_fn().unwrap().private);
+        }
+        kind => panic!("unexpected error: {kind:?}"),
+    }
+}
+
+/// Modules imported at the top level of a module are its sub-modules,
+/// so modules can re-export other modules as a facade.
+#[test]
+fn test_reexported_module() {
+    let a_src = r#"
+import "./b.rhai" as b;
+
+fn f() {
+    import "./b.rhai" as hidden;
+}
+"#;
+
+    let b_src = r#"
+export const x = 1;
+"#;
+
+    let c_src = r#"
+import "./a.rhai" as a;
+
+a::b::x;
+a::hidden::x;
+"#;
+
+    let mut hir = Hir::new();
+
+    for (url, src) in [
+        ("test:///a.rhai", a_src),
+        ("test:///b.rhai", b_src),
+        ("test:///c.rhai", c_src),
+    ] {
+        hir.add_source(
+            &url.parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+    }
+
+    hir.resolve_all();
+
+    let b_module = hir
+        .module_by_url(&"test:///b.rhai".parse().unwrap())
+        .unwrap();
+    let x = hir.find_in_module(b_module, "x").unwrap();
+    let x_refs = &hir[x].kind.as_decl().unwrap().references;
+    assert_eq!(x_refs.len(), 1);
+
+    // Imports in functions are not sub-modules.
+    let c_source = hir
+        .source_by_url(&"test:///c.rhai".parse().unwrap())
+        .unwrap();
+    let unresolved = hir
+        .errors_for_source(c_source)
+        .into_iter()
+        .map(|error| match error.kind {
+            ErrorKind::UnresolvedReference {
+                reference_symbol, ..
+            } => hir[reference_symbol].name(&hir).unwrap(),
+            kind => panic!("unexpected error: {kind:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(unresolved, ["hidden", "x"]);
+}
+
+#[test]
+fn test_cyclic_reexports() {
+    let a_src = r#"
+import "./b.rhai" as b;
+
+export const x = 1;
+"#;
+
+    let b_src = r#"
+import "./a.rhai" as a;
+
+a::b::a::x;
+"#;
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///a.rhai".parse().unwrap(),
+        &Parser::new(a_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///b.rhai".parse().unwrap(),
+        &Parser::new(b_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty(), "{:#?}", hir.errors());
+}
+
+#[test]
+fn test_path_members() {
+    let root_src = r#"
+import "./module.rhai" as m;
+import "./missing.rhai" as unresolved;
+
+m::
+"#;
+
+    let module_src = r#"
+fn foo() {}
+fn bar(x) {}
+private fn hidden() {}
+"#;
+
+    let root_url = "test:///root.rhai".parse().unwrap();
+
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &root_url,
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///module.rhai".parse().unwrap(),
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    let source = hir.source_by_url(&root_url).unwrap();
+    let offset = TextSize::from(u32::try_from(root_src.find("m::").unwrap() + 3).unwrap());
+
+    let mut names = hir
+        .path_members(source, offset, ["m"])
+        .into_iter()
+        .filter_map(|symbol| hir[symbol].name(&hir).map(String::from))
+        .collect::<Vec<_>>();
+    names.sort();
+
+    assert_eq!(names, ["bar", "foo"]);
+
+    assert!(hir.path_members(source, offset, ["unresolved"]).is_empty());
+    assert!(hir.path_members(source, offset, ["missing"]).is_empty());
+    assert!(hir.path_members(source, offset, ["m", "foo"]).is_empty());
+}
+
+#[test]
+fn test_entry_module() {
+    let main_src = r#"
+import "./helpers.rhai";
+
+helper(LIMIT);
+"#;
+
+    let helpers_src = r#"
+import "./more.rhai";
+
+fn helper(x) { x }
+const LIMIT = 10;
+"#;
+
+    let more_src = r#"
+fn more() {}
+"#;
+
+    let main_url: Url = "test:///main.rhai".parse().unwrap();
+
+    let build = |entry: Option<Url>| {
+        let mut hir = Hir::new();
+        hir.set_entry_module(entry);
+
+        for (url, src) in [
+            ("test:///main.rhai", main_src),
+            ("test:///helpers.rhai", helpers_src),
+            ("test:///more.rhai", more_src),
+        ] {
+            hir.add_source(
+                &url.parse().unwrap(),
+                &Parser::new(src).parse_script().into_syntax(),
+            );
+        }
+
+        hir.resolve_all();
+        hir
+    };
+
+    let resolution_errors = |hir: &Hir| {
+        hir.errors()
+            .into_iter()
+            .filter(|error| !matches!(error.kind, ErrorKind::UnusedExport { .. }))
+            .filter(|error| error.kind.lint() != Lint::EmptyBlock)
+            .collect::<Vec<_>>()
+    };
+
+    let hir = build(None);
+    assert_eq!(resolution_errors(&hir).len(), 2);
+
+    let hir = build(Some(main_url.clone()));
+    assert!(resolution_errors(&hir).is_empty(), "{:#?}", hir.errors());
+
+    let main = hir.module_by_url(&main_url).unwrap();
+    assert_eq!(hir.program_modules().len(), 3);
+
+    let mut names = hir
+        .program_symbols(main)
+        .into_iter()
+        .filter_map(|symbol| hir[symbol].name(&hir).map(String::from))
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["LIMIT", "helper", "more"]);
+
+    let helper = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "helper"))
+        .map(|(symbol, _)| symbol)
+        .unwrap();
+    assert_eq!(hir[helper].kind.as_fn().unwrap().references.len(), 1);
+}
+
+#[test]
+fn test_symbol_location() {
+    let root_src = r#"
+import "./module.rhai" as m;
+
+m::add(1, 2);
+"#;
+
+    let module_src = r#"
+export const x = 1;
+
+fn add(a, b) {
+    a + b
+}
+"#;
+
+    let root_url: Url = "test:///root.rhai".parse().unwrap();
+    let module_url: Url = "test:///module.rhai".parse().unwrap();
+
+    let mut hir = Hir::new();
+    hir.add_prelude();
+    hir.add_source(
+        &root_url,
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &module_url,
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let root = hir.source_by_url(&root_url).unwrap();
+    let offset = TextSize::from(root_src.find("add").unwrap() as u32);
+    let reference = hir.symbol_selection_at(root, offset, false).unwrap();
+
+    let Some(ReferenceTarget::Symbol(function)) = hir[reference].target() else {
+        panic!("unresolved reference");
+    };
+
+    let (url, range) = hir.symbol_location(function).unwrap();
+    assert_eq!(url, module_url);
+    assert_eq!(&module_src[range], "add");
+
+    let prelude_fn = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "print"))
+        .map(|(symbol, _)| symbol)
+        .unwrap();
+
+    assert_eq!(hir.symbol_location(prelude_fn), None);
+}
+
+#[test]
+fn test_alias_references() {
+    let root_src = r#"
+import "./module.rhai" as used;
+import "./module.rhai" as unused;
+
+used::add(1, 2);
+"#;
+
+    let root_url: Url = "test:///root.rhai".parse().unwrap();
+    let module_url: Url = "test:///module.rhai".parse().unwrap();
+
+    let mut hir = Hir::new();
+    hir.add_source(
+        &root_url,
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &module_url,
+        &Parser::new("fn add(a, b) { a + b }")
+            .parse_script()
+            .into_syntax(),
+    );
+    hir.resolve_all();
+
+    let alias_references = |name: &str| {
+        hir.symbols()
+            .filter_map(|(_, data)| data.kind.as_import()?.alias)
+            .filter_map(|alias| hir[alias].kind.as_decl())
+            .find(|decl| decl.name == name)
+            .unwrap()
+            .references
+            .len()
+    };
+
+    assert_eq!(alias_references("used"), 1);
+    assert_eq!(alias_references("unused"), 0);
+}
+
+#[test]
+fn test_unresolved_imports() {
+    let root_src = r#"
+import "./module.rhai" as m;
+import "does_not_exist" as missing;
+
+let path = "./mod" + "ule.rhai";
+import path as dynamic;
+"#;
+
+    let root_url: Url = "test:///root.rhai".parse().unwrap();
+    let root_parse = Parser::new(root_src).parse_script();
+
+    let mut hir = Hir::new();
+    hir.add_source(&root_url, &root_parse.clone_syntax());
+    hir.add_source(
+        &"test:///module.rhai".parse().unwrap(),
+        &Parser::new("export const x = 1;")
+            .parse_script()
+            .into_syntax(),
+    );
+    hir.resolve_all();
+
+    let unresolved = hir
+        .diagnostics_for(&root_url, &root_parse, &DiagnosticConfig::default())
+        .into_iter()
+        .filter(|diag| diag.lint == Some(Lint::UnresolvedImport))
+        .map(|diag| (root_src[diag.range].to_string(), diag.message))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        unresolved,
+        [(
+            String::from(r#""does_not_exist""#),
+            String::from("module `does_not_exist` does not exist")
+        )]
+    );
+}
+
+#[test]
+fn test_unused_exports() {
+    let root_src = r#"
+import "./lib.rhai" as lib;
+
+lib::used();
+lib::USED_CONST;
+fn unused_in_root() {}
+"#;
+
+    let lib_src = r#"
+fn used() {}
+fn unused() {}
+fn called_internally() {}
+private fn private_unused() {}
+
+export const USED_CONST = 1;
+export const UNUSED_CONST = 2;
+
+called_internally();
+"#;
+
+    let lib_url: Url = "test:///lib.rhai".parse().unwrap();
+
+    let mut hir = Hir::new();
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(&lib_url, &Parser::new(lib_src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let unused_exports = |hir: &Hir| {
+        let mut names = hir
+            .unused_exports()
+            .into_iter()
+            .map(|symbol| hir[symbol].name(hir).unwrap().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        unused_exports(&hir),
+        ["UNUSED_CONST", "called_internally", "unused"]
+    );
+    assert_eq!(
+        hir.errors()
+            .into_iter()
+            .filter(|error| matches!(error.kind, ErrorKind::UnusedExport { .. }))
+            .count(),
+        3
+    );
+
+    hir.set_public_modules(vec![lib_url]);
+    assert!(unused_exports(&hir).is_empty());
+}
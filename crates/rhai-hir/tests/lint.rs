@@ -0,0 +1,320 @@
+use rhai_hir::{
+    error::ErrorKind,
+    lint::{DiagnosticConfig, Lint, LintLevel},
+    testing, Hir,
+};
+use rhai_rowan::parser::Parser;
+
+const SRC: &str = r#"
+let exported = 1;
+
+private fn never_called() {
+    1
+}
+
+if true {
+    let unused = 1;
+    let _ignored = 2;
+    let used = 3;
+    let also_unused = used;
+}
+"#;
+
+fn build() -> Hir {
+    let mut hir = Hir::new();
+    hir.add_source(
+        &"test:///lint.rhai".parse().unwrap(),
+        &Parser::new(SRC).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+    hir
+}
+
+fn lints(config: &DiagnosticConfig, hir: &Hir) -> Vec<(Lint, LintLevel)> {
+    let mut lints = config
+        .apply(hir.errors())
+        .into_iter()
+        .map(|(error, level)| (error.kind.lint(), level))
+        .collect::<Vec<_>>();
+    lints.sort_by_key(|&(lint, _)| format!("{lint:?}"));
+    lints
+}
+
+#[test]
+fn test_unused_variables() {
+    let hir = build();
+
+    let mut names = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::UnusedVariable { declaration } => {
+                Some(hir[declaration].name(&hir).unwrap().to_string())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+
+    assert_eq!(names, ["also_unused", "unused"]);
+}
+
+#[test]
+fn test_default_levels() {
+    assert_eq!(
+        lints(&DiagnosticConfig::default(), &build()),
+        [
+            (Lint::ConstantCondition, LintLevel::Warning),
+            (Lint::UnusedFunction, LintLevel::Warning),
+            (Lint::UnusedVariable, LintLevel::Hint),
+            (Lint::UnusedVariable, LintLevel::Hint),
+        ]
+    );
+}
+
+#[test]
+fn test_disable_unused_variable() {
+    let config = DiagnosticConfig {
+        unused_variable: Some(LintLevel::Off),
+        ..DiagnosticConfig::default()
+    };
+
+    assert_eq!(
+        lints(&config, &build()),
+        [
+            (Lint::ConstantCondition, LintLevel::Warning),
+            (Lint::UnusedFunction, LintLevel::Warning),
+        ]
+    );
+}
+
+#[test]
+fn test_relabel_lint() {
+    let config = DiagnosticConfig {
+        unused_function: Some(LintLevel::Error),
+        constant_condition: Some(LintLevel::Off),
+        ..DiagnosticConfig::default()
+    };
+
+    assert_eq!(
+        lints(&config, &build()),
+        [
+            (Lint::UnusedFunction, LintLevel::Error),
+            (Lint::UnusedVariable, LintLevel::Hint),
+            (Lint::UnusedVariable, LintLevel::Hint),
+        ]
+    );
+}
+
+fn shadowed_builtins(src: &str, config: &DiagnosticConfig) -> Vec<(String, LintLevel)> {
+    let mut hir = Hir::new();
+    hir.add_prelude();
+    hir.add_source(
+        &"test:///shadow.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    config
+        .apply(hir.errors())
+        .into_iter()
+        .filter_map(|(error, level)| match error.kind {
+            ErrorKind::ShadowedBuiltin {
+                declaration,
+                builtin,
+                name,
+            } => {
+                assert!(hir[builtin].kind.is_fn());
+                assert_eq!(hir[declaration].name(&hir), Some(name.as_str()));
+                Some((name, level))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_shadowed_builtin() {
+    let src = r#"
+fn print() {}
+fn my_print() {}
+
+let type_of = 1;
+let kind = type_of;
+
+fn foo(debug) { debug }
+foo(kind);
+"#;
+
+    assert_eq!(
+        shadowed_builtins(src, &DiagnosticConfig::default()),
+        [
+            ("print".to_string(), LintLevel::Warning),
+            ("type_of".to_string(), LintLevel::Warning),
+        ]
+    );
+
+    let config = DiagnosticConfig {
+        shadowed_builtin: Some(LintLevel::Off),
+        ..DiagnosticConfig::default()
+    };
+
+    assert!(shadowed_builtins(src, &config).is_empty());
+}
+
+fn incompatible_operands(src: &str) -> Vec<(String, LintLevel)> {
+    let (hir, _) = testing::build(src);
+
+    DiagnosticConfig::default()
+        .apply(hir.errors())
+        .into_iter()
+        .filter(|(error, _)| error.kind.lint() == Lint::IncompatibleOperands)
+        .map(|(error, level)| (error.to_string(), level))
+        .collect()
+}
+
+#[test]
+fn test_incompatible_operands() {
+    assert_eq!(
+        incompatible_operands("let x = 1 + true;"),
+        [(
+            "`+` is not defined for `int` and `bool`".to_string(),
+            LintLevel::Hint
+        )]
+    );
+
+    assert_eq!(
+        incompatible_operands("let a = [1]; let x = a < 3;"),
+        [(
+            "`<` is not defined for `[int]` and `int`".to_string(),
+            LintLevel::Hint
+        )]
+    );
+
+    let src = r#"
+let a = 1 + 2.0;
+let b = "a" + 'b';
+let c = [1] + [2];
+let d = 1.0 < 2;
+let e = unknown + 1;
+let f = 1 == "x";
+let g = "x" + 1;
+let h = 1 + "x";
+let i = "a" + true;
+"#;
+
+    assert!(incompatible_operands(src).is_empty());
+}
+
+fn unknown_fields(src: &str) -> Vec<String> {
+    let (hir, _) = testing::build(src);
+
+    DiagnosticConfig::default()
+        .apply(hir.errors())
+        .into_iter()
+        .filter(|(error, _)| error.kind.lint() == Lint::UnknownField)
+        .map(|(error, _)| error.to_string())
+        .collect()
+}
+
+#[test]
+fn test_unknown_field() {
+    let config = "const config = #{ server: #{ port: 80 } };";
+
+    assert!(unknown_fields(&format!("{config} config.server.port;")).is_empty());
+
+    assert_eq!(
+        unknown_fields(&format!("{config} config.client.port;")),
+        ["no field `client` on type `#{server: #{port: int}}`"]
+    );
+    assert_eq!(
+        unknown_fields(&format!("{config} config.server.host;")),
+        ["no field `host` on type `#{port: int}`"]
+    );
+
+    // Variables can gain fields.
+    assert!(unknown_fields("let o = #{}; o.a = 1; o.a;").is_empty());
+}
+
+fn empty_blocks(src: &str, config: &DiagnosticConfig) -> Vec<(String, String)> {
+    let url = "test:///empty.rhai".parse().unwrap();
+    let parse = Parser::new(src).parse_script();
+
+    let mut hir = Hir::new();
+    hir.add_source(&url, &parse.clone_syntax());
+    hir.resolve_all();
+
+    hir.diagnostics_for(&url, &parse, config)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.lint == Some(Lint::EmptyBlock))
+        .map(|diagnostic| (src[diagnostic.range].to_string(), diagnostic.message))
+        .collect()
+}
+
+#[test]
+fn test_empty_block() {
+    let src = r#"
+fn f(a, b) {}
+
+let x = 1;
+
+if x > 0 {} else {}
+
+for item in [1, 2] {}
+"#;
+
+    let empty = |(range, message): &(&str, &str)| (range.to_string(), message.to_string());
+
+    assert_eq!(
+        empty_blocks(src, &DiagnosticConfig::default()),
+        [
+            ("f", "empty function body"),
+            ("{}", "empty `if` branch"),
+            ("{}", "empty `else` branch"),
+            ("{}", "empty loop body"),
+        ]
+        .iter()
+        .map(empty)
+        .collect::<Vec<_>>()
+    );
+
+    let config = DiagnosticConfig {
+        allow_empty_else: true,
+        allow_empty_loop: true,
+        ..DiagnosticConfig::default()
+    };
+
+    assert_eq!(
+        empty_blocks(src, &config),
+        [("f", "empty function body"), ("{}", "empty `if` branch")]
+            .iter()
+            .map(empty)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_intentionally_empty_block() {
+    let src = r#"
+let x = 1;
+
+if x > 0 {
+    // Nothing to do yet.
+}
+
+if x < 0 { x += 1; }
+
+fn todo() {
+    // Not implemented yet.
+}
+
+loop {}
+"#;
+
+    let config = DiagnosticConfig {
+        allow_empty_loop: true,
+        ..DiagnosticConfig::default()
+    };
+
+    assert!(empty_blocks(src, &config).is_empty());
+}
@@ -0,0 +1,163 @@
+use rhai_hir::{
+    error::ErrorKind,
+    eval::{InvalidLiteral, NumericConfig, Value},
+    testing, Hir,
+};
+use rhai_rowan::parser::Parser;
+use test_case::test_case;
+
+/// The decoded value of the only literal in the source,
+/// or the reason why it is invalid.
+fn literal(src: &str) -> Result<Value, InvalidLiteral> {
+    let (hir, _) = testing::build(src);
+    literal_of(&hir)
+}
+
+/// Same as [`literal`] with 32-bit integers.
+fn literal_i32(src: &str) -> Result<Value, InvalidLiteral> {
+    literal_with(
+        src,
+        NumericConfig {
+            only_i32: true,
+            ..NumericConfig::default()
+        },
+    )
+}
+
+fn literal_with(src: &str, numeric: NumericConfig) -> Result<Value, InvalidLiteral> {
+    let mut hir = Hir::new();
+    hir.set_numeric_config(numeric);
+    hir.add_source(
+        &testing::TEST_URL.parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    literal_of(&hir)
+}
+
+fn literal_of(hir: &Hir) -> Result<Value, InvalidLiteral> {
+    let lit = hir
+        .symbols()
+        .find_map(|(_, data)| data.kind.as_lit())
+        .unwrap();
+
+    match &lit.invalid {
+        Some(invalid) => Err(invalid.clone()),
+        None => Ok(lit.value.clone()),
+    }
+}
+
+#[test_case("0xFF", 255 ; "hex")]
+#[test_case("0o17", 15 ; "octal")]
+#[test_case("0b1010", 10 ; "binary")]
+#[test_case("1_000_000", 1_000_000 ; "separators")]
+#[test_case("0xFFFF_FFFF_FFFF_FFFF", -1 ; "hex wraps")]
+#[test_case("9223372036854775807", i64::MAX ; "max")]
+fn test_int(src: &str, expected: i64) {
+    assert_eq!(literal(src).unwrap().as_int(), Some(&expected));
+}
+
+#[test_case("2147483647", Ok(2_147_483_647) ; "max")]
+#[test_case("2147483648", Err(InvalidLiteral::IntegerOverflow) ; "overflow")]
+#[test_case("-2147483649", Err(InvalidLiteral::IntegerOverflow) ; "negated overflow")]
+#[test_case("0xFFFF_FFFF", Ok(-1) ; "hex wraps")]
+#[test_case("0x1_0000_0000", Err(InvalidLiteral::IntegerOverflow) ; "hex overflow")]
+#[test_case("0b1_0000_0000_0000_0000_0000_0000_0000_0000", Err(InvalidLiteral::IntegerOverflow) ; "binary overflow")]
+fn test_int_i32(src: &str, expected: Result<i64, InvalidLiteral>) {
+    assert_eq!(
+        literal_i32(src).map(|value| value.as_int().copied().unwrap()),
+        expected
+    );
+}
+
+#[test_case("-9223372036854775808", false, i64::MIN ; "i64")]
+#[test_case("-2147483648", true, i64::from(i32::MIN) ; "i32")]
+fn test_min_int(src: &str, only_i32: bool, expected: i64) {
+    let mut hir = Hir::new();
+    hir.set_only_i32(only_i32);
+    hir.add_source(
+        &testing::TEST_URL.parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+    assert!(hir.errors().is_empty(), "{:#?}", hir.errors());
+
+    let (unary, _) = hir
+        .symbols()
+        .find(|(_, data)| data.kind.is_unary())
+        .unwrap();
+    assert_eq!(hir.const_value(unary).as_int(), Some(&expected));
+}
+
+#[test]
+fn test_float() {
+    assert_eq!(literal("1_000.5").unwrap().as_float(), Some(&1000.5));
+}
+
+#[test_case("3.5e38", Err(InvalidLiteral::FloatOverflow) ; "overflow")]
+#[test_case("3.0e38", Ok(3.0e38_f32) ; "max")]
+fn test_float_f32(src: &str, expected: Result<f32, InvalidLiteral>) {
+    let numeric = NumericConfig {
+        f32_float: true,
+        ..NumericConfig::default()
+    };
+
+    assert_eq!(
+        literal_with(src, numeric).map(|value| value.as_float().copied().unwrap()),
+        expected.map(f64::from)
+    );
+}
+
+#[test]
+fn test_no_float() {
+    let numeric = NumericConfig {
+        no_float: true,
+        ..NumericConfig::default()
+    };
+
+    assert_eq!(
+        literal_with("1.5", numeric).unwrap_err(),
+        InvalidLiteral::FloatNotSupported
+    );
+    assert_eq!(literal_with("1", numeric).unwrap().as_int(), Some(&1));
+}
+
+#[test_case(r#""a\tb\n""#, "a\tb\n" ; "escapes")]
+#[test_case(r#""\x41B\U00000043""#, "ABC" ; "unicode escapes")]
+#[test_case(r#""a\\b""#, "a\\b" ; "backslash")]
+fn test_escaped_string(src: &str, expected: &str) {
+    assert_eq!(
+        literal(src).unwrap().as_string().map(String::as_str),
+        Some(expected)
+    );
+}
+
+#[test_case("'a'", 'a' ; "plain")]
+#[test_case(r"'\n'", '\n' ; "escaped")]
+#[test_case(r"'\u0041'", 'A' ; "unicode")]
+fn test_char(src: &str, expected: char) {
+    assert_eq!(literal(src).unwrap().as_char(), Some(&expected));
+}
+
+#[test_case("9223372036854775808", InvalidLiteral::IntegerOverflow ; "int overflow")]
+#[test_case("99999999999999999999", InvalidLiteral::IntegerOverflow ; "large int overflow")]
+#[test_case("-9223372036854775809", InvalidLiteral::IntegerOverflow ; "negated int overflow")]
+#[test_case("0x1_0000_0000_0000_0000", InvalidLiteral::IntegerOverflow ; "hex overflow")]
+#[test_case(r#""\q""#, InvalidLiteral::MalformedEscapeSequence(r"\q".into()) ; "bad escape")]
+fn test_invalid(src: &str, expected: InvalidLiteral) {
+    assert_eq!(literal(src).unwrap_err(), expected);
+
+    let (hir, _) = testing::build(src);
+    let errors = hir.errors();
+
+    assert!(
+        matches!(
+            &errors[..],
+            [rhai_hir::error::Error {
+                kind: ErrorKind::InvalidLiteral { reason, .. }
+            }] if *reason == expected
+        ),
+        "{errors:#?}"
+    );
+}
@@ -0,0 +1,43 @@
+use rhai_hir::{module::PRELUDE_URL, symbol::SymbolKind, Hir};
+use rhai_rowan::parser::Parser;
+
+#[test]
+fn test_prelude_call() {
+    let src = r#"
+let x = 2;
+type_of(x);
+"#;
+
+    let mut hir = Hir::new();
+    hir.add_prelude();
+
+    hir.add_source(
+        &"test:///root.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+
+    hir.resolve_all();
+
+    assert!(hir.errors().is_empty());
+
+    let prelude = hir.source_of(&PRELUDE_URL.parse().unwrap()).unwrap();
+
+    let target = hir
+        .symbols()
+        .find_map(|(_, data)| match &data.kind {
+            SymbolKind::Ref(r) if r.name == "type_of" => {
+                r.target.as_ref().and_then(|t| t.as_symbol().copied())
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    let target_data = &hir[target];
+    assert!(target_data.source.is(prelude));
+
+    let SymbolKind::Fn(f) = &target_data.kind else {
+        panic!("expected a function");
+    };
+
+    assert_eq!(f.name, "type_of");
+}
@@ -0,0 +1,383 @@
+use rhai_hir::{error::ErrorKind, symbol::SymbolKind, testing, Hir};
+use rhai_rowan::parser::Parser;
+use test_case::test_case;
+
+#[test]
+fn test_interpolated_reference() {
+    let src = r#"
+let name = "world";
+let greeting = `hello ${name}!`;
+"#;
+
+    let mut hir = Hir::new();
+    hir.add_source(
+        &"test:///references.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let decl = hir
+        .symbols()
+        .find_map(|(_, data)| data.kind.as_decl().filter(|d| d.name == "name"))
+        .unwrap();
+
+    assert_eq!(decl.references.len(), 1);
+
+    let reference = *decl.references.iter().next().unwrap();
+    let SymbolKind::Ref(r) = &hir[reference].kind else {
+        panic!("expected a reference");
+    };
+    assert_eq!(r.name, "name");
+
+    let range = hir[reference].source.text_range.unwrap();
+    assert_eq!(&src[range], "name");
+}
+
+/// The declarations the references named `x` resolve to,
+/// in the order of the references.
+fn resolved_declarations(src: &str) -> Vec<&str> {
+    let mut hir = Hir::new();
+    hir.add_source(
+        &"test:///references.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let mut references = hir
+        .symbols()
+        .filter(|(_, data)| {
+            data.kind
+                .as_reference()
+                .is_some_and(|r| r.name == "x" && !r.field_access)
+        })
+        .filter_map(|(symbol, data)| Some((data.text_range()?.start(), symbol)))
+        .collect::<Vec<_>>();
+    references.sort_by_key(|&(start, _)| start);
+
+    references
+        .into_iter()
+        .map(|(_, reference)| {
+            let target = hir[reference]
+                .kind
+                .as_reference()
+                .and_then(|r| r.target)
+                .and_then(|target| target.as_symbol().copied())
+                .expect("unresolved reference");
+
+            &src[hir[target].text_range().unwrap()]
+        })
+        .collect()
+}
+
+#[test_case(
+    "let x = 1; let x = 2; x",
+    &["let x = 2"] ;
+    "same scope"
+)]
+#[test_case(
+    "let x = 1; let x = x + 1; x",
+    &["let x = 1", "let x = x + 1"] ;
+    "initializer"
+)]
+#[test_case(
+    "let x = 1; { let x = 2; { x } } x",
+    &["let x = 2", "let x = 1"] ;
+    "nested blocks"
+)]
+#[test_case(
+    "let x = 1; { x; let x = 2; x }",
+    &["let x = 1", "let x = 2"] ;
+    "before shadowing"
+)]
+#[test_case(
+    "let x = 1; if true { let x = 2; x } else { x }",
+    &["let x = 2", "let x = 1"] ;
+    "branches"
+)]
+#[test_case(
+    "let x = 1; for x in [] { x } x",
+    &["x", "let x = 1"] ;
+    "loop variable"
+)]
+#[test_case(
+    "let x = 1; try { x } catch (x) { x } x",
+    &["let x = 1", "x", "let x = 1"] ;
+    "catch parameter"
+)]
+#[test_case(
+    "let x = 1; let f = |x| { let y = x; { let x = y; x } }; x",
+    &["x", "let x = y", "let x = 1"] ;
+    "closure parameter"
+)]
+#[test_case(
+    "let x = 1; let y = { let x = 2; x }; let x = 3; x",
+    &["let x = 2", "let x = 3"] ;
+    "block initializer"
+)]
+#[test_case(
+    "fn x() {} let x = 1; x",
+    &["let x = 1"] ;
+    "hoisted function"
+)]
+fn test_shadowed_reference(src: &str, expected: &[&str]) {
+    assert_eq!(resolved_declarations(src), expected);
+}
+
+#[test_case("fn f(x) { x }" ; "function")]
+#[test_case("fn f(x) { let y = 1; { y + x } }" ; "nested block")]
+#[test_case("let x = 1; fn f(x) { x }" ; "shadowed variable")]
+#[test_case("{ fn f(x) { x } }" ; "function in block")]
+fn test_parameter_reference(src: &str) {
+    let (hir, _) = testing::build(src);
+
+    let reference = hir
+        .symbols()
+        .find_map(|(_, data)| data.kind.as_reference().filter(|r| r.name == "x"))
+        .unwrap();
+
+    let target = reference
+        .target
+        .and_then(|target| target.as_symbol().copied())
+        .expect("unresolved parameter");
+
+    assert!(hir[target].kind.as_decl().is_some_and(|decl| decl.is_param));
+}
+
+#[test]
+fn test_references_to_across_modules() {
+    let module_src = r#"
+fn func() {}
+
+func();
+"#;
+
+    let root_src = r#"
+import "./module.rhai" as m;
+import "./reexport.rhai" as r;
+
+m::func();
+r::inner::func();
+"#;
+
+    let reexport_src = r#"
+import "./module.rhai" as inner;
+"#;
+
+    let mut hir = Hir::new();
+
+    let module_url = "test:///module.rhai".parse().unwrap();
+    let root_url = "test:///root.rhai".parse().unwrap();
+
+    hir.add_source(
+        &module_url,
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &root_url,
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///reexport.rhai".parse().unwrap(),
+        &Parser::new(reexport_src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let func = hir
+        .symbols()
+        .find_map(|(symbol, data)| {
+            data.kind
+                .as_fn()
+                .filter(|f| f.name == "func")
+                .map(|_| symbol)
+        })
+        .unwrap();
+
+    let references = hir.references_to(func);
+    assert_eq!(references.len(), 2, "{references:#?}");
+
+    let text_of = |src: &'static str, symbols: &[rhai_hir::Symbol]| {
+        symbols
+            .iter()
+            .map(|&symbol| &src[hir[symbol].text_range().unwrap()])
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(text_of(module_src, &references[&module_url]), ["func"]);
+    assert_eq!(text_of(root_src, &references[&root_url]), ["func", "func"]);
+}
+
+const VALUE_SRC: &str = "export const value = 1;";
+
+const INNER_SRC: &str = r#"import "./value.rhai" as inner;"#;
+
+const PATH_SRC: &str = r#"import "./inner.rhai" as outer;
+
+let x = outer::inner::value;
+"#;
+
+/// The reference and the text and URL of its target
+/// at the offset of the `$` marker in the path.
+fn path_segment_at(marker: &str) -> (String, String, String) {
+    let offset = marker.find('$').unwrap();
+    let line = marker.replace('$', "");
+    let offset = PATH_SRC.find(&line).unwrap() + offset;
+
+    let mut hir = Hir::new();
+
+    for (url, src) in [
+        ("test:///value.rhai", VALUE_SRC),
+        ("test:///inner.rhai", INNER_SRC),
+        ("test:///path.rhai", PATH_SRC),
+    ] {
+        hir.add_source(
+            &url.parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+    }
+    hir.resolve_all();
+
+    let source = hir
+        .source_by_url(&"test:///path.rhai".parse().unwrap())
+        .unwrap();
+
+    let reference = hir
+        .symbol_selection_at(source, (offset as u32).into(), true)
+        .unwrap();
+
+    // The innermost symbol is the same segment.
+    assert_eq!(
+        hir.symbol_at(source, (offset as u32).into(), false),
+        Some(reference)
+    );
+
+    let target = hir[reference]
+        .kind
+        .as_reference()
+        .and_then(|r| r.target)
+        .and_then(|target| target.as_symbol().copied())
+        .unwrap();
+
+    let target_source = hir[target].source.source.unwrap();
+    let target_src = match hir[target_source].url.as_str() {
+        "test:///value.rhai" => VALUE_SRC,
+        "test:///inner.rhai" => INNER_SRC,
+        _ => PATH_SRC,
+    };
+
+    (
+        hir[reference].name(&hir).unwrap().to_string(),
+        hir[target_source].url.to_string(),
+        target_src[hir[target].text_range().unwrap()].to_string(),
+    )
+}
+
+#[test_case("$outer::inner::value", "outer", "test:///path.rhai", "outer" ; "first segment start")]
+#[test_case("out$er::inner::value", "outer", "test:///path.rhai", "outer" ; "first segment")]
+#[test_case("outer::$inner::value", "inner", "test:///inner.rhai", r#"import "./value.rhai" as inner"# ; "middle segment start")]
+#[test_case("outer::inn$er::value", "inner", "test:///inner.rhai", r#"import "./value.rhai" as inner"# ; "middle segment")]
+#[test_case("outer::inner::val$ue", "value", "test:///value.rhai", "const value = 1" ; "last segment")]
+fn test_path_segment_target(marker: &str, name: &str, url: &str, target: &str) {
+    let (reference, target_url, target_text) = path_segment_at(marker);
+
+    assert_eq!(reference, name);
+    assert_eq!(target_url, url);
+    assert_eq!(target_text, target);
+}
+
+/// The ranges and the reference counts of the implicit declarations
+/// of `x`, along with the number of references reported as
+/// unresolved or used before their definitions.
+fn implicit_declarations(src: &str, implicit_globals: bool) -> (Vec<(&str, usize)>, usize) {
+    let mut hir = Hir::new();
+    hir.set_implicit_globals(implicit_globals);
+    hir.add_source(
+        &"test:///references.rhai".parse().unwrap(),
+        &Parser::new(src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    // Resolving again must not duplicate the declarations.
+    hir.resolve_all();
+
+    let declarations = hir
+        .symbols()
+        .filter_map(|(_, data)| {
+            let decl = data.kind.as_decl()?;
+            (decl.is_implicit && decl.name == "x")
+                .then(|| (&src[data.text_range().unwrap()], decl.references.len()))
+        })
+        .collect();
+
+    let unresolved = hir
+        .errors()
+        .iter()
+        .filter(|error| {
+            matches!(
+                error.kind,
+                ErrorKind::UnresolvedReference { .. } | ErrorKind::UseBeforeDefinition { .. }
+            )
+        })
+        .count();
+
+    (declarations, unresolved)
+}
+
+#[test_case("x = 1; let y = x;", 2 ; "read")]
+#[test_case("x = 1; x = 2; x += 1;", 3 ; "assigned again")]
+#[test_case("if true { x = 1; } let y = x;", 2 ; "nested block")]
+fn test_implicit_global(src: &str, references: usize) {
+    assert_eq!(
+        implicit_declarations(src, true),
+        (vec![("x", references)], 0)
+    );
+    assert_eq!(implicit_declarations(src, false), (vec![], references));
+}
+
+#[test]
+fn test_implicit_global_read_before_assignment() {
+    let src = "let y = x; x = 1;";
+    assert_eq!(implicit_declarations(src, true), (vec![("x", 1)], 1));
+}
+
+#[test_case("x += 1;", 1 ; "compound assignment")]
+#[test_case("x[0] = 1;", 1 ; "index assignment")]
+#[test_case("x.field = 1;", 1 ; "field assignment")]
+#[test_case("fn f() { x = 1; x }", 2 ; "function")]
+#[test_case("let f = || { x = 1; }; x", 2 ; "closure")]
+#[test_case("let x = 0; x = 1; x", 0 ; "declared")]
+fn test_no_implicit_global(src: &str, unresolved: usize) {
+    assert_eq!(implicit_declarations(src, true), (vec![], unresolved));
+}
+
+/// The names of the captured declarations of each closure,
+/// in the order of the closures.
+fn closure_captures(src: &str) -> Vec<Vec<String>> {
+    let (hir, _) = testing::build(src);
+
+    let mut closures = hir
+        .symbols()
+        .filter_map(|(_, data)| Some((data.text_range()?.start(), data.kind.as_closure()?)))
+        .collect::<Vec<_>>();
+    closures.sort_by_key(|(start, _)| *start);
+
+    closures
+        .into_iter()
+        .map(|(_, closure)| {
+            closure
+                .captures
+                .iter()
+                .map(|&declaration| hir[declaration].name(&hir).unwrap().to_string())
+                .collect()
+        })
+        .collect()
+}
+
+#[test_case("let x = 1; let f = || x + 1;", &[&["x"]] ; "outer variable")]
+#[test_case("let f = |a| a + 1;", &[&[]] ; "parameter")]
+#[test_case("let f = || { let y = 1; y };", &[&[]] ; "local variable")]
+#[test_case("let x = 1; let f = || x + x;", &[&["x"]] ; "referenced twice")]
+#[test_case("let x = 1; let f = |a| || a + x;", &[&["x"], &["a", "x"]] ; "nested closure")]
+#[test_case("fn g() { 1 } let f = || g();", &[&[]] ; "function")]
+fn test_closure_captures(src: &str, captures: &[&[&str]]) {
+    assert_eq!(closure_captures(src), captures);
+}
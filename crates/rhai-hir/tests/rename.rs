@@ -0,0 +1,239 @@
+use rhai_hir::{symbol::RenameCollision, testing, Hir, Symbol};
+use rhai_rowan::{parser::Parser, TextRange};
+
+fn parse(src: &str) -> Hir {
+    testing::build(src).0
+}
+
+fn decl(hir: &Hir, name: &str) -> Symbol {
+    hir.symbols()
+        .find(|(_, data)| data.kind.as_decl().is_some_and(|d| d.name == name))
+        .map(|(symbol, _)| symbol)
+        .unwrap()
+}
+
+#[test]
+fn test_rename_captured_by_existing_declaration() {
+    let hir = parse(
+        r#"
+let a = 1;
+let b = 2;
+a + b;
+"#,
+    );
+
+    let collisions = hir.rename_collisions(decl(&hir, "a"), "b");
+
+    assert_eq!(collisions.len(), 1, "{collisions:#?}");
+    match collisions[0] {
+        RenameCollision::Captured {
+            reference,
+            declaration,
+        } => {
+            assert_eq!(hir[reference].name(&hir), Some("a"));
+            assert_eq!(declaration, decl(&hir, "b"));
+        }
+        c => panic!("unexpected collision {c:?}"),
+    }
+}
+
+#[test]
+fn test_rename_shadows_existing_reference() {
+    let hir = parse(
+        r#"
+let b = 1;
+let a = 2;
+a + b;
+"#,
+    );
+
+    let collisions = hir.rename_collisions(decl(&hir, "a"), "b");
+
+    assert_eq!(
+        collisions,
+        [RenameCollision::Shadowed {
+            reference: hir[decl(&hir, "b")]
+                .kind
+                .as_decl()
+                .unwrap()
+                .references
+                .iter()
+                .copied()
+                .next()
+                .unwrap(),
+            declaration: Some(decl(&hir, "b")),
+        }]
+    );
+}
+
+#[test]
+fn test_rename_without_collisions() {
+    let hir = parse(
+        r#"
+let a = 1;
+a;
+let c = 2;
+{
+    let b = 3;
+}
+"#,
+    );
+
+    assert!(hir.rename_collisions(decl(&hir, "a"), "b").is_empty());
+}
+
+#[test]
+fn test_rename_edits_across_modules() {
+    let module_src = r#"
+export const VALUE = 1;
+let value = VALUE;
+
+let x = 2;
+export x;
+export x as alias;
+"#;
+
+    let root_src = r#"
+import "./module.rhai" as m;
+
+let value = m::VALUE + m::VALUE;
+let y = m::x + m::alias;
+"#;
+
+    let module_url = "test:///module.rhai".parse().unwrap();
+    let root_url = "test:///root.rhai".parse().unwrap();
+
+    let mut hir = Hir::new();
+    hir.add_source(
+        &module_url,
+        &Parser::new(module_src).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &root_url,
+        &Parser::new(root_src).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    let edits = hir.rename_edits(decl(&hir, "VALUE"), "RENAMED");
+    assert_eq!(edits.len(), 2, "{edits:#?}");
+    assert_eq!(
+        edit_texts(&edits[&module_url], module_src),
+        [("VALUE", "RENAMED"), ("VALUE", "RENAMED")]
+    );
+    assert_eq!(
+        edit_texts(&edits[&root_url], root_src),
+        [("VALUE", "RENAMED"), ("VALUE", "RENAMED")]
+    );
+
+    let edits = hir.rename_edits(decl(&hir, "x"), "z");
+    assert_eq!(edits.len(), 2, "{edits:#?}");
+    // The declaration and both exports, the alias itself is kept.
+    assert_eq!(
+        edit_texts(&edits[&module_url], module_src),
+        [("x", "z"), ("x", "z"), ("x", "z")]
+    );
+    // References through the alias keep working without changes.
+    assert_eq!(edit_texts(&edits[&root_url], root_src), [("x", "z")]);
+
+    for source_edits in edits.values() {
+        assert!(source_edits
+            .windows(2)
+            .all(|w| w[0].0.end() <= w[1].0.start()));
+    }
+}
+
+fn edit_texts<'a>(edits: &'a [(TextRange, String)], src: &'a str) -> Vec<(&'a str, &'a str)> {
+    edits
+        .iter()
+        .map(|(range, new_text)| (&src[*range], new_text.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_file_rename_edits() {
+    let sources = [
+        ("test:///lib/util.rhai", "fn helper() {}"),
+        (
+            "test:///main.rhai",
+            r#"
+import "./lib/util.rhai" as a;
+import "lib/util" as b;
+import "test:///lib/util.rhai" as c;
+import "/lib/util" as d;
+import "./other" as other;
+"#,
+        ),
+        ("test:///lib/nested.rhai", r#"import "./util" as u;"#),
+    ];
+
+    let mut hir = Hir::new();
+    for (url, src) in sources {
+        hir.add_source(
+            &url.parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+    }
+    hir.resolve_all();
+
+    let edits = hir.file_rename_edits(
+        &"test:///lib/util.rhai".parse().unwrap(),
+        &"test:///shared/tools.rhai".parse().unwrap(),
+    );
+    assert_eq!(edits.len(), 2, "{edits:#?}");
+
+    assert_eq!(
+        edit_texts(&edits[&"test:///main.rhai".parse().unwrap()], sources[1].1),
+        [
+            (r#""./lib/util.rhai""#, r#""./shared/tools.rhai""#),
+            (r#""lib/util""#, r#""shared/tools""#),
+            (
+                r#""test:///lib/util.rhai""#,
+                r#""test:///shared/tools.rhai""#
+            ),
+            (r#""/lib/util""#, r#""/shared/tools""#),
+        ]
+    );
+    assert_eq!(
+        edit_texts(
+            &edits[&"test:///lib/nested.rhai".parse().unwrap()],
+            sources[2].1
+        ),
+        [(r#""./util""#, r#""../shared/tools""#)]
+    );
+}
+
+#[test]
+fn test_file_rename_edits_are_not_percent_encoded() {
+    let sources = [
+        ("test:///util.rhai", "fn helper() {}"),
+        (
+            "test:///main.rhai",
+            r#"
+import "./util" as a;
+import "/util" as b;
+"#,
+        ),
+    ];
+
+    let mut hir = Hir::new();
+    for (url, src) in sources {
+        hir.add_source(
+            &url.parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+    }
+    hir.resolve_all();
+
+    let edits = hir.file_rename_edits(
+        &"test:///util.rhai".parse().unwrap(),
+        &"test:///my%20tools/ütil.rhai".parse().unwrap(),
+    );
+
+    assert_eq!(
+        edit_texts(&edits[&"test:///main.rhai".parse().unwrap()], sources[1].1),
+        [
+            (r#""./util""#, r#""./my tools/ütil""#),
+            (r#""/util""#, r#""/my tools/ütil""#),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+use rhai_hir::{source::Source, testing, Hir};
+use rhai_rowan::{
+    parser::Parser,
+    query::surround::{Surround, SurroundKind},
+    TextRange,
+};
+
+const SRC: &str = r#"let a = 1;
+let b = a + 1;
+
+fn foo() {
+    let inner = 2;
+}
+
+print(b);
+"#;
+
+fn statements(hir: &Hir, source: Source, selected: &str) -> Vec<String> {
+    let start = SRC.find(selected).unwrap();
+    let range = TextRange::at(
+        u32::try_from(start).unwrap().into(),
+        u32::try_from(selected.len()).unwrap().into(),
+    );
+
+    hir.statements_in_range(source, range)
+        .into_iter()
+        .map(|symbol| SRC[hir[symbol].text_range().unwrap()].to_string())
+        .collect()
+}
+
+#[test]
+fn test_statements_in_range() {
+    let (hir, _) = testing::build(SRC);
+    let source = hir
+        .source_by_url(&testing::TEST_URL.parse().unwrap())
+        .unwrap();
+
+    // Partially covered first and last statements are included.
+    assert_eq!(
+        statements(&hir, source, "1;\nlet b = a"),
+        ["let a = 1", "let b = a + 1"]
+    );
+    assert_eq!(
+        statements(&hir, source, "+ 1;\n\nfn foo() {\n    let inner"),
+        ["let b = a + 1", "fn foo() {\n    let inner = 2;\n}"]
+    );
+
+    // Nested statements select the top-level ones.
+    assert_eq!(
+        statements(&hir, source, "inner"),
+        ["fn foo() {\n    let inner = 2;\n}"]
+    );
+
+    // An empty range selects the statement that contains it.
+    let offset = u32::try_from(SRC.find("print").unwrap() + 2).unwrap();
+    let empty = TextRange::empty(offset.into());
+    assert_eq!(
+        hir.statements_in_range(source, empty)
+            .into_iter()
+            .map(|symbol| &SRC[hir[symbol].text_range().unwrap()])
+            .collect::<Vec<_>>(),
+        ["print(b)"]
+    );
+
+    // Whitespace between the statements selects nothing.
+    assert!(statements(&hir, source, "\n\n").is_empty());
+}
+
+#[test]
+fn test_inner_statements_in_range() {
+    let (hir, _) = testing::build(SRC);
+    let source = hir
+        .source_by_url(&testing::TEST_URL.parse().unwrap())
+        .unwrap();
+
+    let inner = |selected: &str| {
+        let start = u32::try_from(SRC.find(selected).unwrap()).unwrap();
+        let range = TextRange::at(start.into(), u32::try_from(selected.len()).unwrap().into());
+
+        hir.inner_statements_in_range(source, range)
+            .into_iter()
+            .map(|symbol| &SRC[hir[symbol].text_range().unwrap()])
+            .collect::<Vec<_>>()
+    };
+
+    // Statements of the function body.
+    assert_eq!(inner("inner"), ["let inner = 2"]);
+
+    // The range is not inside the function body.
+    assert_eq!(inner("b);"), ["print(b)"]);
+    assert_eq!(
+        inner("+ 1;\n\nfn foo() {\n    let inner"),
+        ["let b = a + 1", "fn foo() {\n    let inner = 2;\n}"]
+    );
+
+    // Operands of binary expressions are not statements.
+    assert_eq!(inner("a + 1"), ["let b = a + 1"]);
+}
+
+#[test]
+fn test_surround_inner_statements() {
+    let src = "fn foo() {\n    let a = 1;\n    let b = a;\n}\n";
+    let (hir, _) = testing::build(src);
+    let source = hir
+        .source_by_url(&testing::TEST_URL.parse().unwrap())
+        .unwrap();
+
+    // From the middle of the first statement to the middle of the second one.
+    let start = u32::try_from(src.find("= 1").unwrap()).unwrap();
+    let end = u32::try_from(src.find("b =").unwrap()).unwrap();
+    let statements = hir
+        .inner_statements_in_range(source, TextRange::new(start.into(), end.into()))
+        .into_iter()
+        .filter_map(|statement| hir[statement].text_range())
+        .collect::<Vec<_>>();
+
+    let syntax = Parser::new(src).parse_script().into_syntax();
+    let surround = Surround::new(&syntax, &statements, "    ").unwrap();
+
+    assert_eq!(&src[surround.range], "let a = 1;\n    let b = a;");
+    assert_eq!(
+        surround.text(SurroundKind::Block),
+        "{\n        let a = 1;\n        let b = a;\n    }"
+    );
+}
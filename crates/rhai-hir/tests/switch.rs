@@ -0,0 +1,72 @@
+use rhai_hir::{error::ErrorKind, testing};
+use test_case::test_case;
+
+/// The uncovered values of the only switch in the source.
+fn uncovered_values(src: &str) -> Vec<String> {
+    let (hir, _) = testing::build(src);
+
+    let (switch, _) = hir
+        .symbols()
+        .find(|(_, data)| data.kind.is_switch())
+        .unwrap();
+
+    hir.uncovered_switch_values(switch)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test_case("let x = true; switch x { true => 1, }", &["false"] ; "bool")]
+#[test_case("let x = true; switch x { true => 1, fa }", &["false"] ; "bool while typing")]
+#[test_case("fn check() { true } switch check() { }", &["true", "false"] ; "bool call")]
+#[test_case("let x = true; switch x { true => 1, false => 2 }", &[] ; "bool covered")]
+#[test_case("const X = true; switch X { true => 1 }", &[] ; "bool literal")]
+#[test_case(r#"let mode = "read"; mode = "write"; mode = "read"; switch mode { "read" => 1, }"#, &[r#""write""#] ; "assigned strings")]
+#[test_case("let c = 'a'; c = 'b'; switch c { }", &["'a'", "'b'"] ; "chars")]
+#[test_case("let n = 1; n += 1; switch n { 1 => 1 }", &[] ; "compound assignment")]
+#[test_case("switch foo() { 1 => 1 }", &[] ; "unknown")]
+fn test_uncovered_switch_values(src: &str, expected: &[&str]) {
+    assert_eq!(uncovered_values(src), expected);
+}
+
+/// The unresolved references and the type of `s`.
+fn block_arms(src: &str) -> (Vec<String>, String) {
+    let (hir, _) = testing::build(src);
+
+    let unresolved = hir
+        .errors()
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::UnresolvedReference {
+                reference_symbol, ..
+            } => hir[reference_symbol].name(&hir).map(ToString::to_string),
+            _ => None,
+        })
+        .collect();
+
+    let ty = hir
+        .symbols()
+        .find_map(|(_, data)| {
+            data.kind
+                .as_decl()
+                .filter(|decl| decl.name == "s")
+                .map(|_| data.ty)
+        })
+        .unwrap();
+
+    (unresolved, ty.fmt(&hir).to_string())
+}
+
+#[test]
+fn test_block_arms() {
+    let (unresolved, ty) = block_arms("let x = 1; let s = switch x { 1 => { let y = 2; y } };");
+    assert!(unresolved.is_empty());
+    assert_eq!(ty, "int");
+
+    let (unresolved, _) =
+        block_arms("let x = 1; let s = switch x { 1 => { let y = 2; y }, 2 => y, _ => { y } };");
+    assert_eq!(unresolved, ["y", "y"]);
+
+    let (_, ty) = block_arms(r#"let x = 1; let s = switch x { 1 => { let y = 2; y }, _ => "a" };"#);
+    assert_eq!(ty, "int | String");
+}
@@ -0,0 +1,79 @@
+use rhai_hir::{testing, Hir, Module};
+
+const SRC: &str = r#"
+fn greet(name) {
+    let greeting = "Hello, \"friend\"";
+    print(`${greeting} ${name}!`);
+}
+
+const NAMES = ["Alice", `Bob`];
+
+for (name, i) in NAMES {
+    greet(name);
+}
+"#;
+
+fn build() -> (Hir, Module) {
+    testing::build(SRC)
+}
+
+#[test]
+fn test_functions() {
+    let (hir, module) = build();
+
+    let functions = hir
+        .functions(module)
+        .into_iter()
+        .map(|(symbol, range)| (hir[symbol].name(&hir).unwrap(), &SRC[range]))
+        .collect::<Vec<_>>();
+
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].0, "greet");
+    assert!(functions[0].1.starts_with("fn greet(name)"));
+}
+
+#[test]
+fn test_declarations() {
+    let (hir, module) = build();
+
+    let declarations = hir
+        .declarations(module)
+        .into_iter()
+        .map(|(symbol, _)| hir[symbol].name(&hir).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(declarations, ["name", "greeting", "NAMES", "name", "i"]);
+}
+
+#[test]
+fn test_string_literals() {
+    let (hir, module) = build();
+
+    let literals = hir
+        .string_literals(module)
+        .into_iter()
+        .map(|(_, range, value)| (&SRC[range], value))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        literals,
+        [
+            (r#""Hello, \"friend\"""#, String::from(r#"Hello, "friend""#)),
+            (r#""Alice""#, String::from("Alice")),
+            ("`Bob`", String::from("Bob")),
+        ]
+    );
+}
+
+#[test]
+fn test_symbols_by_kind() {
+    let (hir, module) = build();
+
+    let calls = hir.symbols_by_kind(module, |kind| kind.is_call());
+    assert_eq!(calls.len(), 2);
+
+    // Symbols of other modules are not included.
+    assert!(hir
+        .symbols_by_kind(hir.static_module(), |_| true)
+        .is_empty());
+}
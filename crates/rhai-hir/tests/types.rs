@@ -0,0 +1,479 @@
+use rhai_hir::{eval::NumericConfig, lint::DiagnosticConfig, testing, Hir};
+use rhai_rowan::parser::Parser;
+use test_case::test_case;
+
+/// The type of the innermost expression at the `$` marker.
+fn type_at(src: &str) -> String {
+    type_at_with(Hir::new(), src)
+}
+
+fn type_at_with(mut hir: Hir, src: &str) -> String {
+    let offset = src.find('$').unwrap();
+    let src = src.replace('$', "");
+
+    let url = "test:///types.rhai".parse().unwrap();
+    hir.add_source(&url, &Parser::new(&src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let source = hir.source_by_url(&url).unwrap();
+    let symbol = hir
+        .symbol_at(source, (offset as u32).into(), false)
+        .unwrap();
+
+    hir[symbol].ty.fmt(&hir).to_string()
+}
+
+fn type_of_decl(src: &str, name: &str) -> String {
+    type_of_decl_with(Hir::new(), src, name)
+}
+
+fn type_of_decl_with(mut hir: Hir, src: &str, name: &str) -> String {
+    let url = "test:///types.rhai".parse().unwrap();
+    hir.add_source(&url, &Parser::new(src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let ty = hir
+        .symbols()
+        .find_map(|(_, data)| {
+            data.kind
+                .as_decl()
+                .filter(|d| d.name == name)
+                .map(|_| data.ty)
+        })
+        .unwrap();
+
+    ty.fmt(&hir).to_string()
+}
+
+#[test_case("let r = 0..10;", "range<int>" ; "exclusive")]
+#[test_case("let r = 0..=10;", "range=<int>" ; "inclusive")]
+fn test_range(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "r"), expected);
+}
+
+#[test_case("for i in 0..10 {}", "i", "int" ; "range")]
+#[test_case("for i in 0..=10 {}", "i", "int" ; "inclusive_range")]
+#[test_case("for i in [1.0, 2.0] {}", "i", "float" ; "array")]
+#[test_case(r#"for c in "abc" {}"#, "c", "char" ; "string")]
+#[test_case(r#"for (c, i) in "abc" {}"#, "i", "int" ; "counter")]
+#[test_case("for i in 0..10 { let x = i; }", "x", "int" ; "reference")]
+fn test_for_binding(src: &str, name: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, name), expected);
+}
+
+#[test_case("let [a, b] = [1, \"b\"];", "a", "int" ; "array_first")]
+#[test_case("let [a, b] = [1, \"b\"];", "b", "String" ; "array_second")]
+#[test_case("let arr = [1.0, 2.0]; let [a, b] = arr;", "b", "float" ; "array_items")]
+#[test_case("let #{a, b} = #{a: 1, b: 'b'};", "b", "char" ; "object_field")]
+#[test_case("let #{a: x} = #{a: 1, b: 'b'};", "x", "int" ; "object_field_alias")]
+#[test_case("let #{c} = #{a: 1};", "c", "?" ; "object_missing_field")]
+fn test_destructuring(src: &str, name: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, name), expected);
+}
+
+#[test_case("let a = [1, $2.0];", "float" ; "literal")]
+#[test_case("let a = $[1, 2];", "[int]" ; "array")]
+#[test_case("let a = 1; let b = [$a];", "int" ; "reference")]
+#[test_case("let a = 0$..3;", "range<int>" ; "range")]
+#[test_case("let a = foo$();", "?" ; "unknown")]
+#[test_case("const c = #{ s: #{ port: 80 } }; c.s.po$rt;", "int" ; "nested field")]
+#[test_case("const c = #{ s: #{ port: 80 } }; c.$s.port;", "#{port: int}" ; "intermediate field")]
+fn test_expression_type(src: &str, expected: &str) {
+    assert_eq!(type_at(src), expected);
+}
+
+#[test_case("let s = `hello`;", "s", "String" ; "plain")]
+#[test_case("let s = `${1} and ${2.0}`;", "s", "String" ; "interpolated")]
+#[test_case("let s = `${ let n = 1.0; n }`;", "n", "float" ; "segment")]
+fn test_template_string(src: &str, name: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, name), expected);
+}
+
+#[test_case("let a = #{ b: 1 }; let x = a?.b;", "int | ()" ; "safe navigation")]
+#[test_case("let a = #{ b: 1 }; let c = if a.b > 0 { a }; let x = c?.b;", "int | ()" ; "safe navigation on optional")]
+#[test_case("let a = #{ b: 1 }; let x = a.b;", "int" ; "field access")]
+#[test_case("let a = if true { 1 }; let x = a ?? 'c';", "int | char" ; "coalescing")]
+#[test_case("let a = 1; let x = a ?? 2;", "int" ; "coalescing same type")]
+fn test_null_operators(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "x"), expected);
+}
+
+#[test_case("let a = #{ b: 1 }; let x = a.b;", "int" ; "object")]
+#[test_case("let c = true; let a = if c { #{ b: 1 } } else { #{ b: 'c' } }; let x = a.b;", "int | char" ; "present in all members")]
+#[test_case("let c = true; let a = if c { #{ b: 1 } } else { #{ d: 1 } }; let x = a.b;", "int | ()" ; "missing in a member")]
+#[test_case("let c = true; let a = if c { #{ b: 1 } } else { y }; let x = a.b;", "int | ()" ; "unknown member")]
+#[test_case("let c = true; let a = if c { #{ b: 1 } }; let x = a.b;", "int" ; "void member")]
+fn test_field_presence(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "x"), expected);
+}
+
+#[test_case("let c = true; let x = if c { 1 } else { 2 };", "int" ; "same type")]
+#[test_case("let c = true; let x = if c { 1 } else { 'a' };", "int | char" ; "different types")]
+#[test_case("let c = true; let x = if c { 1 } else if !c { 'a' } else { 2 };", "int | char" ; "else if")]
+#[test_case("let c = true; let x = if c { 1 } else { let y = 2; };", "int | ()" ; "statement tail")]
+#[test_case("let c = true; let x = if c { 1 } else {};", "int | ()" ; "empty branch")]
+#[test_case("fn f(c) { let x = if c { 1 } else { return 2; }; x }", "int" ; "diverging branch")]
+fn test_if_with_else(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "x"), expected);
+}
+
+#[test_case("let c = true; let x = if c { 1 };", "int | ()" ; "single branch")]
+#[test_case("let c = true; let x = if c { 1 } else if !c { 'a' };", "int | char | ()" ; "else if")]
+#[test_case("let c = true; let x = if c {};", "()" ; "empty")]
+fn test_if_without_else(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "x"), expected);
+}
+
+#[test_case("let x = type_of(1);", "\"i64\"" ; "int")]
+#[test_case("let x = type_of(1.0);", "\"f64\"" ; "float")]
+#[test_case("let x = type_of(\"a\");", "\"string\"" ; "string")]
+#[test_case("let x = type_of([1]);", "\"array\"" ; "array")]
+#[test_case("let x = type_of(#{});", "\"map\"" ; "object")]
+#[test_case("let x = type_of(1..=2);", "\"range=\"" ; "inclusive range")]
+#[test_case("let x = type_of(type_of(1));", "\"string\"" ; "nested")]
+#[test_case("let x = type_of(y);", "?" ; "unknown argument")]
+#[test_case("fn type_of(v) { 1 } let x = type_of(1);", "int" ; "shadowed")]
+fn test_type_of(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "x"), expected);
+}
+
+fn hir_32_bit() -> Hir {
+    let mut hir = Hir::new();
+    hir.set_numeric_config(NumericConfig {
+        only_i32: true,
+        f32_float: true,
+        ..NumericConfig::default()
+    });
+    hir
+}
+
+#[test_case("let x = type_of(1);", "\"i32\"" ; "int")]
+#[test_case("let x = type_of(1.0);", "\"f32\"" ; "float")]
+#[test_case("let x = 1;", "i32" ; "int literal")]
+#[test_case("let x = [1.5];", "[f32]" ; "float array")]
+fn test_32_bit_numbers(src: &str, expected: &str) {
+    assert_eq!(type_of_decl_with(hir_32_bit(), src, "x"), expected);
+}
+
+#[test]
+fn test_no_float() {
+    let mut hir = Hir::new();
+    hir.set_numeric_config(NumericConfig {
+        no_float: true,
+        ..NumericConfig::default()
+    });
+
+    assert_eq!(type_of_decl_with(hir, "let x = 1.5;", "x"), "?");
+}
+
+/// A switch with 100 arms, each with a type of its own.
+fn large_switch(arm: impl Fn(usize) -> String) -> String {
+    let arms = (0..100)
+        .map(|i| format!("{i} => {},", arm(i)))
+        .collect::<String>();
+
+    format!("let a = 0; let s = switch a {{ {arms} }};")
+}
+
+#[test]
+fn test_large_union_same_kind() {
+    let src = large_switch(|i| format!("[#{{ f{i}: {i} }}]"));
+    assert_eq!(type_of_decl(&src, "s"), "[?]");
+}
+
+#[test]
+fn test_large_union_mixed() {
+    let src = large_switch(|i| {
+        if i % 2 == 0 {
+            format!("[#{{ f{i}: {i} }}]")
+        } else {
+            format!("#{{ f{i}: {i} }}")
+        }
+    });
+    assert_eq!(type_of_decl(&src, "s"), "?");
+}
+
+#[test_case("let a = true; let x = if a { 1 } else { 2.0 };", "float" ; "numeric")]
+#[test_case("let a = true; let x = if a { 1 } else { 'c' };", "?" ; "mixed")]
+fn test_union_widening(src: &str, expected: &str) {
+    let mut hir = Hir::new();
+    hir.set_max_union_size(1);
+    assert_eq!(type_of_decl_with(hir, src, "x"), expected);
+}
+
+#[test]
+fn test_union_below_limit() {
+    let src = "let a = true; let x = if a { 1 } else { 'c' };";
+    assert_eq!(type_of_decl(src, "x"), "int | char");
+}
+
+#[test]
+fn test_diagnostics_do_not_infer_types() {
+    let src = r"
+let a = #{ b: [1] };
+let c = a + 1;
+fn f(x) { f(x) }
+";
+
+    let (hir, _) = testing::build(src);
+
+    // Every symbol is inferred exactly once by the resolution.
+    let inferences = hir.type_inferences();
+    assert_eq!(inferences, hir.symbols().count());
+
+    let url = testing::TEST_URL.parse().unwrap();
+    let parse = Parser::new(src).parse_script();
+
+    let first = hir.diagnostics_for(&url, &parse, &DiagnosticConfig::default());
+    let second = hir.diagnostics_for(&url, &parse, &DiagnosticConfig::default());
+    assert_eq!(first, second);
+
+    // The recursion is unknown instead of overflowing the stack.
+    let (f, _) = hir
+        .symbols()
+        .find(|(_, data)| data.kind.as_fn().is_some_and(|f| f.name == "f"))
+        .unwrap();
+    assert_eq!(hir.type_of(f).fmt(&hir).to_string(), "fn (x: ?) -> ?");
+
+    assert_eq!(hir.type_inferences(), inferences);
+}
+
+#[test_case("let obj = #{ handler: |x| 1 }; obj.hand$ler(1);", "|x: ?| -> int" ; "object field")]
+#[test_case("let obj = #{ handler: || 1 }; obj?.hand$ler;", "|| -> int" ; "safe navigation")]
+#[test_case("let obj = #{ handler: || 1 }; obj.mis$sing;", "?" ; "missing field")]
+fn test_closure_field(src: &str, expected: &str) {
+    assert_eq!(type_at(src), expected);
+}
+
+#[test_case("let h = [|| 1, || 2];", "h", "[|| -> int]" ; "array")]
+#[test_case("let arr = [|| 1, || 2]; let h = arr[0]();", "h", "int" ; "array call")]
+#[test_case("let obj = #{ handler: |x| 1 }; let h = obj.handler(2);", "h", "int" ; "method call")]
+fn test_closure_value(src: &str, name: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, name), expected);
+}
+
+#[test_case("fn area() { $this.w * this.h } let r = #{ w: 1, h: 2 }; r.area();", "#{w: int, h: int}" ; "method")]
+#[test_case("fn area() { this.$w } let r = #{ w: 1 }; r.area();", "int" ; "method field")]
+#[test_case("fn area() { $this.w } area();", "?" ; "not a method")]
+#[test_case("fn area() { $this } 1.area(); 2.area();", "int" ; "same receivers")]
+// The return type of the closure depends on the type of the object itself.
+#[test_case("let r = #{ w: 1, area: || $this.w };", "#{w: int, area: || -> ?}" ; "object field closure")]
+#[test_case("let f = || $this; let r = 1.0; r.call(f);", "float" ; "bound closure")]
+#[test_case("fn f() { let c = || $this; } 1.f();", "?" ; "closure in method")]
+fn test_this(src: &str, expected: &str) {
+    assert_eq!(type_at(src), expected);
+}
+
+#[test_case("let x = 1; x = \"hello\"; $x;", "String" ; "reassigned")]
+#[test_case("let x = 1; x = \"hello\"; x = 2.0; $x;", "float" ; "last assignment")]
+#[test_case("let x = 1; $x; x = \"hello\";", "int" ; "before assignment")]
+#[test_case("let x = 1; { x = 'c'; } $x;", "char" ; "block")]
+#[test_case("let x = 1; x += 2; $x;", "int" ; "compound assignment")]
+#[test_case("let x = 1; x = [x]; $x;", "[int]" ; "self reference")]
+#[test_case("let x = 1; if true { x = 'c'; let y = $x; }", "char" ; "inside branch")]
+fn test_flow_typing(src: &str, expected: &str) {
+    let mut hir = Hir::new();
+    hir.set_flow_typing(true);
+    assert_eq!(type_at_with(hir, src), expected);
+}
+
+#[test_case("let x = 1; if true { x = 'c'; } $x;", "char | int" ; "if without else")]
+#[test_case("let x = 1; if true { x = 'c'; } else { x = 2.0; } $x;", "char | float" ; "if else")]
+#[test_case("let x = 1; if true { x = 'c'; } else if false {} else { x = 'd'; } $x;", "char | int" ; "else if")]
+#[test_case("let x = 1; while true { x = 'c'; } $x;", "char | int" ; "while loop")]
+#[test_case("let x = 1; loop { $x; x = 'c'; }", "char | int" ; "loop body")]
+#[test_case("let x = 1; switch y { 1 => x = \"a\", _ => {} } $x;", "String | int" ; "switch arm")]
+#[test_case("let x = 1; switch y { 1 => { x = \"a\" } } $x;", "String | int" ; "switch arm block")]
+#[test_case("let x = 1; switch y { 1 => x = 'c', _ => x = 'd' } $x;", "char" ; "switch default arm")]
+#[test_case("let x = 1; try { x = \"a\"; } catch {} $x;", "String | int" ; "try without catch assignment")]
+#[test_case("let x = 1; try { x = \"a\"; } catch { x = \"b\"; } $x;", "String" ; "try catch")]
+#[test_case("let x = 1; switch y { 1 => x = 'c', 2 => { $x; } }", "int" ; "other switch arm")]
+fn test_flow_typing_branches(src: &str, expected: &str) {
+    let mut hir = Hir::new();
+    hir.set_flow_typing(true);
+    assert_eq!(type_at_with(hir, src), expected);
+}
+
+#[test_case("let x = $true;", "true" ; "literal")]
+#[test_case("let x = $!true;", "false" ; "negated literal")]
+#[test_case("const X = true; $X;", "true" ; "constant")]
+#[test_case("let x = true; $x;", "bool" ; "variable")]
+#[test_case("fn f() { true } $f();", "fn () -> bool" ; "return type")]
+#[test_case("let x = $[true, false];", "[bool]" ; "both literals")]
+#[test_case("let x = $[true, true];", "[bool]" ; "same literals")]
+#[test_case("let x = $#{ a: true };", "#{a: bool}" ; "object field")]
+fn test_bool_literals(src: &str, expected: &str) {
+    assert_eq!(type_at(src), expected);
+}
+
+#[test_case("let x = true; $x;", "true" ; "initial value")]
+#[test_case("let x = true; if true { x = false; } $x;", "bool" ; "both values")]
+fn test_flow_typing_bool_literals(src: &str, expected: &str) {
+    let mut hir = Hir::new();
+    hir.set_flow_typing(true);
+    assert_eq!(type_at_with(hir, src), expected);
+}
+
+#[test_case(r#"let a = [1, 2]; let b = a.map(|x| "s");"#, "[String]" ; "map")]
+#[test_case("let a = [1, 2]; let b = a.map(|x| [x]);", "[[int]]" ; "map element")]
+#[test_case("let a = [1, 2]; let b = a.filter(|x| x > 1);", "[int]" ; "filter")]
+#[test_case("let a = [1, 2]; let b = a.reduce(|sum, x| 1.0);", "float" ; "reduce")]
+#[test_case("let a = [1, 2]; let b = a.reduce(|sum, x| sum + x, 0);", "int" ; "reduce initial value")]
+#[test_case(r#"let a = [1, 2]; let b = a.reduce_rev(|s, x| s + x, "");"#, "String" ; "reduce_rev initial value")]
+#[test_case("let a = ['a']; let b = a.find(|c| true);", "char | ()" ; "find")]
+#[test_case("let a = [1, 2]; let b = a.some(|x| true);", "bool" ; "some")]
+#[test_case("let a = unknown; let b = a.map(|x| 1);", "?" ; "unknown receiver")]
+fn test_higher_order_methods(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "b"), expected);
+}
+
+#[test_case("[1, 2].map(|$x| x + 1);", "int" ; "map")]
+#[test_case("[1, 2].reduce(|sum, $x| sum + x);", "int" ; "reduce element")]
+#[test_case("[1, 2].reduce(|$sum, x| sum + x);", "?" ; "reduce accumulator")]
+#[test_case("[1, 2].reduce(|$sum, x| sum + x, 0);", "int" ; "reduce initial accumulator")]
+#[test_case("let f = |$x| x; f.call(1);", "?" ; "other call")]
+fn test_higher_order_closure_params(src: &str, expected: &str) {
+    assert_eq!(type_at(src), expected);
+}
+
+#[test]
+fn test_flow_typing_disabled() {
+    assert_eq!(type_at("let x = 1; x = \"hello\"; $x;"), "int");
+}
+
+/// The fields offered by member completion after `this.` at the `$` marker.
+#[test]
+fn test_this_member_completion() {
+    let src = "
+fn describe() {
+    this.$
+}
+
+let point = #{ x: 1, y: 2.0 };
+point.describe();
+";
+    let offset = src.find('$').unwrap();
+    let src = src.replace('$', "");
+
+    let mut hir = Hir::new();
+    let url = "test:///types.rhai".parse().unwrap();
+    hir.add_source(&url, &Parser::new(&src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let source = hir.source_by_url(&url).unwrap();
+    let access = hir
+        .symbol_at(source, (offset as u32).into(), false)
+        .and_then(|symbol| hir[symbol].kind.as_binary().cloned())
+        .filter(rhai_hir::symbol::BinarySymbol::is_field_access)
+        .unwrap();
+
+    let this_ty = hir[access.lhs.unwrap()].ty;
+    let fields = hir[this_ty]
+        .kind
+        .as_object()
+        .map(|object| object.fields.keys().cloned().collect::<Vec<_>>())
+        .unwrap();
+
+    assert_eq!(fields, ["x", "y"]);
+}
+
+const OPERATORS: &str = "
+module static;
+
+op +(int, int) -> int;
+op +(int, float) -> float;
+op -(int) -> int;
+op ==(bool, bool) -> bool;
+";
+
+/// The signature of the operator at the `$` marker.
+fn operator_at(src: &str) -> Option<String> {
+    let offset = src.find('$').unwrap();
+    let src = src.replace('$', "");
+
+    let mut hir = Hir::new();
+    let def_url = "test:///operators.d.rhai".parse().unwrap();
+    hir.add_source(&def_url, &Parser::new(OPERATORS).parse_def().into_syntax());
+
+    let url = "test:///types.rhai".parse().unwrap();
+    hir.add_source(&url, &Parser::new(&src).parse_script().into_syntax());
+    hir.resolve_all();
+
+    let source = hir.source_by_url(&url).unwrap();
+    let symbol = hir.operator_at(source, (offset as u32).into())?;
+
+    hir.operator_signature(symbol)
+}
+
+#[test_case("1 $+ 2", "int + int -> int" ; "exact")]
+#[test_case("1 $+ 2.0", "int + float -> float" ; "promoting")]
+#[test_case("$-1", "-int -> int" ; "unary")]
+#[test_case("1.0 $* 2.0", "float * float -> ?" ; "undefined")]
+#[test_case("true $== false", "bool == bool -> bool" ; "bool literals")]
+fn test_operator_signature(src: &str, expected: &str) {
+    assert_eq!(operator_at(src).as_deref(), Some(expected));
+}
+
+#[test]
+fn test_operator_at_operand() {
+    assert_eq!(operator_at("$1 + 2"), None);
+}
+
+#[test_case("fn f(x) { x } f(1); f(2);", "int" ; "same type")]
+#[test_case(r#"fn f(x) { x } f(1); f("a");"#, "int | String" ; "union")]
+#[test_case("fn f(x) { x }", "?" ; "no calls")]
+#[test_case("fn f(x) { x } f(1, 2);", "?" ; "other overload")]
+#[test_case("fn f(x) { f(x) } f(1);", "int" ; "recursive")]
+#[test_case("fn f(x) { f(x + 1) } f(1);", "int" ; "recursive expression")]
+fn test_param_from_calls(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "x"), expected);
+}
+
+#[test_case("let x = 1; let y = -x;", "int" ; "negated int")]
+#[test_case("let x = 1.0; let y = -x;", "float" ; "negated float")]
+#[test_case("let x = 1; let y = +x;", "int" ; "plus")]
+#[test_case("let flag = true; let y = !flag;", "bool" ; "not")]
+#[test_case("let x = 1; let y = !x;", "bool" ; "not int")]
+#[test_case(r#"let x = "a"; let y = -x;"#, "?" ; "negated string")]
+#[test_case("let y = -x;", "?" ; "unknown operand")]
+fn test_unary(src: &str, expected: &str) {
+    assert_eq!(type_of_decl(src, "y"), expected);
+}
+
+#[test]
+fn test_interned_types() {
+    let src = "let a = #{ x: 1, y: [1] }; let b = #{ y: [2], x: 2 }; let c = #{ x: 1.0 };";
+    let (mut hir, _) = testing::build(src);
+
+    let type_of = |name: &str| {
+        hir.symbols()
+            .find_map(|(_, data)| {
+                data.kind
+                    .as_decl()
+                    .filter(|d| d.name == name)
+                    .map(|_| data.ty)
+            })
+            .unwrap()
+    };
+
+    assert_eq!(type_of("a"), type_of("b"));
+    assert_ne!(type_of("a"), type_of("c"));
+
+    // Resolving again reuses the same types.
+    let types = hir.types().count();
+    hir.resolve_all();
+    assert_eq!(hir.types().count(), types);
+}
+
+/// Rhai has no spread syntax, the parser reports `...` in literals
+/// as errors and the elements it could not parse widen the types.
+#[test]
+fn test_spread_is_not_supported() {
+    let src = "let a = [1]; let b = [...a, 2];";
+    assert!(!Parser::new(src).parse_script().errors.is_empty());
+    assert_eq!(type_of_decl(src, "b"), "[? | int]");
+
+    let src = "let base = #{ a: 1 }; let o = #{ ...base, x: 1 };";
+    assert!(!Parser::new(src).parse_script().errors.is_empty());
+    assert_eq!(type_of_decl(src, "o"), "#{}");
+}
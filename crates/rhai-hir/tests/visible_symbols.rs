@@ -1,4 +1,4 @@
-use rhai_hir::Hir;
+use rhai_hir::{testing, Hir};
 use rhai_rowan::{parser::Parser, util::src_cursor_offset};
 
 #[test]
@@ -48,3 +48,59 @@ fn test_visible_import() {
         .find_map(|symbol| hir[symbol].kind.as_import().and_then(|d| d.alias))
         .is_some())
 }
+
+/// The names of the symbols visible at the `$$` marker.
+fn visible_names(src: &str) -> Vec<String> {
+    let (offset, src) = src_cursor_offset(src);
+    let (hir, _) = testing::build(&src);
+    let source = hir
+        .source_by_url(&testing::TEST_URL.parse().unwrap())
+        .unwrap();
+
+    let mut names = hir
+        .visible_symbols_from_offset(source, offset, false)
+        .filter_map(|symbol| hir[symbol].name(&hir).map(ToString::to_string))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_nested_fn_scope() {
+    let src = r#"
+let a = 1;
+{
+    $$
+    let b = 2;
+    fn f(p) {
+        let local = p;
+    }
+}
+"#;
+
+    // The function is hoisted within the block,
+    // its parameters and locals stay inside of it.
+    assert_eq!(visible_names(src), ["a", "f"]);
+
+    let src = r#"
+{
+    fn f(p) {
+        let local = p;
+    }
+}
+$$
+"#;
+
+    assert!(visible_names(src).is_empty());
+
+    let src = r#"
+{
+    fn f(p) {
+        let local = p;
+        $$
+    }
+}
+"#;
+
+    assert_eq!(visible_names(src), ["f", "local", "p"]);
+}
@@ -0,0 +1,162 @@
+use rhai_hir::{
+    symbol::IfSymbol,
+    visit::{walk_module, Visitor},
+    Hir, Symbol,
+};
+use rhai_rowan::parser::Parser;
+
+const DEF_SRC: &str = "
+module static;
+
+type Name = String;
+
+op is(?, ?) -> bool with (1, 2);
+";
+
+const SRC: &str = r#"
+import "test:///other.rhai" as other;
+
+fn add(a, b) {
+    return a + b;
+}
+
+let value = other::VALUE;
+let array = [1, -2, `${value}`];
+let object = #{ field: array[0] };
+let closure = |x| x * 2;
+
+if value > 1 {
+    closure.call(1);
+} else {
+    add(1, 2);
+}
+
+loop {
+    break;
+}
+
+for item in array {
+    continue;
+}
+
+while false {}
+
+switch value {
+    1 => {},
+    _ => {},
+}
+
+try {
+    throw "error";
+} catch (err) {}
+
+{
+    let x = object.field;
+}
+
+export const exported = 1;
+export exported as alias;
+"#;
+
+const OTHER_SRC: &str = "
+export const VALUE = 1;
+";
+
+fn build() -> Hir {
+    let mut hir = Hir::new();
+
+    hir.add_source(
+        &"test:///defs.d.rhai".parse().unwrap(),
+        &Parser::new(DEF_SRC).parse_def().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///other.rhai".parse().unwrap(),
+        &Parser::new(OTHER_SRC).parse_script().into_syntax(),
+    );
+    hir.add_source(
+        &"test:///visit.rhai".parse().unwrap(),
+        &Parser::new(SRC).parse_script().into_syntax(),
+    );
+    hir.resolve_all();
+
+    hir
+}
+
+#[derive(Default)]
+struct CountVisits {
+    symbols: Vec<Symbol>,
+    kinds: Vec<&'static str>,
+    ifs: Vec<Symbol>,
+}
+
+impl Visitor for CountVisits {
+    fn visit_symbol(&mut self, hir: &Hir, symbol: Symbol) {
+        self.symbols.push(symbol);
+        self.kinds.push((&hir[symbol].kind).into());
+    }
+
+    fn visit_if(&mut self, _hir: &Hir, symbol: Symbol, _if_symbol: &IfSymbol) {
+        self.ifs.push(symbol);
+    }
+}
+
+fn walk_all(hir: &Hir) -> CountVisits {
+    let mut visitor = CountVisits::default();
+
+    for (module, _) in hir.modules() {
+        walk_module(&mut visitor, hir, module);
+    }
+
+    visitor
+}
+
+#[test]
+fn test_walk_visits_every_symbol_once() {
+    let hir = build();
+    let visitor = walk_all(&hir);
+
+    let mut visited = visitor.symbols.clone();
+    visited.sort_unstable();
+    visited.dedup();
+    assert_eq!(
+        visited.len(),
+        visitor.symbols.len(),
+        "visited more than once"
+    );
+
+    let missing = hir
+        .symbols()
+        .map(|(symbol, _)| symbol)
+        .filter(|symbol| visited.binary_search(symbol).is_err())
+        .map(|symbol| format!("{:?}", hir[symbol].kind))
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(), "not visited: {missing:#?}");
+}
+
+#[test]
+fn test_walk_visits_every_kind() {
+    let hir = build();
+    let visitor = walk_all(&hir);
+
+    let mut kinds = visitor.kinds;
+    kinds.sort_unstable();
+    kinds.dedup();
+
+    assert_eq!(
+        kinds,
+        [
+            "Array", "Binary", "Block", "Break", "Call", "Closure", "Continue", "Decl", "Discard",
+            "Export", "Fn", "For", "If", "Import", "Index", "Lit", "Loop", "Object", "Op", "Path",
+            "Ref", "Return", "Switch", "Throw", "Try", "TypeDecl", "Unary", "Virtual", "While",
+        ]
+    );
+}
+
+#[test]
+fn test_walk_calls_kind_methods() {
+    let hir = build();
+    let visitor = walk_all(&hir);
+
+    assert_eq!(visitor.ifs.len(), 1);
+    assert!(hir[visitor.ifs[0]].kind.is_if());
+}
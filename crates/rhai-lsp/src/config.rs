@@ -1,4 +1,6 @@
 use figment::{providers::Serialized, Figment};
+use rhai_hir::lint::DiagnosticConfig;
+use rhai_rowan::query::fn_stub::FnStubPlacement;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
@@ -28,6 +30,8 @@ fn default_configuration_section() -> String {
 #[serde(rename_all = "camelCase")]
 pub struct LspConfig {
     pub syntax: SyntaxConfig,
+    pub diagnostics: DiagnosticsConfig,
+    pub code_actions: CodeActionsConfig,
     pub debug: DebugConfig,
 }
 
@@ -55,10 +59,34 @@ impl Default for SyntaxConfig {
     }
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsConfig {
+    /// Warn about `while true` loops, these are usually intentional.
+    pub while_true: bool,
+    /// Do not warn about empty `else` branches.
+    pub allow_empty_else: bool,
+    /// Do not warn about empty loop bodies, e.g. of loops
+    /// that wait for their conditions.
+    pub allow_empty_loop: bool,
+    /// The levels of the individual lints.
+    pub lints: DiagnosticConfig,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeActionsConfig {
+    /// Where functions generated for calls of undefined functions are placed.
+    pub fn_stub_placement: FnStubPlacement,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DebugConfig {
     pub hir: DebugHirConfig,
+    /// Show the enclosing scopes and the visible symbols
+    /// when hovering identifiers.
+    pub hover_scopes: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
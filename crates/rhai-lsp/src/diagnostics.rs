@@ -5,7 +5,10 @@ use lsp_types::{
     PublishDiagnosticsParams, Url,
 };
 use rhai_common::{environment::Environment, util::Normalize};
-use rhai_hir::{error::ErrorKind, Hir};
+use rhai_hir::{
+    diagnostic::{self as hir, DiagnosticTag},
+    lint::{DiagnosticConfig, LintLevel},
+};
 
 #[tracing::instrument(skip_all)]
 pub(crate) async fn publish_all_diagnostics<E: Environment>(context: Context<World<E>>) {
@@ -29,8 +32,6 @@ pub(crate) async fn publish_diagnostics<E: Environment>(
     mut context: Context<World<E>>,
     document_url: Url,
 ) {
-    let mut diags = Vec::new();
-
     let workspaces = context.workspaces.read().await;
     let ws = workspaces.by_document(&document_url);
 
@@ -39,45 +40,42 @@ pub(crate) async fn publish_diagnostics<E: Environment>(
         None => return,
     };
 
-    collect_syntax_errors(doc, &mut diags);
+    let config = DiagnosticConfig {
+        while_true: ws.config.diagnostics.while_true,
+        allow_empty_else: ws.config.diagnostics.allow_empty_else,
+        allow_empty_loop: ws.config.diagnostics.allow_empty_loop,
+        ..ws.config.diagnostics.lints.clone()
+    };
+
+    // Diagnostics of unchanged functions are reused after a single edit
+    // of the document, after any other change all of them are computed again.
+    let diags = {
+        let mut diagnostics = doc.diagnostics.lock().unwrap();
+        let edit = diagnostics.edit.take();
+        diagnostics.changed = false;
+
+        ws.hir
+            .incremental_diagnostics_for(
+                &document_url.clone().normalize(),
+                doc.parse(),
+                &config,
+                &mut diagnostics.cache,
+                edit,
+            )
+            .into_iter()
+            .map(|diag| into_lsp_diagnostic(&document_url, doc, diag))
+            .collect()
+    };
     drop(workspaces);
 
     context
         .write_notification::<notification::PublishDiagnostics, _>(Some(PublishDiagnosticsParams {
-            uri: document_url.clone(),
-            diagnostics: diags.clone(),
+            uri: document_url,
+            diagnostics: diags,
             version: None,
         }))
         .await
         .unwrap_or_else(|err| tracing::error!("{err}"));
-
-    if !diags.is_empty() {
-        return;
-    }
-
-    let workspaces = context.workspaces.read().await;
-    let ws = workspaces.by_document(&document_url);
-
-    let doc = match ws.documents.get(&document_url) {
-        Some(doc) => doc,
-        None => return,
-    };
-
-    collect_hir_errors(&document_url.clone().normalize(), doc, &ws.hir, &mut diags);
-    drop(workspaces);
-
-    context.clone().env.spawn_local(async move {
-        context
-            .write_notification::<notification::PublishDiagnostics, _>(Some(
-                PublishDiagnosticsParams {
-                    uri: document_url.clone(),
-                    diagnostics: diags.clone(),
-                    version: None,
-                },
-            ))
-            .await
-            .unwrap_or_else(|err| tracing::error!("{err}"));
-    });
 }
 
 #[tracing::instrument(skip_all)]
@@ -95,118 +93,42 @@ pub(crate) async fn clear_diagnostics<E: Environment>(
         .unwrap_or_else(|err| tracing::error!("{}", err));
 }
 
-#[tracing::instrument(skip_all)]
-fn collect_syntax_errors(doc: &Document, diags: &mut Vec<Diagnostic>) {
-    diags.extend(doc.parse.errors.iter().map(|e| {
-        let range = doc.mapper.range(e.range).unwrap_or_default().into_lsp();
-        Diagnostic {
-            range,
-            severity: Some(DiagnosticSeverity::ERROR),
-            code: None,
-            code_description: None,
-            source: Some("Rhai".into()),
-            message: e.kind.to_string(),
-            related_information: None,
-            tags: None,
-            data: None,
-        }
-    }));
-}
+fn into_lsp_diagnostic(uri: &Url, doc: &Document, diag: hir::Diagnostic) -> Diagnostic {
+    let range = |range| doc.line_index.range(range).unwrap_or_default().into_lsp();
 
-#[tracing::instrument(skip_all)]
-fn collect_hir_errors(uri: &Url, doc: &Document, hir: &Hir, diags: &mut Vec<Diagnostic>) {
-    if let Some(source) = hir.source_by_url(uri) {
-        for error in hir.errors_for_source(source) {
-            match &error.kind {
-                ErrorKind::DuplicateFnParameter {
-                    duplicate_symbol,
-                    existing_symbol,
-                } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(
-                            hir[*duplicate_symbol]
-                                .selection_or_text_range()
-                                .unwrap_or_default(),
-                        )
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: Some(Vec::from([DiagnosticRelatedInformation {
-                        message: "parameter with the same name".into(),
-                        location: Location {
-                            range: doc
-                                .mapper
-                                .range(
-                                    hir[*existing_symbol]
-                                        .selection_or_text_range()
-                                        .unwrap_or_default(),
-                                )
-                                .unwrap_or_default()
-                                .into_lsp(),
-                            uri: uri.clone(),
-                        },
-                    }])),
-                    tags: None,
-                    data: None,
-                }),
-                ErrorKind::UnresolvedReference {
-                    reference_symbol,
-                    similar_name: _,
-                } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(
-                            hir[*reference_symbol]
-                                .selection_or_text_range()
-                                .unwrap_or_default(),
-                        )
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                }),
-                ErrorKind::UnresolvedImport { import } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(hir[*import].selection_or_text_range().unwrap_or_default())
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                }),
-                ErrorKind::NestedFunction { function } => diags.push(Diagnostic {
-                    range: doc
-                        .mapper
-                        .range(hir[*function].selection_or_text_range().unwrap_or_default())
-                        .unwrap_or_default()
-                        .into_lsp(),
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    code: None,
-                    code_description: None,
-                    source: Some("Rhai".into()),
-                    message: error.to_string(),
-                    related_information: None,
-                    tags: None,
-                    data: None,
-                }),
-            }
-        }
+    Diagnostic {
+        range: range(diag.range),
+        severity: Some(match diag.level {
+            LintLevel::Hint | LintLevel::Off => DiagnosticSeverity::HINT,
+            LintLevel::Info => DiagnosticSeverity::INFORMATION,
+            LintLevel::Warning => DiagnosticSeverity::WARNING,
+            LintLevel::Error => DiagnosticSeverity::ERROR,
+        }),
+        code: None,
+        code_description: None,
+        source: Some("Rhai".into()),
+        message: diag.message,
+        related_information: (!diag.related.is_empty()).then(|| {
+            diag.related
+                .into_iter()
+                .map(|(related_range, message)| DiagnosticRelatedInformation {
+                    message,
+                    location: Location {
+                        range: range(related_range),
+                        uri: uri.clone(),
+                    },
+                })
+                .collect()
+        }),
+        tags: (!diag.tags.is_empty()).then(|| {
+            diag.tags
+                .into_iter()
+                .map(|tag| match tag {
+                    DiagnosticTag::Unnecessary => lsp_types::DiagnosticTag::UNNECESSARY,
+                    DiagnosticTag::Deprecated => lsp_types::DiagnosticTag::DEPRECATED,
+                })
+                .collect()
+        }),
+        data: None,
     }
 }
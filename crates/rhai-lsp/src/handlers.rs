@@ -31,6 +31,12 @@ pub(crate) use convert_offsets::*;
 mod hover;
 pub(crate) use hover::*;
 
+mod type_of;
+pub(crate) use type_of::*;
+
+mod matching_brace;
+pub(crate) use matching_brace::*;
+
 mod rename;
 pub(crate) use rename::*;
 
@@ -48,3 +54,6 @@ pub(crate) use debug::*;
 
 mod formatting;
 pub(crate) use formatting::*;
+
+mod code_action;
+pub(crate) use code_action::*;
@@ -0,0 +1,503 @@
+use lsp_async_stub::{
+    rpc,
+    util::{LspExt, Range},
+    Context, Params,
+};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse, Command,
+    TextEdit, Url, WorkspaceEdit,
+};
+use rhai_common::{environment::Environment, util::Normalize};
+use rhai_hir::{error::ErrorKind, source::Source, symbol::SymbolKind, Hir};
+use rhai_rowan::{
+    query::{
+        convert_fn::ConvertFn,
+        extract::ExtractVariable,
+        fn_stub::{FnStub, FnStubPlacement},
+        inline::InlineVariable,
+        organize_imports::OrganizeImports,
+        simplify::SimplifyCondition,
+        surround::{Surround, SurroundKind},
+        switch_arms::AddSwitchArms,
+    },
+    syntax::{SyntaxKind, SyntaxNode},
+    TextRange, TextSize,
+};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+use crate::{line_index::LineIndex, world::World};
+
+/// Client-side command that replaces the given range with the given snippet,
+/// code action edits cannot contain snippets.
+const INSERT_SNIPPET_COMMAND: &str = "rhai.insertSnippet";
+
+#[tracing::instrument(skip_all)]
+pub(crate) async fn code_action<E: Environment>(
+    context: Context<World<E>>,
+    params: Params<CodeActionParams>,
+) -> Result<Option<CodeActionResponse>, rpc::Error> {
+    let p = params.required()?;
+
+    let uri = p.text_document.uri;
+
+    let workspaces = context.workspaces.read().await;
+    let ws = workspaces.by_document(&uri);
+    let doc = ws.document(&uri)?;
+
+    let Some(selection) = doc.line_index.text_range(Range::from_lsp(p.range)) else {
+        return Ok(None);
+    };
+
+    let syntax = doc.parse().clone_syntax();
+
+    let indent_string = if syntax.text().contains_char('\t') {
+        String::from("\t")
+    } else {
+        rhai_fmt::Options::default().indent_string
+    };
+
+    let mut actions = Vec::new();
+
+    if let Some(source) = ws.hir.source_of(&uri.clone().normalize()) {
+        if let Some(inline) = inline_variable(&ws.hir, source, &syntax, selection.start()) {
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                "Inline variable",
+                CodeActionKind::REFACTOR_INLINE,
+                inline.edits,
+            ));
+        }
+
+        if let Some((title, convert)) = convert_fn(&ws.hir, source, &syntax, selection.start()) {
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                title,
+                CodeActionKind::REFACTOR_REWRITE,
+                convert.edits,
+            ));
+        }
+
+        if let Some(let_range) = convert_to_const(&ws.hir, source, &syntax, selection.start()) {
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                "Convert to `const`",
+                CodeActionKind::REFACTOR_REWRITE,
+                vec![(let_range, "const".into())],
+            ));
+        }
+
+        for (is_while, value, simplify) in simplify_conditions(
+            &ws.hir,
+            source,
+            &syntax,
+            selection,
+            ws.config.diagnostics.while_true,
+        ) {
+            let title = match (is_while, value) {
+                (true, true) => "Replace with `loop`",
+                (true, false) => "Remove loop that never runs",
+                (false, true) => "Remove unreachable branches",
+                (false, false) => "Remove unreachable branch",
+            };
+
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                title,
+                CodeActionKind::QUICKFIX,
+                simplify.edits,
+            ));
+        }
+
+        for stub in fn_stubs(
+            &ws.hir,
+            source,
+            &syntax,
+            selection,
+            ws.config.code_actions.fn_stub_placement,
+        ) {
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                &format!("Generate function `{}`", stub.name),
+                CodeActionKind::QUICKFIX,
+                stub.edits,
+            ));
+        }
+
+        for (title, add_arms) in
+            add_switch_arms(&ws.hir, source, &syntax, selection, &indent_string)
+        {
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                &title,
+                CodeActionKind::QUICKFIX,
+                add_arms.edits,
+            ));
+        }
+
+        if let Some(organize) = organize_imports(&ws.hir, source, &syntax) {
+            actions.push(edit_action(
+                &doc.line_index,
+                &uri,
+                "Organize imports",
+                CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                organize.edits,
+            ));
+        }
+    }
+
+    let statements = ws
+        .hir
+        .source_of(&uri.clone().normalize())
+        .map(|source| ws.hir.inner_statements_in_range(source, selection))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|statement| ws.hir[statement].text_range())
+        .collect::<Vec<_>>();
+
+    if let (false, [statement]) = (selection.is_empty(), &statements[..]) {
+        if let Some(extract) = ExtractVariable::new(&syntax, selection, *statement) {
+            actions.extend(snippet_action(
+                &doc.line_index,
+                &uri,
+                "Extract into variable",
+                CodeActionKind::REFACTOR_EXTRACT,
+                extract.range(),
+                &extract.snippet(),
+            ));
+        }
+    }
+
+    if let Some(surround) = Surround::new(&syntax, &statements, &indent_string) {
+        for &kind in SurroundKind::ALL {
+            let title = match kind.keyword() {
+                Some(keyword) => format!("Surround with `{keyword}`"),
+                None => String::from("Surround with block `{}`"),
+            };
+
+            actions.extend(snippet_action(
+                &doc.line_index,
+                &uri,
+                &title,
+                CodeActionKind::REFACTOR_REWRITE,
+                surround.range,
+                &surround.snippet(kind),
+            ));
+        }
+    }
+
+    Ok(Some(actions))
+}
+
+/// Inline the variable declared or referenced at the offset,
+/// if it is possible without changing the meaning of the code.
+fn inline_variable(
+    hir: &Hir,
+    source: Source,
+    syntax: &SyntaxNode,
+    offset: TextSize,
+) -> Option<InlineVariable> {
+    let mut symbol = hir.symbol_selection_at(source, offset, true)?;
+
+    if let SymbolKind::Ref(r) = &hir[symbol].kind {
+        symbol = r.target?.as_symbol().copied()?;
+    }
+
+    let data = &hir[symbol];
+    let decl = data.kind.as_decl()?;
+
+    if !data.source.is(source) {
+        return None;
+    }
+
+    let mut references = Vec::with_capacity(decl.references.len());
+
+    for &reference in &decl.references {
+        let reference = &hir[reference];
+
+        // References from other modules cannot be edited here.
+        if !reference.source.is(source) {
+            return None;
+        }
+
+        references.push(reference.selection_or_text_range()?);
+    }
+
+    InlineVariable::new(syntax, data.selection_range()?, &references).ok()
+}
+
+/// Convert the function or closure variable declared or referenced
+/// at the offset, along with the title of the action.
+fn convert_fn(
+    hir: &Hir,
+    source: Source,
+    syntax: &SyntaxNode,
+    offset: TextSize,
+) -> Option<(&'static str, ConvertFn)> {
+    let mut symbol = hir.symbol_selection_at(source, offset, true)?;
+
+    if let SymbolKind::Ref(r) = &hir[symbol].kind {
+        symbol = r.target?.as_symbol().copied()?;
+    }
+
+    let data = &hir[symbol];
+
+    if !data.source.is(source) {
+        return None;
+    }
+
+    let (title, references) = match &data.kind {
+        SymbolKind::Fn(f) => ("Convert to closure", &f.references),
+        SymbolKind::Decl(decl) => ("Convert to function", &decl.references),
+        _ => return None,
+    };
+
+    let mut reference_ranges = Vec::with_capacity(references.len());
+
+    for &reference in references {
+        let reference = &hir[reference];
+
+        // References from other modules cannot be edited here.
+        if !reference.source.is(source) {
+            return None;
+        }
+
+        reference_ranges.push(reference.selection_or_text_range()?);
+    }
+
+    let range = data.selection_range()?;
+
+    let convert = if data.kind.is_fn() {
+        ConvertFn::fn_to_closure(syntax, range, &reference_ranges)
+    } else {
+        ConvertFn::closure_to_fn(syntax, range, &reference_ranges)
+    };
+
+    Some((title, convert.ok()?))
+}
+
+/// The range of the `let` keyword of the variable declared or
+/// referenced at the offset, if it can be a constant instead.
+fn convert_to_const(
+    hir: &Hir,
+    source: Source,
+    syntax: &SyntaxNode,
+    offset: TextSize,
+) -> Option<TextRange> {
+    let mut symbol = hir.symbol_selection_at(source, offset, true)?;
+
+    if let SymbolKind::Ref(r) = &hir[symbol].kind {
+        symbol = r.target?.as_symbol().copied()?;
+    }
+
+    let data = &hir[symbol];
+
+    if !data.source.is(source) || !hir.can_be_const(symbol) {
+        return None;
+    }
+
+    syntax
+        .covering_element(data.selection_range()?)
+        .into_token()?
+        .parent()
+        .filter(|node| node.kind() == SyntaxKind::EXPR_LET)?
+        .children_with_tokens()
+        .find(|element| element.kind() == SyntaxKind::KW_LET)
+        .map(|element| element.text_range())
+}
+
+/// Simplifications of the conditionals and loops with constant
+/// conditions that intersect the selection, along with whether
+/// they are loops and the values of their conditions.
+fn simplify_conditions(
+    hir: &Hir,
+    source: Source,
+    syntax: &SyntaxNode,
+    selection: TextRange,
+    while_true: bool,
+) -> Vec<(bool, bool, SimplifyCondition)> {
+    hir.errors_for_source(source)
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::ConstantCondition {
+                symbol,
+                condition,
+                value,
+            } => {
+                let is_while = hir[symbol].kind.is_while();
+
+                if is_while && value && !while_true {
+                    return None;
+                }
+
+                let range = hir[condition].text_range()?;
+                range.intersect(selection)?;
+
+                Some((
+                    is_while,
+                    value,
+                    SimplifyCondition::new(syntax, range, value)?,
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Edits that add the missing arms or a default arm
+/// to the non-exhaustive switches that intersect the selection,
+/// along with the titles of the actions.
+fn add_switch_arms(
+    hir: &Hir,
+    source: Source,
+    syntax: &SyntaxNode,
+    selection: TextRange,
+    indent_string: &str,
+) -> Vec<(String, AddSwitchArms)> {
+    hir.errors_for_source(source)
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::NonExhaustiveSwitch { switch, missing } => {
+                let range = hir[switch].text_range()?;
+                range.intersect(selection)?;
+                Some((range, missing))
+            }
+            _ => None,
+        })
+        .flat_map(|(range, missing)| {
+            let missing = missing.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+            [
+                ("Add missing arms", missing),
+                ("Add default arm", vec![String::from("_")]),
+            ]
+            .into_iter()
+            .filter_map(move |(title, patterns)| {
+                Some((
+                    title.to_string(),
+                    AddSwitchArms::new(syntax, range, &patterns, indent_string)?,
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Functions for the unresolved callees that intersect the selection.
+fn fn_stubs(
+    hir: &Hir,
+    source: Source,
+    syntax: &SyntaxNode,
+    selection: TextRange,
+    placement: FnStubPlacement,
+) -> Vec<FnStub> {
+    let callees = hir
+        .symbols()
+        .filter_map(|(_, data)| data.kind.as_call()?.lhs)
+        .collect::<HashSet<_>>();
+
+    hir.errors_for_source(source)
+        .into_iter()
+        .filter_map(|error| match error.kind {
+            ErrorKind::UnresolvedReference {
+                reference_symbol, ..
+            } => {
+                if !callees.contains(&reference_symbol) {
+                    return None;
+                }
+
+                let range = hir[reference_symbol].selection_or_text_range()?;
+                range.intersect(selection)?;
+
+                FnStub::new(syntax, range, placement)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Sort the top-level imports of the source and remove the ones
+/// with aliases that are never referenced.
+///
+/// Top-level imports are also available to other modules as
+/// parts of paths (e.g. `a::b::x`), those are kept as well.
+fn organize_imports(hir: &Hir, source: Source, syntax: &SyntaxNode) -> Option<OrganizeImports> {
+    let scope = hir[hir[source].module].scope;
+
+    let referenced_elsewhere = hir
+        .symbols()
+        .filter(|(_, data)| !data.source.is(source))
+        .filter_map(|(_, data)| data.kind.as_reference()?.target?.as_symbol().copied())
+        .collect::<HashSet<_>>();
+
+    let unused = hir
+        .symbols()
+        .filter(|(_, data)| data.source.is(source) && data.parent_scope == scope)
+        .filter(|(import, _)| !referenced_elsewhere.contains(import))
+        .filter_map(|(_, data)| data.kind.as_import()?.alias)
+        .filter(|&alias| {
+            hir[alias]
+                .kind
+                .as_decl()
+                .is_some_and(|decl| decl.references.is_empty())
+        })
+        .filter_map(|alias| hir[alias].selection_or_text_range())
+        .collect::<Vec<_>>();
+
+    OrganizeImports::new(syntax, &unused)
+}
+
+fn snippet_action(
+    line_index: &LineIndex,
+    uri: &Url,
+    title: &str,
+    kind: CodeActionKind,
+    range: TextRange,
+    snippet: &str,
+) -> Option<CodeActionOrCommand> {
+    let range = line_index.range(range)?;
+
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: title.into(),
+        kind: Some(kind),
+        command: Some(Command {
+            title: title.into(),
+            command: INSERT_SNIPPET_COMMAND.into(),
+            arguments: Some(vec![json!(uri), json!(range.into_lsp()), json!(snippet)]),
+        }),
+        ..CodeAction::default()
+    }))
+}
+
+fn edit_action(
+    line_index: &LineIndex,
+    uri: &Url,
+    title: &str,
+    kind: CodeActionKind,
+    edits: Vec<(TextRange, String)>,
+) -> CodeActionOrCommand {
+    let edits = edits
+        .into_iter()
+        .filter_map(|(range, new_text)| {
+            Some(TextEdit {
+                range: line_index.range(range)?.into_lsp(),
+                new_text,
+            })
+        })
+        .collect();
+
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title: title.into(),
+        kind: Some(kind),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), edits)])),
+            ..WorkspaceEdit::default()
+        }),
+        ..CodeAction::default()
+    })
+}
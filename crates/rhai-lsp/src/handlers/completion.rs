@@ -9,12 +9,17 @@ use lsp_async_stub::{
     Context, Params,
 };
 use lsp_types::{
-    Command, CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse,
-    CompletionTextEdit, Documentation, InsertTextFormat, MarkupContent, MarkupKind, TextEdit,
+    Command, CompletionItem, CompletionItemKind, CompletionItemTag, CompletionParams,
+    CompletionResponse, CompletionTextEdit, Documentation, InsertTextFormat, MarkupContent,
+    MarkupKind, TextEdit,
+};
+use rhai_common::{
+    environment::Environment,
+    util::{fuzzy_score, Normalize},
 };
-use rhai_common::{environment::Environment, util::Normalize};
 use rhai_hir::{
     scope::ScopeParent,
+    source::Source,
     symbol::{ReferenceTarget, SymbolKind, VirtualSymbol},
     ty::Type,
     Hir, Symbol, TypeKind,
@@ -36,9 +41,9 @@ pub(crate) async fn completion<E: Environment>(
 
     let doc = ws.document(&uri)?;
 
-    let syntax = doc.parse.clone().into_syntax();
+    let syntax = doc.parse().clone().into_syntax();
 
-    let offset = match doc.mapper.offset(Position::from_lsp(pos)) {
+    let offset = match doc.line_index.offset(Position::from_lsp(pos)) {
         Some(p) => p,
         None => return Ok(None),
     };
@@ -54,6 +59,10 @@ pub(crate) async fn completion<E: Environment>(
         return Ok(None);
     }
 
+    if let Some(items) = switch_arm_completion(&ws.hir, source, &query) {
+        return Ok(Some(CompletionResponse::Array(items)));
+    }
+
     if query.is_field_access() {
         if let Some(sym) = ws.hir.symbol_at(source, offset, true) {
             let sym_data = &ws.hir[sym];
@@ -76,32 +85,12 @@ pub(crate) async fn completion<E: Environment>(
             Ok(None)
         }
     } else if query.is_path() {
-        let modules = ws
-            .hir
-            .visible_symbols_from_offset(source, offset, false)
-            .filter_map(|symbol| {
-                ws.hir[symbol]
-                    .kind
-                    .as_import()
-                    .and_then(|d| d.alias)
-                    .or_else(|| {
-                        if ws.hir[symbol]
-                            .kind
-                            .as_virtual()
-                            .map_or(false, VirtualSymbol::is_module)
-                        {
-                            Some(symbol)
-                        } else {
-                            None
-                        }
-                    })
-            });
-
         let idx = query.path_segment_index();
 
         if idx == 0 {
             return Ok(Some(CompletionResponse::Array(
-                modules
+                ws.hir
+                    .visible_modules_from_offset(source, offset)
                     .filter_map(|symbol| reference_completion(&ws.hir, true, symbol))
                     .unique_by(|(symbol, _)| ws.hir.unique_symbol_name(symbol))
                     .map(|(_, c)| c)
@@ -109,38 +98,18 @@ pub(crate) async fn completion<E: Environment>(
             )));
         }
 
-        let mut symbols = modules.collect::<Vec<_>>();
-
-        for (i, segment) in query.path().unwrap().segments().enumerate() {
-            let module_name = segment.text();
-
-            let module_symbol = symbols
-                .iter()
-                .find(|&&symbol| ws.hir[symbol].name(&ws.hir) == Some(module_name));
-
-            let module_symbol = match module_symbol {
-                Some(s) => *s,
-                None => break,
-            };
-
-            match ws.hir.target_module(module_symbol) {
-                Some(m) => {
-                    symbols = ws
-                        .hir
-                        .scope_symbols(ws.hir[m].scope)
-                        .filter(|s| ws.hir[*s].export)
-                        .collect();
-                }
-                None => break,
-            }
-
-            if i == idx {
-                break;
-            }
-        }
+        // Only the segments before the one being typed
+        // lead to the module, e.g. `foo` in `foo::b`.
+        let segments = query
+            .path()
+            .unwrap()
+            .segments()
+            .take(idx)
+            .map(|segment| segment.text().to_string());
 
         Ok(Some(CompletionResponse::Array(
-            symbols
+            ws.hir
+                .path_members(source, offset, segments)
                 .into_iter()
                 .filter_map(|symbol| reference_completion(&ws.hir, false, symbol))
                 .unique_by(|(symbol, _)| ws.hir.unique_symbol_name(symbol))
@@ -148,9 +117,17 @@ pub(crate) async fn completion<E: Environment>(
                 .collect(),
         )))
     } else if query.can_complete_ref() {
-        Ok(Some(CompletionResponse::Array(
+        let program_symbols = ws
+            .hir
+            .module_by_source(source)
+            .map(|module| ws.hir.program_symbols(module))
+            .unwrap_or_default();
+
+        Ok(Some(CompletionResponse::Array(rank_completions(
+            &typed_prefix(&query),
             ws.hir
                 .visible_symbols_from_offset(source, offset, false)
+                .chain(program_symbols)
                 .filter_map(|symbol| {
                     // Unwrap aliases from import symbols
                     ws.hir[symbol]
@@ -161,9 +138,8 @@ pub(crate) async fn completion<E: Environment>(
                 })
                 .filter_map(|symbol| reference_completion(&ws.hir, false, symbol))
                 .unique_by(|(symbol, _)| ws.hir.unique_symbol_name(symbol))
-                .map(|(_, c)| c)
-                .collect(),
-        )))
+                .map(|(_, c)| c),
+        ))))
     } else if query.can_complete_op() {
         Ok(Some(CompletionResponse::Array(
             ws.hir
@@ -173,7 +149,7 @@ pub(crate) async fn completion<E: Environment>(
                     let text_edit = query.binary_op_ident().map(|ident| {
                         CompletionTextEdit::Edit(TextEdit {
                             new_text: format!("{} ", op.name),
-                            range: doc.mapper.range(ident.text_range()).unwrap().into_lsp(),
+                            range: doc.line_index.range(ident.text_range()).unwrap().into_lsp(),
                         })
                     });
 
@@ -204,7 +180,7 @@ fn binary_field_access_completion<E: Environment>(
     doc: &Document,
     query: &Query,
 ) -> std::option::Option<lsp_types::CompletionResponse> {
-    if let Some(lhs_ty) = b.lhs.map(|lhs| ws.hir[lhs].ty) {
+    if let Some(lhs_ty) = b.lhs.map(|lhs| ws.hir.type_of(lhs)) {
         let lhs_ty_data = &ws.hir[lhs_ty];
 
         match &lhs_ty_data.kind {
@@ -223,8 +199,8 @@ fn binary_field_access_completion<E: Environment>(
                     .collect(),
             )),
             _ => {
-                // TODO: handle the rest of the types,
-                // functions with getters and known `this` type.
+                // TODO: handle the rest of the types
+                // and functions with getters.
                 None
             }
         }
@@ -251,6 +227,10 @@ fn reference_completion(
                 kind: Some(CompletionItemKind::FUNCTION),
                 insert_text: Some(format!("{}($0)", &f.name)),
                 insert_text_format: Some(InsertTextFormat::SNIPPET),
+                tags: f
+                    .tags
+                    .is_deprecated()
+                    .then(|| vec![CompletionItemTag::DEPRECATED]),
                 ..CompletionItem::default()
             },
         )),
@@ -310,6 +290,34 @@ fn reference_completion(
     }
 }
 
+/// Completions of the literal patterns that the arms
+/// of the `switch` at the cursor do not cover yet.
+fn switch_arm_completion(hir: &Hir, source: Source, query: &Query) -> Option<Vec<CompletionItem>> {
+    let switch_range = query.switch_arm_pattern()?.text_range();
+
+    let (switch, _) = hir.symbols().find(|(_, data)| {
+        data.kind.is_switch()
+            && data.source.source == Some(source)
+            && data.source.text_range == Some(switch_range)
+    })?;
+
+    let values = hir.uncovered_switch_values(switch);
+
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(rank_completions(
+        &typed_prefix(query),
+        values.into_iter().map(|value| CompletionItem {
+            label: value.to_string(),
+            kind: Some(CompletionItemKind::VALUE),
+            insert_text: Some(format!("{value} => ")),
+            ..CompletionItem::default()
+        }),
+    ))
+}
+
 fn field_completion(
     doc: &Document,
     hir: &Hir,
@@ -327,13 +335,47 @@ fn field_completion(
         text_edit: existing_ident.map(|range| {
             CompletionTextEdit::Edit(TextEdit {
                 new_text: name.to_string(),
-                range: doc.mapper.range(range).unwrap().into_lsp(),
+                range: doc.line_index.range(range).unwrap().into_lsp(),
             })
         }),
         ..CompletionItem::default()
     }
 }
 
+/// The part of the identifier under the cursor that was already typed.
+fn typed_prefix(query: &Query) -> String {
+    match query.ident() {
+        Some(ident) if ident.text_range().start() <= query.offset => {
+            let len = usize::from(query.offset - ident.text_range().start());
+            ident.text().get(..len).unwrap_or_default().to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Filter out items that do not fuzzy-match the typed prefix
+/// and order the rest by descending match score via `sort_text`.
+fn rank_completions(
+    prefix: &str,
+    items: impl Iterator<Item = CompletionItem>,
+) -> Vec<CompletionItem> {
+    let mut scored = items
+        .filter_map(|item| fuzzy_score(prefix, &item.label).map(|score| (score, item)))
+        .collect::<Vec<_>>();
+
+    // The sort is stable, equal scores keep their original order.
+    scored.sort_by(|(s1, _), (s2, _)| s2.cmp(s1));
+
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (_, item))| CompletionItem {
+            sort_text: Some(format!("{idx:08}")),
+            ..item
+        })
+        .collect()
+}
+
 fn trigger_completion() -> Command {
     Command {
         command: "editor.action.triggerSuggest".into(),
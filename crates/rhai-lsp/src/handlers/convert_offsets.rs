@@ -21,13 +21,18 @@ pub(crate) async fn convert_offsets<E: Environment>(
         positions: p.positions.map(|offsets| {
             offsets
                 .into_iter()
-                .map(|offset| doc.mapper.position(offset).unwrap_or_default().into_lsp())
+                .map(|offset| {
+                    doc.line_index
+                        .position(offset)
+                        .unwrap_or_default()
+                        .into_lsp()
+                })
                 .collect()
         }),
         ranges: p.ranges.map(|ranges| {
             ranges
                 .into_iter()
-                .map(|range| doc.mapper.range(range).unwrap_or_default().into_lsp())
+                .map(|range| doc.line_index.range(range).unwrap_or_default().into_lsp())
                 .collect()
         }),
     }))
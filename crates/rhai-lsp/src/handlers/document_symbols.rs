@@ -1,11 +1,7 @@
 #![allow(deprecated)]
 
-use crate::{utils::signature_of, world::World};
-use lsp_async_stub::{
-    rpc,
-    util::{LspExt, Mapper},
-    Context, Params,
-};
+use crate::{line_index::LineIndex, utils::signature_of, world::World};
+use lsp_async_stub::{rpc, util::LspExt, Context, Params};
 use lsp_types::{DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, SymbolKind};
 use rhai_common::{environment::Environment, util::Normalize};
 use rhai_hir::{source::Source, symbol::ObjectSymbol, Hir, Scope};
@@ -26,7 +22,7 @@ pub(crate) async fn document_symbols<E: Environment>(
 
     let doc = ws.document(&p.text_document.uri)?;
 
-    let syntax = doc.parse.clone().into_syntax();
+    let syntax = doc.parse().clone().into_syntax();
 
     let source = match ws.hir.source_of(&p.text_document.uri.clone().normalize()) {
         Some(s) => s,
@@ -41,7 +37,7 @@ pub(crate) async fn document_symbols<E: Environment>(
     let root_scope = ws.hir[module].scope;
 
     Ok(Some(DocumentSymbolResponse::Nested(collect_symbols(
-        &doc.mapper,
+        &doc.line_index,
         &syntax,
         &ws.hir,
         root_scope,
@@ -50,7 +46,7 @@ pub(crate) async fn document_symbols<E: Environment>(
 }
 
 fn collect_symbols(
-    mapper: &Mapper,
+    line_index: &LineIndex,
     root: &SyntaxNode,
     hir: &Hir,
     scope: Scope,
@@ -96,21 +92,27 @@ fn collect_symbols(
                     deprecated: None,
                     kind: SymbolKind::FUNCTION,
                     name: ident.to_string(),
-                    range: mapper
+                    range: line_index
                         .range(expr.syntax().text_range())
                         .unwrap_or_default()
                         .into_lsp(),
-                    selection_range: mapper
+                    selection_range: line_index
                         .range(ident.text_range())
                         .unwrap_or_default()
                         .into_lsp(),
                     detail: Some(signature_of(hir, symbol)),
-                    children: Some(collect_symbols(mapper, root, hir, f.scope, source)),
+                    children: Some(collect_symbols(line_index, root, hir, f.scope, source)),
                     tags: None,
                 });
             }
             rhai_hir::symbol::SymbolKind::Block(block) => {
-                document_symbols.extend(collect_symbols(mapper, root, hir, block.scope, source));
+                document_symbols.extend(collect_symbols(
+                    line_index,
+                    root,
+                    hir,
+                    block.scope,
+                    source,
+                ));
             }
             rhai_hir::symbol::SymbolKind::Decl(decl) => {
                 let syntax = match syntax {
@@ -132,11 +134,11 @@ fn collect_symbols(
                     deprecated: None,
                     kind: SymbolKind::VARIABLE,
                     name: ident.to_string(),
-                    range: mapper
+                    range: line_index
                         .range(syntax.text_range())
                         .unwrap_or_default()
                         .into_lsp(),
-                    selection_range: mapper
+                    selection_range: line_index
                         .range(ident.text_range())
                         .unwrap_or_default()
                         .into_lsp(),
@@ -150,16 +152,22 @@ fn collect_symbols(
                             rhai_hir::symbol::SymbolKind::Closure(closure) => {
                                 match closure.expr.map(|s| &hir[s]) {
                                     Some(exp) => match &exp.kind {
-                                        rhai_hir::symbol::SymbolKind::Block(block) => Some(
-                                            collect_symbols(mapper, root, hir, block.scope, source),
-                                        ),
+                                        rhai_hir::symbol::SymbolKind::Block(block) => {
+                                            Some(collect_symbols(
+                                                line_index,
+                                                root,
+                                                hir,
+                                                block.scope,
+                                                source,
+                                            ))
+                                        }
                                         _ => None,
                                     },
                                     None => None,
                                 }
                             }
                             rhai_hir::symbol::SymbolKind::Object(object) => {
-                                Some(collect_object_fields(mapper, root, hir, object, source))
+                                Some(collect_object_fields(line_index, root, hir, object, source))
                             }
                             _ => None,
                         },
@@ -176,7 +184,7 @@ fn collect_symbols(
 }
 
 fn collect_object_fields(
-    mapper: &Mapper,
+    line_index: &LineIndex,
     root: &SyntaxNode,
     hir: &Hir,
     obj: &ObjectSymbol,
@@ -199,8 +207,8 @@ fn collect_object_fields(
                 deprecated: None,
                 kind: SymbolKind::PROPERTY,
                 name: name.to_string(),
-                range: mapper.range(range).unwrap_or_default().into_lsp(),
-                selection_range: mapper.range(ident_range).unwrap_or_default().into_lsp(),
+                range: line_index.range(range).unwrap_or_default().into_lsp(),
+                selection_range: line_index.range(ident_range).unwrap_or_default().into_lsp(),
                 detail: None,
                 children: match field.value.map(|s| &hir[s]) {
                     Some(v) => match &v.kind {
@@ -208,7 +216,7 @@ fn collect_object_fields(
                             match closure.expr.map(|s| &hir[s]) {
                                 Some(exp) => match &exp.kind {
                                     rhai_hir::symbol::SymbolKind::Block(block) => Some(
-                                        collect_symbols(mapper, root, hir, block.scope, source),
+                                        collect_symbols(line_index, root, hir, block.scope, source),
                                     ),
                                     _ => None,
                                 },
@@ -216,7 +224,7 @@ fn collect_object_fields(
                             }
                         }
                         rhai_hir::symbol::SymbolKind::Object(object) => {
-                            Some(collect_object_fields(mapper, root, hir, object, source))
+                            Some(collect_object_fields(line_index, root, hir, object, source))
                         }
                         _ => None,
                     },
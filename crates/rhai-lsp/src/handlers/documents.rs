@@ -1,11 +1,16 @@
-use lsp_async_stub::{Context, Params};
+use lsp_async_stub::{
+    util::{LspExt, Range},
+    Context, Params,
+};
 use lsp_types::{
     DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, Url,
+    DidSaveTextDocumentParams, TextDocumentContentChangeEvent, Url,
 };
 use rhai_common::environment::Environment;
+use rhai_rowan::{TextRange, TextSize};
 use crate::{
     diagnostics::{publish_all_diagnostics, publish_diagnostics},
+    line_index::LineIndex,
     world::World,
 };
 
@@ -23,6 +28,7 @@ pub(crate) async fn document_open<E: Environment>(
         context.clone(),
         p.text_document.uri.clone(),
         &p.text_document.text,
+        Some(p.text_document.version),
     )
     .await;
     publish_diagnostics(context.clone(), p.text_document.uri).await;
@@ -38,18 +44,52 @@ pub(crate) async fn document_change<E: Environment>(
     context: Context<World<E>>,
     params: Params<DidChangeTextDocumentParams>,
 ) {
-    let mut p = match params.optional() {
+    let p = match params.optional() {
         None => return,
         Some(p) => p,
     };
 
-    // We expect one full change
-    let change = match p.content_changes.pop() {
-        None => return,
-        Some(c) => c,
-    };
+    // The changes are relative to the current text, so they are
+    // applied under the same lock that replaces the document.
+    {
+        let mut workspaces = context.workspaces.write().await;
+        let ws = workspaces.by_document_mut(&p.text_document.uri);
+
+        let text = match ws.document(&p.text_document.uri) {
+            Ok(doc) => {
+                if doc
+                    .version
+                    .is_some_and(|version| p.text_document.version <= version)
+                {
+                    tracing::warn!(
+                        uri = %p.text_document.uri,
+                        version = p.text_document.version,
+                        "out of order document change"
+                    );
+                    return;
+                }
+
+                doc.text.clone()
+            }
+            Err(_) => String::new(),
+        };
+
+        let edit = single_edit(&text, &p.content_changes);
+
+        let Some(text) = apply_changes(text, &p.content_changes) else {
+            tracing::warn!(uri = %p.text_document.uri, "invalid document change");
+            return;
+        };
+
+        ws.update_document(
+            p.text_document.uri.clone(),
+            &text,
+            Some(p.text_document.version),
+            edit,
+        )
+        .await;
+    }
 
-    update_document(context.clone(), p.text_document.uri.clone(), &change.text).await;
     publish_diagnostics(context.clone(), p.text_document.uri).await;
 
     context
@@ -58,6 +98,38 @@ pub(crate) async fn document_change<E: Environment>(
         .spawn(publish_all_diagnostics(context));
 }
 
+/// Apply the changes in order, each range is relative
+/// to the text after the previous changes.
+///
+/// Returns `None` if a range is not valid in the text.
+fn apply_changes(mut text: String, changes: &[TextDocumentContentChangeEvent]) -> Option<String> {
+    for change in changes {
+        let Some(range) = change.range else {
+            text.clone_from(&change.text);
+            continue;
+        };
+
+        let range = LineIndex::new(&text).text_range(Range::from_lsp(range))?;
+        text.replace_range(std::ops::Range::<usize>::from(range), &change.text);
+    }
+
+    Some(text)
+}
+
+/// The replaced range of the text and the length of the new text
+/// if the changes consist of a single incremental change.
+fn single_edit(
+    text: &str,
+    changes: &[TextDocumentContentChangeEvent],
+) -> Option<(TextRange, TextSize)> {
+    let [change] = changes else {
+        return None;
+    };
+
+    let range = LineIndex::new(text).text_range(Range::from_lsp(change.range?))?;
+    Some((range, TextSize::of(change.text.as_str())))
+}
+
 #[tracing::instrument(skip_all)]
 pub(crate) async fn document_save<E: Environment>(
     _context: Context<World<E>>,
@@ -75,9 +147,120 @@ pub(crate) async fn document_close<E: Environment>(
 }
 
 #[tracing::instrument(skip_all)]
-pub(crate) async fn update_document<E: Environment>(ctx: Context<World<E>>, uri: Url, text: &str) {
+pub(crate) async fn update_document<E: Environment>(
+    ctx: Context<World<E>>,
+    uri: Url,
+    text: &str,
+    version: Option<i32>,
+) {
     let mut ws = ctx.workspaces.write().await;
     let ws = ws.by_document_mut(&uri);
-    ws.add_document(uri, text);
-    ws.hir.resolve_all();
+    ws.update_document(uri, text, version, None).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use super::*;
+
+    fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range::new(
+                Position::new(start.0, start.1),
+                Position::new(end.0, end.1),
+            )),
+            range_length: None,
+            text: text.into(),
+        }
+    }
+
+    fn full(text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn test_incremental_changes() {
+        let text = "let a = 1;\nlet b = 2;\n";
+
+        let changes = [
+            // `a` -> `value`
+            change((0, 4), (0, 5), "value"),
+            // Insert a line in between.
+            change((1, 0), (1, 0), "let c = value;\n"),
+            // Remove `let b = 2;` which is now on the third line.
+            change((2, 0), (3, 0), ""),
+            // Append at the end of the document.
+            change((2, 0), (2, 0), "print(c);"),
+        ];
+
+        let expected = "let value = 1;\nlet c = value;\nprint(c);";
+
+        assert_eq!(
+            apply_changes(text.into(), &changes).as_deref(),
+            Some(expected)
+        );
+        assert_eq!(
+            apply_changes(text.into(), &[full(expected)]).as_deref(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_utf16_changes() {
+        let text = "let s = \"😀é\";\r\nlet t = s;";
+
+        let changes = [
+            // Replace `é`, the emoji takes two UTF-16 code units.
+            change((0, 11), (0, 12), "e"),
+            change((1, 4), (1, 5), "u"),
+        ];
+
+        assert_eq!(
+            apply_changes(text.into(), &changes).as_deref(),
+            Some("let s = \"😀e\";\r\nlet u = s;")
+        );
+
+        // Inside the surrogate pair.
+        assert_eq!(
+            apply_changes(text.into(), &[change((0, 10), (0, 10), "x")]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_single_edit() {
+        let text = "let a = 1;\nlet b = 2;\n";
+
+        assert_eq!(
+            single_edit(text, &[change((1, 4), (1, 5), "value")]),
+            Some((TextRange::new(15.into(), 16.into()), 5.into()))
+        );
+        assert_eq!(
+            single_edit(
+                text,
+                &[change((0, 4), (0, 5), "x"), change((1, 4), (1, 5), "y")]
+            ),
+            None
+        );
+        assert_eq!(single_edit(text, &[full("let a = 1;")]), None);
+    }
+
+    #[test]
+    fn test_full_change_resets() {
+        let changes = [
+            change((0, 0), (0, 0), "ignored"),
+            full("let a = 1;"),
+            change((0, 9), (0, 9), "0"),
+        ];
+
+        assert_eq!(
+            apply_changes("x".into(), &changes).as_deref(),
+            Some("let a = 10;")
+        );
+    }
 }
@@ -1,12 +1,8 @@
-use crate::world::World;
+use crate::{line_index::LineIndex, world::World};
 use rhai_common::environment::Environment;
 
 use az::SaturatingAs;
-use lsp_async_stub::{
-    rpc,
-    util::{LspExt, Mapper},
-    Context, Params,
-};
+use lsp_async_stub::{rpc, util::LspExt, Context, Params};
 use lsp_types::{FoldingRange, FoldingRangeKind, FoldingRangeParams, Range};
 use rhai_rowan::syntax::{SyntaxElement, SyntaxKind::*, SyntaxNode};
 
@@ -22,14 +18,16 @@ pub(crate) async fn folding_ranges<E: Environment>(
 
     let doc = ws.document(&p.text_document.uri)?;
 
-    let syntax = doc.parse.clone().into_syntax();
+    let syntax = doc.parse().clone().into_syntax();
 
     Ok(Some(
         syntax
             .descendants_with_tokens()
             .filter_map(|d| match d.kind() {
-                EXPR_BLOCK | EXPR_OBJECT | COMMENT_BLOCK_DOC | COMMENT_BLOCK => {
-                    doc.mapper.range(d.text_range()).map(|range| FoldingRange {
+                EXPR_BLOCK | EXPR_OBJECT | COMMENT_BLOCK_DOC | COMMENT_BLOCK => doc
+                    .line_index
+                    .range(d.text_range())
+                    .map(|range| FoldingRange {
                         start_line: range.start.line.saturating_as(),
                         end_line: range.end.line.saturating_as(),
                         kind: match d.kind() {
@@ -37,17 +35,16 @@ pub(crate) async fn folding_ranges<E: Environment>(
                             _ => None,
                         },
                         ..FoldingRange::default()
-                    })
-                }
+                    }),
                 _ => None,
             })
-            .chain(collect_consecutive_comments(&doc.mapper, &syntax))
+            .chain(collect_consecutive_comments(&doc.line_index, &syntax))
             .collect(),
     ))
 }
 
 fn collect_consecutive_comments(
-    mapper: &Mapper,
+    line_index: &LineIndex,
     syntax: &SyntaxNode,
 ) -> impl Iterator<Item = FoldingRange> {
     let mut ranges = Vec::new();
@@ -62,7 +59,7 @@ fn collect_consecutive_comments(
     {
         match token.kind() {
             COMMENT_LINE => {
-                let range = mapper
+                let range = line_index
                     .range(token.text_range())
                     .unwrap_or_default()
                     .into_lsp();
@@ -89,7 +86,7 @@ fn collect_consecutive_comments(
                 }
             }
             COMMENT_LINE_DOC => {
-                let range = mapper
+                let range = line_index
                     .range(token.text_range())
                     .unwrap_or_default()
                     .into_lsp();
@@ -1,6 +1,13 @@
 use crate::world::World;
-use lsp_async_stub::{rpc, util::LspExt, Context, Params};
-use lsp_types::{DocumentFormattingParams, TextEdit};
+use lsp_async_stub::{
+    rpc,
+    util::{LspExt, Position, Range},
+    Context, Params,
+};
+use lsp_types::{
+    DocumentFormattingParams, DocumentOnTypeFormattingParams, DocumentRangeFormattingParams,
+    FormattingOptions, TextEdit,
+};
 use rhai_common::environment::Environment;
 
 #[tracing::instrument(skip_all)]
@@ -20,17 +27,96 @@ pub(crate) async fn format<E: Environment>(
         }
     };
 
-    let format_opts = rhai_fmt::Options {
-        indent_string: if p.options.insert_spaces {
-            " ".repeat(p.options.tab_size as usize)
+    Ok(Some(vec![TextEdit {
+        range: doc.line_index.all_range().into_lsp(),
+        new_text: rhai_fmt::format_syntax(doc.parse().clone_syntax(), format_options(&p.options)),
+    }]))
+}
+
+#[tracing::instrument(skip_all)]
+pub(crate) async fn format_range<E: Environment>(
+    context: Context<World<E>>,
+    params: Params<DocumentRangeFormattingParams>,
+) -> Result<Option<Vec<TextEdit>>, rpc::Error> {
+    let p = params.required()?;
+
+    let workspaces = context.workspaces.read().await;
+    let ws = workspaces.by_document(&p.text_document.uri);
+    let doc = match ws.document(&p.text_document.uri) {
+        Ok(d) => d,
+        Err(error) => {
+            tracing::debug!(%error, "failed to get document from workspace");
+            return Ok(None);
+        }
+    };
+
+    let Some(range) = doc.line_index.text_range(Range::from_lsp(p.range)) else {
+        return Ok(None);
+    };
+
+    let edit = rhai_fmt::range::format_range(
+        &doc.parse().clone_syntax(),
+        range,
+        &format_options(&p.options),
+    );
+
+    Ok(edit.and_then(|(range, new_text)| {
+        Some(vec![TextEdit {
+            range: doc.line_index.range(range)?.into_lsp(),
+            new_text,
+        }])
+    }))
+}
+
+#[tracing::instrument(skip_all)]
+pub(crate) async fn format_on_type<E: Environment>(
+    context: Context<World<E>>,
+    params: Params<DocumentOnTypeFormattingParams>,
+) -> Result<Option<Vec<TextEdit>>, rpc::Error> {
+    let p = params.required()?;
+
+    let uri = p.text_document_position.text_document.uri;
+
+    let workspaces = context.workspaces.read().await;
+    let ws = workspaces.by_document(&uri);
+    let doc = match ws.document(&uri) {
+        Ok(d) => d,
+        Err(error) => {
+            tracing::debug!(%error, "failed to get document from workspace");
+            return Ok(None);
+        }
+    };
+
+    let (Some(ch), Some(offset)) = (
+        p.ch.chars().next(),
+        doc.line_index
+            .offset(Position::from_lsp(p.text_document_position.position)),
+    ) else {
+        return Ok(None);
+    };
+
+    let edit = rhai_fmt::on_type::format_on_type(
+        &doc.parse().clone_syntax(),
+        offset,
+        ch,
+        &format_options(&p.options),
+    );
+
+    Ok(edit.and_then(|(range, new_text)| {
+        Some(vec![TextEdit {
+            range: doc.line_index.range(range)?.into_lsp(),
+            new_text,
+        }])
+    }))
+}
+
+fn format_options(options: &FormattingOptions) -> rhai_fmt::Options {
+    rhai_fmt::Options {
+        indent_string: if options.insert_spaces {
+            " ".repeat(options.tab_size as usize)
         } else {
             "\t".into()
         },
         ..Default::default()
-    };
-
-    Ok(Some(vec![TextEdit {
-        range: doc.mapper.all_range().into_lsp(),
-        new_text: rhai_fmt::format_syntax(doc.parse.clone_syntax(), format_opts),
-    }]))
+    }
 }
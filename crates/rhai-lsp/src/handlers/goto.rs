@@ -49,7 +49,7 @@ async fn goto_target<E: Environment>(
     let doc = ws.document(&uri)?;
 
     let offset = match doc
-        .mapper
+        .line_index
         .offset(lsp_async_stub::util::Position::from_lsp(pos))
     {
         Some(p) => p,
@@ -69,7 +69,7 @@ async fn goto_target<E: Environment>(
     if let Some((_, data)) = target_symbol {
         let origin_selection_range = data
             .selection_or_text_range()
-            .and_then(|range| doc.mapper.range(range).map(LspExt::into_lsp));
+            .and_then(|range| doc.line_index.range(range).map(LspExt::into_lsp));
         match &data.kind {
             rhai_hir::symbol::SymbolKind::Ref(r) => {
                 if let Some(ReferenceTarget::Symbol(target)) = &r.target {
@@ -87,17 +87,24 @@ async fn goto_target<E: Environment>(
                         None => return Ok(None),
                     };
 
-                    let target_range = match target_data
-                        .text_range()
-                        .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
-                    {
+                    let target_range = match target_data.text_range().and_then(|range| {
+                        target_document
+                            .line_index
+                            .range(range)
+                            .map(LspExt::into_lsp)
+                    }) {
                         Some(range) => range,
                         None => return Ok(None),
                     };
 
                     let target_selection_range = target_data
                         .selection_range()
-                        .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
+                        .and_then(|range| {
+                            target_document
+                                .line_index
+                                .range(range)
+                                .map(LspExt::into_lsp)
+                        })
                         .unwrap_or(target_range);
 
                     return Ok(Some(vec![LocationLink {
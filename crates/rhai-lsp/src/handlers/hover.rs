@@ -1,8 +1,17 @@
-use crate::{utils::documentation_for, world::World};
+use crate::{
+    line_index::LineIndex,
+    utils::{documentation_for, signature_of, RhaiStringExt},
+    world::World,
+};
 use lsp_async_stub::{rpc, util::LspExt, Context, Params};
 use lsp_types::{Hover, HoverContents, HoverParams, MarkupContent, MarkupKind, Range};
 use rhai_common::{environment::Environment, util::Normalize};
-use rhai_hir::{symbol::ReferenceTarget, Hir, Symbol};
+use rhai_hir::{
+    eval::Value,
+    source::Source,
+    symbol::{ReferenceTarget, SymbolKind, VirtualSymbol},
+    Hir, Symbol,
+};
 use rhai_rowan::{query::Query, syntax::SyntaxNode, TextSize};
 
 pub(crate) async fn hover<E: Environment>(
@@ -19,20 +28,28 @@ pub(crate) async fn hover<E: Environment>(
 
     let doc = ws.document(&uri)?;
 
-    let offset = match doc
-        .mapper
+    let Some(pos_offset) = doc
+        .line_index
         .offset(lsp_async_stub::util::Position::from_lsp(pos))
-    {
-        Some(p) => p + TextSize::from(1),
-        None => return Ok(None),
+    else {
+        return Ok(None);
     };
+    let offset = pos_offset + TextSize::from(1);
 
     let source = match ws.hir.source_of(&uri.clone().normalize()) {
         Some(s) => s,
         None => return Ok(None),
     };
 
-    let syntax = doc.parse.clone_syntax();
+    if let Some(hover) = hover_for_operator(&ws.hir, &doc.line_index, source, pos_offset) {
+        return Ok(Some(hover));
+    }
+
+    let syntax = doc.parse().clone_syntax();
+
+    if let Some(hover) = hover_for_literal(&ws.hir, &doc.line_index, &syntax, source, pos_offset) {
+        return Ok(Some(hover));
+    }
 
     let query = Query::at(&syntax, offset);
 
@@ -43,7 +60,10 @@ pub(crate) async fn hover<E: Environment>(
                     kind: MarkupKind::Markdown,
                     value: op.docs.clone(),
                 }),
-                range: doc.mapper.range(ident.text_range()).map(LspExt::into_lsp),
+                range: doc
+                    .line_index
+                    .range(ident.text_range())
+                    .map(LspExt::into_lsp),
             }));
         }
     }
@@ -56,19 +76,117 @@ pub(crate) async fn hover<E: Environment>(
     if let Some((symbol, data)) = target_symbol {
         let highlight_range = data
             .selection_or_text_range()
-            .and_then(|range| doc.mapper.range(range).map(LspExt::into_lsp));
+            .and_then(|range| doc.line_index.range(range).map(LspExt::into_lsp));
 
-        return Ok(hover_for_symbol(
+        let mut hover = hover_for_symbol(
             &ws.hir,
-            &doc.parse.clone_syntax(),
+            &doc.parse().clone_syntax(),
             highlight_range,
             symbol,
-        ));
+        );
+
+        if ws.config.debug.hover_scopes {
+            let scopes = debug_scopes(&ws.hir, symbol);
+
+            match &mut hover {
+                Some(Hover {
+                    contents: HoverContents::Markup(markup),
+                    ..
+                }) => {
+                    markup.value += "\n\n---\n";
+                    markup.value += &scopes;
+                }
+                _ => {
+                    hover = Some(Hover {
+                        contents: HoverContents::Markup(MarkupContent {
+                            kind: MarkupKind::Markdown,
+                            value: scopes,
+                        }),
+                        range: highlight_range,
+                    });
+                }
+            }
+        }
+
+        return Ok(hover);
     }
 
     Ok(None)
 }
 
+/// The operator definition chosen for the unary or binary
+/// expression whose operator is at the offset.
+fn hover_for_operator(
+    hir: &Hir,
+    line_index: &LineIndex,
+    source: Source,
+    offset: TextSize,
+) -> Option<Hover> {
+    let symbol = hir.operator_at(source, offset)?;
+
+    let (op_range, overload) = match &hir[symbol].kind {
+        SymbolKind::Unary(u) => (u.op_range?, u.overload),
+        SymbolKind::Binary(b) => (b.op_range?, b.overload),
+        _ => return None,
+    };
+
+    let mut value = hir.operator_signature(symbol)?.wrap_rhai_markdown();
+
+    if let Some(op) = overload
+        .and_then(|op| hir.symbol(op))
+        .and_then(|op| op.kind.as_op())
+        .filter(|op| !op.docs.is_empty())
+    {
+        value += "\n";
+        value += &op.docs;
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: line_index.range(op_range).map(LspExt::into_lsp),
+    })
+}
+
+/// The decoded value of the literal at the offset
+/// if it is written differently, e.g. `255` for `0xFF`.
+fn hover_for_literal(
+    hir: &Hir,
+    line_index: &LineIndex,
+    root: &SyntaxNode,
+    source: Source,
+    offset: TextSize,
+) -> Option<Hover> {
+    let symbol = hir.symbol_at(source, offset, false)?;
+    let lit = hir[symbol].kind.as_lit()?;
+
+    if !lit.interpolated_scopes.is_empty() {
+        return None;
+    }
+
+    let range = hir[symbol].text_range()?;
+    let text = root.text().slice(range).to_string();
+    let text = text.trim();
+
+    let decoded = match &lit.value {
+        Value::Int(v) => Some(format!("`{v}`")).filter(|_| v.to_string() != text),
+        Value::Float(v) => Some(format!("`{v:?}`")).filter(|_| format!("{v:?}") != text),
+        Value::String(v) => Some(format!("```text\n{v}\n```")).filter(|_| text.contains('\\')),
+        Value::Char(c) => Some(format!("`{c:?}` (U+{:04X})", u32::from(*c))),
+        Value::Bool(_) | Value::Unknown => None,
+    }?;
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("{}\n---\n{decoded}", text.wrap_rhai_markdown()),
+        }),
+        range: line_index.range(range).map(LspExt::into_lsp),
+    })
+}
+
 fn hover_for_symbol(
     hir: &Hir,
     root: &SyntaxNode,
@@ -76,21 +194,109 @@ fn hover_for_symbol(
     symbol: Symbol,
 ) -> Option<Hover> {
     match &hir[symbol].kind {
-        rhai_hir::symbol::SymbolKind::Fn(_) | rhai_hir::symbol::SymbolKind::Decl(_) => {
-            Some(Hover {
-                contents: HoverContents::Markup(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: documentation_for(hir, symbol, true),
-                }),
-                range: highlight_range,
-            })
-        }
+        // Path segments can also refer to modules imported in the same source.
+        rhai_hir::symbol::SymbolKind::Fn(_)
+        | rhai_hir::symbol::SymbolKind::Decl(_)
+        | rhai_hir::symbol::SymbolKind::Virtual(VirtualSymbol::Module(_)) => Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: documentation_for(hir, symbol, true),
+            }),
+            range: highlight_range,
+        }),
         rhai_hir::symbol::SymbolKind::Ref(r) => match &r.target {
             Some(ReferenceTarget::Symbol(target)) => {
                 hover_for_symbol(hir, root, highlight_range, *target)
             }
+            // Object fields have no declarations, only inferred types.
+            None if r.field_access && !hir[hir.type_of(symbol)].kind.is_unknown() => Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: signature_of(hir, symbol).wrap_rhai_markdown(),
+                }),
+                range: highlight_range,
+            }),
+            // The type of `this` is inferred from the method calls.
+            None if r.name == "this" && !r.field_access => Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: signature_of(hir, symbol).wrap_rhai_markdown(),
+                }),
+                range: highlight_range,
+            }),
             _ => None,
         },
         _ => None,
     }
 }
+
+/// The scopes that enclose the symbol with the kinds of their
+/// parent symbols, and the names of the symbols visible from it,
+/// for debugging name resolution.
+fn debug_scopes(hir: &Hir, symbol: Symbol) -> String {
+    let scopes = hir
+        .ancestors(symbol)
+        .map(|(scope, parent)| {
+            let Some(parent) = parent else {
+                return format!("- `{scope:?}`");
+            };
+
+            let data = &hir[parent];
+            let kind: &'static str = (&data.kind).into();
+
+            match data.name(hir) {
+                Some(name) => format!("- `{scope:?}` of `{kind}` `{name}`"),
+                None => format!("- `{scope:?}` of `{kind}`"),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let visible = hir
+        .visible_symbols_from_symbol(symbol)
+        .filter_map(|visible| hir[visible].name(hir))
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>();
+
+    format!(
+        "**Scopes**\n\n{}\n\n**Visible symbols**\n\n{}",
+        scopes.join("\n"),
+        visible.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rhai_rowan::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_debug_scopes() {
+        let src = r"
+let outer = 1;
+
+fn add(a, b) {
+    let sum = a + b;
+    sum
+}
+
+let after = 2;
+";
+
+        let url = "test:///hover.rhai".parse().unwrap();
+
+        let mut hir = Hir::new();
+        hir.add_source(&url, &Parser::new(src).parse_script().into_syntax());
+        hir.resolve_all();
+
+        let source = hir.source_of(&url).unwrap();
+        let offset = TextSize::try_from(src.rfind("sum").unwrap()).unwrap();
+        let symbol = hir.symbol_selection_at(source, offset, true).unwrap();
+
+        let scopes = debug_scopes(&hir, symbol);
+        let (chain, visible) = scopes.split_once("**Visible symbols**").unwrap();
+
+        assert!(chain.contains("of `Fn` `add`"), "{scopes}");
+        assert_eq!(visible.trim(), "`sum`, `b`, `a`, `add`", "{scopes}");
+    }
+}
@@ -7,14 +7,18 @@ use crate::{
 };
 use lsp_async_stub::{rpc::Error, Context, Params};
 use lsp_types::{
-    CompletionOptions, DeclarationCapability, FoldingRangeProviderCapability,
-    HoverProviderCapability, InitializedParams, OneOf, RenameOptions, SemanticTokensFullOptions,
-    SemanticTokensLegend, SemanticTokensOptions, SemanticTokensServerCapabilities,
-    ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind,
-    WorkDoneProgressOptions, WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities,
+    CodeActionKind, CodeActionOptions, CodeActionProviderCapability, CompletionOptions,
+    DeclarationCapability, DocumentOnTypeFormattingOptions, FileOperationFilter,
+    FileOperationPattern, FileOperationPatternKind, FileOperationRegistrationOptions,
+    FoldingRangeProviderCapability, HoverProviderCapability, InitializedParams, OneOf,
+    RenameOptions, SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions,
+    SemanticTokensServerCapabilities, ServerCapabilities, ServerInfo, TextDocumentSyncCapability,
+    TextDocumentSyncKind, WorkDoneProgressOptions, WorkspaceFileOperationsServerCapabilities,
+    WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities,
 };
 use lsp_types::{InitializeParams, InitializeResult};
 use rhai_common::environment::Environment;
+use rhai_fmt::on_type;
 use std::sync::Arc;
 
 #[tracing::instrument(skip_all)]
@@ -49,9 +53,23 @@ pub async fn initialize<E: Environment>(
                     supported: Some(true),
                     change_notifications: Some(OneOf::Left(true)),
                 }),
-                ..Default::default()
+                file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+                    will_rename: Some(FileOperationRegistrationOptions {
+                        filters: vec![FileOperationFilter {
+                            scheme: Some("file".into()),
+                            pattern: FileOperationPattern {
+                                glob: "**/*.rhai".into(),
+                                matches: Some(FileOperationPatternKind::File),
+                                options: None,
+                            },
+                        }],
+                    }),
+                    ..Default::default()
+                }),
             }),
-            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                TextDocumentSyncKind::INCREMENTAL,
+            )),
             rename_provider: Some(OneOf::Right(RenameOptions {
                 prepare_provider: Some(true),
                 work_done_progress_options: Default::default(),
@@ -63,6 +81,25 @@ pub async fn initialize<E: Environment>(
             document_symbol_provider: Some(OneOf::Left(true)),
             hover_provider: Some(HoverProviderCapability::Simple(true)),
             document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
+            document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                first_trigger_character: on_type::TRIGGER_CHARACTERS[0].to_string(),
+                more_trigger_character: Some(
+                    on_type::TRIGGER_CHARACTERS[1..]
+                        .iter()
+                        .map(char::to_string)
+                        .collect(),
+                ),
+            }),
+            code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                code_action_kinds: Some(vec![
+                    CodeActionKind::REFACTOR_EXTRACT,
+                    CodeActionKind::REFACTOR_INLINE,
+                    CodeActionKind::REFACTOR_REWRITE,
+                ]),
+                work_done_progress_options: Default::default(),
+                resolve_provider: None,
+            })),
             semantic_tokens_provider: Some(
                 SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
                     work_done_progress_options: WorkDoneProgressOptions {
@@ -97,6 +134,7 @@ pub async fn initialized<E: Environment>(
 ) {
     update_configuration(context.clone()).await;
 
+    let init_config = context.init_config.load();
     let mut workspaces = context.workspaces.write().await;
 
     for (ws_url, ws) in workspaces.iter_mut() {
@@ -108,7 +146,7 @@ pub async fn initialized<E: Environment>(
             tracing::error!(%error, "failed to load Rhai config");
         }
 
-        ws.load_all_files().await;
+        ws.load_all_files(init_config.cache_path.as_deref()).await;
     }
 
     drop(workspaces);
@@ -0,0 +1,39 @@
+use lsp_async_stub::{
+    rpc,
+    util::{LspExt, Position},
+    Context, Params,
+};
+use rhai_common::environment::Environment;
+use rhai_rowan::util::Delimiters;
+
+use crate::{
+    lsp_ext::request::{MatchingBraceParams, MatchingBraceResult},
+    world::World,
+};
+
+#[tracing::instrument(skip_all)]
+pub(crate) async fn matching_brace<E: Environment>(
+    context: Context<World<E>>,
+    params: Params<MatchingBraceParams>,
+) -> Result<Option<MatchingBraceResult>, rpc::Error> {
+    let p = params.required()?;
+    let workspaces = context.workspaces.read().await;
+    let ws = workspaces.by_document(&p.uri);
+
+    let doc = ws.document(&p.uri)?;
+
+    let Some(offset) = doc.line_index.offset(Position::from_lsp(p.position)) else {
+        return Ok(None);
+    };
+
+    let Some(range) = Delimiters::new(&doc.parse().clone_syntax()).matching(offset) else {
+        return Ok(None);
+    };
+
+    Ok(doc
+        .line_index
+        .range(range)
+        .map(|range| MatchingBraceResult {
+            range: range.into_lsp(),
+        }))
+}
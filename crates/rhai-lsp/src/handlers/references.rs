@@ -21,7 +21,7 @@ pub(crate) async fn references<E: Environment>(
     let doc = ws.document(&uri)?;
 
     let offset = match doc
-        .mapper
+        .line_index
         .offset(lsp_async_stub::util::Position::from_lsp(pos))
     {
         Some(p) => p + TextSize::from(1),
@@ -29,7 +29,7 @@ pub(crate) async fn references<E: Environment>(
     };
 
     let elem = doc
-        .parse
+        .parse()
         .clone_syntax()
         .covering_element(TextRange::new(offset, offset));
 
@@ -59,37 +59,29 @@ pub(crate) fn collect_references<E: Environment>(
 ) {
     let target_data = &w.hir[target_symbol];
 
-    let references = match &target_data.kind {
-        rhai_hir::symbol::SymbolKind::Fn(f) => &f.references,
-        rhai_hir::symbol::SymbolKind::Decl(d) => &d.references,
-        _ => return,
-    };
-
-    locations.extend(
-        references
-            .iter()
-            .filter_map(|&reference| {
-                let reference_data = &w.hir[reference];
-
-                let reference_source = match reference_data.source.source {
-                    Some(s) => s,
-                    None => return None,
-                };
-
-                let reference_source_data = &w.hir[reference_source];
+    if !matches!(
+        target_data.kind,
+        rhai_hir::symbol::SymbolKind::Fn(_) | rhai_hir::symbol::SymbolKind::Decl(_)
+    ) {
+        return;
+    }
 
-                let target_document = match w.documents.get(&reference_source_data.url) {
-                    Some(d) => d,
-                    None => return None,
-                };
+    for (url, references) in w.hir.references_to(target_symbol) {
+        let target_document = match w.documents.get(&url) {
+            Some(d) => d,
+            None => continue,
+        };
 
-                reference_data
-                    .selection_or_text_range()
-                    .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
-                    .map(|range| (reference_source_data.url.clone(), range))
-            })
-            .map(|(url, range)| Location { uri: url, range }),
-    );
+        locations.extend(references.into_iter().filter_map(|reference| {
+            w.hir[reference]
+                .selection_or_text_range()
+                .and_then(|range| target_document.line_index.range(range))
+                .map(|range| Location {
+                    uri: url.clone(),
+                    range: range.into_lsp(),
+                })
+        }));
+    }
 
     if include_declaration {
         let target_data = &w.hir[target_symbol];
@@ -106,10 +98,12 @@ pub(crate) fn collect_references<E: Environment>(
             None => return,
         };
 
-        if let Some(range) = target_data
-            .selection_or_text_range()
-            .and_then(|range| target_document.mapper.range(range).map(LspExt::into_lsp))
-        {
+        if let Some(range) = target_data.selection_or_text_range().and_then(|range| {
+            target_document
+                .line_index
+                .range(range)
+                .map(LspExt::into_lsp)
+        }) {
             locations.push(Location {
                 uri: target_source_data.url.clone(),
                 range,
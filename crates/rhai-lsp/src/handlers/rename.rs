@@ -1,15 +1,18 @@
 use crate::world::{Workspace, World};
-use core::iter;
 use lsp_async_stub::{
     rpc::Error,
     util::{LspExt, Position},
     Context, Params,
 };
 use lsp_types::{
-    PrepareRenameResponse, RenameParams, TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
+    PrepareRenameResponse, RenameFilesParams, RenameParams, TextDocumentPositionParams, TextEdit,
+    Url, WorkspaceEdit,
 };
 use rhai_common::{environment::Environment, util::Normalize};
-use rhai_hir::{symbol, Hir, Symbol};
+use rhai_hir::{
+    symbol::{self, RenameCollision},
+    Hir, Symbol,
+};
 use std::collections::HashMap;
 
 #[tracing::instrument(skip_all)]
@@ -25,7 +28,7 @@ pub async fn prepare_rename<E: Environment>(
     let doc = ws.document(&document_uri)?;
 
     let position = p.position;
-    let offset = match doc.mapper.offset(Position::from_lsp(position)) {
+    let offset = match doc.line_index.offset(Position::from_lsp(position)) {
         Some(ofs) => ofs,
         None => {
             tracing::error!(?position, "document position not found");
@@ -44,7 +47,7 @@ pub async fn prepare_rename<E: Environment>(
         .map(|s| (s, &ws.hir[s]));
 
     Ok(target_symbol.and_then(|(_, data)| {
-        let range = data.selection_range().and_then(|r| doc.mapper.range(r));
+        let range = data.selection_range().and_then(|r| doc.line_index.range(r));
 
         match range {
             Some(range) => match &data.kind {
@@ -73,7 +76,7 @@ pub async fn rename<E: Environment>(
     let doc = ws.document(&document_uri)?;
 
     let position = p.text_document_position.position;
-    let offset = match doc.mapper.offset(Position::from_lsp(position)) {
+    let offset = match doc.line_index.offset(Position::from_lsp(position)) {
         Some(ofs) => ofs,
         None => {
             tracing::error!(?position, "document position not found");
@@ -109,24 +112,20 @@ pub async fn rename<E: Environment>(
         }
     }
 
+    if let Some((target_symbol, _)) = target_symbol {
+        if let Some(collision) = ws.hir.rename_collisions(target_symbol, &p.new_name).first() {
+            return Err(Error::new(&collision_message(
+                &ws.hir,
+                &p.new_name,
+                *collision,
+            )));
+        }
+    }
+
     Ok(
         target_symbol.and_then(|(target_symbol, data)| match &data.kind {
-            symbol::SymbolKind::Fn(target) => Some(WorkspaceEdit {
-                changes: Some(rename_symbols(
-                    &ws.hir,
-                    iter::once(target_symbol).chain(target.references.iter().copied()),
-                    &p.new_name,
-                    ws,
-                )),
-                ..Default::default()
-            }),
-            symbol::SymbolKind::Decl(target) => Some(WorkspaceEdit {
-                changes: Some(rename_symbols(
-                    &ws.hir,
-                    iter::once(target_symbol).chain(target.references.iter().copied()),
-                    &p.new_name,
-                    ws,
-                )),
+            symbol::SymbolKind::Fn(_) | symbol::SymbolKind::Decl(_) => Some(WorkspaceEdit {
+                changes: Some(rename_symbol(&ws.hir, target_symbol, &p.new_name, ws)),
                 ..Default::default()
             }),
             _ => None,
@@ -134,43 +133,94 @@ pub async fn rename<E: Environment>(
     )
 }
 
-fn rename_symbols<E: Environment>(
-    hir: &Hir,
-    symbols: impl Iterator<Item = Symbol>,
-    new_name: &str,
-    ws: &Workspace<E>,
-) -> HashMap<Url, Vec<TextEdit>> {
-    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+/// Update the import paths of moved or renamed files.
+#[tracing::instrument(skip_all)]
+pub async fn will_rename_files<E: Environment>(
+    context: Context<World<E>>,
+    params: Params<RenameFilesParams>,
+) -> Result<Option<WorkspaceEdit>, Error> {
+    let p = params.required()?;
 
-    for symbol in symbols {
-        let data = &hir[symbol];
+    let workspaces = context.workspaces.read().await;
 
-        let source = match data.source.source {
-            Some(s) => s,
-            None => continue,
-        };
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
 
-        let range = match data.source.selection_text_range {
-            Some(range) => range,
-            None => continue,
+    for file in p.files {
+        let (Ok(old_url), Ok(new_url)) = (file.old_uri.parse::<Url>(), file.new_uri.parse::<Url>())
+        else {
+            continue;
         };
 
-        let url = &hir[source].url;
+        let ws = workspaces.by_document(&old_url);
+
+        let edits = ws
+            .hir
+            .file_rename_edits(&old_url.normalize(), &new_url.normalize());
+
+        for (url, edits) in edits {
+            let Ok(doc) = ws.document(&url) else {
+                continue;
+            };
+
+            changes
+                .entry(url)
+                .or_default()
+                .extend(edits.into_iter().filter_map(|(range, new_text)| {
+                    Some(TextEdit {
+                        range: doc.line_index.range(range)?.into_lsp(),
+                        new_text,
+                    })
+                }));
+        }
+    }
 
-        let doc = match ws.document(url) {
-            Ok(d) => d,
-            Err(_) => continue,
-        };
+    Ok((!changes.is_empty()).then(|| WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    }))
+}
 
-        let document_edits = changes.entry(url.clone()).or_default();
+fn collision_message(hir: &Hir, new_name: &str, collision: RenameCollision) -> String {
+    match collision {
+        RenameCollision::Captured { declaration, .. } => {
+            let kind = if hir[declaration].kind.is_fn() {
+                "function"
+            } else {
+                "variable"
+            };
 
-        if let Some(range) = doc.mapper.range(range) {
-            document_edits.push(TextEdit {
-                new_text: new_name.into(),
-                range: range.into_lsp(),
-            });
+            format!("a {kind} named `{new_name}` is already visible and would capture references of the renamed symbol")
+        }
+        RenameCollision::Shadowed { .. } => {
+            format!("the renamed symbol would shadow existing references to `{new_name}`")
         }
     }
+}
 
-    changes
+/// All edits of the rename in a single change set,
+/// edits in sources without documents are skipped.
+fn rename_symbol<E: Environment>(
+    hir: &Hir,
+    symbol: Symbol,
+    new_name: &str,
+    ws: &Workspace<E>,
+) -> HashMap<Url, Vec<TextEdit>> {
+    hir.rename_edits(symbol, new_name)
+        .into_iter()
+        .filter_map(|(url, edits)| {
+            let doc = ws.document(&url).ok()?;
+
+            let edits = edits
+                .into_iter()
+                .filter_map(|(range, new_text)| {
+                    Some(TextEdit {
+                        range: doc.line_index.range(range)?.into_lsp(),
+                        new_text,
+                    })
+                })
+                .collect();
+
+            Some((url, edits))
+        })
+        .collect()
 }
@@ -1,7 +1,7 @@
-use crate::World;
+use crate::{line_index::LineIndex, World};
 use lsp_async_stub::{
     rpc::Error,
-    util::{relative_range, LspExt, Mapper},
+    util::{relative_range, LspExt},
     Context, Params,
 };
 use lsp_types::{
@@ -12,7 +12,7 @@ use rhai_common::{environment::Environment, util::Normalize};
 use rhai_hir::{
     symbol::{BinaryOpKind, ReferenceTarget, SymbolKind},
     ty::Type,
-    Hir, TypeKind,
+    Hir, Symbol, TypeKind,
 };
 use rhai_rowan::TextRange;
 
@@ -37,7 +37,7 @@ pub(crate) async fn semantic_tokens<E: Environment>(
         None => return Ok(None),
     };
 
-    let mut token_builder = SemanticTokensBuilder::new(&doc.mapper);
+    let mut token_builder = SemanticTokensBuilder::new(&doc.line_index);
 
     token_builder.extend(ws.hir.symbols().filter_map(|(symbol, data)| {
         if !data.source.is(source) {
@@ -45,65 +45,13 @@ pub(crate) async fn semantic_tokens<E: Environment>(
         }
 
         match &data.kind {
-            SymbolKind::Decl(d) => {
-                if let Some(ty) = token_for_ty(&ws.hir, ws.hir[symbol].ty) {
-                    Some((ws.hir[symbol].selection_range()?, ty, vec![]))
-                } else if d.is_const {
-                    Some((
-                        ws.hir[symbol].selection_range()?,
-                        TokenType::Variable,
-                        vec![TokenModifier::ReadOnly],
-                    ))
-                } else {
-                    None
-                }
-            }
-            SymbolKind::Ref(r) => {
-                if let Some(&target_symbol) = r.target.as_ref().and_then(ReferenceTarget::as_symbol)
-                {
-                    if let Some(ty) = token_for_ty(&ws.hir, ws.hir[target_symbol].ty) {
-                        Some((ws.hir[symbol].selection_range()?, ty, vec![]))
-                    } else if let Some(d) = ws.hir[target_symbol].kind.as_decl() {
-                        if  d.is_const {
-                            Some((
-                                ws.hir[symbol].selection_range()?,
-                                TokenType::Variable,
-                                vec![TokenModifier::ReadOnly],
-                            ))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
+            SymbolKind::Decl(_) | SymbolKind::Ref(_) => {
+                let (ty, modifiers) = token_for_symbol(&ws.hir, symbol)?;
+                Some((data.selection_range()?, ty, modifiers))
             }
             SymbolKind::Path(p) => p.segments.last().and_then(|&sym| {
-                if let Some(ty) = token_for_ty(&ws.hir, ws.hir[sym].ty) {
-                    Some((ws.hir[sym].selection_range()?, ty, vec![]))
-                } else if let Some(&target) = ws.hir[sym]
-                    .kind
-                    .as_reference()
-                    .and_then(|r| r.target.as_ref().and_then(ReferenceTarget::as_symbol))
-                {
-                    if let Some(decl) = ws.hir[target].kind.as_decl() {
-                        if decl.is_const {
-                            Some((
-                                ws.hir[sym].selection_range()?,
-                                TokenType::Variable,
-                                vec![TokenModifier::ReadOnly],
-                            ))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
+                let (ty, modifiers) = token_for_symbol(&ws.hir, sym)?;
+                Some((ws.hir[sym].selection_range()?, ty, modifiers))
             }),
             SymbolKind::Binary(b) => {
                 b.op.as_ref()
@@ -120,6 +68,31 @@ pub(crate) async fn semantic_tokens<E: Environment>(
     })))
 }
 
+/// The token of a declaration or a reference,
+/// references are highlighted the same way as their targets.
+fn token_for_symbol(hir: &Hir, symbol: Symbol) -> Option<(TokenType, Vec<TokenModifier>)> {
+    let target = match &hir[symbol].kind {
+        SymbolKind::Ref(r) => *r.target.as_ref().and_then(ReferenceTarget::as_symbol)?,
+        _ => symbol,
+    };
+
+    if let Some(ty) = token_for_ty(hir, hir.type_of(target)) {
+        return Some((ty, vec![]));
+    }
+
+    let decl = hir[target].kind.as_decl()?;
+
+    Some(if decl.is_param {
+        (TokenType::Parameter, vec![])
+    } else if decl.is_const {
+        (TokenType::Variable, vec![TokenModifier::ReadOnly])
+    } else if decl.is_pat {
+        (TokenType::Variable, vec![TokenModifier::Pattern])
+    } else {
+        (TokenType::Variable, vec![])
+    })
+}
+
 fn token_for_ty(hir: &Hir, ty: Type) -> Option<TokenType> {
     match &hir[ty].kind {
         TypeKind::Module => Some(TokenType::Module),
@@ -135,6 +108,7 @@ pub enum TokenType {
     Function,
     Module,
     Variable,
+    Parameter,
 }
 
 impl TokenType {
@@ -143,31 +117,36 @@ impl TokenType {
         SemanticTokenType::FUNCTION,
         SemanticTokenType::NAMESPACE,
         SemanticTokenType::VARIABLE,
+        SemanticTokenType::PARAMETER,
     ];
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u32)]
 pub enum TokenModifier {
     ReadOnly,
+    /// Variables bound by patterns, such as loop variables.
+    Pattern,
 }
 
 impl TokenModifier {
-    pub const MODIFIERS: &'static [SemanticTokenModifier] = &[SemanticTokenModifier::READONLY];
+    pub const MODIFIERS: &'static [SemanticTokenModifier] = &[
+        SemanticTokenModifier::READONLY,
+        SemanticTokenModifier::new("pattern"),
+    ];
 }
 
 struct SemanticTokensBuilder<'b> {
     tokens: Vec<(TextRange, TokenType, Vec<TokenModifier>)>,
     // tokens: Vec<SemanticToken>,
-    mapper: &'b Mapper,
+    line_index: &'b LineIndex,
 }
 
 impl<'b> SemanticTokensBuilder<'b> {
-    fn new(mapper: &'b Mapper) -> Self {
+    fn new(line_index: &'b LineIndex) -> Self {
         Self {
             tokens: Vec::new(),
-            mapper,
+            line_index,
         }
     }
 
@@ -198,7 +177,7 @@ impl<'b> SemanticTokensBuilder<'b> {
         let mut tokens = Vec::with_capacity(self.tokens.len());
 
         for (range, ty, modifiers) in self.tokens {
-            let range = self.mapper.range(range).unwrap();
+            let range = self.line_index.range(range).unwrap();
 
             let relative = relative_range(
                 range,
@@ -214,13 +193,9 @@ impl<'b> SemanticTokensBuilder<'b> {
                     .character
                     .saturating_sub(relative.start.character)) as u32,
                 token_type: ty as u32,
-                token_modifiers_bitset: modifiers.iter().enumerate().fold(
-                    0,
-                    |mut total, (i, _)| {
-                        total += 1 << i;
-                        total
-                    },
-                ),
+                token_modifiers_bitset: modifiers
+                    .iter()
+                    .fold(0, |total, &modifier| total | 1 << modifier as u32),
             });
 
             last_range = Some(range.into_lsp());
@@ -229,3 +204,65 @@ impl<'b> SemanticTokensBuilder<'b> {
         tokens
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rhai_rowan::parser::Parser;
+
+    #[test]
+    fn test_parameter_reference() {
+        let src = "fn f(a){ a + 1 }";
+
+        let mut hir = Hir::new();
+        hir.add_source(
+            &"test:///semantic_tokens.rhai".parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+        hir.resolve_all();
+
+        let (reference, _) = hir
+            .symbols()
+            .find(|(_, data)| data.kind.as_reference().is_some_and(|r| r.name == "a"))
+            .unwrap();
+
+        assert!(matches!(
+            token_for_symbol(&hir, reference),
+            Some((TokenType::Parameter, modifiers)) if modifiers.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_embedded_script() {
+        let host = "<h1>Title</h1>\n<script>\nlet a = 1;\na\n</script>\n";
+        let start = host.find("<script>").unwrap() + "<script>".len();
+        let end = host.find("</script>").unwrap();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let script = TextRange::new((start as u32).into(), (end as u32).into());
+        let src = &host[script];
+
+        let mut hir = Hir::new();
+        hir.add_source(
+            &"test:///embedded.rhai".parse().unwrap(),
+            &Parser::new(src).parse_script().into_syntax(),
+        );
+        hir.resolve_all();
+
+        let line_index = LineIndex::embedded(host, script);
+        let mut token_builder = SemanticTokensBuilder::new(&line_index);
+        token_builder.extend(hir.symbols().filter_map(|(_, data)| {
+            let range = data.selection_range()?;
+            Some((range, TokenType::Variable, []))
+        }));
+
+        let tokens = token_builder
+            .finish()
+            .into_iter()
+            .map(|token| (token.delta_line, token.delta_start, token.length))
+            .collect::<Vec<_>>();
+
+        // The declaration on the third line and the reference on the fourth.
+        assert_eq!(tokens, [(2, 4, 1), (1, 0, 1)]);
+    }
+}
@@ -0,0 +1,50 @@
+use lsp_async_stub::{
+    rpc,
+    util::{LspExt, Position},
+    Context, Params,
+};
+use rhai_common::{environment::Environment, util::Normalize};
+
+use crate::{
+    lsp_ext::request::{TypeOfParams, TypeOfResult},
+    world::World,
+};
+
+#[tracing::instrument(skip_all)]
+pub(crate) async fn type_of<E: Environment>(
+    context: Context<World<E>>,
+    params: Params<TypeOfParams>,
+) -> Result<Option<TypeOfResult>, rpc::Error> {
+    let p = params.required()?;
+    let workspaces = context.workspaces.read().await;
+    let ws = workspaces.by_document(&p.uri);
+
+    let doc = ws.document(&p.uri)?;
+
+    let Some(offset) = doc.line_index.offset(Position::from_lsp(p.position)) else {
+        return Ok(None);
+    };
+
+    let Some(source) = ws.hir.source_of(&p.uri.clone().normalize()) else {
+        return Ok(None);
+    };
+
+    // The type is shown even if the inference failed,
+    // so that it's clear that there was an expression.
+    let Some(symbol) = ws.hir.symbol_at(source, offset, false) else {
+        return Ok(Some(TypeOfResult {
+            ty: "?".into(),
+            range: None,
+        }));
+    };
+
+    let data = &ws.hir[symbol];
+
+    Ok(Some(TypeOfResult {
+        ty: ws.hir.type_of(symbol).fmt(&ws.hir).to_string(),
+        range: data
+            .text_range()
+            .and_then(|range| doc.line_index.range(range))
+            .map(LspExt::into_lsp),
+    }))
+}
@@ -22,6 +22,11 @@ pub(crate) async fn watched_file_change<E: Environment>(
 
         let mut workspaces = context.workspaces.write().await;
 
+        // The file might be a module that was not found before.
+        for (_, ws) in workspaces.iter_mut() {
+            ws.absent_modules.clear();
+        }
+
         match change.typ {
             FileChangeType::CREATED | FileChangeType::CHANGED => {
                 let path = match context.env.url_to_file_path(&uri) {
@@ -49,7 +54,7 @@ pub(crate) async fn watched_file_change<E: Environment>(
                 };
 
                 drop(workspaces);
-                update_document(context.clone(), uri, &source).await;
+                update_document(context.clone(), uri, &source, None).await;
             }
             FileChangeType::DELETED => {
                 let ws = workspaces.by_document_mut(&uri);
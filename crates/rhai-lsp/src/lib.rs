@@ -19,6 +19,7 @@ pub use world::{World, WorldState};
 
 pub(crate) mod config;
 pub(crate) mod diagnostics;
+pub mod line_index;
 pub(crate) mod lsp_ext;
 pub(crate) mod utils;
 pub(crate) mod world;
@@ -41,7 +42,11 @@ pub fn create_server<E: Environment>() -> Server<World<E>> {
         .on_request::<request::Completion, _>(handlers::completion)
         .on_request::<request::PrepareRenameRequest, _>(handlers::prepare_rename)
         .on_request::<request::Rename, _>(handlers::rename)
+        .on_request::<request::WillRenameFiles, _>(handlers::will_rename_files)
         .on_request::<request::Formatting, _>(handlers::format)
+        .on_request::<request::RangeFormatting, _>(handlers::format_range)
+        .on_request::<request::OnTypeFormatting, _>(handlers::format_on_type)
+        .on_request::<request::CodeActionRequest, _>(handlers::code_action)
         .on_notification::<notification::Initialized, _>(handlers::initialized)
         .on_notification::<notification::DidOpenTextDocument, _>(handlers::document_open)
         .on_notification::<notification::DidChangeTextDocument, _>(handlers::document_change)
@@ -53,6 +58,8 @@ pub fn create_server<E: Environment>() -> Server<World<E>> {
         .on_request::<lsp_ext::request::HirDump, _>(handlers::hir_dump)
         .on_request::<lsp_ext::request::SyntaxTree, _>(handlers::syntax_tree)
         .on_request::<lsp_ext::request::ConvertOffsets, _>(handlers::convert_offsets)
+        .on_request::<lsp_ext::request::TypeOf, _>(handlers::type_of)
+        .on_request::<lsp_ext::request::MatchingBrace, _>(handlers::matching_brace)
         .build()
 }
 
@@ -0,0 +1,436 @@
+//! Conversion between byte offsets of the syntax tree,
+//! character offsets and LSP positions.
+//!
+//! LSP positions are zero-based lines and UTF-16 code unit columns,
+//! lines are terminated by `\n`, `\r\n` or `\r`.
+//!
+//! Scripts embedded in other documents use the lines of the
+//! host document, see [`LineIndex::embedded`].
+
+use lsp_async_stub::util::{Position, Range};
+use rhai_rowan::{TextRange, TextSize};
+
+/// A character that is encoded with more than one byte.
+#[derive(Debug, Clone, Copy)]
+struct WideChar {
+    /// Byte offset from the start of the line.
+    start: u32,
+    len_utf8: u32,
+    len_utf16: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    /// Byte offset of the first character.
+    start: TextSize,
+    /// Byte offset of the line break, or the end of the text.
+    end: TextSize,
+    /// Number of characters in all the previous lines.
+    chars_before: u32,
+    wide_chars: Vec<WideChar>,
+}
+
+impl Line {
+    /// Bytes of the wide characters that start before the byte column
+    /// that are not counted by `count`.
+    fn extra_bytes(&self, byte_col: u32, count: impl Fn(&WideChar) -> u32) -> u32 {
+        self.wide_chars
+            .iter()
+            .take_while(|wide| wide.start < byte_col)
+            .map(|wide| wide.len_utf8 - count(wide))
+            .sum()
+    }
+
+    fn is_char_boundary(&self, byte_col: u32) -> bool {
+        !self
+            .wide_chars
+            .iter()
+            .any(|wide| wide.start < byte_col && byte_col < wide.start + wide.len_utf8)
+    }
+}
+
+/// Line information of a document used to convert between
+/// byte offsets, character offsets and LSP positions.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    lines: Vec<Line>,
+    len: TextSize,
+    /// The range of the script in the document,
+    /// byte and character offsets are relative to its start.
+    script: TextRange,
+}
+
+impl LineIndex {
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        Self::embedded(text, TextRange::up_to(text_size(text.len())))
+    }
+
+    /// Line information of a host document that embeds a script at
+    /// the given range, e.g. a script block of a template.
+    ///
+    /// Offsets are relative to the start of the script and positions
+    /// are in the host document, so the ranges of the syntax tree of the
+    /// script can be used as they are.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not in the text.
+    #[must_use]
+    pub fn embedded(text: &str, script: TextRange) -> Self {
+        assert!(
+            script.end() <= text_size(text.len()),
+            "the script is not in the text"
+        );
+
+        let mut lines = Vec::new();
+
+        let mut line_start = 0;
+        let mut chars_before = 0;
+        let mut chars = 0;
+        let mut wide_chars = Vec::new();
+
+        let mut char_indices = text.char_indices().peekable();
+
+        while let Some((idx, c)) = char_indices.next() {
+            chars += 1;
+
+            match c {
+                '\n' | '\r' => {
+                    let mut next_start = idx + 1;
+
+                    if c == '\r' && char_indices.next_if(|&(_, c)| c == '\n').is_some() {
+                        chars += 1;
+                        next_start += 1;
+                    }
+
+                    lines.push(Line {
+                        start: text_size(line_start),
+                        end: text_size(idx),
+                        chars_before,
+                        wide_chars: std::mem::take(&mut wide_chars),
+                    });
+
+                    line_start = next_start;
+                    chars_before = chars;
+                }
+                c if c.len_utf8() > 1 => wide_chars.push(WideChar {
+                    start: u32::from(text_size(idx - line_start)),
+                    len_utf8: u32::from(text_size(c.len_utf8())),
+                    len_utf16: u32::from(text_size(c.len_utf16())),
+                }),
+                _ => {}
+            }
+        }
+
+        lines.push(Line {
+            start: text_size(line_start),
+            end: text_size(text.len()),
+            chars_before,
+            wide_chars,
+        });
+
+        Self {
+            lines,
+            len: text_size(text.len()),
+            script,
+        }
+    }
+
+    /// The number of lines, a trailing line break starts a new empty line.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The range of the entire script, this is the
+    /// entire document unless the script is embedded.
+    #[must_use]
+    pub fn all_range(&self) -> Range {
+        Range {
+            start: self.position(0.into()).unwrap_or_default(),
+            end: self.position(self.script.len()).unwrap_or_default(),
+        }
+    }
+
+    /// The LSP position of the byte offset.
+    ///
+    /// Offsets inside line breaks are at the end of their line.
+    ///
+    /// Returns `None` if the offset is out of bounds or is not
+    /// at a character boundary.
+    #[must_use]
+    pub fn position(&self, offset: TextSize) -> Option<Position> {
+        if offset > self.script.len() {
+            return None;
+        }
+
+        let offset = self.script.start() + offset;
+
+        let line_idx = self.line_of_offset(offset);
+        let line = &self.lines[line_idx];
+
+        let byte_col = u32::from(offset.min(line.end) - line.start);
+
+        if !line.is_char_boundary(byte_col) {
+            return None;
+        }
+
+        let utf16_col = byte_col - line.extra_bytes(byte_col, |wide| wide.len_utf16);
+
+        Some(Position::new(line_idx as u64, u64::from(utf16_col)))
+    }
+
+    /// The byte offset of the LSP position.
+    ///
+    /// Columns past the end of the line are at the end of the line
+    /// as defined by the specification.
+    ///
+    /// Returns `None` if the line does not exist, the column is
+    /// between the surrogate pair of a character or the position
+    /// is outside of an embedded script.
+    #[must_use]
+    pub fn offset(&self, position: Position) -> Option<TextSize> {
+        let offset = self.host_offset(position)?;
+
+        if self.script.contains_inclusive(offset) {
+            Some(offset - self.script.start())
+        } else {
+            None
+        }
+    }
+
+    fn host_offset(&self, position: Position) -> Option<TextSize> {
+        let line = self.lines.get(usize::try_from(position.line).ok()?)?;
+        let utf16_col = u32::try_from(position.character).unwrap_or(u32::MAX);
+
+        let mut byte_col = utf16_col;
+
+        for wide in &line.wide_chars {
+            let wide_utf16_start = wide.start - (byte_col - utf16_col);
+
+            if wide_utf16_start >= utf16_col {
+                break;
+            }
+
+            if utf16_col < wide_utf16_start + wide.len_utf16 {
+                return None;
+            }
+
+            byte_col = byte_col.saturating_add(wide.len_utf8 - wide.len_utf16);
+        }
+
+        let line_len = u32::from(line.end - line.start);
+
+        Some(line.start + TextSize::from(byte_col.min(line_len)))
+    }
+
+    #[must_use]
+    pub fn range(&self, range: TextRange) -> Option<Range> {
+        Some(Range {
+            start: self.position(range.start())?,
+            end: self.position(range.end())?,
+        })
+    }
+
+    #[must_use]
+    pub fn text_range(&self, range: Range) -> Option<TextRange> {
+        Some(TextRange::new(
+            self.offset(range.start)?,
+            self.offset(range.end)?,
+        ))
+    }
+
+    /// The number of characters before the byte offset.
+    ///
+    /// Returns `None` if the offset is out of bounds or is not
+    /// at a character boundary.
+    #[must_use]
+    pub fn char_offset(&self, offset: TextSize) -> Option<usize> {
+        if offset > self.script.len() {
+            return None;
+        }
+
+        Some(
+            self.host_char_offset(self.script.start() + offset)?
+                - self.host_char_offset(self.script.start())?,
+        )
+    }
+
+    fn host_char_offset(&self, offset: TextSize) -> Option<usize> {
+        let line = &self.lines[self.line_of_offset(offset)];
+        let byte_col = u32::from(offset - line.start);
+
+        if !line.is_char_boundary(byte_col) {
+            return None;
+        }
+
+        // Line breaks are single byte characters.
+        let chars = line.chars_before + byte_col - line.extra_bytes(byte_col, |_| 1);
+
+        Some(chars as usize)
+    }
+
+    /// The byte offset of the character at the given character offset.
+    ///
+    /// Returns `None` if the offset is out of bounds.
+    #[must_use]
+    pub fn offset_at_char(&self, char_offset: usize) -> Option<TextSize> {
+        let char_offset = char_offset.checked_add(self.host_char_offset(self.script.start())?)?;
+        let offset = self.host_offset_at_char(char_offset)?;
+
+        if self.script.contains_inclusive(offset) {
+            Some(offset - self.script.start())
+        } else {
+            None
+        }
+    }
+
+    fn host_offset_at_char(&self, char_offset: usize) -> Option<TextSize> {
+        let char_offset = u32::try_from(char_offset).ok()?;
+
+        let line_idx = self
+            .lines
+            .partition_point(|line| line.chars_before <= char_offset)
+            .saturating_sub(1);
+        let line = &self.lines[line_idx];
+
+        let char_col = char_offset - line.chars_before;
+        let mut byte_col = char_col;
+
+        for wide in &line.wide_chars {
+            let wide_char_start = wide.start - (byte_col - char_col);
+
+            if wide_char_start >= char_col {
+                break;
+            }
+
+            byte_col += wide.len_utf8 - 1;
+        }
+
+        let offset = line.start + TextSize::from(byte_col);
+
+        (offset <= self.len).then_some(offset)
+    }
+
+    fn line_of_offset(&self, offset: TextSize) -> usize {
+        self.lines
+            .partition_point(|line| line.start <= offset)
+            .saturating_sub(1)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn text_size(offset: usize) -> TextSize {
+    TextSize::from(offset as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every character boundary must survive a round-trip
+    /// through positions and character offsets.
+    fn assert_round_trip(text: &str) {
+        let index = LineIndex::new(text);
+
+        for (char_offset, (idx, c)) in text
+            .char_indices()
+            .chain(std::iter::once((text.len(), ' ')))
+            .enumerate()
+        {
+            let offset = text_size(idx);
+
+            assert_eq!(index.char_offset(offset), Some(char_offset));
+            assert_eq!(index.offset_at_char(char_offset), Some(offset));
+
+            // The second half of `\r\n` has the same position as the first.
+            if c == '\n' && text[..idx].ends_with('\r') {
+                continue;
+            }
+
+            let position = index.position(offset).unwrap();
+            assert_eq!(index.offset(position), Some(offset), "{position:?}");
+        }
+    }
+
+    #[test]
+    fn test_emoji() {
+        let text = "let a = \"😀\";\nlet b = \"é😀x\";\n";
+        let index = LineIndex::new(text);
+
+        let x = text_size(text.find('x').unwrap());
+        assert_eq!(index.position(x), Some(Position::new(1, 12)));
+        assert_eq!(index.offset(Position::new(1, 12)), Some(x));
+
+        // Inside the surrogate pair.
+        assert_eq!(index.offset(Position::new(0, 10)), None);
+        // Inside the UTF-8 encoding.
+        assert_eq!(index.position(text_size(10)), None);
+
+        assert_eq!(index.char_offset(x), Some(24));
+
+        assert_round_trip(text);
+    }
+
+    #[test]
+    fn test_crlf() {
+        let text = "let a = 1;\r\nlet 😀 = 2;\r\n\r\nlet c = 3;";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.line_count(), 4);
+
+        let c = text_size(text.find('c').unwrap());
+        assert_eq!(index.position(c), Some(Position::new(3, 4)));
+        assert_eq!(index.offset(Position::new(3, 4)), Some(c));
+
+        // The end of the first line, excluding the line break.
+        assert_eq!(index.offset(Position::new(0, 100)), Some(text_size(10)));
+        assert_eq!(index.position(text_size(11)), Some(Position::new(0, 10)));
+
+        assert_eq!(index.all_range().end, Position::new(3, 10));
+
+        assert_round_trip(text);
+    }
+
+    #[test]
+    fn test_cr() {
+        let text = "a\rb\n\r";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.position(text_size(2)), Some(Position::new(1, 0)));
+
+        assert_round_trip(text);
+    }
+
+    #[test]
+    fn test_embedded() {
+        let host = "<p>é</p>\n<script>let a = 1;\nlet 😀 = a;</script>\n";
+        let start = host.find("let").unwrap();
+        let end = host.find("</script>").unwrap();
+        let index = LineIndex::embedded(host, TextRange::new(text_size(start), text_size(end)));
+
+        let script = &host[start..end];
+        let a = text_size(script.rfind('a').unwrap());
+
+        assert_eq!(index.position(0.into()), Some(Position::new(1, 8)));
+        assert_eq!(index.position(a), Some(Position::new(2, 9)));
+        assert_eq!(index.offset(Position::new(2, 9)), Some(a));
+        assert_eq!(index.char_offset(a), Some(script.chars().count() - 2));
+        assert_eq!(index.offset_at_char(script.chars().count() - 2), Some(a));
+
+        // Outside of the script.
+        assert_eq!(index.position(text_size(script.len() + 1)), None);
+        assert_eq!(index.offset(Position::new(0, 1)), None);
+        assert_eq!(index.offset(Position::new(2, 100)), None);
+
+        assert_eq!(
+            index.all_range(),
+            Range {
+                start: Position::new(1, 8),
+                end: Position::new(2, 11),
+            }
+        );
+    }
+}
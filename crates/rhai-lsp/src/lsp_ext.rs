@@ -75,4 +75,54 @@ pub mod request {
 
         const METHOD: &'static str = "rhai/convertOffsets";
     }
+
+    pub enum TypeOf {}
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TypeOfParams {
+        pub uri: Url,
+        pub position: lsp_types::Position,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct TypeOfResult {
+        /// The inferred type of the innermost expression,
+        /// `?` if the type could not be inferred.
+        pub ty: String,
+        /// The range of the expression.
+        pub range: Option<lsp_types::Range>,
+    }
+
+    impl Request for TypeOf {
+        type Params = TypeOfParams;
+
+        type Result = Option<TypeOfResult>;
+
+        const METHOD: &'static str = "rhai/typeOf";
+    }
+
+    pub enum MatchingBrace {}
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct MatchingBraceParams {
+        pub uri: Url,
+        pub position: lsp_types::Position,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct MatchingBraceResult {
+        /// The range of the delimiter that matches
+        /// the delimiter at the position.
+        pub range: lsp_types::Range,
+    }
+
+    impl Request for MatchingBrace {
+        type Params = MatchingBraceParams;
+
+        type Result = Option<MatchingBraceResult>;
+
+        const METHOD: &'static str = "rhai/matchingBrace";
+    }
 }
@@ -25,11 +25,14 @@ pub fn signature_of(hir: &Hir, symbol: Symbol) -> String {
                     "let "
                 },
                 decl.name,
-                sym_data.ty.fmt(hir)
+                hir.type_of(symbol).fmt(hir)
             )
         }
+        SymbolKind::Ref(r) if r.field_access || (r.name == "this" && r.target.is_none()) => {
+            format!("{}: {}", r.name, hir.type_of(symbol).fmt(hir))
+        }
         _ => {
-            format!("{}", sym_data.ty.fmt(hir))
+            format!("{}", hir.type_of(symbol).fmt(hir))
         }
     }
 }
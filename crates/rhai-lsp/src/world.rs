@@ -1,20 +1,33 @@
 use crate::{
     config::{InitConfig, LspConfig},
+    line_index::LineIndex,
     utils::Debouncer,
     IndexMap,
 };
 use anyhow::anyhow;
 use arc_swap::ArcSwap;
-use lsp_async_stub::{rpc, util::Mapper};
+use lsp_async_stub::rpc;
 use lsp_types::Url;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use rhai_common::{config::Config, environment::Environment, util::Normalize};
-use rhai_hir::{ty::Type, Hir};
+use rhai_hir::{
+    cache::content_hash,
+    diagnostic::DiagnosticsCache,
+    module::{PRELUDE, PRELUDE_URL},
+    ty::Type,
+    Hir,
+};
 use rhai_rowan::{
     parser::{Operator, Parse, Parser},
     util::{is_rhai_def, is_valid_ident},
+    TextRange, TextSize,
+};
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
-use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::sync::RwLock as AsyncRwLock;
 
 pub static DEFAULT_WORKSPACE_URL: Lazy<Url> = Lazy::new(|| Url::parse("root:///").unwrap());
@@ -124,6 +137,9 @@ pub struct Workspace<E: Environment> {
     /// A set of custom operators from definitions,
     /// along with their lhs and rhs types.
     pub(crate) custom_operators: HashSet<(String, Type, Type, (u8, u8))>,
+    /// Imported modules that do not exist on the file system,
+    /// they are not looked up again until files are created or changed.
+    pub(crate) absent_modules: HashSet<Url>,
 }
 
 impl<E: Environment> Workspace<E> {
@@ -135,8 +151,13 @@ impl<E: Environment> Workspace<E> {
             rhai_config: Default::default(),
             config: LspConfig::default(),
             documents: Default::default(),
-            hir: Default::default(),
+            hir: {
+                let mut hir = Hir::new();
+                hir.add_prelude();
+                hir
+            },
             custom_operators: Default::default(),
+            absent_modules: Default::default(),
         }
     }
 }
@@ -181,7 +202,7 @@ impl<E: Environment> Workspace<E> {
         self.rhai_config.prepare(&self.env, &root_path)
     }
 
-    pub(crate) async fn load_all_files(&mut self) {
+    pub(crate) async fn load_all_files(&mut self, cache_path: Option<&Path>) {
         let includes = self.rhai_config.source.include.as_ref().unwrap();
 
         let mut paths = Vec::new();
@@ -210,6 +231,8 @@ impl<E: Environment> Workspace<E> {
 
         tracing::info!(count = all, excluded, "found files");
 
+        let mut files = Vec::with_capacity(paths.len());
+
         for path in paths {
             if self.env.is_dir(&path) {
                 continue;
@@ -234,55 +257,206 @@ impl<E: Environment> Workspace<E> {
                 }
             };
 
-            self.add_document(document_url, &source_text);
+            files.push((document_url, source_text));
+        }
+
+        let source_hashes = files
+            .iter()
+            .map(|(url, text)| (url.clone().normalize(), content_hash(text)))
+            .chain([(PRELUDE_URL.parse().unwrap(), content_hash(PRELUDE))])
+            .collect::<Vec<_>>();
+
+        let cache_file = cache_path.map(|p| {
+            p.join(format!(
+                "hir-{:016x}.cbor",
+                content_hash(self.root.as_str())
+            ))
+        });
+
+        if let Some(cache_file) = &cache_file {
+            if self.load_hir_cache(cache_file, source_hashes.clone()).await {
+                // The HIR is complete, the syntax trees of the
+                // documents are only parsed once they are used.
+                self.custom_operators = self.hir_operators();
+
+                for (url, text) in files {
+                    let document = self.parse_document(text);
+                    self.documents.insert(url, document);
+                }
+
+                if self.load_missing_modules().await {
+                    self.hir.resolve_all();
+                }
+
+                return;
+            }
+        }
+
+        for (url, text) in files {
+            self.add_document(url, &text);
         }
         self.hir.resolve_all();
+
+        if let Some(cache_file) = &cache_file {
+            self.save_hir_cache(cache_file, source_hashes).await;
+        }
+
+        // The cache only covers the included files, modules
+        // outside of them are always read again.
+        if self.load_missing_modules().await {
+            self.hir.resolve_all();
+        }
     }
 
-    pub fn add_document(&mut self, url: Url, text: &str) {
-        let is_def = is_rhai_def(text);
-
-        let parse = if is_rhai_def(text) {
-            Parser::new(text)
-                .with_operators(self.custom_operators.iter().filter_map(|(name, .., bp)| {
-                    if is_valid_ident(name) {
-                        Some((name.clone(), Operator { binding_power: *bp }))
-                    } else {
-                        None
-                    }
-                }))
-                .parse_def()
-        } else {
-            Parser::new(text)
-                .with_operators(self.custom_operators.iter().filter_map(|(name, .., bp)| {
-                    if is_valid_ident(name) {
-                        Some((name.clone(), Operator { binding_power: *bp }))
-                    } else {
-                        None
-                    }
-                }))
-                .parse_script()
+    /// Load the imported modules that are not part of the workspace
+    /// but exist on the file system, so that only the imports of modules
+    /// that do not exist are reported.
+    ///
+    /// Modules that were not found are remembered in `absent_modules`.
+    ///
+    /// Returns whether any modules were loaded, the HIR must be resolved
+    /// again in that case.
+    pub(crate) async fn load_missing_modules(&mut self) -> bool {
+        let mut loaded = false;
+        let mut probed = HashSet::new();
+
+        loop {
+            let missing = self
+                .hir
+                .missing_modules()
+                .filter(|url| !probed.contains(url) && !self.absent_modules.contains(url))
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() {
+                return loaded;
+            }
+
+            for url in missing {
+                probed.insert(url.clone());
+
+                let Some(path) = self.env.url_to_file_path(&url) else {
+                    self.absent_modules.insert(url);
+                    continue;
+                };
+
+                let Ok(Ok(text)) = self.env.read_file(&path).await.map(String::from_utf8) else {
+                    tracing::debug!(%url, "imported module does not exist");
+                    self.absent_modules.insert(url);
+                    continue;
+                };
+
+                self.add_document(url, &text);
+                loaded = true;
+            }
+        }
+    }
+
+    async fn load_hir_cache(&mut self, cache_file: &Path, source_hashes: Vec<(Url, u64)>) -> bool {
+        let bytes = match self.env.read_file(cache_file).await {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                tracing::debug!(%error, "no HIR cache");
+                return false;
+            }
+        };
+
+        match self.hir.load_cache(&bytes, source_hashes) {
+            Ok(true) => {
+                tracing::info!(path = ?cache_file, "loaded HIR from cache");
+                true
+            }
+            Ok(false) => {
+                tracing::debug!(path = ?cache_file, "HIR cache is stale");
+                false
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to load HIR cache");
+                false
+            }
+        }
+    }
+
+    async fn save_hir_cache(&self, cache_file: &Path, source_hashes: Vec<(Url, u64)>) {
+        let bytes = match self.hir.save_cache(source_hashes) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                tracing::warn!(%error, "failed to create HIR cache");
+                return;
+            }
         };
 
-        let mapper = Mapper::new_utf16(text, false);
+        if let Err(error) = self.env.write_file(cache_file, &bytes).await {
+            tracing::warn!(%error, "failed to write HIR cache");
+        }
+    }
+
+    pub fn add_document(&mut self, url: Url, text: &str) {
+        let document = self.parse_document(text.to_string());
+        let is_def = document.is_def;
 
         let normalized_url = url.clone().normalize();
 
-        self.hir.add_source(&normalized_url, &parse.clone_syntax());
-        self.documents.insert(
-            url,
-            Document {
-                parse,
-                mapper,
-                is_def,
-            },
-        );
+        self.hir
+            .add_source(&normalized_url, &document.parse().clone_syntax());
+        self.documents.insert(url, document);
 
         if is_def {
             self.check_operators();
         }
     }
 
+    /// Add or replace a document with the text of the given version
+    /// from the client and resolve the HIR again.
+    ///
+    /// The edit is the replaced range of the previous text and the
+    /// length of the new text, it is used to reuse diagnostics.
+    pub(crate) async fn update_document(
+        &mut self,
+        url: Url,
+        text: &str,
+        version: Option<i32>,
+        edit: Option<(TextRange, TextSize)>,
+    ) {
+        let mut diagnostics = self
+            .documents
+            .get_mut(&url)
+            .map(|document| std::mem::take(document.diagnostics.get_mut().unwrap()))
+            .unwrap_or_default();
+
+        // Only a single edit since the previous diagnostics can be tracked.
+        diagnostics.edit = edit.filter(|_| !diagnostics.changed);
+        diagnostics.changed = true;
+
+        self.add_document(url.clone(), text);
+
+        if let Some(document) = self.documents.get_mut(&url) {
+            document.version = version;
+            document.diagnostics = Mutex::new(diagnostics);
+        }
+
+        self.load_missing_modules().await;
+        self.hir.resolve_all();
+    }
+
+    /// Create a document that is parsed with the current
+    /// custom operators on first use.
+    fn parse_document(&self, text: String) -> Document {
+        Document {
+            version: None,
+            is_def: is_rhai_def(&text),
+            line_index: LineIndex::new(&text),
+            operators: self
+                .custom_operators
+                .iter()
+                .filter(|(name, ..)| is_valid_ident(name))
+                .map(|(name, .., bp)| (name.clone(), *bp))
+                .collect(),
+            parse: OnceCell::new(),
+            diagnostics: Mutex::default(),
+            text,
+        }
+    }
+
     pub fn remove_document(&mut self, uri: &Url) {
         if let Some(src) = self.hir.source_by_url(&uri.clone().normalize()) {
             self.hir.remove_source(src);
@@ -297,14 +471,7 @@ impl<E: Environment> Workspace<E> {
 
     /// Reparses scripts if the list of defined operators change.
     pub(crate) fn check_operators(&mut self) {
-        let new_operators = self
-            .hir
-            .operators()
-            .filter_map(|op| {
-                let rhs_ty = op.rhs_ty?;
-                Some((op.name.clone(), op.lhs_ty, rhs_ty, op.binding_powers))
-            })
-            .collect::<HashSet<_>>();
+        let new_operators = self.hir_operators();
 
         if new_operators == self.custom_operators {
             return;
@@ -320,7 +487,7 @@ impl<E: Environment> Workspace<E> {
                     self.hir.remove_source(src);
                 }
 
-                docs_to_reparse.push((uri.clone(), doc.parse.green.to_string()));
+                docs_to_reparse.push((uri.clone(), doc.text.clone()));
             }
 
             doc.is_def
@@ -330,11 +497,63 @@ impl<E: Environment> Workspace<E> {
             self.add_document(uri, &text);
         }
     }
+
+    /// Binary operators defined in the HIR.
+    fn hir_operators(&self) -> HashSet<(String, Type, Type, (u8, u8))> {
+        self.hir
+            .operators()
+            .filter_map(|op| {
+                let rhs_ty = op.rhs_ty?;
+                Some((op.name.clone(), op.lhs_ty, rhs_ty, op.binding_powers))
+            })
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Document {
-    pub(crate) parse: Parse,
-    pub(crate) mapper: Mapper,
+    /// The version of the document from the client,
+    /// `None` for documents that were not opened by it.
+    pub(crate) version: Option<i32>,
+    pub(crate) text: String,
+    pub(crate) line_index: LineIndex,
     pub(crate) is_def: bool,
+    /// The custom operators and their binding powers
+    /// at the time the document was added.
+    operators: Vec<(String, (u8, u8))>,
+    parse: OnceCell<Parse>,
+    pub(crate) diagnostics: Mutex<DocumentDiagnostics>,
+}
+
+/// The diagnostics of a document from the previous run.
+#[derive(Debug, Default)]
+pub(crate) struct DocumentDiagnostics {
+    pub(crate) cache: DiagnosticsCache,
+    /// The edit of the text since the previous run,
+    /// `None` if all diagnostics must be computed again.
+    pub(crate) edit: Option<(TextRange, TextSize)>,
+    /// Whether the text was changed since the previous run.
+    pub(crate) changed: bool,
+}
+
+impl Document {
+    /// The syntax tree of the document.
+    ///
+    /// Documents loaded along with a cached HIR are
+    /// only parsed once this is first called.
+    pub(crate) fn parse(&self) -> &Parse {
+        self.parse.get_or_init(|| {
+            let parser = Parser::new(&self.text).with_operators(
+                self.operators
+                    .iter()
+                    .map(|(name, bp)| (name.clone(), Operator { binding_power: *bp })),
+            );
+
+            if self.is_def {
+                parser.parse_def()
+            } else {
+                parser.parse_script()
+            }
+        })
+    }
 }
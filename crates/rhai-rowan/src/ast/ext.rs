@@ -7,10 +7,14 @@ use rowan::NodeOrToken;
 
 use super::{
     AstNode, DefOpPrecedence, Expr, Lit, LitStrTemplate, LitStrTemplateInterpolation, ObjectField,
-    Param, ParamList, Stmt, SwitchArm, SwitchArmCondition, Type, TypeObjectField, TypedParam,
+    Param, ParamList, Pat, PatField, PatIdent, Stmt, SwitchArm, SwitchArmCondition, Type,
+    TypeObjectField, TypedParam,
 };
 use super::{ExprBlock, ExprIf, T};
-use crate::syntax::{SyntaxElement, SyntaxKind, SyntaxToken};
+use crate::{
+    syntax::{SyntaxElement, SyntaxKind, SyntaxToken},
+    util::{self, Comment},
+};
 
 impl super::Rhai {
     #[must_use]
@@ -32,6 +36,18 @@ impl super::Rhai {
 
         s
     }
+
+    /// All comments in the script in source order.
+    pub fn comments(&self) -> impl Iterator<Item = Comment> {
+        util::comments(&self.syntax())
+    }
+}
+
+impl super::RhaiDef {
+    /// All comments in the definitions in source order.
+    pub fn comments(&self) -> impl Iterator<Item = Comment> {
+        util::comments(&self.syntax())
+    }
 }
 
 impl super::Lit {
@@ -85,6 +101,19 @@ impl super::LitStrTemplateInterpolation {
 }
 
 impl super::ExprLet {
+    pub fn ident_token(&self) -> Option<SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .find(|t| t.kind() == T!["ident"])
+    }
+
+    /// The destructuring pattern if the declaration
+    /// is not a single identifier.
+    pub fn pat(&self) -> Option<Pat> {
+        self.syntax().children().find_map(Pat::cast)
+    }
+
     pub fn expr(&self) -> Option<Expr> {
         self.syntax().children().find_map(Expr::cast)
     }
@@ -94,6 +123,15 @@ impl super::Stmt {
     pub fn item(&self) -> Option<super::Item> {
         self.syntax().children().find_map(super::Item::cast)
     }
+
+    /// Whether the statement ends with a semicolon,
+    /// the value of the item is discarded then.
+    #[must_use]
+    pub fn has_semicolon(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|t| t.kind() == T![";"])
+    }
 }
 
 impl super::Item {
@@ -199,11 +237,58 @@ impl super::ExprIf {
 }
 
 impl super::Pat {
+    /// All the identifiers bound by the pattern.
+    pub fn idents(&self) -> impl Iterator<Item = SyntaxToken> {
+        let idents: Vec<_> = match self {
+            Pat::Object(obj) => obj.fields().filter_map(|f| f.binding()).collect(),
+            _ => self
+                .syntax()
+                .descendants_with_tokens()
+                .filter(|t| t.kind() == T!["ident"])
+                .filter_map(SyntaxElement::into_token)
+                .collect(),
+        };
+
+        idents.into_iter()
+    }
+}
+
+impl super::PatArray {
     pub fn idents(&self) -> impl Iterator<Item = SyntaxToken> {
         self.syntax()
-            .descendants_with_tokens()
+            .children_with_tokens()
+            .filter_map(SyntaxElement::into_token)
             .filter(|t| t.kind() == T!["ident"])
+    }
+}
+
+impl super::PatObject {
+    pub fn fields(&self) -> impl Iterator<Item = PatField> {
+        self.syntax().children().filter_map(PatField::cast)
+    }
+}
+
+impl super::PatField {
+    #[must_use]
+    pub fn property(&self) -> Option<SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
             .filter_map(SyntaxElement::into_token)
+            .find(|t| t.kind() == T!["lit_str"] || t.kind() == T!["ident"])
+    }
+
+    /// The identifier the field is bound to,
+    /// either the alias after `:` or the property itself.
+    #[must_use]
+    pub fn binding(&self) -> Option<SyntaxToken> {
+        match self.syntax().children().find_map(PatIdent::cast) {
+            Some(pat) => pat
+                .syntax()
+                .children_with_tokens()
+                .filter_map(SyntaxElement::into_token)
+                .find(|t| t.kind() == T!["ident"]),
+            None => self.property().filter(|t| t.kind() == T!["ident"]),
+        }
     }
 }
 
@@ -216,13 +301,16 @@ impl super::SwitchArmList {
 impl super::SwitchArm {
     #[must_use]
     pub fn pattern_expr(&self) -> Option<Expr> {
-        let fat_arrow = self.punct_arrow_fat_token();
+        let first = self.syntax().children().next()?;
 
-        fat_arrow
-            .and_then(|arrow| arrow.prev_sibling_or_token())
-            .and_then(SyntaxElement::into_node)
-            .and_then(Expr::cast)
-            .or_else(|| self.syntax().children().next().and_then(Expr::cast))
+        // The pattern is missing, e.g. in `_ => value`.
+        if let Some(arrow) = self.punct_arrow_fat_token() {
+            if first.text_range().start() >= arrow.text_range().end() {
+                return None;
+            }
+        }
+
+        Expr::cast(first)
     }
 
     #[must_use]
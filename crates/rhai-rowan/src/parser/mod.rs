@@ -129,8 +129,60 @@ impl Parse {
     pub fn into_syntax(self) -> SyntaxNode {
         SyntaxNode::new_root(self.green)
     }
+
+    /// The errors worth reporting, ordered by their position.
+    ///
+    /// A single mistake (e.g. a missing `}` or `;`) usually makes the parser
+    /// report errors for the following few tokens as well until it recovers,
+    /// errors that start less than [`ERROR_CASCADE_WINDOW`] tokens after
+    /// a previous error are assumed to be caused by it and are left out.
+    #[must_use]
+    pub fn reported_errors(&self) -> Vec<&ParseError> {
+        let token_starts = self
+            .clone_syntax()
+            .descendants_with_tokens()
+            .filter_map(rowan::NodeOrToken::into_token)
+            .filter(|token| {
+                !matches!(
+                    token.kind(),
+                    SyntaxKind::WHITESPACE
+                        | SyntaxKind::COMMENT_LINE
+                        | SyntaxKind::COMMENT_BLOCK
+                        | SyntaxKind::COMMENT_LINE_DOC
+                        | SyntaxKind::COMMENT_BLOCK_DOC
+                )
+            })
+            .map(|token| token.text_range().start())
+            .collect::<Vec<_>>();
+
+        let token_index = |error: &ParseError| {
+            token_starts.partition_point(|&start| start <= error.range.start())
+        };
+
+        let mut errors = self.errors.iter().collect::<Vec<_>>();
+        errors.sort_by_key(|error| error.range.start());
+
+        let mut reported = Vec::new();
+        let mut previous = None;
+
+        for error in errors {
+            let index = token_index(error);
+
+            if previous.is_none_or(|previous| index >= previous + ERROR_CASCADE_WINDOW) {
+                reported.push(error);
+            }
+
+            previous = Some(index);
+        }
+
+        reported
+    }
 }
 
+/// The amount of tokens after a parse error
+/// in which errors are not reported by [`Parse::reported_errors`].
+pub const ERROR_CASCADE_WINDOW: usize = 3;
+
 /// A parse (syntax) error.
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[error("parse error at {range:?}: {kind}")]
@@ -173,4 +225,10 @@ pub enum ParseErrorKind {
             .collect::<Vec<String>>().join(",")
     )]
     ExpectedOneOfTokens(Vec<SyntaxKind>),
+
+    #[error("{0}")]
+    MalformedCapture(&'static str),
+
+    #[error("destructuring is not supported by Rhai")]
+    UnsupportedDestructuring,
 }
@@ -194,6 +194,10 @@ fn parse_expr_bp(ctx: &mut Context, min_bp: u8) {
         }
     }
 
+    // The kind of the expression parsed so far, or the operator
+    // of the last binary expression, used to validate `f!(x)` calls.
+    let mut lhs_kind = EXPR;
+
     // Handle "standalone" expressions, and
     // unary operators.
     match token {
@@ -332,7 +336,14 @@ fn parse_expr_bp(ctx: &mut Context, min_bp: u8) {
         LIT_INT | LIT_FLOAT | LIT_BOOL | LIT_STR | LIT_CHAR | __TEMP_STR_TEMPLATE_START => {
             parse_expr_lit(ctx);
         }
-        IDENT => parse_expr_path_or_ident(ctx),
+        IDENT => {
+            lhs_kind = if ctx.remainder().trim_start().starts_with("::") {
+                EXPR_PATH
+            } else {
+                EXPR_IDENT
+            };
+            parse_expr_path_or_ident(ctx);
+        }
         op => {
             if let Some(r_bp) = op.prefix_binding_power() {
                 ctx.start_node_at(expr_start, EXPR_UNARY);
@@ -359,7 +370,15 @@ fn parse_expr_bp(ctx: &mut Context, min_bp: u8) {
             Some(t) => t,
         };
 
-        if let Some(l_bp) = op_token.postfix_binding_power() {
+        let postfix_binding_power = match op_token {
+            // Calls that share the scope of the caller, e.g. `f!(x)`.
+            T!["!"] if ctx.remainder().trim_start().starts_with('(') => {
+                T!["("].postfix_binding_power()
+            }
+            _ => op_token.postfix_binding_power(),
+        };
+
+        if let Some(l_bp) = postfix_binding_power {
             if l_bp < min_bp {
                 break;
             }
@@ -381,14 +400,19 @@ fn parse_expr_bp(ctx: &mut Context, min_bp: u8) {
 
                     ctx.finish_node();
                 }
-                T!["("] => {
+                T!["("] | T!["!"] => {
                     ctx.start_node_at(expr_start, EXPR_CALL);
+                    if op_token == T!["!"] {
+                        validate_call_scope(ctx, lhs_kind);
+                        ctx.eat();
+                    }
                     parse_arg_list(ctx);
                     ctx.finish_node();
                 }
                 _ => unreachable!(),
             }
 
+            lhs_kind = EXPR;
             continue;
         }
 
@@ -424,11 +448,26 @@ fn parse_expr_bp(ctx: &mut Context, min_bp: u8) {
         ctx.start_node_at(expr_start, EXPR_BINARY);
         parse_expr_bp(ctx, r_bp);
         ctx.finish_node();
+
+        lhs_kind = op_token;
     }
 
     ctx.finish_node();
 }
 
+/// Only functions called by their name can share
+/// the scope of the caller with `f!(x)`.
+fn validate_call_scope(ctx: &mut Context, lhs_kind: SyntaxKind) {
+    let reason = match lhs_kind {
+        EXPR_IDENT => return,
+        EXPR_PATH => "'!' cannot be used to call module functions",
+        T!["."] | T!["?."] => "method calls cannot share the scope of the caller",
+        _ => "'!' can only be used to call functions by name",
+    };
+
+    ctx.add_error(ParseErrorKind::MalformedCapture(reason));
+}
+
 #[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
 pub fn parse_expr_throw(ctx: &mut Context) {
     ctx.start_node(EXPR_THROW);
@@ -491,7 +530,12 @@ pub fn parse_expr_let(ctx: &mut Context) {
     ctx.start_node(EXPR_LET);
 
     expect_token!(ctx in node, T!["let"]);
-    expect_token!(ctx in node, T!["ident"]);
+
+    if matches!(ctx.token(), Some(T!["["] | T!["#{"])) {
+        parse_pat(ctx);
+    } else {
+        expect_token!(ctx in node, T!["ident"]);
+    }
 
     if !matches!(ctx.token(), Some(T!["="])) {
         ctx.finish_node();
@@ -875,6 +919,8 @@ pub fn parse_pat(ctx: &mut Context) {
     match token {
         T!["ident"] => parse_pat_ident(ctx),
         T!["("] => parse_pat_tuple(ctx),
+        T!["["] => parse_pat_array(ctx),
+        T!["#{"] => parse_pat_object(ctx),
         _ => {
             ctx.eat_error(ParseErrorKind::UnexpectedToken);
         }
@@ -937,6 +983,100 @@ fn parse_pat_tuple(ctx: &mut Context) {
     ctx.finish_node();
 }
 
+#[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
+fn parse_pat_array(ctx: &mut Context) {
+    ctx.start_node(PAT_ARRAY);
+
+    ctx.add_error(ParseErrorKind::UnsupportedDestructuring);
+    expect_token!(ctx in node, T!["["]);
+
+    loop {
+        let token = require_token!(ctx in node);
+        if matches!(token, T!["]"]) {
+            ctx.eat();
+            break;
+        }
+
+        expect_token!(ctx in node, T!["ident"]);
+
+        let end_token = require_token!(ctx in node);
+
+        match end_token {
+            T!["]"] => {
+                ctx.eat();
+                break;
+            }
+            T![","] => {
+                ctx.eat();
+            }
+            _ => {
+                ctx.eat_error(ParseErrorKind::ExpectedToken(T![","]));
+                break;
+            }
+        }
+    }
+
+    ctx.finish_node();
+}
+
+#[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
+fn parse_pat_object(ctx: &mut Context) {
+    ctx.start_node(PAT_OBJECT);
+
+    ctx.add_error(ParseErrorKind::UnsupportedDestructuring);
+    expect_token!(ctx in node, T!["#{"]);
+
+    loop {
+        let token = require_token!(ctx in node);
+        if matches!(token, T!["}"]) {
+            ctx.eat();
+            break;
+        }
+
+        parse_pat_field(ctx);
+
+        let end_token = require_token!(ctx in node);
+
+        match end_token {
+            T!["}"] => {
+                ctx.eat();
+                break;
+            }
+            T![","] => {
+                ctx.eat();
+            }
+            _ => {
+                ctx.eat_error(ParseErrorKind::ExpectedToken(T![","]));
+                break;
+            }
+        }
+    }
+
+    ctx.finish_node();
+}
+
+#[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
+fn parse_pat_field(ctx: &mut Context) {
+    ctx.start_node(PAT_FIELD);
+
+    if !matches!(require_token!(ctx in node), T!["ident"] | T!["lit_str"]) {
+        ctx.eat_error(ParseErrorKind::ExpectedOneOfTokens(vec![
+            T!["ident"],
+            T!["lit_str"],
+        ]));
+        ctx.finish_node();
+        return;
+    }
+    ctx.eat();
+
+    if matches!(ctx.token(), Some(T![":"])) {
+        ctx.eat();
+        parse_pat_ident(ctx);
+    }
+
+    ctx.finish_node();
+}
+
 #[tracing::instrument(level = tracing::Level::TRACE, skip(ctx))]
 fn parse_object_field(ctx: &mut Context) {
     ctx.start_node(OBJECT_FIELD);
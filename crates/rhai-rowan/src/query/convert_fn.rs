@@ -0,0 +1,341 @@
+//! Converting between functions and variables that hold closures.
+
+use rowan::{NodeOrToken, TextRange};
+
+use super::util::{is_first_operand, op_kind, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// Reasons why a function or a closure cannot be converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertFnError {
+    /// The range is not the name of a function.
+    NotAFunction,
+    /// The range is not the name of a `let` or `const` declaration
+    /// with a closure value.
+    NotAClosure,
+    /// The function calls itself at the given reference,
+    /// closures cannot refer to their own variable.
+    Recursive(TextRange),
+    /// The function is used before its definition at the given reference,
+    /// which only works because functions are hoisted.
+    UsedBeforeDefinition(TextRange),
+    /// The function is used inside another function at the given reference,
+    /// where variables are not visible.
+    UsedInFunction(TextRange),
+    /// Functions can only be defined at the global level.
+    NotGlobal,
+    /// The variable is exported and might be used by other modules.
+    Exported,
+    /// The closure captures the variable at the given range,
+    /// functions cannot access variables outside of them.
+    Captured(TextRange),
+    /// The variable is used at the given reference
+    /// in a way other than calling it.
+    InvalidReference(TextRange),
+}
+
+/// Edits that turn a function into a variable that holds a closure
+/// or the other way around.
+///
+/// The name stays the same, so calls do not have to be changed.
+#[derive(Debug, Clone)]
+pub struct ConvertFn {
+    /// Non-overlapping edits in the order of their ranges.
+    pub edits: Vec<(TextRange, String)>,
+}
+
+impl ConvertFn {
+    /// Replace the function with the name at `function` with
+    /// a variable that holds an equivalent closure.
+    ///
+    /// References are resolved by the caller, all of them must
+    /// be in the same document as the function.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function cannot be converted
+    /// without changing the meaning of the code.
+    pub fn fn_to_closure(
+        root: &SyntaxNode,
+        function: TextRange,
+        references: &[TextRange],
+    ) -> Result<Self, ConvertFnError> {
+        let fn_expr = node_at(root, function)
+            .filter(|node| node.kind() == EXPR_FN)
+            .ok_or(ConvertFnError::NotAFunction)?;
+
+        let name = fn_expr
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find(|token| token.kind() == IDENT)
+            .ok_or(ConvertFnError::NotAFunction)?;
+
+        let body = fn_expr
+            .children()
+            .find(|node| node.kind() == EXPR_BLOCK)
+            .ok_or(ConvertFnError::NotAFunction)?;
+
+        let fn_range = trimmed_range(&fn_expr).ok_or(ConvertFnError::NotAFunction)?;
+
+        for &reference in references {
+            if fn_range.contains_range(reference) {
+                return Err(ConvertFnError::Recursive(reference));
+            }
+
+            if reference.start() < fn_range.start() {
+                return Err(ConvertFnError::UsedBeforeDefinition(reference));
+            }
+
+            let in_function = root
+                .covering_element(reference)
+                .ancestors()
+                .any(|node| node.kind() == EXPR_FN);
+
+            if in_function {
+                return Err(ConvertFnError::UsedInFunction(reference));
+            }
+        }
+
+        let text = root.text().to_string();
+
+        let body_range = trimmed_range(&body).ok_or(ConvertFnError::NotAFunction)?;
+        let body_text = match single_expr(&body) {
+            Some(expr) if !text[body_range].contains('\n') => {
+                &text[trimmed_range(&expr).ok_or(ConvertFnError::NotAFunction)?]
+            }
+            _ => &text[body_range],
+        };
+
+        let has_semi = fn_expr
+            .ancestors()
+            .find(|node| node.kind() == STMT)
+            .is_some_and(|stmt| {
+                stmt.children_with_tokens()
+                    .any(|child| child.kind() == PUNCT_SEMI)
+            });
+
+        let mut new_text = format!(
+            "let {} = |{}| {body_text}",
+            name.text(),
+            param_names(&fn_expr).join(", ")
+        );
+
+        if !has_semi {
+            new_text += ";";
+        }
+
+        Ok(Self {
+            edits: vec![(fn_range, new_text)],
+        })
+    }
+
+    /// Replace the variable declared with the name at `decl`
+    /// with a function that is equivalent to its closure.
+    ///
+    /// References are resolved by the caller, all of them must
+    /// be in the same document as the declaration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the closure cannot be converted
+    /// without changing the meaning of the code.
+    pub fn closure_to_fn(
+        root: &SyntaxNode,
+        decl: TextRange,
+        references: &[TextRange],
+    ) -> Result<Self, ConvertFnError> {
+        let decl_expr = node_at(root, decl)
+            .filter(|node| matches!(node.kind(), EXPR_LET | EXPR_CONST))
+            .ok_or(ConvertFnError::NotAClosure)?;
+
+        let name = decl_expr
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find(|token| token.kind() == IDENT)
+            .ok_or(ConvertFnError::NotAClosure)?;
+
+        let closure = decl_expr
+            .children()
+            .find(|node| node.kind() == EXPR)
+            .and_then(|value| value.first_child())
+            .filter(|node| node.kind() == EXPR_CLOSURE)
+            .ok_or(ConvertFnError::NotAClosure)?;
+
+        let body = closure
+            .children()
+            .find(|node| node.kind() == EXPR)
+            .ok_or(ConvertFnError::NotAClosure)?;
+
+        if decl_expr.ancestors().any(|node| node.kind() == EXPR_EXPORT) {
+            return Err(ConvertFnError::Exported);
+        }
+
+        let stmt = decl_expr
+            .ancestors()
+            .find(|node| node.kind() == STMT)
+            .filter(|stmt| stmt.parent().is_some_and(|parent| parent.kind() == RHAI))
+            .ok_or(ConvertFnError::NotGlobal)?;
+
+        let params = param_names(&closure);
+
+        if let Some(captured) = captured_variable(&body, &params) {
+            return Err(ConvertFnError::Captured(captured));
+        }
+
+        for &reference in references {
+            if !is_callee(root, reference) {
+                return Err(ConvertFnError::InvalidReference(reference));
+            }
+        }
+
+        let text = root.text().to_string();
+
+        let body_range = trimmed_range(&body).ok_or(ConvertFnError::NotAClosure)?;
+        let body_text = if body.first_child().is_some_and(|n| n.kind() == EXPR_BLOCK) {
+            text[body_range].to_string()
+        } else {
+            format!("{{ {} }}", &text[body_range])
+        };
+
+        let stmt_range = trimmed_range(&stmt).ok_or(ConvertFnError::NotAClosure)?;
+        let decl_range = trimmed_range(&decl_expr).ok_or(ConvertFnError::NotAClosure)?;
+
+        Ok(Self {
+            edits: vec![(
+                TextRange::new(decl_range.start(), stmt_range.end()),
+                format!("fn {}({}) {body_text}", name.text(), params.join(", ")),
+            )],
+        })
+    }
+
+    /// Apply the edits to the text of the document.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        for (range, replacement) in self.edits.iter().rev() {
+            text.replace_range(std::ops::Range::<usize>::from(*range), replacement);
+        }
+
+        text
+    }
+}
+
+/// The node of the name at the range.
+fn node_at(root: &SyntaxNode, range: TextRange) -> Option<SyntaxNode> {
+    match root.covering_element(range) {
+        NodeOrToken::Node(node) => Some(node),
+        NodeOrToken::Token(token) if token.kind() == IDENT => token.parent(),
+        NodeOrToken::Token(_) => None,
+    }
+}
+
+/// The names of the parameters of a function or a closure.
+fn param_names(node: &SyntaxNode) -> Vec<String> {
+    node.children()
+        .find(|node| node.kind() == PARAM_LIST)
+        .into_iter()
+        .flat_map(|list| list.children())
+        .filter(|node| node.kind() == PARAM)
+        .filter_map(|param| param.first_token())
+        .map(|token| token.text().to_string())
+        .collect()
+}
+
+/// The only expression of the block if it does nothing else,
+/// so that it can be used without the block.
+fn single_expr(block: &SyntaxNode) -> Option<SyntaxNode> {
+    let mut stmts = block.children().filter(|node| node.kind() == STMT);
+    let stmt = stmts.next()?;
+
+    if stmts.next().is_some()
+        || block
+            .children_with_tokens()
+            .any(|child| matches!(child.kind(), COMMENT_LINE | COMMENT_BLOCK))
+        || stmt
+            .children_with_tokens()
+            .any(|child| child.kind() == PUNCT_SEMI)
+    {
+        return None;
+    }
+
+    let expr = stmt
+        .children()
+        .find(|node| node.kind() == ITEM)?
+        .children()
+        .find(|node| node.kind() == EXPR)?;
+
+    match expr.first_child()?.kind() {
+        EXPR_LET | EXPR_CONST | EXPR_RETURN | EXPR_THROW | EXPR_IMPORT | EXPR_EXPORT | EXPR_FN => {
+            None
+        }
+        _ => Some(expr),
+    }
+}
+
+/// The first variable in the closure body that is declared
+/// outside of the closure.
+///
+/// Called names are not variables unless they are declared,
+/// so calls are assumed to call functions.
+fn captured_variable(body: &SyntaxNode, params: &[String]) -> Option<TextRange> {
+    let declared = body
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == IDENT)
+        .filter(|token| {
+            token.parent().is_some_and(|parent| {
+                matches!(
+                    parent.kind(),
+                    EXPR_LET | EXPR_CONST | PARAM | PAT_IDENT | PAT_TUPLE | PAT_ARRAY
+                )
+            })
+        })
+        .map(|token| token.text().to_string())
+        .collect::<Vec<_>>();
+
+    body.descendants()
+        .filter(|node| node.kind() == EXPR_IDENT)
+        .filter_map(|node| node.first_token())
+        .filter(|token| token.kind() == IDENT && token.text() != "this")
+        .filter(|token| {
+            let name = token.text().to_string();
+            !params.contains(&name) && !declared.contains(&name)
+        })
+        .find(|token| {
+            let Some(expr) = token.parent().and_then(|ident| ident.parent()) else {
+                return false;
+            };
+
+            let Some(parent) = expr.parent() else {
+                return true;
+            };
+
+            match parent.kind() {
+                EXPR_CALL => !is_first_operand(&expr, &parent),
+                EXPR_BINARY => {
+                    is_first_operand(&expr, &parent)
+                        || !matches!(op_kind(&parent), Some(PUNCT_DOT | OP_NULL_ACCESS))
+                }
+                _ => true,
+            }
+        })
+        .map(|token| token.text_range())
+}
+
+/// Whether the reference is the function of a call.
+fn is_callee(root: &SyntaxNode, reference: TextRange) -> bool {
+    let ident = match root.covering_element(reference) {
+        NodeOrToken::Node(node) => Some(node),
+        NodeOrToken::Token(token) => token.parent(),
+    };
+
+    ident
+        .filter(|node| node.kind() == EXPR_IDENT)
+        .and_then(|ident| ident.parent())
+        .filter(|expr| expr.kind() == EXPR)
+        .is_some_and(|expr| {
+            expr.parent()
+                .is_some_and(|call| call.kind() == EXPR_CALL && is_first_operand(&expr, &call))
+        })
+}
@@ -0,0 +1,232 @@
+//! Extracting expressions into variables.
+
+use rowan::{NodeOrToken, TextRange, TextSize};
+
+use super::util::{escape_snippet, line_indent, not_ws_or_comment, statement_at, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// The base of the names of extracted variables.
+const DEFAULT_NAME: &str = "value";
+
+/// An expression that can be moved into a new `let` declaration
+/// before the statement that contains it.
+#[derive(Debug, Clone)]
+pub struct ExtractVariable {
+    /// The range of the extracted expression.
+    pub expr_range: TextRange,
+    /// The start of the statement the declaration is inserted before.
+    pub stmt_start: TextSize,
+    /// A name for the variable that is not used anywhere in the document.
+    pub name: String,
+    expr: String,
+    /// Text of the statement before the expression.
+    prefix: String,
+    indent: String,
+}
+
+impl ExtractVariable {
+    /// Find the smallest expression that contains the selection
+    /// in the statement with the given range, e.g. the statement
+    /// of the selection from `Hir::inner_statements_in_range`.
+    ///
+    /// Returns `None` if there is no such expression, or
+    /// if moving it before its statement would change its meaning,
+    /// e.g. if it is the body of a closure or the condition of a `while` loop.
+    #[must_use]
+    pub fn new(root: &SyntaxNode, selection: TextRange, statement: TextRange) -> Option<Self> {
+        let text = root.text().to_string();
+        let selection = trim_selection(&text, selection)?;
+
+        let covering = match root.covering_element(selection) {
+            NodeOrToken::Node(node) => node,
+            NodeOrToken::Token(token) => token.parent()?,
+        };
+
+        let expr = covering.ancestors().find(|node| node.kind() == EXPR)?;
+
+        if !is_extractable(&expr) {
+            return None;
+        }
+
+        let stmt = statement_at(root, statement)?;
+
+        if !stmt.text_range().contains_range(expr.text_range()) || is_conditional(&expr, &stmt) {
+            return None;
+        }
+
+        let expr_range = trimmed_range(&expr)?;
+        let stmt_start = trimmed_range(&stmt)?.start();
+
+        Some(Self {
+            expr_range,
+            stmt_start,
+            name: unused_name(root),
+            expr: text[expr_range].to_string(),
+            prefix: text[TextRange::new(stmt_start, expr_range.start())].to_string(),
+            indent: line_indent(&text, stmt_start),
+        })
+    }
+
+    /// The range that is replaced by [`ExtractVariable::text`]
+    /// or [`ExtractVariable::snippet`].
+    #[must_use]
+    pub fn range(&self) -> TextRange {
+        TextRange::new(self.stmt_start, self.expr_range.end())
+    }
+
+    /// The declaration followed by the statement up to and
+    /// including the reference that replaced the expression.
+    #[must_use]
+    pub fn text(&self) -> String {
+        format!(
+            "let {name} = {expr};\n{indent}{prefix}{name}",
+            name = self.name,
+            expr = self.expr,
+            indent = self.indent,
+            prefix = self.prefix,
+        )
+    }
+
+    /// Same as [`ExtractVariable::text`] as an LSP snippet,
+    /// both occurrences of the name are a single placeholder,
+    /// so that it can be renamed right away.
+    #[must_use]
+    pub fn snippet(&self) -> String {
+        format!(
+            "let ${{1:{name}}} = {expr};\n{indent}{prefix}${{1:{name}}}",
+            name = self.name,
+            expr = escape_snippet(&self.expr),
+            indent = self.indent,
+            prefix = escape_snippet(&self.prefix),
+        )
+    }
+}
+
+/// Whether the expression is evaluated conditionally or repeatedly
+/// within the statement, such expressions cannot be moved before it.
+///
+/// Blocks have their own statements, so only expressions
+/// outside of blocks end up here.
+fn is_conditional(expr: &SyntaxNode, stmt: &SyntaxNode) -> bool {
+    let mut node = expr.clone();
+
+    while &node != stmt {
+        let Some(parent) = node.parent() else {
+            return false;
+        };
+
+        let conditional = match parent.kind() {
+            EXPR_CLOSURE | SWITCH_ARM | EXPR_WHILE => true,
+            // The right operands of short-circuiting operators.
+            EXPR_BINARY => {
+                parent.children().next().as_ref() != Some(&node)
+                    && parent.children_with_tokens().any(|element| {
+                        matches!(
+                            element.kind(),
+                            OP_BOOL_AND | OP_BOOL_OR | OP_NULL_OR | OP_NULL_ACCESS
+                        )
+                    })
+            }
+            // The condition of an `else if`.
+            EXPR_IF => parent.parent().is_some_and(|p| p.kind() == EXPR_IF),
+            _ => false,
+        };
+
+        if conditional {
+            return true;
+        }
+
+        node = parent;
+    }
+
+    false
+}
+
+/// Whether the expression is a value that can be stored
+/// in a variable without changing the meaning of the code.
+fn is_extractable(expr: &SyntaxNode) -> bool {
+    let Some(inner) = expr.first_child() else {
+        return false;
+    };
+
+    if matches!(
+        inner.kind(),
+        EXPR_LET
+            | EXPR_CONST
+            | EXPR_FN
+            | EXPR_IMPORT
+            | EXPR_EXPORT
+            | EXPR_RETURN
+            | EXPR_BREAK
+            | EXPR_CONTINUE
+            | EXPR_THROW
+    ) {
+        return false;
+    }
+
+    let Some(parent) = expr.parent() else {
+        return false;
+    };
+
+    let is_first_operand = parent
+        .children()
+        .find(|child| child.kind() == EXPR)
+        .is_some_and(|first| &first == expr);
+
+    match parent.kind() {
+        // Function names are not values.
+        EXPR_CALL => !is_first_operand,
+        EXPR_BINARY => {
+            let op = parent
+                .children_with_tokens()
+                .filter_map(NodeOrToken::into_token)
+                .find(not_ws_or_comment)
+                .map(|token| token.kind());
+
+            match op {
+                // Fields and methods are not values either.
+                Some(PUNCT_DOT) => is_first_operand,
+                // Neither are assignment targets.
+                Some(op) if op.is_assign_op() => !is_first_operand,
+                _ => true,
+            }
+        }
+        _ => true,
+    }
+}
+
+/// The selection without leading and trailing whitespace.
+fn trim_selection(text: &str, selection: TextRange) -> Option<TextRange> {
+    let selected = &text[selection];
+    let trimmed = selected.trim();
+
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let leading = selected.len() - selected.trim_start().len();
+    let start = selection.start() + TextSize::of(&selected[..leading]);
+
+    Some(TextRange::at(start, TextSize::of(trimmed)))
+}
+
+/// A variable name that does not appear as an identifier
+/// anywhere in the document.
+fn unused_name(root: &SyntaxNode) -> String {
+    let idents = root
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == IDENT)
+        .map(|token| token.text().to_string())
+        .collect::<Vec<_>>();
+
+    let mut name = String::from(DEFAULT_NAME);
+    let mut idx = 1;
+
+    while idents.contains(&name) {
+        name = format!("{DEFAULT_NAME}{idx}");
+        idx += 1;
+    }
+
+    name
+}
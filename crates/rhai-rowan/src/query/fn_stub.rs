@@ -0,0 +1,97 @@
+//! Generating functions for calls of functions that do not exist.
+
+use rowan::{NodeOrToken, TextRange, TextSize};
+use serde::{Deserialize, Serialize};
+
+use super::util::{is_first_operand, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// Where a generated function is placed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FnStubPlacement {
+    /// After the top-level statement that contains the call.
+    #[default]
+    AfterStatement,
+    /// At the end of the document.
+    EndOfFile,
+}
+
+/// An edit that adds an empty function with the name
+/// and the number of parameters of a call.
+#[derive(Debug, Clone)]
+pub struct FnStub {
+    /// The name of the generated function.
+    pub name: String,
+    /// Non-overlapping edits in the order of their ranges.
+    pub edits: Vec<(TextRange, String)>,
+}
+
+impl FnStub {
+    /// Add a function for the call with the callee name at `callee`,
+    /// the parameters are named after their positions (`arg0`, `arg1`, ...).
+    ///
+    /// Returns `None` if the range is not the name of a called function,
+    /// method calls and calls of paths are not supported.
+    #[must_use]
+    pub fn new(root: &SyntaxNode, callee: TextRange, placement: FnStubPlacement) -> Option<Self> {
+        let ident = match root.covering_element(callee) {
+            NodeOrToken::Token(token) if token.kind() == IDENT => token,
+            _ => return None,
+        };
+
+        let expr = ident
+            .parent()
+            .filter(|node| node.kind() == EXPR_IDENT)?
+            .parent()
+            .filter(|node| node.kind() == EXPR)?;
+        let call = expr
+            .parent()
+            .filter(|call| call.kind() == EXPR_CALL && is_first_operand(&expr, call))?;
+
+        let arg_count = call
+            .children()
+            .find(|node| node.kind() == ARG_LIST)
+            .map_or(0, |args| {
+                args.children().filter(|node| node.kind() == EXPR).count()
+            });
+
+        let params = (0..arg_count)
+            .map(|idx| format!("arg{idx}"))
+            .collect::<Vec<_>>();
+
+        let offset = match placement {
+            FnStubPlacement::AfterStatement => {
+                let stmt = call.ancestors().filter(|node| node.kind() == STMT).last()?;
+                trimmed_range(&stmt)?.end()
+            }
+            FnStubPlacement::EndOfFile => trimmed_range(root).map_or(0.into(), TextRange::end),
+        };
+
+        let separator = if offset == TextSize::from(0) {
+            ""
+        } else {
+            "\n\n"
+        };
+
+        Some(Self {
+            name: ident.text().to_string(),
+            edits: vec![(
+                TextRange::empty(offset),
+                format!("{separator}fn {}({}) {{}}", ident.text(), params.join(", ")),
+            )],
+        })
+    }
+
+    /// Apply the edits to the text of the document.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        for (range, replacement) in self.edits.iter().rev() {
+            text.replace_range(std::ops::Range::<usize>::from(*range), replacement);
+        }
+
+        text
+    }
+}
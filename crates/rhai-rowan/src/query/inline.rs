@@ -0,0 +1,266 @@
+//! Inlining variables into their references.
+
+use rowan::{NodeOrToken, TextRange, TextSize};
+
+use super::util::{is_assignment_target, is_first_operand, op_kind, removed_range, trimmed_range};
+use crate::syntax::{SyntaxKind, SyntaxKind::*, SyntaxNode};
+
+/// Binding powers of initializers that are not binary expressions,
+/// they need parentheses whenever they are an operand.
+const LOWEST_BP: (u8, u8) = (0, 0);
+
+/// Unary operators bind tighter than anything but postfix
+/// operators and field access, and never need parentheses
+/// on the right-hand side.
+const UNARY_BP: (u8, u8) = (u8::MAX, 252);
+
+/// Binding power of postfix operators (indexing and calls).
+const POSTFIX_BP: u8 = 253;
+
+/// Reasons why a variable cannot be inlined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineError {
+    /// The range is not the name of a `let` or `const` declaration.
+    NotAVariable,
+    /// The declaration has no initializer.
+    NoValue,
+    /// The variable is exported and might be used by other modules.
+    Exported,
+    /// Evaluating the initializer might have side effects,
+    /// e.g. it contains function calls or assignments.
+    SideEffects,
+    /// The variable is assigned to at the given reference.
+    AssignedReference(TextRange),
+    /// The reference at the given range is not an expression.
+    InvalidReference(TextRange),
+    /// A variable used in the initializer is reassigned or shadowed
+    /// at the given range before the last reference.
+    OperandChanged(TextRange),
+}
+
+/// Edits that replace every reference of a variable with its initializer
+/// and remove the declaration.
+#[derive(Debug, Clone)]
+pub struct InlineVariable {
+    /// Non-overlapping edits in the order of their ranges.
+    pub edits: Vec<(TextRange, String)>,
+}
+
+impl InlineVariable {
+    /// Inline the variable declared with the name at `decl`
+    /// into all of the given `references`.
+    ///
+    /// References are resolved by the caller, all of them must
+    /// be in the same document as the declaration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variable cannot be inlined
+    /// without changing the meaning of the code.
+    pub fn new(
+        root: &SyntaxNode,
+        decl: TextRange,
+        references: &[TextRange],
+    ) -> Result<Self, InlineError> {
+        let decl_expr = match root.covering_element(decl) {
+            NodeOrToken::Node(node) => Some(node),
+            NodeOrToken::Token(token) if token.kind() == IDENT => token.parent(),
+            NodeOrToken::Token(_) => None,
+        }
+        .filter(|node| matches!(node.kind(), EXPR_LET | EXPR_CONST))
+        .ok_or(InlineError::NotAVariable)?;
+
+        if decl_expr.ancestors().any(|node| node.kind() == EXPR_EXPORT) {
+            return Err(InlineError::Exported);
+        }
+
+        let value = decl_expr
+            .children()
+            .find(|node| node.kind() == EXPR)
+            .ok_or(InlineError::NoValue)?;
+
+        if has_side_effects(&value) {
+            return Err(InlineError::SideEffects);
+        }
+
+        let stmt = decl_expr
+            .ancestors()
+            .find(|node| node.kind() == STMT)
+            .ok_or(InlineError::NotAVariable)?;
+
+        let text = root.text().to_string();
+        let value_range = trimmed_range(&value).ok_or(InlineError::NoValue)?;
+        let value_text = &text[value_range];
+        let value_bp = binding_power(&value);
+
+        let mut edits = Vec::with_capacity(references.len() + 1);
+
+        for &reference in references {
+            let site = reference_site(root, reference)?;
+
+            if is_assignment_target(&site) {
+                return Err(InlineError::AssignedReference(reference));
+            }
+
+            let site_range = trimmed_range(&site).unwrap_or(reference);
+
+            let replacement = if value_bp.is_some_and(|bp| needs_parens(&site, bp)) {
+                format!("({value_text})")
+            } else {
+                value_text.to_string()
+            };
+
+            edits.push((site_range, replacement));
+        }
+
+        let stmt_range = trimmed_range(&stmt).ok_or(InlineError::NotAVariable)?;
+
+        if let Some(last_reference) = references.iter().map(|r| r.end()).max() {
+            check_operands(root, &value, stmt_range.end(), last_reference)?;
+        }
+
+        edits.push((removed_range(&text, stmt_range), String::new()));
+        edits.sort_by_key(|(range, _)| range.start());
+
+        Ok(Self { edits })
+    }
+
+    /// Apply the edits to the text of the document.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        for (range, replacement) in self.edits.iter().rev() {
+            text.replace_range(std::ops::Range::<usize>::from(*range), replacement);
+        }
+
+        text
+    }
+}
+
+/// The expression node of the reference.
+fn reference_site(root: &SyntaxNode, reference: TextRange) -> Result<SyntaxNode, InlineError> {
+    let ident = match root.covering_element(reference) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token
+            .parent()
+            .ok_or(InlineError::InvalidReference(reference))?,
+    };
+
+    if ident.kind() != EXPR_IDENT {
+        return Err(InlineError::InvalidReference(reference));
+    }
+
+    ident
+        .parent()
+        .filter(|node| node.kind() == EXPR)
+        .ok_or(InlineError::InvalidReference(reference))
+}
+
+/// Whether evaluating the expression might do anything
+/// other than produce a value.
+fn has_side_effects(expr: &SyntaxNode) -> bool {
+    expr.descendants().any(|node| match node.kind() {
+        EXPR_BINARY => {
+            op_kind(&node).is_none_or(|op| op.is_assign_op() || op.infix_binding_power().is_none())
+        }
+        EXPR_CALL | EXPR_LET | EXPR_CONST | EXPR_FN | EXPR_IMPORT | EXPR_EXPORT | EXPR_RETURN
+        | EXPR_BREAK | EXPR_CONTINUE | EXPR_THROW | EXPR_LOOP | EXPR_FOR | EXPR_WHILE
+        | EXPR_TRY => true,
+        _ => false,
+    })
+}
+
+/// The binding powers of the outermost operator of the initializer,
+/// `None` if it never needs parentheses.
+fn binding_power(value: &SyntaxNode) -> Option<(u8, u8)> {
+    let inner = value.first_child()?;
+
+    match inner.kind() {
+        EXPR_BINARY => Some(
+            op_kind(&inner)
+                .and_then(SyntaxKind::infix_binding_power)
+                .unwrap_or(LOWEST_BP),
+        ),
+        EXPR_UNARY => Some(UNARY_BP),
+        EXPR_CLOSURE | EXPR_IF | EXPR_SWITCH | EXPR_BLOCK => Some(LOWEST_BP),
+        _ => None,
+    }
+}
+
+/// Whether an initializer with the given binding powers has to be
+/// wrapped in parentheses to replace the expression at `site`.
+fn needs_parens(site: &SyntaxNode, value_bp: (u8, u8)) -> bool {
+    let Some(parent) = site.parent() else {
+        return false;
+    };
+
+    let is_lhs = is_first_operand(site, &parent);
+
+    match parent.kind() {
+        // An operator that follows the value would take
+        // its right-hand side as the operand.
+        EXPR_INDEX | EXPR_CALL if is_lhs => POSTFIX_BP >= value_bp.1,
+        EXPR_BINARY => {
+            let Some((l_bp, r_bp)) = op_kind(&parent).and_then(SyntaxKind::infix_binding_power)
+            else {
+                return true;
+            };
+
+            if is_lhs {
+                l_bp >= value_bp.1
+            } else {
+                value_bp.0 < r_bp
+            }
+        }
+        EXPR_UNARY => value_bp.0 < UNARY_BP.1,
+        _ => false,
+    }
+}
+
+/// Make sure that the variables used in the initializer refer to
+/// the same values between the declaration and the last reference.
+fn check_operands(
+    root: &SyntaxNode,
+    value: &SyntaxNode,
+    start: TextSize,
+    end: TextSize,
+) -> Result<(), InlineError> {
+    let operands = value
+        .descendants()
+        .filter(|node| node.kind() == EXPR_IDENT)
+        .filter_map(|node| node.first_token())
+        .map(|token| token.text().to_string())
+        .collect::<Vec<_>>();
+
+    if operands.is_empty() {
+        return Ok(());
+    }
+
+    let range = TextRange::new(start, end);
+
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == IDENT && range.contains_range(token.text_range()))
+        .filter(|token| operands.iter().any(|name| name == token.text()))
+    {
+        let Some(parent) = token.parent() else {
+            continue;
+        };
+
+        let changed = match parent.kind() {
+            EXPR_LET | EXPR_CONST | PARAM | PAT_IDENT | PAT_TUPLE | PAT_ARRAY => true,
+            EXPR_IDENT => parent
+                .parent()
+                .is_some_and(|expr| is_assignment_target(&expr)),
+            _ => false,
+        };
+
+        if changed {
+            return Err(InlineError::OperandChanged(token.text_range()));
+        }
+    }
+
+    Ok(())
+}
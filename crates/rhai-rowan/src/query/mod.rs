@@ -10,6 +10,15 @@ use crate::{
 
 use self::util::SyntaxExt;
 
+pub mod convert_fn;
+pub mod extract;
+pub mod fn_stub;
+pub mod inline;
+pub mod organize_imports;
+pub mod simplify;
+pub mod surround;
+pub mod switch_arms;
+
 mod util;
 
 #[derive(Debug, Default)]
@@ -166,6 +175,49 @@ impl Query {
             .unwrap_or_else(|| p.segments().count())
     }
 
+    /// The `switch` expression if the cursor is where
+    /// the pattern of an arm is expected or being typed.
+    #[must_use]
+    pub fn switch_arm_pattern(&self) -> Option<SyntaxNode> {
+        let before = &self.before.as_ref()?.syntax;
+        let parent = before.parent()?;
+
+        let arm_list = if parent.kind() == SWITCH_ARM_LIST {
+            if !matches!(before.kind(), PUNCT_BRACE_START | PUNCT_COMMA | WHITESPACE) {
+                return None;
+            }
+
+            parent
+        } else {
+            if before.kind() == WHITESPACE {
+                return None;
+            }
+
+            // The pattern is the first node of the arm.
+            let pattern = before
+                .parent_ancestors()
+                .take_while(|node| node.kind() != SWITCH_ARM)
+                .last()?;
+            let arm = pattern.parent()?;
+
+            if arm.kind() != SWITCH_ARM || arm.first_child().as_ref() != Some(&pattern) {
+                return None;
+            }
+
+            if arm
+                .children_with_tokens()
+                .take_while(|child| child.as_node() != Some(&pattern))
+                .any(|child| child.kind() == PUNCT_ARROW_FAT)
+            {
+                return None;
+            }
+
+            arm.parent()?
+        };
+
+        arm_list.parent().filter(|node| node.kind() == EXPR_SWITCH)
+    }
+
     #[must_use]
     pub fn is_in_comment(&self) -> bool {
         match (&self.before, &self.after) {
@@ -0,0 +1,196 @@
+//! Sorting and cleaning up the imports of a module.
+
+use rowan::{NodeOrToken, TextRange, TextSize};
+
+use super::util::{removed_range, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// Edits that move the top-level imports of a module into a single
+/// sorted block where the first import is.
+///
+/// Comments on the lines right above an import and after it
+/// on the same line are moved along with the import.
+#[derive(Debug, Clone)]
+pub struct OrganizeImports {
+    /// Non-overlapping edits in the order of their ranges.
+    pub edits: Vec<(TextRange, String)>,
+}
+
+/// A top-level import with its comments.
+struct Import {
+    /// The import path as written, used for sorting.
+    path: String,
+    alias: Option<(String, TextRange)>,
+    /// The range of the import and its comments.
+    range: TextRange,
+}
+
+impl OrganizeImports {
+    /// Sort the imports by their paths and then by their aliases,
+    /// and remove duplicates and the imports with aliases at
+    /// one of the `unused` ranges.
+    ///
+    /// Which imports are used is decided by the caller, imports
+    /// without aliases are always kept.
+    ///
+    /// Returns `None` if the imports are already organized.
+    #[must_use]
+    pub fn new(root: &SyntaxNode, unused: &[TextRange]) -> Option<Self> {
+        let text = root.text().to_string();
+
+        let imports = root
+            .children()
+            .filter(|node| node.kind() == STMT)
+            .filter_map(|stmt| import(&stmt))
+            .collect::<Vec<_>>();
+
+        let first = imports.first()?.range;
+
+        let mut organized: Vec<&Import> = Vec::with_capacity(imports.len());
+
+        for import in &imports {
+            let is_unused = import
+                .alias
+                .as_ref()
+                .is_some_and(|(_, range)| unused.contains(range));
+
+            let is_duplicate = organized.iter().any(|other| {
+                other.path == import.path
+                    && other.alias.as_ref().map(|(alias, _)| alias)
+                        == import.alias.as_ref().map(|(alias, _)| alias)
+            });
+
+            if !is_unused && !is_duplicate {
+                organized.push(import);
+            }
+        }
+
+        organized.sort_by(|a, b| {
+            let alias = |import: &Import| import.alias.as_ref().map(|(alias, _)| alias.clone());
+            (&a.path, alias(a)).cmp(&(&b.path, alias(b)))
+        });
+
+        let block = organized
+            .iter()
+            .map(|import| &text[import.range])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut edits = vec![(first, block)];
+
+        edits.extend(
+            imports
+                .iter()
+                .skip(1)
+                .map(|import| (removed_range(&text, import.range), String::new())),
+        );
+
+        let this = Self { edits };
+
+        (this.apply(&text) != text).then_some(this)
+    }
+
+    /// Apply the edits to the text of the document.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        for (range, replacement) in self.edits.iter().rev() {
+            text.replace_range(std::ops::Range::<usize>::from(*range), replacement);
+        }
+
+        text
+    }
+}
+
+/// The import of a top-level statement.
+fn import(stmt: &SyntaxNode) -> Option<Import> {
+    let import = stmt
+        .children()
+        .find(|node| node.kind() == ITEM)?
+        .children()
+        .find(|node| node.kind() == EXPR)?
+        .first_child()
+        .filter(|node| node.kind() == EXPR_IMPORT)?;
+
+    let path = import
+        .children()
+        .find(|node| node.kind() == EXPR)
+        .map(|expr| expr.text().to_string().trim().to_string())
+        .unwrap_or_default();
+
+    let alias = import
+        .children_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .skip_while(|token| token.kind() != KW_AS)
+        .find(|token| token.kind() == IDENT)
+        .map(|token| (token.text().to_string(), token.text_range()));
+
+    let stmt_range = trimmed_range(stmt)?;
+
+    Some(Import {
+        path,
+        alias,
+        range: TextRange::new(leading_comments_start(stmt), trailing_comment_end(stmt))
+            .cover(stmt_range),
+    })
+}
+
+/// The start of the comments on the lines right above the statement,
+/// documentation comments of the module are not included.
+fn leading_comments_start(stmt: &SyntaxNode) -> TextSize {
+    let mut start = stmt.text_range().start();
+    let mut candidate = None;
+
+    for element in stmt.siblings_with_tokens(rowan::Direction::Prev).skip(1) {
+        let text = element.to_string();
+
+        match element.kind() {
+            WHITESPACE if text.matches('\n').count() > 1 => break,
+            WHITESPACE => {
+                // Only comments on their own lines belong to the statement.
+                if let Some(comment_start) = candidate.take() {
+                    if !text.contains('\n') {
+                        break;
+                    }
+                    start = comment_start;
+                }
+            }
+            COMMENT_LINE | COMMENT_BLOCK if !is_doc_comment(&text) && candidate.is_none() => {
+                candidate = Some(element.text_range().start());
+            }
+            _ => {
+                candidate = None;
+                break;
+            }
+        }
+    }
+
+    // A comment at the start of the document.
+    candidate.unwrap_or(start)
+}
+
+/// The end of a comment after the statement on the same line.
+fn trailing_comment_end(stmt: &SyntaxNode) -> TextSize {
+    let end = stmt.text_range().end();
+
+    let mut siblings = stmt.siblings_with_tokens(rowan::Direction::Next).skip(1);
+
+    match (siblings.next(), siblings.next()) {
+        (Some(ws), Some(comment))
+            if ws.kind() == WHITESPACE
+                && !ws.to_string().contains('\n')
+                && comment.kind() == COMMENT_LINE =>
+        {
+            comment.text_range().end()
+        }
+        (Some(comment), _) if comment.kind() == COMMENT_LINE => comment.text_range().end(),
+        _ => end,
+    }
+}
+
+fn is_doc_comment(text: &str) -> bool {
+    ["///", "//!", "/**", "/*!"]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+}
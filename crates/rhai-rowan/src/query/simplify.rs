@@ -0,0 +1,123 @@
+//! Simplifying conditionals and loops with constant conditions.
+
+use rowan::{NodeOrToken, TextRange};
+
+use super::util::{removed_range, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// Edits that remove the code that is never evaluated
+/// because of a condition that is always `true` or `false`.
+#[derive(Debug, Clone)]
+pub struct SimplifyCondition {
+    /// Non-overlapping edits in the order of their ranges.
+    pub edits: Vec<(TextRange, String)>,
+}
+
+impl SimplifyCondition {
+    /// Simplify the `if` or `while` expression whose condition
+    /// is at `condition` and always evaluates to `value`.
+    ///
+    /// - `if true { a } else { b }` becomes `{ a }`.
+    /// - `if false { a } else { b }` becomes `{ b }`, without an
+    ///   `else` branch the whole expression is removed.
+    /// - `while false { a }` is removed.
+    /// - `while true { a }` becomes `loop { a }`.
+    ///
+    /// Returns `None` if there is no such expression at the range.
+    #[must_use]
+    pub fn new(root: &SyntaxNode, condition: TextRange, value: bool) -> Option<Self> {
+        let covering = match root.covering_element(condition) {
+            NodeOrToken::Node(node) => node,
+            NodeOrToken::Token(token) => token.parent()?,
+        };
+
+        let expr = covering
+            .ancestors()
+            .find(|node| matches!(node.kind(), EXPR_IF | EXPR_WHILE))?;
+
+        let condition_expr = expr.children().find(|node| node.kind() == EXPR)?;
+
+        if !trimmed_range(&condition_expr)?.contains_range(condition) {
+            return None;
+        }
+
+        let text = root.text().to_string();
+        let expr_range = trimmed_range(&expr)?;
+
+        let edit = match (expr.kind(), value) {
+            (EXPR_WHILE, true) => (
+                TextRange::new(expr_range.start(), trimmed_range(&condition_expr)?.end()),
+                String::from("loop"),
+            ),
+            (EXPR_WHILE, false) => remove(&text, &expr)?,
+            (_, true) => {
+                let then_block = expr.children().find(|node| node.kind() == EXPR_BLOCK)?;
+                (expr_range, text[trimmed_range(&then_block)?].to_string())
+            }
+            (_, false) => match else_branch(&expr) {
+                Some(else_branch) => (expr_range, text[trimmed_range(&else_branch)?].to_string()),
+                None => remove(&text, &expr)?,
+            },
+        };
+
+        Some(Self { edits: vec![edit] })
+    }
+
+    /// Apply the edits to the text of the document.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        for (range, replacement) in self.edits.iter().rev() {
+            text.replace_range(std::ops::Range::<usize>::from(*range), replacement);
+        }
+
+        text
+    }
+}
+
+/// The block or `if` expression after the `else` keyword.
+fn else_branch(if_expr: &SyntaxNode) -> Option<SyntaxNode> {
+    if_expr
+        .children()
+        .filter(|node| matches!(node.kind(), EXPR_BLOCK | EXPR_IF))
+        .nth(1)
+}
+
+/// The edit that removes an expression that has no effect.
+///
+/// An `else if` branch is removed along with the `else` keyword,
+/// a statement is removed entirely, any other expression
+/// is replaced with `()`.
+fn remove(text: &str, expr: &SyntaxNode) -> Option<(TextRange, String)> {
+    let expr_range = trimmed_range(expr)?;
+    let parent = expr.parent()?;
+
+    if parent.kind() == EXPR_IF {
+        let else_keyword = parent
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find(|token| token.kind() == KW_ELSE)?;
+
+        // Up to the end of the previous branch.
+        let start = else_keyword
+            .prev_sibling_or_token()
+            .filter(|prev| prev.kind() == WHITESPACE)
+            .map_or(else_keyword.text_range().start(), |ws| {
+                ws.text_range().start()
+            });
+
+        return Some((TextRange::new(start, expr_range.end()), String::new()));
+    }
+
+    let stmt = parent
+        .parent()
+        .filter(|item| item.kind() == ITEM)
+        .and_then(|item| item.parent())
+        .filter(|stmt| stmt.kind() == STMT);
+
+    match stmt {
+        Some(stmt) => Some((removed_range(text, trimmed_range(&stmt)?), String::new())),
+        None => Some((expr_range, String::from("()"))),
+    }
+}
@@ -0,0 +1,121 @@
+//! Wrapping statements in blocks, conditionals and loops.
+
+use rowan::{TextRange, TextSize};
+
+use super::util::{escape_snippet, line_indent, statement_at, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// The construct to wrap statements in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurroundKind {
+    /// `{ ... }`
+    Block,
+    /// `if condition { ... }`
+    If,
+    /// `while condition { ... }`
+    While,
+}
+
+impl SurroundKind {
+    pub const ALL: &'static [SurroundKind] = &[Self::Block, Self::If, Self::While];
+
+    #[must_use]
+    pub fn keyword(self) -> Option<&'static str> {
+        match self {
+            SurroundKind::Block => None,
+            SurroundKind::If => Some("if"),
+            SurroundKind::While => Some("while"),
+        }
+    }
+}
+
+/// Statements covered by a selection that can be wrapped
+/// with [`Surround::text`] or [`Surround::snippet`].
+#[derive(Debug, Clone)]
+pub struct Surround {
+    /// The range of the statements without
+    /// surrounding whitespace, this range should be replaced.
+    pub range: TextRange,
+    /// Indentation of the line of the first statement.
+    indent: String,
+    /// The statements, already indented by one level.
+    body: String,
+}
+
+impl Surround {
+    /// Wrap the consecutive statements with the given ranges,
+    /// e.g. the statements of a selection from `Hir::inner_statements_in_range`.
+    ///
+    /// Returns `None` if there are no statements, or if
+    /// the first and last statements are not in the same block.
+    #[must_use]
+    pub fn new(root: &SyntaxNode, statements: &[TextRange], indent_string: &str) -> Option<Self> {
+        let first = statement_at(root, *statements.first()?)?;
+        let last = statement_at(root, *statements.last()?)?;
+
+        if first.parent() != last.parent() {
+            return None;
+        }
+
+        let range = trimmed_range(&first)?.cover(trimmed_range(&last)?);
+
+        let text = root.text().to_string();
+        let indent = line_indent(&text, range.start());
+
+        let mut body = String::new();
+        let mut offset = range.start();
+
+        for (idx, line) in text[range].split('\n').enumerate() {
+            if idx != 0 {
+                body.push('\n');
+            }
+
+            if idx == 0 {
+                body += &indent;
+                body += indent_string;
+            } else if !line.trim().is_empty() && !is_inside_string(root, offset) {
+                body += indent_string;
+            }
+
+            body += line;
+            offset += TextSize::of(line) + TextSize::of('\n');
+        }
+
+        Some(Self {
+            range,
+            indent,
+            body,
+        })
+    }
+
+    /// The wrapped statements as plain text.
+    ///
+    /// The condition of `if` and `while` is left as a `condition` placeholder identifier.
+    #[must_use]
+    pub fn text(&self, kind: SurroundKind) -> String {
+        self.wrap(kind, "condition", "", &self.body)
+    }
+
+    /// The wrapped statements as an LSP snippet with a tab stop
+    /// for the condition of `if` and `while`.
+    #[must_use]
+    pub fn snippet(&self, kind: SurroundKind) -> String {
+        self.wrap(kind, "${1:condition}", "$0", &escape_snippet(&self.body))
+    }
+
+    fn wrap(&self, kind: SurroundKind, condition: &str, end: &str, body: &str) -> String {
+        let head = match kind.keyword() {
+            Some(keyword) => format!("{keyword} {condition} {{"),
+            None => String::from("{"),
+        };
+
+        format!("{head}\n{body}\n{indent}}}{end}", indent = self.indent)
+    }
+}
+
+/// Whether the offset is inside (but not at the start of) a string literal,
+/// lines starting there must not be reindented.
+fn is_inside_string(root: &SyntaxNode, offset: TextSize) -> bool {
+    root.token_at_offset(offset)
+        .any(|token| token.kind() == LIT_STR && token.text_range().start() < offset)
+}
@@ -0,0 +1,109 @@
+//! Adding arms to `switch` expressions.
+
+use rowan::{NodeOrToken, TextRange};
+
+use super::util::{line_indent, trimmed_range};
+use crate::syntax::{SyntaxKind::*, SyntaxNode};
+
+/// An edit that adds arms after the last arm of a `switch` expression.
+#[derive(Debug, Clone)]
+pub struct AddSwitchArms {
+    /// Non-overlapping edits in the order of their ranges.
+    pub edits: Vec<(TextRange, String)>,
+}
+
+impl AddSwitchArms {
+    /// Add an arm for each pattern to the `switch` expression
+    /// at the range, the arms evaluate to `()`.
+    ///
+    /// If the arms are on separate lines, the new arms are added on
+    /// new lines with the indentation of the existing arms.
+    ///
+    /// Returns `None` if there is no such expression at the range.
+    #[must_use]
+    pub fn new(
+        root: &SyntaxNode,
+        switch: TextRange,
+        patterns: &[String],
+        indent_string: &str,
+    ) -> Option<Self> {
+        let switch_expr = match root.covering_element(switch) {
+            NodeOrToken::Node(node) => node,
+            NodeOrToken::Token(token) => token.parent()?,
+        }
+        .ancestors()
+        .find(|node| node.kind() == EXPR_SWITCH)?;
+
+        let arm_list = switch_expr
+            .children()
+            .find(|node| node.kind() == SWITCH_ARM_LIST)?;
+        let brace_start = arm_list
+            .children_with_tokens()
+            .find(|child| child.kind() == PUNCT_BRACE_START)?;
+
+        let text = root.text().to_string();
+
+        let first_arm = arm_list.children().find(|node| node.kind() == SWITCH_ARM);
+        let last_arm = arm_list
+            .children()
+            .filter(|node| node.kind() == SWITCH_ARM)
+            .last();
+
+        let (offset, needs_comma) = match &last_arm {
+            Some(last_arm) => {
+                let comma = last_arm
+                    .siblings_with_tokens(rowan::Direction::Next)
+                    .skip(1)
+                    .find(|child| child.kind() != WHITESPACE)
+                    .filter(|child| child.kind() == PUNCT_COMMA);
+
+                match comma {
+                    Some(comma) => (comma.text_range().end(), false),
+                    None => (trimmed_range(last_arm)?.end(), true),
+                }
+            }
+            None => (brace_start.text_range().end(), false),
+        };
+
+        let multiline = text[arm_list.text_range()].contains('\n');
+
+        let mut new_text = String::new();
+
+        if needs_comma {
+            new_text += ",";
+        }
+
+        let separator = if multiline {
+            let indent = match &first_arm {
+                Some(arm) => line_indent(&text, trimmed_range(arm)?.start()),
+                None => line_indent(&text, switch_expr.text_range().start()) + indent_string,
+            };
+
+            format!("\n{indent}")
+        } else {
+            String::from(" ")
+        };
+
+        for pattern in patterns {
+            new_text += &separator;
+            new_text += pattern;
+            new_text += " => (),";
+        }
+
+        Some(Self {
+            edits: vec![(TextRange::empty(offset), new_text)],
+        })
+    }
+
+    /// Apply the edits to the text of the document.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        for (range, replacement) in self.edits.iter().rev() {
+            text.replace_range(std::ops::Range::<usize>::from(*range), replacement);
+        }
+
+        text
+    }
+}
@@ -1,3 +1,5 @@
+use rowan::{NodeOrToken, TextRange, TextSize};
+
 use crate::{
     parser::{Operator, Parser},
     util::{src_cursor_offset, src_cursor_offsets},
@@ -331,3 +333,802 @@ fn test_complete_ref_fails_in_decl() {
         assert!(!q.can_complete_ref(), "test failed for index {idx}",);
     }
 }
+
+/// The ranges between pairs of `$$` markers.
+fn marked_ranges(offsets: impl Iterator<Item = TextSize>) -> Vec<TextRange> {
+    let offsets = offsets.collect::<Vec<_>>();
+    offsets
+        .chunks(2)
+        .map(|pair| TextRange::new(pair[0], pair[1]))
+        .collect()
+}
+
+/// Applies the surround edit for the statements between pairs of `$$` markers.
+fn surround(src: &str, kind: surround::SurroundKind) -> String {
+    let (offsets, src) = src_cursor_offsets(src);
+
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+    let surround = surround::Surround::new(&syntax, &marked_ranges(offsets), "    ").unwrap();
+
+    let mut new_src = src.clone();
+    new_src.replace_range(
+        std::ops::Range::<usize>::from(surround.range),
+        &surround.text(kind),
+    );
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    new_src
+}
+
+#[test]
+fn test_surround_statements() {
+    let src = r"
+let a = 1;
+$$let b = a + 2$$;
+$$print(b)$$;
+let c = 3;
+";
+
+    assert_eq!(
+        surround(src, surround::SurroundKind::If),
+        r"
+let a = 1;
+if condition {
+    let b = a + 2;
+    print(b);
+}
+let c = 3;
+"
+    );
+}
+
+#[test]
+fn test_surround_nested() {
+    let src = r"
+fn foo() {
+    $$let a = #{
+        b: 2
+    }$$;
+    a
+}
+";
+
+    assert_eq!(
+        surround(src, surround::SurroundKind::While),
+        r"
+fn foo() {
+    while condition {
+        let a = #{
+            b: 2
+        };
+    }
+    a
+}
+"
+    );
+}
+
+#[test]
+fn test_surround_keeps_strings() {
+    let src = r"
+$$let a = `first
+second ${1}`$$;
+";
+
+    assert_eq!(
+        surround(src, surround::SurroundKind::Block),
+        r"
+{
+    let a = `first
+second ${1}`;
+}
+"
+    );
+}
+
+#[test]
+fn test_surround_snippet() {
+    let (offsets, src) = src_cursor_offsets("$$let a = `${b}`$$;");
+
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+    let surround = surround::Surround::new(&syntax, &marked_ranges(offsets), "  ").unwrap();
+
+    assert_eq!(
+        surround.snippet(surround::SurroundKind::If),
+        "if ${1:condition} {\n  let a = `\\${b\\}`;\n}$0"
+    );
+}
+
+/// Applies the extraction of the selection between the inner
+/// `$$` markers from the statement between the outer ones.
+fn extract_variable(src: &str) -> Option<String> {
+    let (offsets, src) = src_cursor_offsets(src);
+    let [statement_start, start, end, statement_end] = offsets.collect::<Vec<_>>()[..] else {
+        panic!("expected four markers");
+    };
+
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+    let extract = extract::ExtractVariable::new(
+        &syntax,
+        TextRange::new(start, end),
+        TextRange::new(statement_start, statement_end),
+    )?;
+
+    let mut new_src = src.clone();
+    new_src.replace_range(
+        std::ops::Range::<usize>::from(extract.range()),
+        &extract.text(),
+    );
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    Some(new_src)
+}
+
+#[test]
+fn test_extract_call_argument() {
+    let src = r"
+fn foo(value) {
+    $$bar(1, $$value * 2$$)$$;
+}
+";
+
+    assert_eq!(
+        extract_variable(src).unwrap(),
+        r"
+fn foo(value) {
+    let value1 = value * 2;
+    bar(1, value1);
+}
+"
+    );
+}
+
+#[test]
+fn test_extract_binary_operand() {
+    let src = r"
+let a = 1;
+$$let b = a + $$(a - 1) $$* 3$$;
+";
+
+    assert_eq!(
+        extract_variable(src).unwrap(),
+        r"
+let a = 1;
+let value = (a - 1);
+let b = a + value * 3;
+"
+    );
+}
+
+#[test]
+fn test_extract_partial_operand() {
+    let src = r"
+$$let b = 1 + 2$$ + $$3$$;
+";
+
+    assert_eq!(
+        extract_variable(src).unwrap(),
+        r"
+let value = 1 + 2 + 3;
+let b = value;
+"
+    );
+}
+
+#[test]
+fn test_extract_left_operand() {
+    let src = r"
+$$let x = $$a > 1$$ && b$$;
+";
+
+    assert_eq!(
+        extract_variable(src).unwrap(),
+        r"
+let value = a > 1;
+let x = value && b;
+"
+    );
+}
+
+#[test]
+fn test_extract_invalid() {
+    assert!(extract_variable("let f = |x| $$$$x + 1$$$$;").is_none());
+    assert!(extract_variable("let a = #{}; $$a.$$b$$ = 2$$;").is_none());
+    assert!(extract_variable("$$$$foo$$(1)$$;").is_none());
+    assert!(extract_variable("$$while $$a > 1$$ { a -= 1; }$$").is_none());
+    assert!(extract_variable("$$let x = a && $$b > 1$$$$;").is_none());
+    assert!(extract_variable("$$let x = a || $$b > 1$$$$;").is_none());
+    assert!(extract_variable("$$let x = a ?? $$b + 1$$$$;").is_none());
+    assert!(extract_variable("$$let x = a?.$$b$$$$;").is_none());
+    assert!(extract_variable("$$if a {} else if $$b > 1$$ {}$$").is_none());
+}
+
+#[test]
+fn test_extract_snippet() {
+    let (offsets, src) = src_cursor_offsets("let a = $$`${1}`$$;");
+    let [start, end] = offsets.collect::<Vec<_>>()[..] else {
+        panic!("expected two markers");
+    };
+    let selection = TextRange::new(start, end);
+
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+    let statement = TextRange::up_to(TextSize::of("let a = `${1}`"));
+    let extract = extract::ExtractVariable::new(&syntax, selection, statement).unwrap();
+
+    assert_eq!(
+        extract.snippet(),
+        "let ${1:value} = `\\${1\\}`;\nlet a = ${1:value}"
+    );
+}
+
+/// Inlines the first declaration of `name`, every other identifier
+/// with the same name is treated as its reference.
+fn inline_variable(src: &str, name: &str) -> Result<String, inline::InlineError> {
+    let syntax = Parser::new(src).parse_script().into_syntax();
+
+    let mut idents = syntax
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == IDENT && token.text() == name);
+
+    let decl = idents.next().unwrap().text_range();
+    let references = idents.map(|token| token.text_range()).collect::<Vec<_>>();
+
+    let inline = inline::InlineVariable::new(&syntax, decl, &references)?;
+    let new_src = inline.apply(src);
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    Ok(new_src)
+}
+
+#[test]
+fn test_inline_lower_precedence() {
+    let src = r"
+let x = a + b;
+let y = x * 2;
+let z = 2 - x;
+";
+
+    assert_eq!(
+        inline_variable(src, "x").unwrap(),
+        r"
+let y = (a + b) * 2;
+let z = 2 - (a + b);
+"
+    );
+}
+
+#[test]
+fn test_inline_higher_precedence() {
+    let src = r"
+let x = a * b;
+let y = x + 2;
+let z = 2 - x;
+";
+
+    assert_eq!(
+        inline_variable(src, "x").unwrap(),
+        r"
+let y = a * b + 2;
+let z = 2 - a * b;
+"
+    );
+}
+
+#[test]
+fn test_inline_associativity() {
+    assert_eq!(
+        inline_variable("let x = a - b; x - c;", "x").unwrap(),
+        "a - b - c;"
+    );
+    assert_eq!(
+        inline_variable("let x = a - b; c - x;", "x").unwrap(),
+        "c - (a - b);"
+    );
+    assert_eq!(
+        inline_variable("let x = a ** b; c ** x;", "x").unwrap(),
+        "c ** a ** b;"
+    );
+    assert_eq!(
+        inline_variable("let x = a ** b; x ** c;", "x").unwrap(),
+        "(a ** b) ** c;"
+    );
+}
+
+#[test]
+fn test_inline_postfix_and_unary() {
+    assert_eq!(
+        inline_variable("let x = -a; x[0];", "x").unwrap(),
+        "(-a)[0];"
+    );
+    assert_eq!(
+        inline_variable("let x = a.b; x[0];", "x").unwrap(),
+        "a.b[0];"
+    );
+    assert_eq!(
+        inline_variable("let x = a + 1; -x;", "x").unwrap(),
+        "-(a + 1);"
+    );
+    assert_eq!(
+        inline_variable("let x = a + 1; foo(x, [x]);", "x").unwrap(),
+        "foo(a + 1, [a + 1]);"
+    );
+}
+
+#[test]
+fn test_inline_refused() {
+    assert_eq!(
+        inline_variable("let x = 1; x = 2;", "x").unwrap_err(),
+        inline::InlineError::AssignedReference(TextRange::new(11.into(), 12.into()))
+    );
+    assert!(matches!(
+        inline_variable("let x = [1]; x[0] += 1;", "x"),
+        Err(inline::InlineError::AssignedReference(_))
+    ));
+    assert_eq!(
+        inline_variable("let x = foo(); x;", "x").unwrap_err(),
+        inline::InlineError::SideEffects
+    );
+    assert!(matches!(
+        inline_variable("let x = a + 1; a = 2; x;", "x"),
+        Err(inline::InlineError::OperandChanged(_))
+    ));
+    assert_eq!(
+        inline_variable("let x; x;", "x").unwrap_err(),
+        inline::InlineError::NoValue
+    );
+}
+
+/// Converts the function or closure variable with the name at the
+/// `$$` marker, every other identifier with the same name
+/// is treated as its reference.
+fn convert_fn(src: &str, to_closure: bool) -> Result<String, convert_fn::ConvertFnError> {
+    let (offset, src) = src_cursor_offset(src);
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+
+    let decl = syntax
+        .token_at_offset(offset)
+        .right_biased()
+        .unwrap()
+        .text_range();
+
+    let references = syntax
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == IDENT && token.text() == &src[decl])
+        .map(|token| token.text_range())
+        .filter(|&range| range != decl)
+        .collect::<Vec<_>>();
+
+    let convert = if to_closure {
+        convert_fn::ConvertFn::fn_to_closure(&syntax, decl, &references)?
+    } else {
+        convert_fn::ConvertFn::closure_to_fn(&syntax, decl, &references)?
+    };
+    let new_src = convert.apply(&src);
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    Ok(new_src)
+}
+
+#[test]
+fn test_convert_fn_to_closure() {
+    assert_eq!(
+        convert_fn("fn $$add(a, b) { a + b }\nadd(1, 2);", true).unwrap(),
+        "let add = |a, b| a + b;\nadd(1, 2);"
+    );
+
+    let src = r"
+/// Docs.
+fn $$log(message) {
+    print(message);
+    message
+}
+log(1);
+";
+
+    assert_eq!(
+        convert_fn(src, true).unwrap(),
+        r"
+/// Docs.
+let log = |message| {
+    print(message);
+    message
+};
+log(1);
+"
+    );
+
+    assert_eq!(
+        convert_fn("fn $$f() { x; }", true).unwrap(),
+        "let f = || { x; };"
+    );
+}
+
+#[test]
+fn test_convert_fn_to_closure_refused() {
+    assert_eq!(
+        convert_fn("fn $$f(n) { f(n - 1) }", true).unwrap_err(),
+        convert_fn::ConvertFnError::Recursive(TextRange::new(10.into(), 11.into()))
+    );
+    assert_eq!(
+        convert_fn("f(1);\nfn $$f(n) { n }", true).unwrap_err(),
+        convert_fn::ConvertFnError::UsedBeforeDefinition(TextRange::new(0.into(), 1.into()))
+    );
+    assert!(matches!(
+        convert_fn("fn $$f(n) { n }\nfn g() { f(1) }", true),
+        Err(convert_fn::ConvertFnError::UsedInFunction(_))
+    ));
+    assert_eq!(
+        convert_fn("let $$f = 1;", true).unwrap_err(),
+        convert_fn::ConvertFnError::NotAFunction
+    );
+}
+
+#[test]
+fn test_convert_closure_to_fn() {
+    assert_eq!(
+        convert_fn("let $$add = |a, b| a + b;\nadd(1, 2);", false).unwrap(),
+        "fn add(a, b) { a + b }\nadd(1, 2);"
+    );
+
+    let src = r"
+const $$f = |x| {
+    let y = x.len;
+    for (v, i) in y { print(v); }
+    this.value + y
+};
+";
+
+    assert_eq!(
+        convert_fn(src, false).unwrap(),
+        r"
+fn f(x) {
+    let y = x.len;
+    for (v, i) in y { print(v); }
+    this.value + y
+}
+"
+    );
+}
+
+#[test]
+fn test_convert_closure_to_fn_refused() {
+    assert_eq!(
+        convert_fn("let a = 1;\nlet $$f = |x| x + a;", false).unwrap_err(),
+        convert_fn::ConvertFnError::Captured(TextRange::new(27.into(), 28.into()))
+    );
+    assert!(matches!(
+        convert_fn("let $$f = |x| x;\nlet g = f;", false),
+        Err(convert_fn::ConvertFnError::InvalidReference(_))
+    ));
+    assert_eq!(
+        convert_fn("{ let $$f = |x| x; }", false).unwrap_err(),
+        convert_fn::ConvertFnError::NotGlobal
+    );
+    assert_eq!(
+        convert_fn("export const $$f = |x| x;", false).unwrap_err(),
+        convert_fn::ConvertFnError::Exported
+    );
+    assert_eq!(
+        convert_fn("let $$f = 1;", false).unwrap_err(),
+        convert_fn::ConvertFnError::NotAClosure
+    );
+}
+
+/// Simplifies the conditional or loop with the condition
+/// at the `$$` marker that always evaluates to `value`.
+fn simplify_condition(src: &str, value: bool) -> String {
+    let (offset, src) = src_cursor_offset(src);
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+
+    let condition = syntax
+        .token_at_offset(offset)
+        .right_biased()
+        .unwrap()
+        .text_range();
+
+    let simplify = simplify::SimplifyCondition::new(&syntax, condition, value).unwrap();
+    let new_src = simplify.apply(&src);
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    new_src
+}
+
+#[test]
+fn test_simplify_if() {
+    assert_eq!(
+        simplify_condition("let a = if $$true { 1 } else { 2 };", true),
+        "let a = { 1 };"
+    );
+    assert_eq!(
+        simplify_condition("let a = if $$false { 1 } else { 2 };", false),
+        "let a = { 2 };"
+    );
+    assert_eq!(
+        simplify_condition("let a = if $$false { 1 };", false),
+        "let a = ();"
+    );
+}
+
+#[test]
+fn test_simplify_if_statement() {
+    let src = r"
+let a = 1;
+if $$false {
+    print(a);
+}
+print(a);
+";
+
+    assert_eq!(
+        simplify_condition(src, false),
+        r"
+let a = 1;
+print(a);
+"
+    );
+}
+
+#[test]
+fn test_simplify_else_if() {
+    assert_eq!(
+        simplify_condition("if a { 1 } else if $$false { 2 } else { 3 }", false),
+        "if a { 1 } else { 3 }"
+    );
+    assert_eq!(
+        simplify_condition("if a { 1 } else if $$false { 2 }", false),
+        "if a { 1 }"
+    );
+    assert_eq!(
+        simplify_condition("if a { 1 } else if $$true { 2 } else { 3 }", true),
+        "if a { 1 } else { 2 }"
+    );
+}
+
+#[test]
+fn test_simplify_while() {
+    assert_eq!(
+        simplify_condition("while $$true { break; }", true),
+        "loop { break; }"
+    );
+    assert_eq!(
+        simplify_condition("let a = 1;\nwhile $$false { a += 1; }\n", false),
+        "let a = 1;\n"
+    );
+}
+
+#[test]
+fn test_query_switch_arm_pattern() {
+    let is_arm_pattern = |src: &str| {
+        let (offset, src) = src_cursor_offset(src);
+        let syntax = Parser::new(&src).parse_script().into_syntax();
+        Query::at(&syntax, offset).switch_arm_pattern().is_some()
+    };
+
+    assert!(is_arm_pattern("switch x { true => 1, $$ }"));
+    assert!(is_arm_pattern("switch x { true => 1, fa$$ }"));
+    assert!(is_arm_pattern("switch x {\n    $$\n}"));
+    assert!(is_arm_pattern("switch x { $$true => 1 }"));
+
+    assert!(!is_arm_pattern("switch x { true => $$1 }"));
+    assert!(!is_arm_pattern("switch x { true $$=> 1 }"));
+    assert!(!is_arm_pattern("switch x$$ { true => 1 }"));
+    assert!(!is_arm_pattern("let x = fa$$;"));
+}
+
+/// Adds arms with the patterns to the switch
+/// with a token next to the `$$` marker.
+fn add_switch_arms(src: &str, patterns: &[&str]) -> String {
+    let (offset, src) = src_cursor_offset(src);
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+
+    let token = syntax.token_at_offset(offset);
+    let switch = token
+        .clone()
+        .right_biased()
+        .or_else(|| token.left_biased())
+        .unwrap()
+        .text_range();
+
+    let patterns = patterns.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let add = switch_arms::AddSwitchArms::new(&syntax, switch, &patterns, "    ").unwrap();
+    let new_src = add.apply(&src);
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    new_src
+}
+
+#[test]
+fn test_add_switch_arms() {
+    assert_eq!(
+        add_switch_arms("$$switch x { true => 1 }", &["false"]),
+        "switch x { true => 1, false => (), }"
+    );
+    assert_eq!(
+        add_switch_arms("$$switch x { true => 1, }", &["false"]),
+        "switch x { true => 1, false => (), }"
+    );
+    assert_eq!(
+        add_switch_arms("$$switch x {}", &["true", "false"]),
+        "switch x { true => (), false => (),}"
+    );
+    assert_eq!(
+        add_switch_arms(
+            "fn f(x) {\n    $$switch x {\n        \"a\" => 1,\n    }\n}",
+            &["\"b\"", "_"]
+        ),
+        "fn f(x) {\n    switch x {\n        \"a\" => 1,\n        \"b\" => (),\n        _ => (),\n    }\n}"
+    );
+    assert_eq!(
+        add_switch_arms("switch x {\n}$$", &["_"]),
+        "switch x {\n    _ => (),\n}"
+    );
+}
+
+/// Generates a function for the call with the callee at the `$$` marker.
+fn fn_stub(src: &str, placement: fn_stub::FnStubPlacement) -> Option<String> {
+    let (offset, src) = src_cursor_offset(src);
+    let syntax = Parser::new(&src).parse_script().into_syntax();
+
+    let callee = syntax
+        .token_at_offset(offset)
+        .right_biased()
+        .unwrap()
+        .text_range();
+
+    let stub = fn_stub::FnStub::new(&syntax, callee, placement)?;
+    let new_src = stub.apply(&src);
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    Some(new_src)
+}
+
+#[test]
+fn test_fn_stub() {
+    use fn_stub::FnStubPlacement::{AfterStatement, EndOfFile};
+
+    assert_eq!(
+        fn_stub(r#"$$foo(1, "x");"#, AfterStatement).unwrap(),
+        "foo(1, \"x\");\n\nfn foo(arg0, arg1) {}"
+    );
+
+    let src = r"
+fn main() {
+    let x = $$bar() + 1;
+}
+
+main();
+";
+
+    assert_eq!(
+        fn_stub(src, AfterStatement).unwrap(),
+        r"
+fn main() {
+    let x = bar() + 1;
+}
+
+fn bar() {}
+
+main();
+"
+    );
+
+    assert_eq!(
+        fn_stub(src, EndOfFile).unwrap(),
+        r"
+fn main() {
+    let x = bar() + 1;
+}
+
+main();
+
+fn bar() {}
+"
+    );
+}
+
+#[test]
+fn test_fn_stub_not_a_call() {
+    use fn_stub::FnStubPlacement::AfterStatement;
+
+    assert!(fn_stub("let x = $$foo;", AfterStatement).is_none());
+    assert!(fn_stub("x.$$foo(1);", AfterStatement).is_none());
+    assert!(fn_stub("foo($$x);", AfterStatement).is_none());
+}
+
+/// Organizes the imports, the aliases in `unused` are removed.
+fn organize_imports(src: &str, unused: &[&str]) -> Option<String> {
+    let syntax = Parser::new(src).parse_script().into_syntax();
+
+    let unused = syntax
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| {
+            token.kind() == crate::syntax::SyntaxKind::IDENT && unused.contains(&token.text())
+        })
+        .map(|token| token.text_range())
+        .collect::<Vec<_>>();
+
+    let organized = organize_imports::OrganizeImports::new(&syntax, &unused)?;
+    let new_src = organized.apply(src);
+
+    let parse = Parser::new(&new_src).parse_script();
+    assert!(parse.errors.is_empty(), "{:#?}\n{new_src}", parse.errors);
+
+    Some(new_src)
+}
+
+#[test]
+fn test_organize_imports() {
+    let src = r#"import "c" as c;
+import "a" as a;
+import "b";
+import "a" as a;
+
+a::foo(c::bar);
+"#;
+
+    assert_eq!(
+        organize_imports(src, &[]).unwrap(),
+        r#"import "a" as a;
+import "b";
+import "c" as c;
+
+a::foo(c::bar);
+"#
+    );
+
+    assert_eq!(
+        organize_imports(src, &["c"]).unwrap(),
+        r#"import "a" as a;
+import "b";
+
+a::foo(c::bar);
+"#
+    );
+}
+
+#[test]
+fn test_organize_imports_comments() {
+    let src = r#"//! Module docs.
+
+// The second module.
+import "b" as b; // Used below.
+import "a" as a;
+
+b::foo(a::bar);
+"#;
+
+    assert_eq!(
+        organize_imports(src, &[]).unwrap(),
+        r#"//! Module docs.
+
+import "a" as a;
+// The second module.
+import "b" as b; // Used below.
+
+b::foo(a::bar);
+"#
+    );
+}
+
+#[test]
+fn test_organize_imports_organized() {
+    assert!(organize_imports("import \"a\" as a;\nimport \"b\";\n\na::foo();", &[]).is_none());
+    assert!(organize_imports("let x = 1;", &[]).is_none());
+}
@@ -1,3 +1,5 @@
+use rowan::{NodeOrToken, TextRange, TextSize};
+
 use crate::syntax::{
     SyntaxElement,
     SyntaxKind::{self, *},
@@ -69,3 +71,118 @@ pub(super) fn not_ws_or_comment<T: SyntaxExt>(token: &T) -> bool {
         WHITESPACE | COMMENT_BLOCK | COMMENT_LINE
     )
 }
+
+/// The range of the node without leading and trailing whitespace.
+pub(super) fn trimmed_range(node: &SyntaxNode) -> Option<TextRange> {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() != WHITESPACE);
+
+    let first = tokens.next()?;
+    let last = tokens.last().unwrap_or_else(|| first.clone());
+
+    Some(TextRange::new(
+        first.text_range().start(),
+        last.text_range().end(),
+    ))
+}
+
+/// The statement node of an expression, e.g. the range of a statement
+/// symbol in the HIR, the node also contains the trailing semicolon.
+pub(super) fn statement_at(root: &SyntaxNode, range: TextRange) -> Option<SyntaxNode> {
+    let covering = match root.covering_element(range) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+
+    covering.ancestors().find(|node| node.kind() == STMT)
+}
+
+/// The leading whitespace of the line that contains the offset.
+pub(super) fn line_indent(text: &str, offset: TextSize) -> String {
+    let offset = usize::from(offset);
+    let line_start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+
+    text[line_start..offset]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect()
+}
+
+/// Escape text so that it is inserted verbatim as a part of an LSP snippet.
+pub(super) fn escape_snippet(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('$', "\\$")
+        .replace('}', "\\}")
+}
+
+/// The range to remove for a statement,
+/// including the whole line if nothing else is on it.
+pub(super) fn removed_range(text: &str, stmt: TextRange) -> TextRange {
+    let start = usize::from(stmt.start());
+    let end = usize::from(stmt.end());
+
+    let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    let line_end = text[end..]
+        .find('\n')
+        .map_or(text.len(), |idx| end + idx + 1);
+
+    let trailing =
+        text[end..line_end].len() - text[end..line_end].trim_start_matches([' ', '\t']).len();
+
+    if text[line_start..start].trim().is_empty() && text[end..line_end].trim().is_empty() {
+        TextRange::new(
+            TextSize::from(line_start as u32),
+            TextSize::from(line_end as u32),
+        )
+    } else {
+        TextRange::new(stmt.start(), stmt.end() + TextSize::from(trailing as u32))
+    }
+}
+
+/// The operator of a binary expression.
+pub(super) fn op_kind(binary: &SyntaxNode) -> Option<SyntaxKind> {
+    binary
+        .children_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .find(not_ws_or_comment)
+        .map(|token| token.kind())
+}
+
+/// Whether the expression is the first operand of its parent.
+pub(super) fn is_first_operand(expr: &SyntaxNode, parent: &SyntaxNode) -> bool {
+    parent
+        .children()
+        .find(|child| child.kind() == EXPR)
+        .is_some_and(|first| &first == expr)
+}
+
+/// Whether the expression is assigned to, or a part of it
+/// is assigned to via indexing or field access.
+pub(super) fn is_assignment_target(expr: &SyntaxNode) -> bool {
+    let mut expr = expr.clone();
+
+    while let Some(parent) = expr.parent() {
+        if !is_first_operand(&expr, &parent) {
+            return false;
+        }
+
+        match parent.kind() {
+            EXPR_INDEX => {}
+            EXPR_BINARY => match op_kind(&parent) {
+                Some(op) if op.is_assign_op() => return true,
+                Some(PUNCT_DOT | OP_NULL_ACCESS) => {}
+                _ => return false,
+            },
+            _ => return false,
+        }
+
+        match parent.parent() {
+            Some(wrapper) if wrapper.kind() == EXPR => expr = wrapper,
+            _ => return false,
+        }
+    }
+
+    false
+}
@@ -389,11 +389,11 @@ pub enum SyntaxKind {
     LIT_STR_TEMPLATE,
     LIT_STR_TEMPLATE_INTERPOLATION,
     LIT,
+    PAT,
     OBJECT_FIELD,
     ARG_LIST,
     PARAM_LIST,
     PARAM,
-    PAT,
     SWITCH_ARM_LIST,
     SWITCH_ARM,
     SWITCH_ARM_CONDITION,
@@ -401,6 +401,9 @@ pub enum SyntaxKind {
     EXPORT_IDENT,
     PAT_TUPLE,
     PAT_IDENT,
+    PAT_ARRAY,
+    PAT_OBJECT,
+    PAT_FIELD,
     RHAI_DEF,
     DEF_MODULE_DECL,
     DEF_STMT,
@@ -447,6 +450,20 @@ impl SyntaxKind {
     pub fn is_def(&self) -> bool {
         self >= &SyntaxKind::RHAI_DEF && self <= &SyntaxKind::DEF_FN
     }
+
+    /// Whether the syntax kind is an assignment operator,
+    /// including compound assignments such as `+=`.
+    #[must_use]
+    pub fn is_assign_op(&self) -> bool {
+        self >= &SyntaxKind::OP_ASSIGN && self <= &SyntaxKind::OP_XOR_ASSIGN
+    }
+
+    /// Whether the syntax kind is a compound assignment operator
+    /// that also reads its left-hand side (e.g. `+=`).
+    #[must_use]
+    pub fn is_compound_assign_op(&self) -> bool {
+        self >= &SyntaxKind::OP_ADD_ASSIGN && self <= &SyntaxKind::OP_XOR_ASSIGN
+    }
 }
 
 impl From<SyntaxKind> for rowan::SyntaxKind {
@@ -528,6 +545,7 @@ impl<'source> Iterator for Lexer<'source> {
 }
 
 fn lex_str(lex: &mut LogosLexer<SyntaxKind>) -> Option<()> {
+    // Whether `last_char` is preceded by an unescaped backslash.
     let mut escaped = false;
     let mut last_char = 0_u8;
 
@@ -539,12 +557,12 @@ fn lex_str(lex: &mut LogosLexer<SyntaxKind>) -> Option<()> {
             }
             last_char = 0_u8;
         } else {
-            escaped = b == b'\\';
+            escaped = !escaped && last_char == b'\\';
             last_char = b;
         }
     }
 
-    if last_char == b'"' {
+    if !escaped && last_char == b'"' {
         lex.bump(lex.remainder().bytes().len());
         Some(())
     } else {
@@ -723,3 +741,28 @@ enum AmbiguousToken<'lexer> {
         ident: Option<(SyntaxKind, &'lexer str, Range<usize>)>,
     },
 }
+
+#[cfg(test)]
+#[test]
+fn test_lex_escaped_quotes() {
+    let lit_strs = |src| {
+        let mut lexer = SyntaxKind::lexer(src);
+        let mut lit_strs = Vec::new();
+
+        while let Some(kind) = lexer.next() {
+            if kind == SyntaxKind::LIT_STR {
+                lit_strs.push(lexer.slice());
+            }
+        }
+
+        lit_strs
+    };
+
+    assert_eq!(
+        lit_strs(r#"let a = "say \"hi\""; let b = "\\";"#),
+        [r#""say \"hi\"""#, r#""\\""#]
+    );
+    assert_eq!(lit_strs(r#"let a = "\\\""; "b""#), [r#""\\\"""#, r#""b""#]);
+    // The last quote is escaped.
+    assert!(lit_strs(r#""unclosed\""#).is_empty());
+}
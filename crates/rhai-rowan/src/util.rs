@@ -1,6 +1,9 @@
-use crate::parser::{
-    parsers::{self, def::parse_def_header, parse_expr},
-    Parse, Parser,
+use crate::{
+    parser::{
+        parsers::{self, def::parse_def_header, parse_expr},
+        Parse, Parser,
+    },
+    syntax::{SyntaxKind, SyntaxNode, SyntaxToken},
 };
 use rowan::{TextRange, TextSize};
 use thiserror::Error;
@@ -99,6 +102,225 @@ pub fn is_valid_ident(text: &str) -> bool {
     ident_parser.finish().errors.is_empty()
 }
 
+/// The kind of a [`Comment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentKind {
+    /// `// comment`
+    Line,
+    /// `/* comment */`
+    Block,
+    /// `/// comment`, `/** comment */` or `//! comment`
+    Doc,
+}
+
+/// A comment token in the syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comment {
+    token: SyntaxToken,
+}
+
+impl Comment {
+    /// Returns `None` if the token is not a comment.
+    #[must_use]
+    pub fn cast(token: SyntaxToken) -> Option<Self> {
+        matches!(
+            token.kind(),
+            SyntaxKind::COMMENT_LINE
+                | SyntaxKind::COMMENT_LINE_DOC
+                | SyntaxKind::COMMENT_BLOCK
+                | SyntaxKind::COMMENT_BLOCK_DOC
+        )
+        .then_some(Self { token })
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> CommentKind {
+        match self.token.kind() {
+            SyntaxKind::COMMENT_LINE_DOC | SyntaxKind::COMMENT_BLOCK_DOC => CommentKind::Doc,
+            SyntaxKind::COMMENT_LINE if self.token.text().starts_with("//!") => CommentKind::Doc,
+            SyntaxKind::COMMENT_LINE => CommentKind::Line,
+            _ => CommentKind::Block,
+        }
+    }
+
+    #[must_use]
+    pub fn range(&self) -> TextRange {
+        self.token.text_range()
+    }
+
+    /// The full text of the comment, including the delimiters.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        self.token.text()
+    }
+
+    /// The text of the comment without the delimiters
+    /// and the surrounding whitespace.
+    #[must_use]
+    pub fn content(&self) -> &str {
+        let text = self.token.text();
+
+        let content = match self.token.kind() {
+            SyntaxKind::COMMENT_BLOCK | SyntaxKind::COMMENT_BLOCK_DOC => {
+                let text = text.strip_suffix("*/").unwrap_or(text);
+                text.strip_prefix("/**")
+                    .or_else(|| text.strip_prefix("/*"))
+                    .unwrap_or(text)
+            }
+            _ => text
+                .strip_prefix("///")
+                .or_else(|| text.strip_prefix("//!"))
+                .or_else(|| text.strip_prefix("//"))
+                .unwrap_or(text),
+        };
+
+        content.trim()
+    }
+
+    #[must_use]
+    pub fn token(&self) -> &SyntaxToken {
+        &self.token
+    }
+}
+
+/// All comments under the node in source order,
+/// including the trivia between any of its tokens.
+pub fn comments(node: &SyntaxNode) -> impl Iterator<Item = Comment> {
+    node.descendants_with_tokens()
+        .filter_map(rowan::NodeOrToken::into_token)
+        .filter_map(Comment::cast)
+}
+
+/// An error of a delimiter that has no matching pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DelimiterError {
+    /// The opening delimiter is never closed.
+    Unclosed { open: TextRange },
+    /// The closing delimiter was not opened.
+    Unopened { close: TextRange },
+    /// The closing delimiter does not match the innermost
+    /// opening delimiter, it is also unopened.
+    Mismatched { open: TextRange, close: TextRange },
+}
+
+/// Pairs of `()`, `[]` and `{}` delimiters of a syntax tree.
+///
+/// The delimiters are matched by the tokens alone
+/// without relying on the recovery of the parser,
+/// delimiters in strings and comments are part
+/// of other tokens so they are ignored.
+#[derive(Debug, Default, Clone)]
+pub struct Delimiters {
+    /// Ranges of the opening and closing delimiters in source order.
+    pub pairs: Vec<(TextRange, TextRange)>,
+    /// Delimiters without a matching pair in source order.
+    pub errors: Vec<DelimiterError>,
+}
+
+impl Delimiters {
+    #[must_use]
+    pub fn new(node: &SyntaxNode) -> Self {
+        let mut delimiters = Self::default();
+        let mut open: Vec<(SyntaxKind, TextRange)> = Vec::new();
+
+        for token in node
+            .descendants_with_tokens()
+            .filter_map(rowan::NodeOrToken::into_token)
+        {
+            let kind = token.kind();
+            let range = token.text_range();
+
+            if closing_delimiter(kind).is_some() {
+                open.push((kind, range));
+                continue;
+            }
+
+            if !matches!(
+                kind,
+                SyntaxKind::PUNCT_PAREN_END
+                    | SyntaxKind::PUNCT_BRACKET_END
+                    | SyntaxKind::PUNCT_BRACE_END
+            ) {
+                continue;
+            }
+
+            let matching = open
+                .iter()
+                .rposition(|&(open_kind, _)| closing_delimiter(open_kind) == Some(kind));
+
+            match matching {
+                Some(idx) => {
+                    // Everything opened after the match is left unclosed.
+                    for (_, unclosed) in open.drain(idx + 1..) {
+                        delimiters
+                            .errors
+                            .push(DelimiterError::Unclosed { open: unclosed });
+                    }
+
+                    if let Some((_, open_range)) = open.pop() {
+                        delimiters.pairs.push((open_range, range));
+                    }
+                }
+                None => delimiters.errors.push(match open.last() {
+                    Some(&(_, open_range)) => DelimiterError::Mismatched {
+                        open: open_range,
+                        close: range,
+                    },
+                    None => DelimiterError::Unopened { close: range },
+                }),
+            }
+        }
+
+        delimiters.errors.extend(
+            open.into_iter()
+                .map(|(_, open)| DelimiterError::Unclosed { open }),
+        );
+
+        delimiters.pairs.sort_by_key(|(open, _)| open.start());
+        delimiters.errors.sort_by_key(|error| match error {
+            DelimiterError::Unclosed { open: range }
+            | DelimiterError::Unopened { close: range }
+            | DelimiterError::Mismatched { close: range, .. } => range.start(),
+        });
+
+        delimiters
+    }
+
+    /// The range of the delimiter that matches the delimiter
+    /// at the offset, the delimiter right after the offset
+    /// is preferred to the one right before it.
+    #[must_use]
+    pub fn matching(&self, offset: TextSize) -> Option<TextRange> {
+        let find = |at: &dyn Fn(TextRange) -> bool| {
+            self.pairs.iter().find_map(|&(open, close)| {
+                if at(open) {
+                    Some(close)
+                } else if at(close) {
+                    Some(open)
+                } else {
+                    None
+                }
+            })
+        };
+
+        find(&|range| range.contains(offset)).or_else(|| find(&|range| range.end() == offset))
+    }
+}
+
+/// The closing delimiter of an opening delimiter.
+fn closing_delimiter(kind: SyntaxKind) -> Option<SyntaxKind> {
+    match kind {
+        SyntaxKind::PUNCT_PAREN_START => Some(SyntaxKind::PUNCT_PAREN_END),
+        SyntaxKind::PUNCT_BRACKET_START | SyntaxKind::PUNCT_NULL_BRACKET_START => {
+            Some(SyntaxKind::PUNCT_BRACKET_END)
+        }
+        SyntaxKind::PUNCT_BRACE_START
+        | SyntaxKind::PUNCT_MAP_START
+        | SyntaxKind::INTERPOLATION_START => Some(SyntaxKind::PUNCT_BRACE_END),
+        _ => None,
+    }
+}
+
 #[must_use]
 pub fn unescape(s: &str, termination_char: char) -> (String, Vec<EscapeError>) {
     let mut chars = s.chars().peekable();
@@ -0,0 +1,49 @@
+use rhai_rowan::{
+    ast::{AstNode, Rhai},
+    parser::Parser,
+    util::CommentKind,
+};
+
+const SRC: &str = r#"//! Module docs.
+
+// TODO: split this up
+/// Adds things.
+fn add(a, /* the other one */ b) {
+    // FIXME
+    a + b // trailing
+}
+
+/**
+ * Block docs.
+ */
+const X = [1, /* two */ 2];
+
+let s = "// not a comment";
+"#;
+
+#[test]
+fn test_comments() {
+    let rhai = Rhai::cast(Parser::new(SRC).parse_script().into_syntax()).unwrap();
+    let comments = rhai.comments().collect::<Vec<_>>();
+
+    assert_eq!(
+        comments
+            .iter()
+            .map(|c| (c.kind(), c.content()))
+            .collect::<Vec<_>>(),
+        [
+            (CommentKind::Doc, "Module docs."),
+            (CommentKind::Line, "TODO: split this up"),
+            (CommentKind::Doc, "Adds things."),
+            (CommentKind::Block, "the other one"),
+            (CommentKind::Line, "FIXME"),
+            (CommentKind::Line, "trailing"),
+            (CommentKind::Doc, "* Block docs."),
+            (CommentKind::Block, "two"),
+        ]
+    );
+
+    for comment in &comments {
+        assert_eq!(&SRC[comment.range()], comment.text());
+    }
+}
@@ -0,0 +1,104 @@
+use rhai_rowan::{
+    parser::Parser,
+    util::{src_cursor_offset, DelimiterError, Delimiters},
+    TextRange,
+};
+
+fn match_delimiters(src: &str) -> Delimiters {
+    Delimiters::new(&Parser::new(src).parse_script().into_syntax())
+}
+
+/// The range of a single character delimiter at the offset.
+fn delimiter_at(offset: usize) -> TextRange {
+    TextRange::at((offset as u32).into(), 1.into())
+}
+
+#[test]
+fn test_matched() {
+    let src = r#"let a = #{ b: [f(1), `${x[0]}`] }; // ) ]
+let s = "{ (";
+"#;
+
+    let delimiters = match_delimiters(src);
+
+    assert!(delimiters.errors.is_empty(), "{:?}", delimiters.errors);
+    assert_eq!(
+        delimiters
+            .pairs
+            .iter()
+            .map(|&(open, close)| (&src[open], &src[close]))
+            .collect::<Vec<_>>(),
+        [("#{", "}"), ("[", "]"), ("(", ")"), ("${", "}"), ("[", "]"),]
+    );
+}
+
+#[test]
+fn test_missing_closer() {
+    let src = "fn f() {\n    g(1;\n}\n";
+    let delimiters = match_delimiters(src);
+
+    let open = src.find("g(").unwrap() + 1;
+    assert_eq!(
+        delimiters.errors,
+        [DelimiterError::Unclosed {
+            open: delimiter_at(open)
+        }]
+    );
+
+    // The block is still matched.
+    assert!(delimiters
+        .pairs
+        .iter()
+        .any(|&(open, close)| &src[open] == "{" && &src[close] == "}"));
+}
+
+#[test]
+fn test_stray_closer() {
+    let src = "let a = 1);\nlet b = [2);";
+    let delimiters = match_delimiters(src);
+
+    let stray = src.find(')').unwrap();
+    let mismatched = src.rfind(')').unwrap();
+    let bracket = src.find('[').unwrap();
+
+    assert_eq!(
+        delimiters.errors,
+        [
+            DelimiterError::Unopened {
+                close: delimiter_at(stray)
+            },
+            DelimiterError::Unclosed {
+                open: delimiter_at(bracket)
+            },
+            DelimiterError::Mismatched {
+                open: delimiter_at(bracket),
+                close: delimiter_at(mismatched)
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_matching() {
+    let (offset, src) = src_cursor_offset("let a = f$$(g());");
+    let delimiters = match_delimiters(&src);
+
+    assert_eq!(
+        delimiters.matching(offset).map(|range| &src[range]),
+        Some(")")
+    );
+    assert_eq!(
+        delimiters.matching(offset).unwrap().end(),
+        (src.len() as u32 - 1).into()
+    );
+
+    let (offset, src) = src_cursor_offset("let a = f(g()$$);");
+    let delimiters = match_delimiters(&src);
+    let matching = delimiters.matching(offset).unwrap();
+
+    // The delimiter after the offset is preferred.
+    assert_eq!(usize::from(matching.start()), src.find('(').unwrap());
+
+    let (offset, src) = src_cursor_offset("let a$$ = f(g());");
+    assert_eq!(match_delimiters(&src).matching(offset), None);
+}
@@ -0,0 +1,85 @@
+use rhai_rowan::{
+    parser::{ParseErrorKind, Parser},
+    syntax::SyntaxKind,
+};
+use test_case::test_case;
+
+fn reported_errors(src: &str) -> Vec<(&str, ParseErrorKind)> {
+    let parse = Parser::new(src).parse_script();
+    assert!(!parse.errors.is_empty());
+
+    parse
+        .reported_errors()
+        .into_iter()
+        .map(|e| (&src[e.range], e.kind.clone()))
+        .collect()
+}
+
+#[test_case("let a = ;\nlet b = 2;", &[";"] ; "missing value")]
+#[test_case("let x = foo(1, 2;\nlet y = 3;\nlet z = 4;", &[";"] ; "unclosed call")]
+#[test_case("let a = [1, 2, 3;\nprint(a);\nprint(b);", &[";"] ; "unclosed array")]
+#[test_case("let a = #{ a: 1, b 2, c: 3 };", &["2"] ; "missing colon")]
+#[test_case("if x { let a = 1 + ; } let b = 3 +;", &[";", ";"] ; "separate errors")]
+#[test_case("fn foo() {\n    let a = 1;\n    let b = 2;\n", &[""] ; "missing brace")]
+fn test_reported_errors(src: &str, expected: &[&str]) {
+    let reported = reported_errors(src);
+
+    assert_eq!(
+        reported.iter().map(|(text, _)| *text).collect::<Vec<_>>(),
+        expected,
+        "{reported:#?}"
+    );
+}
+
+#[test]
+fn test_reported_errors_are_ordered() {
+    let src = "let a = #{ a: 1, b 2 };\nlet b = 1;\nlet c = ;";
+
+    let reported = reported_errors(src);
+
+    assert_eq!(
+        reported,
+        [
+            ("2", ParseErrorKind::ExpectedToken(SyntaxKind::PUNCT_COLON)),
+            (";", ParseErrorKind::UnexpectedToken),
+        ]
+    );
+}
+
+#[test_case("m::f!(1);", "'!' cannot be used to call module functions" ; "module function")]
+#[test_case("x.f!(1);", "method calls cannot share the scope of the caller" ; "method")]
+#[test_case("f(1)!(2);", "'!' can only be used to call functions by name" ; "other expression")]
+fn test_malformed_call_scope(src: &str, expected: &'static str) {
+    let reported = reported_errors(src);
+
+    assert_eq!(
+        reported,
+        [("!", ParseErrorKind::MalformedCapture(expected))],
+        "{reported:#?}"
+    );
+}
+
+#[test]
+fn test_call_scope() {
+    assert!(Parser::new("f!(1);").parse_script().errors.is_empty());
+}
+
+/// Rhai has no default parameter values.
+#[test]
+fn test_default_parameter_value() {
+    let reported = reported_errors("fn f(a, b = 10) {}\nf(1);");
+
+    assert_eq!(reported.first().map(|(text, _)| *text), Some("="));
+}
+
+#[test_case("let [a, b] = [1, 2];", "[" ; "array")]
+#[test_case("let #{a, b: c} = #{ a: 1, b: 2 };", "#{" ; "object")]
+fn test_destructuring(src: &str, pattern: &str) {
+    let reported = reported_errors(src);
+
+    assert_eq!(
+        reported,
+        [(pattern, ParseErrorKind::UnsupportedDestructuring)],
+        "{reported:#?}"
+    );
+}
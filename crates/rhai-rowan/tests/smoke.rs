@@ -36,6 +36,7 @@ use test_case::test_case;
 #[test_case("string_escape", include_str!("../../../testdata/valid/string_escape.rhai"))]
 #[test_case("template", include_str!("../../../testdata/valid/template.rhai"))]
 #[test_case("unary_ops", include_str!("../../../testdata/valid/unary_ops.rhai"))]
+#[test_case("call_scope", include_str!("../../../testdata/valid/call_scope.rhai"))]
 fn parse_valid(name: &str, src: &str) {
     let parse = Parser::new(src)
         // This operator does not actually exist among the scripts.
@@ -28,6 +28,57 @@ export function registerCommands(
         const doc = await vscode.workspace.openTextDocument({content: res.hir});
         vscode.window.showTextDocument(doc);
       }
-    })
+    }),
+    vscode.commands.registerCommand("rhai.showTypeOf", async () => {
+      const editor = vscode.window.activeTextEditor;
+
+      if (!editor) {
+        return;
+      }
+
+      const s: Lsp.Client.RequestMethod = "rhai/typeOf";
+      const params: Lsp.Client.RequestParams<"rhai/typeOf"> = {
+        uri: editor.document.uri.toString(),
+        position: editor.selection.active,
+      };
+      const res = await client.sendRequest<Lsp.Client.RequestResponse<"rhai/typeOf">>(s, params);
+
+      if (res) {
+        vscode.window.showInformationMessage(res.ty);
+      }
+    }),
+    // Used by code actions that place the cursor or placeholders after the edit.
+    vscode.commands.registerCommand(
+      "rhai.insertSnippet",
+      async (uri: string, range: client.Range, snippet: string) => {
+        const editor = vscode.window.activeTextEditor;
+
+        if (!editor || editor.document.uri.toString() !== uri) {
+          return;
+        }
+
+        // The snippet is already indented, but VS Code
+        // indents every inserted line to the first one.
+        const indent = editor.document
+          .lineAt(range.start.line)
+          .text.match(/^\s*/)[0];
+        const unindented = snippet
+          .split("\n")
+          .map((line, idx) =>
+            idx > 0 && line.startsWith(indent) ? line.slice(indent.length) : line
+          )
+          .join("\n");
+
+        await editor.insertSnippet(
+          new vscode.SnippetString(unindented),
+          new vscode.Range(
+            range.start.line,
+            range.start.character,
+            range.end.line,
+            range.end.character
+          )
+        );
+      }
+    )
   );
 }
@@ -70,6 +70,22 @@ export namespace Client {
         positions?: Array<Position>;
       } | null;
     };
+    "rhai/typeOf": {
+      params: {
+        /**
+         * URI of the document.
+         */
+        uri: string;
+        position: Position;
+      };
+      response: {
+        /**
+         * The inferred type, `?` if it is unknown.
+         */
+        ty: string;
+        range?: Range;
+      } | null;
+    };
   }
 
   export type NotificationMethod = keyof ClientNotifications;